@@ -1,4 +1,11 @@
-use std::path::PathBuf;
+use std::env;
+use std::path::{Path, PathBuf};
+
+/// The native symbol sets, each generated with `make native` and enabled by the feature of the
+/// same name
+const NATIVE_VERSIONS: &[&str] = &[
+    "php_7_4", "php_8_0", "php_8_1", "php_8_2", "php_8_3", "php_8_4",
+];
 
 fn main() {
     let dir: PathBuf = ["tree-sitter-php", "php", "src"].iter().collect();
@@ -8,4 +15,37 @@ fn main() {
         .file(dir.join("parser.c"))
         .file(dir.join("scanner.c"))
         .compile("tree-sitter-php");
+
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed={}", dir.display());
+    println!("cargo:rerun-if-changed=src/native");
+
+    detect_native_versions();
+}
+
+///
+/// Enables `cfg(native_php_X_Y)` for the enabled native symbol sets. An enabled set which
+/// isn't generated fails the build, as the analyzer wouldn't know any of PHP's own symbols
+///
+fn detect_native_versions() {
+    for version in NATIVE_VERSIONS {
+        println!("cargo:rustc-check-cfg=cfg(native_{})", version);
+        let feature = format!("CARGO_FEATURE_{}", version.to_uppercase());
+        if env::var_os(feature).is_none() {
+            continue;
+        }
+        if Path::new("src/native")
+            .join(version)
+            .join("mod.rs")
+            .exists()
+        {
+            println!("cargo:rustc-cfg=native_{}", version);
+        } else {
+            panic!(
+                "The native symbols of {} are not generated. Run `make native` and commit \
+                 src/native/{}, or disable the feature",
+                version, version
+            );
+        }
+    }
 }
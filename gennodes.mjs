@@ -8,6 +8,17 @@ const additional_properties = {
     "method_declaration": "crate::nodeanalysis::method_declaration::MethodDeclarationState",
 };
 
+// Statement lists, where a child which can't be converted is kept as an ErrorNode
+const recovering_children = [
+    "compound_statement",
+    "colon_block",
+    "case_statement",
+    "default_statement",
+    "declaration_list",
+    "enum_declaration_list",
+    "program",
+];
+
 const node_defs = JSON.parse(raw_json);
 let operators = {
     // Assignments
@@ -412,6 +423,7 @@ let any_names = [];
 let any_buffer = "\n\n";
 any_buffer += "\n#[derive(Debug, Clone)]\n";
 any_buffer += "pub enum AnyNode {\n";
+any_buffer += "    Error(Box<ErrorNode>),\n";
 
 let any_ref_buffer = "\n\n";
 any_ref_buffer += "\nuse crate::operators::operator::Operators;\n\n";
@@ -617,7 +629,12 @@ for (const node_def of node_defs) {
                     extra += ".filter(|node| !skip_nodes.contains(&node.id()))";
                 }
                 children_filter += '.filter(|node| node.kind() != "comment")';
-                impl_members += "    children: " + base_children_type + "::parse_vec(node.named_children(&mut node.walk())" + children_filter + ", source)?,\n";
+                if (recovering_children.includes(node_def.type)) {
+                    uses["crate::autotree::RecoveringNodeParser"] = "";
+                    impl_members += "    children: " + base_children_type + "::parse_vec_recovering(node.named_children(&mut node.walk())" + children_filter + ", source),\n";
+                } else {
+                    impl_members += "    children: " + base_children_type + "::parse_vec(node.named_children(&mut node.walk())" + children_filter + ", source)?,\n";
+                }
                 extra += ",\nsource)?,";
                 impl_members += extra;
                 child_cast += "child_vec.extend(self.children.iter().map(|n| n.as_any()));\n"
@@ -801,6 +818,7 @@ impl AnyNode {
         Ok(match node.kind() {
             // "comment" => 
             // "text_interpolation" => 
+            "ERROR" => AnyNode::Error(Box::new(ErrorNode::parse(node, source)?)),
 ${Object.values(node_defs).map(node_def => {
     const name = get_rust_type_name(node_def.type);
     if (name.match(/'static/)) {
@@ -832,6 +850,7 @@ impl NodeAccess for AnyNode {
 
     fn brief_desc(&self) -> String {
         match self {
+            AnyNode::Error(x) => x.brief_desc(),
         ${Object.values(node_defs).map(node_def => {
     const name = get_rust_type_name(node_def.type);
     if (name.match(/'static/)) {
@@ -848,6 +867,7 @@ impl NodeAccess for AnyNode {
 
     fn range(&self) -> Range {
         match self {
+            AnyNode::Error(x) => x.range(),
 ${Object.values(node_defs).map(node_def => {
     const name = get_rust_type_name(node_def.type);
     if (name.match(/'static/)) {
@@ -864,6 +884,7 @@ ${Object.values(node_defs).map(node_def => {
 
     fn as_any<'a>(&'a self) -> AnyNodeRef<'a> {
         match self {
+            AnyNode::Error(x) => x.as_any(),
 ${Object.values(node_defs).map(node_def => {
     const name = get_rust_type_name(node_def.type);
     if (name.match(/'static/)) {
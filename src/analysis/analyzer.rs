@@ -1,20 +1,65 @@
+use std::collections::HashSet;
+use std::error::Error;
 use std::ffi::OsString;
+use std::fmt::Display;
 use std::os::unix::prelude::OsStrExt;
+use std::panic::{self, AssertUnwindSafe};
+
+use tree_sitter::LanguageError;
 
 use crate::autonodes::any::AnyNodeRef;
 use crate::autotree::AutoTree;
 use crate::autotree::NodeAccess;
+use crate::autotree::ParseError;
 use crate::config::PHPAnalyzeConfig;
 use crate::issue::Issue;
 use crate::issue::IssueEmitter;
 use crate::issue::IssuePosition;
+use crate::locks::RecoverableLock;
 use crate::nodeanalysis::analysis::ErrorPassAnalyzableNode;
 use crate::nodeanalysis::analysis::FirstPassAnalyzeableNode;
 use crate::nodeanalysis::analysis::SecondPassAnalyzeableNode;
 use crate::nodeanalysis::analysis::ThirdPassAnalyzeableNode;
+use crate::nodeanalysis::include_expression::collect_top_level_variables;
 
 use super::state::AnalysisState;
 
+///
+/// Why a file couldn't be turned into a tree for analysis
+///
+#[derive(Debug)]
+pub enum AnalyzerError {
+    /// The content couldn't be read
+    Read(std::io::Error),
+    /// The PHP grammar couldn't be loaded into the parser
+    Language(LanguageError),
+    /// The parser didn't produce a tree at all
+    NoTree,
+    /// The syntax tree couldn't be converted to typed nodes. Also emitted as a `ParseError`
+    Conversion(ParseError),
+}
+
+impl Display for AnalyzerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnalyzerError::Read(err) => write!(f, "Unable to read the content: {}", err),
+            AnalyzerError::Language(err) => write!(f, "Unable to load the PHP grammar: {}", err),
+            AnalyzerError::NoTree => write!(f, "The parser didn't produce a syntax tree"),
+            AnalyzerError::Conversion(err) => write!(f, "Unable to parse: {}", err.error),
+        }
+    }
+}
+
+impl Error for AnalyzerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            AnalyzerError::Read(err) => Some(err),
+            AnalyzerError::Language(err) => Some(err),
+            AnalyzerError::NoTree | AnalyzerError::Conversion(_) => None,
+        }
+    }
+}
+
 pub struct Analyzer {
     config: PHPAnalyzeConfig,
     get_content: Box<dyn FnMut() -> std::io::Result<Vec<u8>> + Send + Sync>,
@@ -50,32 +95,32 @@ impl Analyzer {
         }
     }
 
-    pub fn parse(&mut self, emitter: &dyn IssueEmitter) -> Result<(), &'static str> {
+    pub fn parse(&mut self, emitter: &dyn IssueEmitter) -> Result<(), AnalyzerError> {
         use crate::parser::PHPParser;
-        let mut parser = PHPParser::new();
+        let mut parser = PHPParser::try_new().map_err(AnalyzerError::Language)?;
 
-        let contents = (self.get_content)().expect("Something went wrong reading the file content");
+        let contents = (self.get_content)().map_err(AnalyzerError::Read)?;
 
-        let stru = match parser.parse_struct(contents.clone()) {
+        let stru = match parser.parse_struct(contents) {
             Ok(Some(stru)) => stru,
             Err(err) => {
                 emitter.emit(Issue::ParseError(
                     IssuePosition::new(&Some(self.content_id.clone().into()), err.range),
                     err.error.clone().into(),
                 ));
-
-                /*eprintln!(
-                    "ERROR: {}:{}:{}: {}",
-                    self.content_id.to_string_lossy(),
-                    err.range.start_point.row,
-                    err.range.start_point.column,
-                    err.error
-                );*/
-                return Err("TODO Trouble with something");
+                return Err(AnalyzerError::Conversion(err));
             }
-            Ok(None) => return Err("TODO Trouble with something else"),
+            Ok(None) => return Err(AnalyzerError::NoTree),
         };
 
+        // The tree is kept and analyzed around any syntax errors
+        for err in &stru.syntax_errors {
+            emitter.emit(Issue::ParseError(
+                IssuePosition::new(&Some(self.content_id.clone().into()), err.range),
+                err.error.clone().into(),
+            ));
+        }
+
         self.tree = Some(stru);
 
         Ok(())
@@ -116,25 +161,68 @@ impl Analyzer {
 
     pub fn first_pass(&self, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
         if let Some(tree) = &self.tree {
-            let any_root = tree.root.as_any();
-            any_root.analyze_errors(&any_root, state, emitter);
-            any_root.analyze_first_pass(state, emitter);
+            // What the file includes is registered again by this pass
+            if let Some(filename) = &state.filename {
+                let mut variables = HashSet::new();
+                collect_top_level_variables(&tree.root.as_any(), &mut variables);
+                let mut includes = state.symbol_data.includes.write_or_recover();
+                includes.clear_file(filename);
+                includes.set_variables(filename, variables);
+            }
+            self.isolate_panics(tree, state, emitter, |state, emitter| {
+                let any_root = tree.root.as_any();
+                any_root.analyze_errors(&any_root, state, emitter);
+                any_root.analyze_first_pass(state, emitter);
+            });
         }
     }
 
     pub fn second_pass(&self, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
         if let Some(tree) = &self.tree {
-            tree.root.as_any().analyze_second_pass(state, emitter);
+            self.isolate_panics(tree, state, emitter, |state, emitter| {
+                tree.root.as_any().analyze_second_pass(state, emitter);
+            });
         }
     }
 
     pub fn third_pass(&self, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
         if let Some(tree) = &self.tree {
-            let path = vec![];
-            tree.root.as_any().analyze_third_pass(state, emitter, &path);
+            self.isolate_panics(tree, state, emitter, |state, emitter| {
+                let path = vec![];
+                tree.root.as_any().analyze_third_pass(state, emitter, &path);
+            });
         }
     }
 
+    ///
+    /// Runs a pass over `tree`, and reports a panic in it as a `ParseAnomaly` instead of
+    /// aborting, so the rest of the files are still analyzed
+    ///
+    fn isolate_panics<F>(
+        &self,
+        tree: &AutoTree,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+        pass: F,
+    ) where
+        F: FnOnce(&mut AnalysisState, &dyn IssueEmitter),
+    {
+        let Err(payload) = panic::catch_unwind(AssertUnwindSafe(|| pass(state, emitter))) else {
+            return;
+        };
+        let message = if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            "unknown cause".to_string()
+        };
+        emitter.emit(Issue::ParseAnomaly(
+            IssuePosition::new(&Some(self.content_id.clone().into()), tree.root.range),
+            format!("Analysis of the file was aborted: {}", message).into(),
+        ));
+    }
+
     pub fn dump(&self) {
         if let Some(tree) = &self.tree {
             tree.debug_dump();
@@ -1,7 +1,8 @@
 //use tree_sitter::Range;
 use crate::parser::Range;
 
-use crate::types::union::PHPType;
+use crate::analysis::taint::Taint;
+use crate::types::union::{PHPType, ShapeTypeKey};
 use crate::{symbols::Name, types::union::UnionType, value::PHPValue};
 
 #[derive(Debug)]
@@ -18,6 +19,10 @@ pub struct VarData {
     pub is_argument: bool,
     // Some branches did not initialize this variable
     pub is_partial: bool,
+    /// User input the variable might hold
+    pub taint: Option<Taint>,
+    /// Keys of the array known to be set, from an `isset()` guard, until it's written again
+    pub checked_keys: Vec<ShapeTypeKey>,
 }
 
 impl VarData {
@@ -34,6 +39,8 @@ impl VarData {
             referenced_ranges: vec![],
             is_argument: false,
             is_partial: false,
+            taint: None,
+            checked_keys: vec![],
         }
     }
 
@@ -61,18 +68,18 @@ impl VarData {
         self.all_written_data.push(data.clone());
         self.last_written_data = vec![data];
         self.written_to += 1;
+        self.checked_keys.clear();
     }
 
     /// when multiple branches, which have all written to it is joined
     pub fn multi_write_to(
         &mut self,
-        _last: Vec<(PHPType, Option<PHPValue>)>,
-        _all_data: Vec<(PHPType, Option<PHPValue>)>,
+        last: Vec<(PHPType, Option<PHPValue>)>,
+        all_data: Vec<(PHPType, Option<PHPValue>)>,
     ) {
-        todo!();
-        /*         let data = (utype, value);
-        self.all_written_data.push(data.clone());
-        self.last_written_data = vec![data];
-        self.written_to += 1; */
+        self.all_written_data.extend(all_data);
+        self.last_written_data = last;
+        self.written_to += 1;
+        self.checked_keys.clear();
     }
 }
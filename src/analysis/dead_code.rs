@@ -0,0 +1,381 @@
+use crate::locks::RecoverableLock;
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    sync::{Arc, RwLock},
+};
+
+use crate::{
+    config::{DeadCodeConfig, PHPAnalyzeConfig},
+    issue::{Issue, IssueEmitter},
+    phpdoc::types::{PHPDocComment, PHPDocEntry},
+    symboldata::{
+        attributes::AttributeData,
+        class::{
+            ClassData, ClassMemberVisibility, ClassModifier, ClassName, ClassType, MethodData,
+            PropertyData,
+        },
+        FileLocation, SymbolData,
+    },
+    symbols::{FullyQualifiedName, Name},
+    types::union::{DiscreteType, PHPType, SpecialType},
+};
+
+///
+/// Reports private and protected methods, properties and private constants, functions,
+/// classes, interfaces and traits which are never used.
+///
+/// Usage is registered while analyzing, so this must be run after the third pass has been
+/// completed for every file in the project.
+pub fn analyze_dead_code(
+    symbol_data: &Arc<SymbolData>,
+    config: &PHPAnalyzeConfig,
+    emitter: &dyn IssueEmitter,
+) {
+    let config = &config.dead_code;
+    let classes: Vec<_> = symbol_data
+        .classes
+        .read_or_recover()
+        .values()
+        .cloned()
+        .collect();
+    let functions: Vec<_> = symbol_data
+        .functions
+        .read_or_recover()
+        .values()
+        .cloned()
+        .collect();
+
+    // Classes referenced from declarations, rather than from code
+    let referenced = RefCell::new(HashSet::new());
+    for class_handle in &classes {
+        let class_type = class_handle.read_or_recover();
+        match &*class_type {
+            ClassType::Class(c) => {
+                let mut refs = referenced.borrow_mut();
+                if let Some(base) = &c.base_class_name {
+                    refs.insert(base.get_fq_name().to_ascii_lowercase());
+                }
+                for name in &c.interfaces {
+                    refs.insert(name.get_fq_name().to_ascii_lowercase());
+                }
+                for import in &c.traits.imports {
+                    refs.insert(import.trait_name.get_fq_name().to_ascii_lowercase());
+                }
+                drop(refs);
+                collect_attribute_classes(&c.attributes, &referenced);
+                collect_property_class_names(&c.properties, &referenced);
+                collect_method_class_names(&c.methods, &referenced);
+            }
+            ClassType::Interface(i) => {
+                for name in i.base_interface_names.iter().flatten() {
+                    referenced
+                        .borrow_mut()
+                        .insert(name.get_fq_name().to_ascii_lowercase());
+                }
+                collect_method_class_names(&i.methods, &referenced);
+            }
+            ClassType::Trait(t) => {
+                for import in &t.traits.imports {
+                    referenced
+                        .borrow_mut()
+                        .insert(import.trait_name.get_fq_name().to_ascii_lowercase());
+                }
+                collect_property_class_names(&t.properties, &referenced);
+                collect_method_class_names(&t.methods, &referenced);
+            }
+            ClassType::None => (),
+        }
+    }
+    for function in &functions {
+        let function = function.read_or_recover();
+        collect_attribute_classes(&function.attributes, &referenced);
+        for t in [&function.php_return_type, &function.inferred_return_type]
+            .into_iter()
+            .flatten()
+            .chain(function.comment_return_type.iter().map(|(t, _)| t))
+        {
+            collect_class_names(t, &referenced);
+        }
+        for arg in &function.arguments {
+            for t in [&arg.arg_type, &arg.phpdoc_type].into_iter().flatten() {
+                collect_class_names(t, &referenced);
+            }
+        }
+    }
+    let referenced = referenced.into_inner();
+
+    for class_handle in &classes {
+        let class_type = class_handle.read_or_recover();
+        let (class_name, position, phpdoc, is_native) = match &*class_type {
+            ClassType::Class(class_data) => {
+                if !class_data.is_native && !is_internal(&class_data.position) {
+                    analyze_dead_class(class_data, &referenced, symbol_data, config, emitter);
+                }
+                continue;
+            }
+            ClassType::Interface(i) => (&i.interface_name, &i.position, &i.phpdoc, i.is_native),
+            ClassType::Trait(t) => (&t.trait_name, &t.position, &t.phpdoc, t.is_native),
+            ClassType::None => continue,
+        };
+        let fq_name = class_name.get_fq_name();
+        if is_native
+            || is_internal(position)
+            || is_entry_point(phpdoc, &[], config)
+            || has_entry_point_suffix(class_name, config)
+            || symbol_data.usage.read_or_recover().is_class_used(fq_name)
+            || referenced.contains(&fq_name.to_ascii_lowercase())
+        {
+            continue;
+        }
+        emitter.emit(Issue::UnusedClass(position.clone().into(), fq_name.clone()));
+    }
+
+    let usage = symbol_data.usage.read_or_recover();
+    for function in &functions {
+        let function = function.read_or_recover();
+        if function.is_native
+            || is_internal(&function.position)
+            || is_entry_point(&function.phpdoc, &function.attributes, config)
+            || usage.is_function_used(&function.name)
+        {
+            continue;
+        }
+        emitter.emit(Issue::UnusedFunction(
+            function.position.clone().into(),
+            function.name.clone(),
+        ));
+    }
+}
+
+fn analyze_dead_class(
+    class_data: &ClassData,
+    referenced: &HashSet<FullyQualifiedName>,
+    symbol_data: &Arc<SymbolData>,
+    config: &DeadCodeConfig,
+    emitter: &dyn IssueEmitter,
+) {
+    let fq_name = class_data.class_name.get_fq_name();
+    let usage = symbol_data.usage.read_or_recover();
+
+    let class_is_entry_point = is_entry_point(&class_data.phpdoc, &class_data.attributes, config)
+        || has_entry_point_suffix(&class_data.class_name, config);
+    if !class_is_entry_point
+        && !usage.is_class_used(fq_name)
+        && !referenced.contains(&fq_name.to_ascii_lowercase())
+    {
+        emitter.emit(Issue::UnusedClass(
+            class_data.position.clone().into(),
+            fq_name.clone(),
+        ));
+    }
+
+    // A call to a method on a parent class, an interface or a trait might end up
+    // in this class
+    let related = get_related_class_names(class_data, symbol_data);
+
+    for method in class_data.methods.values() {
+        let method = method.read_or_recover();
+        if method.visibility == ClassMemberVisibility::Public
+            || matches!(method.modifier, ClassModifier::Abstract)
+            // Magic methods are called by PHP itself
+            || method.name.to_string().starts_with("__")
+            || is_entry_point(&method.phpdoc, &method.attributes, config)
+            || related
+                .iter()
+                .any(|cname| usage.is_method_used(cname, &method.name))
+        {
+            continue;
+        }
+        emitter.emit(Issue::UnusedMethod(
+            method.position.clone().into(),
+            fq_name.clone(),
+            method.name.clone(),
+        ));
+    }
+
+    for property in class_data.properties.values() {
+        let property = property.read_or_recover();
+        if property.visibility == ClassMemberVisibility::Public
+            || property.read_from > 0
+            || usage.is_untyped_property_used(&property.name)
+            || is_entry_point(&property.phpdoc, &property.attributes, config)
+        {
+            continue;
+        }
+        emitter.emit(Issue::UnusedProperty(
+            property.position.clone().into(),
+            fq_name.clone(),
+            property.name.clone(),
+        ));
+    }
+
+    for (constant_name, constant) in &class_data.constant_declarations {
+        if constant.visibility != ClassMemberVisibility::Private
+            || usage.is_constant_used(fq_name, constant_name)
+        {
+            continue;
+        }
+        emitter.emit(Issue::UnusedClassConstant(
+            constant.position.clone().into(),
+            fq_name.clone(),
+            constant_name.clone(),
+        ));
+    }
+}
+
+fn is_internal(position: &FileLocation) -> bool {
+    position.uri == FileLocation::internal().uri
+}
+
+fn has_entry_point_suffix(class_name: &ClassName, config: &DeadCodeConfig) -> bool {
+    let name = class_name.get_name().to_string();
+    config
+        .class_name_suffixes
+        .iter()
+        .any(|suffix| name.ends_with(suffix))
+}
+
+fn is_entry_point(
+    phpdoc: &Option<PHPDocComment>,
+    attributes: &[AttributeData],
+    config: &DeadCodeConfig,
+) -> bool {
+    let has_tag = phpdoc.as_ref().is_some_and(|doc| {
+        doc.entries.iter().any(|entry| match entry {
+            PHPDocEntry::General(_, tag) | PHPDocEntry::GeneralWithParam(_, tag, _) => {
+                config.phpdoc_tags.iter().any(|t| tag == *t)
+            }
+            _ => false,
+        })
+    });
+    has_tag
+        || config.attributes.iter().any(|fq_name| {
+            let fq_name = FullyQualifiedName::from(*fq_name);
+            attributes.iter().any(|a| a.is_attribute_class(&fq_name))
+        })
+}
+
+/// The class itself, its ancestors, the interfaces they implement and the traits they use
+fn get_related_class_names(
+    class_data: &ClassData,
+    symbol_data: &Arc<SymbolData>,
+) -> Vec<FullyQualifiedName> {
+    let mut seen = HashSet::new();
+    let mut queue: Vec<ClassName> = vec![class_data.class_name.clone()];
+    let mut related = vec![];
+    while let Some(cname) = queue.pop() {
+        if !seen.insert(cname.get_fq_name().to_ascii_lowercase()) {
+            continue;
+        }
+        related.push(cname.get_fq_name().clone());
+        let Some(handle) = symbol_data.get_class(&cname) else {
+            continue;
+        };
+        let class_type = handle.read_or_recover();
+        match &*class_type {
+            ClassType::Class(c) => {
+                queue.extend(c.base_class_name.iter().cloned());
+                queue.extend(c.interfaces.iter().cloned());
+                queue.extend(c.traits.imports.iter().map(|i| i.trait_name.clone()));
+            }
+            ClassType::Interface(i) => {
+                queue.extend(i.base_interface_names.iter().flatten().cloned());
+            }
+            ClassType::Trait(t) => {
+                queue.extend(t.traits.imports.iter().map(|i| i.trait_name.clone()));
+            }
+            ClassType::None => (),
+        }
+    }
+    related
+}
+
+fn collect_property_class_names(
+    properties: &HashMap<Name, Arc<RwLock<PropertyData>>>,
+    names: &RefCell<HashSet<FullyQualifiedName>>,
+) {
+    for property in properties.values() {
+        let property = property.read_or_recover();
+        collect_attribute_classes(&property.attributes, names);
+        if let Some(t) = &property.declared_type {
+            collect_class_names(t, names);
+        }
+        if let Some((t, _)) = &property.comment_type {
+            collect_class_names(t, names);
+        }
+    }
+}
+
+fn collect_method_class_names(
+    methods: &HashMap<Name, Arc<RwLock<MethodData>>>,
+    names: &RefCell<HashSet<FullyQualifiedName>>,
+) {
+    for method in methods.values() {
+        let method = method.read_or_recover();
+        collect_attribute_classes(&method.attributes, names);
+        for t in [&method.php_return_type, &method.inferred_return_type]
+            .into_iter()
+            .flatten()
+            .chain(method.comment_return_type.iter().map(|(t, _)| t))
+        {
+            collect_class_names(t, names);
+        }
+        for arg in &method.arguments {
+            for t in [&arg.arg_type, &arg.phpdoc_type].into_iter().flatten() {
+                collect_class_names(t, names);
+            }
+        }
+    }
+}
+
+fn collect_attribute_classes(
+    attributes: &[AttributeData],
+    names: &RefCell<HashSet<FullyQualifiedName>>,
+) {
+    let mut names = names.borrow_mut();
+    for attribute in attributes {
+        names.insert(attribute.class_name.get_fq_name().to_ascii_lowercase());
+    }
+}
+
+fn collect_class_names(ptype: &PHPType, names: &RefCell<HashSet<FullyQualifiedName>>) {
+    ptype.map(&|dtype| {
+        collect_discrete_class_names(&dtype, names);
+        dtype
+    });
+}
+
+fn collect_discrete_class_names(
+    dtype: &DiscreteType,
+    names: &RefCell<HashSet<FullyQualifiedName>>,
+) {
+    match dtype {
+        DiscreteType::Named(_, fq_name) | DiscreteType::ClassType(fq_name, _) => {
+            names.borrow_mut().insert(fq_name.to_ascii_lowercase());
+        }
+        DiscreteType::Special(SpecialType::ClassString(Some(fq_name))) => {
+            names.borrow_mut().insert(fq_name.to_ascii_lowercase());
+        }
+        DiscreteType::Generic(base, args) => {
+            collect_discrete_class_names(base, names);
+            for arg in args {
+                collect_class_names(arg, names);
+            }
+        }
+        DiscreteType::Vector(t) | DiscreteType::List(t) | DiscreteType::NonEmptyList(t) => {
+            collect_class_names(t, names)
+        }
+        DiscreteType::HashMap(k, v) | DiscreteType::NonEmptyArray(k, v) => {
+            collect_class_names(k, names);
+            collect_class_names(v, names);
+        }
+        DiscreteType::TypedCallable(args, ret) => {
+            for arg in args {
+                collect_class_names(arg, names);
+            }
+            collect_class_names(ret, names);
+        }
+        _ => (),
+    }
+}
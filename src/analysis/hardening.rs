@@ -1,19 +1,34 @@
-use std::sync::{Arc, RwLock};
+use crate::locks::RecoverableLock;
+use std::{
+    collections::HashMap,
+    os::unix::ffi::OsStrExt,
+    sync::{Arc, RwLock},
+};
 
 use crate::{
     autonodes::{
         _expression::_ExpressionNode,
         _primary_expression::_PrimaryExpressionNode,
+        argument::ArgumentChildren,
+        arguments::ArgumentsNode,
         assignment_expression::{AssignmentExpressionLeft, AssignmentExpressionNode},
-        binary_expression::{BinaryExpressionNode, BinaryExpressionOperator},
+        binary_expression::{
+            BinaryExpressionNode, BinaryExpressionOperator, BinaryExpressionRight,
+        },
+        function_call_expression::FunctionCallExpressionNode,
         member_access_expression::MemberAccessExpressionNode,
+        member_call_expression::MemberCallExpressionNode,
         parenthesized_expression::ParenthesizedExpressionNode,
+        scoped_call_expression::ScopedCallExpressionNode,
+        subscript_expression::SubscriptExpressionDereferenceable,
         unary_op_expression::{UnaryOpExpressionNode, UnaryOpExpressionOperator},
         variable_name::VariableNameNode,
     },
-    issue::VoidEmitter,
+    issue::{IssueEmitter, VoidEmitter},
     operators::binary::BinaryOperatorBranchTypeHardening,
-    types::union::{DiscretlyAccessedType, PHPType, UnionType},
+    symboldata::assertions::AssertionData,
+    symbols::{FullyQualifiedName, Name},
+    types::union::{DiscreteType, DiscretlyAccessedType, PHPType, ShapeTypeKey, UnionType},
 };
 
 use super::{
@@ -39,13 +54,12 @@ where
     // string, int, float and bool we can strip the nullable
     // and inversly, if we evaluate to false, then we're left only with null
     //
-    let new_scope = scope.branch();
     let emitter = VoidEmitter::new();
 
     let utype = if let Some(utype) = variable_node.get_utype(state, &emitter) {
         utype
     } else {
-        return new_scope;
+        return scope.branch();
     };
     let vtypes: Vec<PHPType> = utype
         .as_discrete_variants()
@@ -62,21 +76,499 @@ where
         new_type
     };
 
-    let var_name = variable_node.get_variable_name();
+    new_scope_with_variable_type(scope, variable_node, new_type)
+}
+
+/// A branch of the scope, where the variable is of the given type
+pub fn new_scope_with_variable_type(
+    scope: Arc<RwLock<Scope>>,
+    variable_node: &VariableNameNode,
+    new_type: PHPType,
+) -> Arc<RwLock<Scope>> {
+    let new_scope = scope.branch();
+    set_variable_type(&new_scope, variable_node.get_variable_name(), new_type);
+    new_scope
+}
 
-    let var_data = {
-        let mut writeable_scope = new_scope.write().unwrap();
-        writeable_scope.get_or_create_local_var(var_name)
+fn set_variable_type(scope: &Arc<RwLock<Scope>>, var_name: Name, new_type: PHPType) {
+    let (var_data, outer) = {
+        let mut writeable_scope = scope.write_or_recover();
+        let outer = writeable_scope.get_var(&var_name).map(|outer| {
+            let outer = outer.read_or_recover();
+            (outer.taint.clone(), outer.checked_keys.clone())
+        });
+        (writeable_scope.get_or_create_local_var(var_name), outer)
     };
 
-    {
-        let mut data = var_data.write().unwrap();
+    let mut data = var_data.write_or_recover();
+    if let Some((taint, checked_keys)) = outer {
+        data.taint = taint;
+        data.checked_keys = checked_keys;
+    }
+
+    // FIXME for starters we do it this way
+    data.all_written_data.push((new_type.clone(), None));
+    data.last_written_data = vec![(new_type, None)];
+}
+
+/// How a discrete type relates to a type check, like `is_string()` or `=== null`
+pub enum TypeMatch {
+    /// Always passes the check
+    Always,
+    /// Never passes the check
+    Never,
+    /// Might pass, and is then of the given type, like `mixed` passing `is_string()`
+    NarrowsTo(PHPType),
+    /// Might pass, without it telling us anything more about the type, like templates
+    Maybe,
+}
+
+pub type TypeCheck = fn(&DiscreteType) -> TypeMatch;
 
-        // FIXME for starters we do it this way
-        data.all_written_data.push((new_type.clone(), None));
-        data.last_written_data = vec![(new_type, None)];
+/// The type after the check has passed or failed, depending on `branch_side`
+pub fn narrow_type_by_check(utype: &PHPType, check: TypeCheck, branch_side: BranchSide) -> PHPType {
+    let mut narrowed = UnionType::new();
+    for variant in utype.as_discrete_variants() {
+        let DiscretlyAccessedType::Discrete(dtype) = &variant else {
+            crate::missing!("Intersection in type check narrowing");
+            narrowed.append(&variant);
+            continue;
+        };
+        match (check(dtype), branch_side) {
+            (TypeMatch::NarrowsTo(ptype), BranchSide::TrueBranch) => narrowed.append(ptype),
+            (TypeMatch::Always, BranchSide::FalseBranch)
+            | (TypeMatch::Never, BranchSide::TrueBranch) => (),
+            _ => narrowed.append(dtype.clone()),
+        }
     }
+    narrowed.into()
+}
+
+/// A branch of the scope where the variable is narrowed by the check
+pub fn new_scope_with_type_check(
+    scope: Arc<RwLock<Scope>>,
+    variable_node: &VariableNameNode,
+    state: &mut AnalysisState,
+    check: TypeCheck,
+    branch_side: BranchSide,
+) -> Arc<RwLock<Scope>> {
+    new_scope_with_type_checks(scope, &[(variable_node, check)], state, branch_side)
+}
 
+/// A branch of the scope where each of the variables are narrowed by their check
+pub fn new_scope_with_type_checks(
+    scope: Arc<RwLock<Scope>>,
+    checks: &[(&VariableNameNode, TypeCheck)],
+    state: &mut AnalysisState,
+    branch_side: BranchSide,
+) -> Arc<RwLock<Scope>> {
+    let new_scope = scope.branch();
+    for (variable_node, check) in checks {
+        if let Some(utype) = variable_node.get_utype(state, &VoidEmitter::new()) {
+            let new_type = narrow_type_by_check(&utype, *check, branch_side);
+            set_variable_type(&new_scope, variable_node.get_variable_name(), new_type);
+        }
+    }
+    new_scope
+}
+
+/// Types which we know nothing more about, and which can be anything
+fn is_unconstrained(dtype: &DiscreteType) -> bool {
+    matches!(dtype, DiscreteType::Mixed | DiscreteType::Unknown)
+}
+
+/// Types which are resolved later, and might be anything
+fn is_unresolved(dtype: &DiscreteType) -> bool {
+    matches!(
+        dtype,
+        DiscreteType::Template(_) | DiscreteType::Operator(_) | DiscreteType::Special(_)
+    )
+}
+
+pub fn check_null(dtype: &DiscreteType) -> TypeMatch {
+    match dtype {
+        DiscreteType::NULL | DiscreteType::Void => TypeMatch::Always,
+        d if is_unconstrained(d) => TypeMatch::NarrowsTo(DiscreteType::NULL.into()),
+        d if is_unresolved(d) => TypeMatch::Maybe,
+        _ => TypeMatch::Never,
+    }
+}
+
+pub fn check_bool(dtype: &DiscreteType) -> TypeMatch {
+    match dtype {
+        DiscreteType::Bool | DiscreteType::True | DiscreteType::False => TypeMatch::Always,
+        d if is_unconstrained(d) || matches!(d, DiscreteType::Scalar) => {
+            TypeMatch::NarrowsTo(DiscreteType::Bool.into())
+        }
+        d if is_unresolved(d) => TypeMatch::Maybe,
+        _ => TypeMatch::Never,
+    }
+}
+
+pub fn check_int(dtype: &DiscreteType) -> TypeMatch {
+    match dtype {
+        DiscreteType::Int | DiscreteType::IntRange(_, _) | DiscreteType::IntLiteral(_) => {
+            TypeMatch::Always
+        }
+        d if is_unconstrained(d)
+            || matches!(
+                d,
+                DiscreteType::Scalar | DiscreteType::ArrayKey | DiscreteType::Numeric
+            ) =>
+        {
+            TypeMatch::NarrowsTo(DiscreteType::Int.into())
+        }
+        d if is_unresolved(d) => TypeMatch::Maybe,
+        _ => TypeMatch::Never,
+    }
+}
+
+pub fn check_float(dtype: &DiscreteType) -> TypeMatch {
+    match dtype {
+        DiscreteType::Float => TypeMatch::Always,
+        d if is_unconstrained(d) || matches!(d, DiscreteType::Scalar | DiscreteType::Numeric) => {
+            TypeMatch::NarrowsTo(DiscreteType::Float.into())
+        }
+        d if is_unresolved(d) => TypeMatch::Maybe,
+        _ => TypeMatch::Never,
+    }
+}
+
+pub fn check_string(dtype: &DiscreteType) -> TypeMatch {
+    match dtype {
+        DiscreteType::String | DiscreteType::StringLiteral(_) => TypeMatch::Always,
+        d if is_unconstrained(d)
+            || matches!(
+                d,
+                DiscreteType::Scalar | DiscreteType::ArrayKey | DiscreteType::Numeric
+            ) =>
+        {
+            TypeMatch::NarrowsTo(DiscreteType::String.into())
+        }
+        // Function names are callable
+        d if is_unresolved(d) || matches!(d, DiscreteType::Callable) => TypeMatch::Maybe,
+        _ => TypeMatch::Never,
+    }
+}
+
+pub fn check_array(dtype: &DiscreteType) -> TypeMatch {
+    match dtype {
+        DiscreteType::Array
+        | DiscreteType::Vector(_)
+        | DiscreteType::HashMap(_, _)
+        | DiscreteType::Shape(_)
+        | DiscreteType::List(_)
+        | DiscreteType::NonEmptyList(_)
+        | DiscreteType::NonEmptyArray(_, _) => TypeMatch::Always,
+        d if is_unconstrained(d) || matches!(d, DiscreteType::Iterable) => {
+            TypeMatch::NarrowsTo(DiscreteType::Array.into())
+        }
+        d if is_unresolved(d) || matches!(d, DiscreteType::Callable) => TypeMatch::Maybe,
+        _ => TypeMatch::Never,
+    }
+}
+
+pub fn check_object(dtype: &DiscreteType) -> TypeMatch {
+    match dtype {
+        DiscreteType::Object
+        | DiscreteType::Named(_, _)
+        | DiscreteType::ClassType(_, _)
+        | DiscreteType::Generic(_, _) => TypeMatch::Always,
+        d if is_unconstrained(d) => TypeMatch::NarrowsTo(DiscreteType::Object.into()),
+        d if is_unresolved(d) || matches!(d, DiscreteType::Iterable | DiscreteType::Callable) => {
+            TypeMatch::Maybe
+        }
+        _ => TypeMatch::Never,
+    }
+}
+
+pub fn check_callable(dtype: &DiscreteType) -> TypeMatch {
+    match dtype {
+        DiscreteType::Callable | DiscreteType::TypedCallable(_, _) => TypeMatch::Always,
+        d if is_unconstrained(d) => TypeMatch::NarrowsTo(DiscreteType::Callable.into()),
+        // Function names, `[$object, 'method']` and invokable objects
+        d if matches!(check_string(d), TypeMatch::Always)
+            || matches!(check_array(d), TypeMatch::Always)
+            || matches!(check_object(d), TypeMatch::Always)
+            || is_unresolved(d) =>
+        {
+            TypeMatch::Maybe
+        }
+        _ => TypeMatch::Never,
+    }
+}
+
+pub fn check_resource(dtype: &DiscreteType) -> TypeMatch {
+    match dtype {
+        DiscreteType::Resource => TypeMatch::Always,
+        d if is_unconstrained(d) => TypeMatch::NarrowsTo(DiscreteType::Resource.into()),
+        d if is_unresolved(d) => TypeMatch::Maybe,
+        _ => TypeMatch::Never,
+    }
+}
+
+/// The check done by native functions like `is_string()`
+pub fn get_type_check_function(fq_name: &FullyQualifiedName) -> Option<TypeCheck> {
+    let lc_name = fq_name.to_ascii_lowercase().to_os_string();
+    let check: TypeCheck = match lc_name.as_bytes() {
+        b"\\is_null" => check_null,
+        b"\\is_bool" => check_bool,
+        b"\\is_int" | b"\\is_integer" | b"\\is_long" => check_int,
+        b"\\is_float" | b"\\is_double" => check_float,
+        b"\\is_string" => check_string,
+        b"\\is_array" => check_array,
+        b"\\is_object" => check_object,
+        b"\\is_callable" => check_callable,
+        b"\\is_resource" => check_resource,
+        _ => return None,
+    };
+    Some(check)
+}
+
+/// The function returning the name of the type of a value
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TypeNameFunction {
+    /// `gettype()`, with names like `integer`, `double` and `NULL`
+    GetType,
+    /// `get_debug_type()`, with names like `int`, `float` and `null`, and the class name of
+    /// objects
+    GetDebugType,
+}
+
+/// The check corresponding to a type name returned by `function`
+pub fn get_type_name_check(function: TypeNameFunction, type_name: &[u8]) -> Option<TypeCheck> {
+    let check: TypeCheck = match function {
+        TypeNameFunction::GetType => match type_name {
+            b"NULL" => check_null,
+            b"boolean" => check_bool,
+            b"integer" => check_int,
+            b"double" => check_float,
+            b"string" => check_string,
+            b"array" => check_array,
+            b"object" => check_object,
+            b"resource" => check_resource,
+            _ => return None,
+        },
+        TypeNameFunction::GetDebugType => match type_name {
+            b"null" => check_null,
+            b"bool" => check_bool,
+            b"int" => check_int,
+            b"float" => check_float,
+            b"string" => check_string,
+            b"array" => check_array,
+            _ => return None,
+        },
+    };
+    Some(check)
+}
+
+/// The variable, if the expression is just a variable
+pub fn get_variable_node(expression: &_ExpressionNode) -> Option<&VariableNameNode> {
+    match expression {
+        _ExpressionNode::_PrimaryExpression(p) => match &**p {
+            _PrimaryExpressionNode::VariableName(v) => Some(v),
+            _PrimaryExpressionNode::ParenthesizedExpression(p) => get_variable_node(&p.child),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// The variable passed as argument `idx` to the call
+pub fn get_variable_argument(
+    call: &FunctionCallExpressionNode,
+    idx: usize,
+) -> Option<&VariableNameNode> {
+    let argument = call.arguments.get_argument_nodes().nth(idx)?;
+    if argument.name.is_some() {
+        return None;
+    }
+    match &*argument.child {
+        ArgumentChildren::_Expression(e) => get_variable_node(e),
+        _ => None,
+    }
+}
+
+/// The variable and literal key subscripted in argument `idx` to the call, like `isset($row['id'])`
+fn get_subscript_argument<'a>(
+    call: &'a FunctionCallExpressionNode,
+    idx: usize,
+    state: &mut AnalysisState,
+) -> Option<(&'a VariableNameNode, ShapeTypeKey)> {
+    let argument = call.arguments.get_argument_nodes().nth(idx)?;
+    let ArgumentChildren::_Expression(expression) = &*argument.child else {
+        return None;
+    };
+    let _ExpressionNode::_PrimaryExpression(p) = &**expression else {
+        return None;
+    };
+    let _PrimaryExpressionNode::SubscriptExpression(subscript) = &**p else {
+        return None;
+    };
+    let SubscriptExpressionDereferenceable::VariableName(variable) = &*subscript.dereferenceable
+    else {
+        return None;
+    };
+    let key = subscript.get_shape_key(state, &VoidEmitter::new())?;
+    Some((variable, key))
+}
+
+/// Marks `key` as set in the array of the variable, in the branch `scope`
+fn set_checked_key(
+    scope: &Arc<RwLock<Scope>>,
+    variable_node: &VariableNameNode,
+    key: ShapeTypeKey,
+    state: &mut AnalysisState,
+) {
+    let var_name = variable_node.get_variable_name();
+    if !scope.read_or_recover().vars.contains_key(&var_name) {
+        let Some(utype) = variable_node.get_utype(state, &VoidEmitter::new()) else {
+            return;
+        };
+        set_variable_type(scope, var_name.clone(), utype);
+    }
+    let var_data = scope.write_or_recover().get_or_create_local_var(var_name);
+    var_data.write_or_recover().checked_keys.push(key);
+}
+
+/// The variable passed to the parameter of the assertion
+fn get_asserted_variable<'a>(
+    arguments: &'a ArgumentsNode,
+    assertion: &AssertionData,
+) -> Option<&'a VariableNameNode> {
+    let mut position = 0;
+    for argument in arguments.get_argument_nodes() {
+        let is_asserted = if let Some(name) = &argument.name {
+            name.get_name() == assertion.parameter
+        } else {
+            position += 1;
+            position - 1 == assertion.argument
+        };
+        if is_asserted {
+            return match &*argument.child {
+                ArgumentChildren::_Expression(e) => get_variable_node(e),
+                _ => None,
+            };
+        }
+    }
+    None
+}
+
+///
+/// A branch where the variables passed to a call are narrowed by the assertions of the
+/// function or method which hold when the call evaluated to `branch_side`. With `None`, the
+/// call returned, without us knowing what it evaluated to.
+///
+pub fn new_scope_with_assertions(
+    scope: Arc<RwLock<Scope>>,
+    assertions: &[AssertionData],
+    arguments: &ArgumentsNode,
+    branch_side: Option<BranchSide>,
+    state: &mut AnalysisState,
+) -> Arc<RwLock<Scope>> {
+    let new_scope = scope.branch();
+    // Several assertions might apply to the same variable
+    let mut narrowed: HashMap<Name, PHPType> = HashMap::new();
+    for assertion in assertions.iter().filter(|a| a.holds_when(branch_side)) {
+        let Some(variable) = get_asserted_variable(arguments, assertion) else {
+            continue;
+        };
+        let var_name = variable.get_variable_name();
+        let utype = match narrowed.get(&var_name) {
+            Some(utype) => Some(utype.clone()),
+            None => variable.get_utype(state, &VoidEmitter::new()),
+        };
+        if let Some(new_type) = assertion.narrow_type(utype, &state.symbol_data) {
+            narrowed.insert(var_name, new_type);
+        }
+    }
+    for (var_name, new_type) in narrowed {
+        set_variable_type(&new_scope, var_name, new_type);
+    }
+    new_scope
+}
+
+/// Narrows the variables in the scope itself by the assertions which hold once the call returned
+pub fn harden_scope_with_assertions(
+    assertions: &[AssertionData],
+    arguments: &ArgumentsNode,
+    state: &mut AnalysisState,
+    emitter: &dyn IssueEmitter,
+) {
+    if !assertions.iter().any(|a| a.holds_when(None)) {
+        return;
+    }
+    let scope = state.current_scope();
+    let branch = new_scope_with_assertions(scope.clone(), assertions, arguments, None, state);
+    scope.join(vec![branch], emitter);
+}
+
+/// Narrows the types in the scope itself, for code which is only reached when the condition
+/// evaluated to `branch_side`, like the code following `assert($x instanceof Foo)`
+pub fn harden_scope_based_on_conditional_node<N: BranchTypeHardening + ?Sized>(
+    node: &N,
+    scope: Arc<RwLock<Scope>>,
+    branch_side: BranchSide,
+    state: &mut AnalysisState,
+    emitter: &dyn IssueEmitter,
+) {
+    let branch =
+        node.branch_with_hardened_types_base_on_conditional_node(scope.clone(), branch_side, state);
+    scope.join(vec![branch], emitter);
+}
+
+/// A branch where the first condition evaluated to `first_side`, and the second one, which is
+/// evaluated in the narrowed scope of the first, evaluated to `second_side`
+fn new_scope_with_both_conditions(
+    scope: Arc<RwLock<Scope>>,
+    first: &_ExpressionNode,
+    first_side: BranchSide,
+    second: &BinaryExpressionRight,
+    second_side: BranchSide,
+    state: &mut AnalysisState,
+) -> Arc<RwLock<Scope>> {
+    let first_scope =
+        first.branch_with_hardened_types_base_on_conditional_node(scope, first_side, state);
+    state.push_scope(first_scope.clone());
+    let second_scope = second.branch_with_hardened_types_base_on_conditional_node(
+        first_scope.clone(),
+        second_side,
+        state,
+    );
+    state.pop_scope();
+    second_scope
+        .write_or_recover()
+        .inherit_vars(&first_scope.read_or_recover());
+    second_scope
+}
+
+/// A branch where we don't know which of the two branches were taken. Only variables narrowed in
+/// both of them are narrowed, to the union of their types
+fn new_scope_with_either_branch(
+    scope: Arc<RwLock<Scope>>,
+    first: Arc<RwLock<Scope>>,
+    second: Arc<RwLock<Scope>>,
+) -> Arc<RwLock<Scope>> {
+    let new_scope = scope.branch();
+    let first = first.read_or_recover();
+    let second = second.read_or_recover();
+    for (var_name, first_data) in &first.vars {
+        let Some(second_data) = second.vars.get(var_name) else {
+            continue;
+        };
+        let first_data = first_data.read_or_recover();
+        let second_data = second_data.read_or_recover();
+        if first_data.last_written_data.is_empty() || second_data.last_written_data.is_empty() {
+            continue;
+        }
+        let types: Vec<PHPType> = first_data
+            .last_written_data
+            .iter()
+            .chain(second_data.last_written_data.iter())
+            .map(|(ptype, _)| ptype.clone())
+            .collect();
+        set_variable_type(&new_scope, var_name.clone(), UnionType::from(types).into());
+    }
     new_scope
 }
 
@@ -172,7 +664,13 @@ impl BranchTypeHardening for _PrimaryExpressionNode {
             _PrimaryExpressionNode::CastExpression(_) => crate::missing!(),
             _PrimaryExpressionNode::ClassConstantAccessExpression(_) => crate::missing!(),
             _PrimaryExpressionNode::DynamicVariableName(_) => crate::missing!(),
-            _PrimaryExpressionNode::FunctionCallExpression(_) => return scope.branch(),
+            _PrimaryExpressionNode::FunctionCallExpression(f) => {
+                return f.branch_with_hardened_types_base_on_conditional_node(
+                    scope,
+                    branch_side,
+                    state,
+                )
+            }
             _PrimaryExpressionNode::MemberAccessExpression(_) => {
                 crate::missing!();
                 /*                 return m.branch_with_hardened_types_base_on_conditional_node(
@@ -181,7 +679,13 @@ impl BranchTypeHardening for _PrimaryExpressionNode {
                     state,
                 )*/
             }
-            _PrimaryExpressionNode::MemberCallExpression(_) => crate::missing!(),
+            _PrimaryExpressionNode::MemberCallExpression(m) => {
+                return m.branch_with_hardened_types_base_on_conditional_node(
+                    scope,
+                    branch_side,
+                    state,
+                )
+            }
             _PrimaryExpressionNode::Name(_) => crate::missing!(),
             _PrimaryExpressionNode::NullsafeMemberAccessExpression(_) => crate::missing!(),
             _PrimaryExpressionNode::NullsafeMemberCallExpression(_) => crate::missing!(),
@@ -195,7 +699,13 @@ impl BranchTypeHardening for _PrimaryExpressionNode {
             }
             _PrimaryExpressionNode::PrintIntrinsic(_) => return scope.branch(),
             _PrimaryExpressionNode::QualifiedName(_) => return scope.branch(),
-            _PrimaryExpressionNode::ScopedCallExpression(_) => crate::missing!(),
+            _PrimaryExpressionNode::ScopedCallExpression(s) => {
+                return s.branch_with_hardened_types_base_on_conditional_node(
+                    scope,
+                    branch_side,
+                    state,
+                )
+            }
             _PrimaryExpressionNode::ScopedPropertyAccessExpression(_) => crate::missing!(),
             _PrimaryExpressionNode::ShellCommandExpression(_) => return scope.branch(),
             _PrimaryExpressionNode::SubscriptExpression(_) => crate::missing!(),
@@ -226,15 +736,30 @@ impl BranchTypeHardening for BinaryExpressionNode {
             BinaryExpressionOperator::NotEqual(_) => {
                 crate::missing!("BinaryExpressionOperator::NotEqual")
             }
-            BinaryExpressionOperator::NotIdentical(_) => {
-                crate::missing!("BinaryExpressionOperator::NotIdentical")
+            BinaryExpressionOperator::NotIdentical(not_identical_operator) => {
+                if let Some(x) = not_identical_operator
+                    .branch_with_hardened_types_base_on_conditional_node(
+                        &self.left,
+                        &self.right,
+                        scope.clone(),
+                        branch_side,
+                        state,
+                    )
+                {
+                    return x;
+                }
             }
             BinaryExpressionOperator::Mod(_) => crate::missing!("BinaryExpressionOperator::Mod"),
             BinaryExpressionOperator::BinaryAnd(_) => {
                 crate::missing!("BinaryExpressionOperator::BinaryAnd")
             }
-            BinaryExpressionOperator::BooleanAnd(_) => {
-                crate::missing!("BinaryExpressionOperator::BooleanAnd")
+            BinaryExpressionOperator::BooleanAnd(_) | BinaryExpressionOperator::LogicalAnd(_) => {
+                return self.branch_on_both_operands(
+                    scope,
+                    BranchSide::TrueBranch,
+                    branch_side,
+                    state,
+                );
             }
             BinaryExpressionOperator::Mult(_) => {
                 crate::missing!("BinaryExpressionOperator::Mult")
@@ -260,8 +785,18 @@ impl BranchTypeHardening for BinaryExpressionNode {
             BinaryExpressionOperator::Equal(_) => {
                 crate::missing!("BinaryExpressionOperator::Equal")
             }
-            BinaryExpressionOperator::Identical(_) => {
-                crate::missing!("BinaryExpressionOperator::Identical")
+            BinaryExpressionOperator::Identical(identical_operator) => {
+                if let Some(x) = identical_operator
+                    .branch_with_hardened_types_base_on_conditional_node(
+                        &self.left,
+                        &self.right,
+                        scope.clone(),
+                        branch_side,
+                        state,
+                    )
+                {
+                    return x;
+                }
             }
             BinaryExpressionOperator::GreaterThan(_) => {
                 crate::missing!("BinaryExpressionOperator::GreaterThan")
@@ -275,9 +810,6 @@ impl BranchTypeHardening for BinaryExpressionNode {
             BinaryExpressionOperator::BinaryXor(_) => {
                 crate::missing!("BinaryExpressionOperator::BinaryXor")
             }
-            BinaryExpressionOperator::LogicalAnd(_) => {
-                crate::missing!("BinaryExpressionOperator::LogicalAnd")
-            }
             BinaryExpressionOperator::Instanceof(instanceof_operator) => {
                 if let Some(x) = instanceof_operator
                     .branch_with_hardened_types_base_on_conditional_node(
@@ -291,17 +823,19 @@ impl BranchTypeHardening for BinaryExpressionNode {
                     return x;
                 }
             }
-            BinaryExpressionOperator::LogicalOr(_) => {
-                crate::missing!("BinaryExpressionOperator::LogicalOr")
-            }
             BinaryExpressionOperator::LogicalXor(_) => {
                 crate::missing!("BinaryExpressionOperator::LogicalXor")
             }
             BinaryExpressionOperator::BinaryOr(_) => {
                 crate::missing!("BinaryExpressionOperator::BinaryOr")
             }
-            BinaryExpressionOperator::BooleanOr(_) => {
-                crate::missing!("BinaryExpressionOperator::BooleanOr")
+            BinaryExpressionOperator::BooleanOr(_) | BinaryExpressionOperator::LogicalOr(_) => {
+                return self.branch_on_both_operands(
+                    scope,
+                    BranchSide::FalseBranch,
+                    branch_side,
+                    state,
+                );
             }
 
             BinaryExpressionOperator::NullCoalesce(_) => {
@@ -318,6 +852,58 @@ impl BranchTypeHardening for BinaryExpressionNode {
     }
 }
 
+impl BinaryExpressionNode {
+    /// Narrowing by `&&` and `||`, where the right operand only is evaluated when the left one
+    /// evaluates to `right_evaluated_on`; `true` for `&&` and `false` for `||`
+    fn branch_on_both_operands(
+        &self,
+        scope: Arc<RwLock<Scope>>,
+        right_evaluated_on: BranchSide,
+        branch_side: BranchSide,
+        state: &mut AnalysisState,
+    ) -> Arc<RwLock<Scope>> {
+        let both_evaluated = new_scope_with_both_conditions(
+            scope.clone(),
+            &self.left,
+            right_evaluated_on,
+            &self.right,
+            branch_side,
+            state,
+        );
+        if branch_side == right_evaluated_on {
+            // Both operands had to evaluate this way
+            return both_evaluated;
+        }
+        // Either the left operand decided it, or the right one did
+        let left_decided = self
+            .left
+            .branch_with_hardened_types_base_on_conditional_node(scope.clone(), branch_side, state);
+        new_scope_with_either_branch(scope, left_decided, both_evaluated)
+    }
+}
+
+impl BranchTypeHardening for BinaryExpressionRight {
+    fn branch_with_hardened_types_base_on_conditional_node(
+        &self,
+        scope: Arc<RwLock<Scope>>,
+        branch_side: BranchSide,
+        state: &mut AnalysisState,
+    ) -> Arc<RwLock<Scope>> {
+        match self {
+            BinaryExpressionRight::_Expression(e) => {
+                e.branch_with_hardened_types_base_on_conditional_node(scope, branch_side, state)
+            }
+            BinaryExpressionRight::VariableName(v) => {
+                v.branch_with_hardened_types_base_on_conditional_node(scope, branch_side, state)
+            }
+            BinaryExpressionRight::MemberAccessExpression(m) => {
+                m.branch_with_hardened_types_base_on_conditional_node(scope, branch_side, state)
+            }
+            _ => scope.branch(),
+        }
+    }
+}
+
 impl BranchTypeHardening for UnaryOpExpressionNode {
     fn branch_with_hardened_types_base_on_conditional_node(
         &self,
@@ -327,9 +913,10 @@ impl BranchTypeHardening for UnaryOpExpressionNode {
     ) -> Arc<RwLock<Scope>> {
         match &*self.operator {
             UnaryOpExpressionOperator::Not(_) => {
-                self.argument
+                return self
+                    .argument
                     .branch_with_hardened_types_base_on_conditional_node(
-                        scope.clone(),
+                        scope,
                         branch_side.inverse(),
                         state,
                     );
@@ -345,6 +932,134 @@ impl BranchTypeHardening for UnaryOpExpressionNode {
     }
 }
 
+impl BranchTypeHardening for FunctionCallExpressionNode {
+    fn branch_with_hardened_types_base_on_conditional_node(
+        &self,
+        scope: Arc<RwLock<Scope>>,
+        branch_side: BranchSide,
+        state: &mut AnalysisState,
+    ) -> Arc<RwLock<Scope>> {
+        let Some(fq_name) = self.get_fq_function_name(state, &VoidEmitter::new()) else {
+            return scope.branch();
+        };
+        if let Some(check) = get_type_check_function(&fq_name) {
+            if let Some(variable) = get_variable_argument(self, 0) {
+                return new_scope_with_type_check(scope, variable, state, check, branch_side);
+            }
+            return scope.branch();
+        }
+        match fq_name.to_ascii_lowercase().to_os_string().as_bytes() {
+            b"\\isset" => {
+                let argument_count = self.arguments.get_argument_nodes().count();
+                match branch_side {
+                    // Every argument is non-null, and every key is set
+                    BranchSide::TrueBranch => {
+                        let checks: Vec<_> = (0..argument_count)
+                            .filter_map(|idx| get_variable_argument(self, idx))
+                            .map(|variable| (variable, check_null as TypeCheck))
+                            .collect();
+                        let new_scope = new_scope_with_type_checks(
+                            scope,
+                            &checks,
+                            state,
+                            BranchSide::FalseBranch,
+                        );
+                        for idx in 0..argument_count {
+                            if let Some((variable, key)) = get_subscript_argument(self, idx, state)
+                            {
+                                set_checked_key(&new_scope, variable, key, state);
+                            }
+                        }
+                        return new_scope;
+                    }
+                    // With a single argument, we know it's null
+                    BranchSide::FalseBranch if argument_count == 1 => {
+                        if let Some(variable) = get_variable_argument(self, 0) {
+                            return new_scope_with_type_check(
+                                scope,
+                                variable,
+                                state,
+                                check_null,
+                                BranchSide::TrueBranch,
+                            );
+                        }
+                    }
+                    BranchSide::FalseBranch => (),
+                }
+            }
+            // `empty($x)` is the same as `!$x`, except for not warning on undefined variables
+            b"\\empty" => {
+                if let Some(variable) = get_variable_argument(self, 0) {
+                    return variable.branch_with_hardened_types_base_on_conditional_node(
+                        scope,
+                        branch_side.inverse(),
+                        state,
+                    );
+                }
+            }
+            // The array argument has to be an array for the key to exist
+            b"\\array_key_exists" | b"\\key_exists" => {
+                if let (BranchSide::TrueBranch, Some(variable)) =
+                    (branch_side, get_variable_argument(self, 1))
+                {
+                    return new_scope_with_type_check(
+                        scope,
+                        variable,
+                        state,
+                        check_array,
+                        BranchSide::TrueBranch,
+                    );
+                }
+            }
+            _ => (),
+        }
+        let assertions = self.get_assertions(state, &VoidEmitter::new());
+        new_scope_with_assertions(
+            scope,
+            &assertions,
+            &self.arguments,
+            Some(branch_side),
+            state,
+        )
+    }
+}
+
+impl BranchTypeHardening for MemberCallExpressionNode {
+    fn branch_with_hardened_types_base_on_conditional_node(
+        &self,
+        scope: Arc<RwLock<Scope>>,
+        branch_side: BranchSide,
+        state: &mut AnalysisState,
+    ) -> Arc<RwLock<Scope>> {
+        let assertions = self.get_assertions(state, &VoidEmitter::new());
+        new_scope_with_assertions(
+            scope,
+            &assertions,
+            &self.arguments,
+            Some(branch_side),
+            state,
+        )
+    }
+}
+
+impl BranchTypeHardening for ScopedCallExpressionNode {
+    fn branch_with_hardened_types_base_on_conditional_node(
+        &self,
+        scope: Arc<RwLock<Scope>>,
+        branch_side: BranchSide,
+        state: &mut AnalysisState,
+    ) -> Arc<RwLock<Scope>> {
+        let assertions = self.get_assertions(state, &VoidEmitter::new());
+        new_scope_with_assertions(
+            scope,
+            &assertions,
+            &self.arguments,
+            Some(branch_side),
+            state,
+        )
+    }
+}
+
 impl BranchTypeHardening for VariableNameNode {
     fn branch_with_hardened_types_base_on_conditional_node(
         &self,
@@ -1,5 +1,10 @@
 pub mod analyzer;
 pub mod data;
+pub mod dead_code;
 pub mod hardening;
+pub mod project;
 pub mod scope;
+pub mod security;
 pub mod state;
+pub mod taint;
+pub mod termination;
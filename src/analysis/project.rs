@@ -0,0 +1,666 @@
+//!
+//! Loading of composer-projects. The autoload-rules in `composer.json` decide which files
+//! belong to the project, and are analyzed fully, while the installed packages listed in
+//! `vendor/composer/installed.json` are only indexed for their declarations.
+//!
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::OsString,
+    io::{Error, ErrorKind},
+    os::unix::ffi::OsStrExt,
+    path::{Component, Path, PathBuf},
+    sync::{Arc, OnceLock},
+};
+
+use regex::bytes::Regex;
+use serde_json::Value;
+
+use crate::{
+    config::PHPAnalyzeConfig,
+    issue::{Issue, IssueEmitter, VoidEmitter},
+    locks::RecoverableLock,
+    symboldata::{class::ClassType, ClassResolver, FileLocation, SymbolData},
+    symbols::FullyQualifiedName,
+};
+
+use super::{
+    analyzer::Analyzer, dead_code::analyze_dead_code, state::AnalysisState,
+    taint::TaintRoundEmitter,
+};
+
+/// How many times the third pass is run, at most, while the input returned from functions
+/// changes
+pub(crate) const MAX_THIRD_PASS_ROUNDS: usize = 5;
+
+/// The autoload-section of a package, with the paths resolved relative to the package
+#[derive(Clone, Debug, Default)]
+pub struct AutoloadRules {
+    /// Namespace prefixes, like `App\`, and the directories they map to
+    pub psr4: Vec<(String, Vec<PathBuf>)>,
+    /// Namespace- or class-prefixes, and the directories they map to
+    pub psr0: Vec<(String, Vec<PathBuf>)>,
+    /// Directories and files which are scanned for classes
+    pub classmap: Vec<PathBuf>,
+    /// Files which are always loaded
+    pub files: Vec<PathBuf>,
+}
+
+impl AutoloadRules {
+    fn parse(autoload: Option<&Value>, base: &Path) -> Self {
+        let Some(autoload) = autoload else {
+            return Self::default();
+        };
+        Self {
+            psr4: parse_prefixes(autoload.get("psr-4"), base),
+            psr0: parse_prefixes(autoload.get("psr-0"), base),
+            classmap: parse_paths(autoload.get("classmap"), base),
+            files: parse_paths(autoload.get("files"), base),
+        }
+    }
+
+    fn extend(&mut self, other: AutoloadRules) {
+        self.psr4.extend(other.psr4);
+        self.psr0.extend(other.psr0);
+        self.classmap.extend(other.classmap);
+        self.files.extend(other.files);
+        sort_prefixes(&mut self.psr4);
+        sort_prefixes(&mut self.psr0);
+    }
+
+    /// Every directory and file the rules cover
+    fn get_roots(&self) -> impl Iterator<Item = &PathBuf> {
+        self.psr4
+            .iter()
+            .chain(&self.psr0)
+            .flat_map(|(_, dirs)| dirs)
+            .chain(&self.classmap)
+            .chain(&self.files)
+    }
+
+    /// The files which by PSR-4 or PSR-0 would declare the class
+    pub fn get_candidate_files(&self, fq_name: &FullyQualifiedName) -> Vec<PathBuf> {
+        let class_name = class_name_string(fq_name);
+        let mut candidates = vec![];
+        for (prefix, dirs) in &self.psr4 {
+            let Some(relative) = strip_namespace_prefix(&class_name, prefix) else {
+                continue;
+            };
+            let relative = psr4_relative_path(relative);
+            candidates.extend(dirs.iter().map(|dir| dir.join(&relative)));
+        }
+        for (prefix, dirs) in &self.psr0 {
+            if strip_namespace_prefix(&class_name, prefix).is_none() {
+                continue;
+            }
+            // Underscores in the class name, but not in the namespace, are directory separators
+            let (namespace, name) = match class_name.rfind('\\') {
+                Some(idx) => class_name.split_at(idx + 1),
+                None => ("", class_name.as_str()),
+            };
+            let relative = format!(
+                "{}{}.php",
+                namespace.replace('\\', "/"),
+                name.replace('_', "/")
+            );
+            candidates.extend(dirs.iter().map(|dir| dir.join(&relative)));
+        }
+        candidates
+    }
+}
+
+/// Strips `prefix` from `class_name`, ignoring case as PHP does for namespaces
+fn strip_namespace_prefix<'a>(class_name: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = class_name.get(..prefix.len())?;
+    if head.eq_ignore_ascii_case(prefix) {
+        Some(&class_name[prefix.len()..])
+    } else {
+        None
+    }
+}
+
+/// The path of a class, relative to the directory of its PSR-4 prefix
+fn psr4_relative_path(relative_class_name: &str) -> String {
+    format!("{}.php", relative_class_name.replace('\\', "/"))
+}
+
+/// The class name without a leading backslash, like `App\Models\User`
+fn class_name_string(fq_name: &FullyQualifiedName) -> String {
+    let name = fq_name.to_os_string();
+    let name = String::from_utf8_lossy(name.as_bytes());
+    name.trim_start_matches('\\').to_string()
+}
+
+fn parse_prefixes(rules: Option<&Value>, base: &Path) -> Vec<(String, Vec<PathBuf>)> {
+    let Some(Value::Object(rules)) = rules else {
+        return vec![];
+    };
+    let mut prefixes: Vec<_> = rules
+        .iter()
+        .map(|(prefix, dirs)| (prefix.clone(), parse_paths(Some(dirs), base)))
+        .collect();
+    sort_prefixes(&mut prefixes);
+    prefixes
+}
+
+/// The most specific prefix first
+fn sort_prefixes(prefixes: &mut [(String, Vec<PathBuf>)]) {
+    prefixes.sort_by_key(|(prefix, _)| std::cmp::Reverse(prefix.len()));
+}
+
+/// A single path, or a list of paths
+fn parse_paths(paths: Option<&Value>, base: &Path) -> Vec<PathBuf> {
+    match paths {
+        Some(Value::String(path)) => vec![base.join(path)],
+        Some(Value::Array(paths)) => paths
+            .iter()
+            .filter_map(Value::as_str)
+            .map(|path| base.join(path))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Resolves `..` and `.` without touching the file system
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+fn read_json(path: &Path) -> std::io::Result<Value> {
+    let content = std::fs::read(path)?;
+    serde_json::from_slice(&content).map_err(|err| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("{}: {}", path.display(), err),
+        )
+    })
+}
+
+#[derive(Clone, Debug)]
+pub struct ComposerPackage {
+    pub name: String,
+    pub path: PathBuf,
+    pub autoload: AutoloadRules,
+}
+
+/// How much of a file is analyzed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnalysisMode {
+    /// Every pass, with issues reported
+    Full,
+    /// Only the first pass, registering the declared symbols
+    IndexOnly,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProjectFile {
+    pub path: PathBuf,
+    pub mode: AnalysisMode,
+}
+
+#[derive(Debug)]
+pub struct ComposerProject {
+    pub root: PathBuf,
+    pub vendor_dir: PathBuf,
+    /// The project itself, with the rules from both `autoload` and `autoload-dev`
+    pub package: ComposerPackage,
+    /// The installed dependencies
+    pub dependencies: Vec<ComposerPackage>,
+    /// Lower-case class name to file, from scanning the classmap-paths
+    classmap: OnceLock<HashMap<String, PathBuf>>,
+}
+
+impl ComposerProject {
+    ///
+    /// Reads `composer.json` in `root`, and `vendor/composer/installed.json` if the
+    /// dependencies are installed
+    ///
+    pub fn load(root: &Path) -> std::io::Result<Self> {
+        let composer = read_json(&root.join("composer.json"))?;
+        let vendor_dir = root.join(
+            composer
+                .pointer("/config/vendor-dir")
+                .and_then(Value::as_str)
+                .unwrap_or("vendor"),
+        );
+        let mut autoload = AutoloadRules::parse(composer.get("autoload"), root);
+        autoload.extend(AutoloadRules::parse(composer.get("autoload-dev"), root));
+        let package = ComposerPackage {
+            name: composer
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            path: root.to_path_buf(),
+            autoload,
+        };
+
+        let installed_path = vendor_dir.join("composer").join("installed.json");
+        let dependencies = if installed_path.exists() {
+            Self::parse_installed(&read_json(&installed_path)?, &vendor_dir)
+        } else {
+            vec![]
+        };
+
+        Ok(Self {
+            root: root.to_path_buf(),
+            vendor_dir,
+            package,
+            dependencies,
+            classmap: OnceLock::new(),
+        })
+    }
+
+    /// Composer 2 wraps the list of packages in an object, while composer 1 doesn't
+    fn parse_installed(installed: &Value, vendor_dir: &Path) -> Vec<ComposerPackage> {
+        let packages = match installed {
+            Value::Object(_) => installed.get("packages").and_then(Value::as_array),
+            Value::Array(packages) => Some(packages),
+            _ => None,
+        };
+        let Some(packages) = packages else {
+            return vec![];
+        };
+        packages
+            .iter()
+            .filter_map(|package| {
+                let name = package.get("name")?.as_str()?.to_string();
+                let path = match package.get("install-path").and_then(Value::as_str) {
+                    // Relative to `vendor/composer`
+                    Some(install_path) => {
+                        normalize_path(&vendor_dir.join("composer").join(install_path))
+                    }
+                    None => vendor_dir.join(&name),
+                };
+                let autoload = AutoloadRules::parse(package.get("autoload"), &path);
+                Some(ComposerPackage {
+                    name,
+                    path,
+                    autoload,
+                })
+            })
+            .collect()
+    }
+
+    ///
+    /// The PHP-files covered by the autoload-rules. The files of the project are
+    /// analyzed fully, while those of the dependencies only are indexed
+    ///
+    pub fn get_files(&self) -> Vec<ProjectFile> {
+        let mut files = vec![];
+        let mut seen = std::collections::HashSet::new();
+        let packages = std::iter::once((&self.package, AnalysisMode::Full)).chain(
+            self.dependencies
+                .iter()
+                .map(|package| (package, AnalysisMode::IndexOnly)),
+        );
+        for (package, mode) in packages {
+            let mut paths = vec![];
+            for root in package.autoload.get_roots() {
+                self.collect_php_files(root, &mut paths);
+            }
+            for path in paths {
+                if seen.insert(path.clone()) {
+                    files.push(ProjectFile { path, mode });
+                }
+            }
+        }
+        files
+    }
+
+    fn collect_php_files(&self, path: &Path, files: &mut Vec<PathBuf>) {
+        if path.is_file() {
+            files.push(path.to_path_buf());
+            return;
+        }
+        // A project with an autoload-rule for its root directory, mustn't bring in `vendor/`
+        if path == self.vendor_dir {
+            return;
+        }
+        let Ok(entries) = std::fs::read_dir(path) else {
+            return;
+        };
+        let mut entries: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+        entries.sort();
+        for entry in entries {
+            if entry.is_dir() {
+                self.collect_php_files(&entry, files);
+            } else if entry.extension().is_some_and(|ext| ext == "php") {
+                files.push(entry);
+            }
+        }
+    }
+
+    /// The file which the autoloader would load the class from, if it exists
+    pub fn locate_class(&self, fq_name: &FullyQualifiedName) -> Option<PathBuf> {
+        let packages = std::iter::once(&self.package).chain(&self.dependencies);
+        for package in packages {
+            let found = package
+                .autoload
+                .get_candidate_files(fq_name)
+                .into_iter()
+                .find(|path| path.is_file());
+            if found.is_some() {
+                return found;
+            }
+        }
+        self.get_classmap()
+            .get(&class_name_string(fq_name).to_ascii_lowercase())
+            .cloned()
+    }
+
+    fn get_classmap(&self) -> &HashMap<String, PathBuf> {
+        self.classmap.get_or_init(|| {
+            let mut files = vec![];
+            let packages = std::iter::once(&self.package).chain(&self.dependencies);
+            for package in packages {
+                for path in &package.autoload.classmap {
+                    self.collect_php_files(path, &mut files);
+                }
+            }
+            let mut classmap = HashMap::new();
+            for file in files {
+                let Ok(content) = std::fs::read(&file) else {
+                    continue;
+                };
+                for class_name in scan_declared_classes(&content) {
+                    classmap
+                        .entry(class_name.to_ascii_lowercase())
+                        .or_insert_with(|| file.clone());
+                }
+            }
+            classmap
+        })
+    }
+}
+
+///
+/// A lightweight scan for the classes, interfaces, traits and enums declared in a file,
+/// without parsing it. Used for classmap-autoloading, like composer does.
+///
+fn scan_declared_classes(content: &[u8]) -> Vec<String> {
+    lazy_static! {
+        static ref DECLARATION: Regex = Regex::new(
+            r"(?mi)^\s*(?:namespace\s+([\w\\]+)\s*[;{]|(?:(?:abstract|final|readonly)\s+)*(?:class|interface|trait|enum)\s+(\w+))"
+        )
+        .unwrap();
+    }
+    let mut namespace = String::new();
+    let mut classes = vec![];
+    for captures in DECLARATION.captures_iter(content) {
+        if let Some(ns) = captures.get(1) {
+            namespace = String::from_utf8_lossy(ns.as_bytes()).to_string();
+        } else if let Some(name) = captures.get(2) {
+            let name = String::from_utf8_lossy(name.as_bytes());
+            if namespace.is_empty() {
+                classes.push(name.to_string());
+            } else {
+                classes.push(format!("{}\\{}", namespace, name));
+            }
+        }
+    }
+    classes
+}
+
+fn get_class_position(class_type: &ClassType) -> Option<&FileLocation> {
+    match class_type {
+        ClassType::None => None,
+        ClassType::Class(c) => Some(&c.position),
+        ClassType::Interface(i) => Some(&i.position),
+        ClassType::Trait(t) => Some(&t.position),
+    }
+}
+
+///
+/// Reports classes of the project, declared in a PSR-4 directory, which the autoloader
+/// wouldn't find: Those in the wrong file, and those outside of the directory's namespace
+///
+pub fn analyze_psr4_locations(
+    project: &ComposerProject,
+    symbol_data: &Arc<SymbolData>,
+    emitter: &dyn IssueEmitter,
+) {
+    let classes: Vec<_> = symbol_data
+        .classes
+        .read_or_recover()
+        .values()
+        .cloned()
+        .collect();
+    for class_handle in classes {
+        let class_type = class_handle.read_or_recover();
+        let Some(position) = get_class_position(&class_type) else {
+            continue;
+        };
+        let file = PathBuf::from(&position.uri);
+        let fq_name = class_type.get_fq_name();
+        let class_name = class_name_string(&fq_name);
+
+        let mut expected = None;
+        let mut outside_prefix = None;
+        let mut found = false;
+        for (prefix, dirs) in &project.package.autoload.psr4 {
+            for dir in dirs.iter().filter(|dir| file.starts_with(dir)) {
+                match strip_namespace_prefix(&class_name, prefix) {
+                    Some(relative) => {
+                        let path = dir.join(psr4_relative_path(relative));
+                        found |= path == file;
+                        expected.get_or_insert(path);
+                    }
+                    None => {
+                        outside_prefix.get_or_insert_with(|| prefix.clone());
+                    }
+                }
+            }
+        }
+        if found {
+            continue;
+        }
+        if let Some(expected) = expected {
+            emitter.emit(Issue::ClassLocationMismatch(
+                position.clone().into(),
+                fq_name,
+                expected.into_os_string(),
+            ));
+        } else if let Some(prefix) = outside_prefix {
+            emitter.emit(Issue::ClassNamespaceMismatch(
+                position.clone().into(),
+                fq_name,
+                OsString::from(prefix),
+            ));
+        }
+    }
+}
+
+///
+/// Indexes the files of classes through the autoload-rules of the project, with the
+/// configuration of the analysis. Files which are analyzed anyway are left to that, so
+/// their classes aren't declared twice
+///
+#[derive(Debug)]
+struct ProjectClassResolver {
+    project: Arc<ComposerProject>,
+    config: PHPAnalyzeConfig,
+    queued: HashSet<PathBuf>,
+}
+
+impl ClassResolver for ProjectClassResolver {
+    fn resolve_class(&self, symbol_data: Arc<SymbolData>, fq_name: &FullyQualifiedName) -> bool {
+        let Some(path) = self.project.locate_class(fq_name) else {
+            return false;
+        };
+        if self.queued.contains(&canonical_path(&path)) {
+            return false;
+        }
+        index_file(symbol_data, &path, &self.config)
+    }
+}
+
+fn canonical_path(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+///
+/// Indexes the declarations of a file, by running the first and second pass without
+/// reporting issues. Used for loading the files of classes on demand.
+///
+pub fn index_file(symbol_data: Arc<SymbolData>, path: &Path, config: &PHPAnalyzeConfig) -> bool {
+    let void_emitter = VoidEmitter::new();
+    let Ok(content) = std::fs::read(path) else {
+        return false;
+    };
+    let mut analyzer = Analyzer::new(
+        config.clone(),
+        Box::new(move || Ok(content.clone())),
+        path.as_os_str().to_os_string(),
+    );
+    if analyzer.parse(&void_emitter).is_err() {
+        return false;
+    }
+    for pass in [1, 2] {
+        let mut state = AnalysisState::new_with_symbols(symbol_data.clone());
+        state.config = config.clone();
+        state.pass = pass;
+        state.filename = Some(path.to_path_buf());
+        if pass == 1 {
+            analyzer.first_pass(&mut state, &void_emitter);
+        } else {
+            analyzer.second_pass(&mut state, &void_emitter);
+        }
+    }
+    true
+}
+
+fn new_symbol_data(config: &PHPAnalyzeConfig, emitter: &dyn IssueEmitter) -> Arc<SymbolData> {
+    let symbol_data = Arc::new(SymbolData::new());
+    let mut state = AnalysisState::new_with_symbols(symbol_data.clone());
+    state.config = config.clone();
+    crate::native::register(&mut state, emitter);
+    symbol_data
+}
+
+///
+/// Runs the first and second pass for every file, like [index_file], and the third pass
+/// for the files which are analyzed fully. Issues are only reported for those.
+///
+/// Functions may be called before the input they return has been seen, so the third pass
+/// is run again while that changes, reporting only the tainted sinks found by doing so.
+///
+fn analyze_files(
+    symbol_data: &Arc<SymbolData>,
+    files: &[ProjectFile],
+    config: &PHPAnalyzeConfig,
+    emitter: &dyn IssueEmitter,
+) {
+    let void_emitter = VoidEmitter::new();
+    let mut analyzers = vec![];
+    for file in files {
+        let path = file.path.clone();
+        let mut analyzer = Analyzer::new(
+            config.clone(),
+            Box::new(move || std::fs::read(&path)),
+            file.path.clone().into_os_string(),
+        );
+        let file_emitter: &dyn IssueEmitter = match file.mode {
+            AnalysisMode::Full => emitter,
+            AnalysisMode::IndexOnly => &void_emitter,
+        };
+        if analyzer.parse(file_emitter).is_err() {
+            continue;
+        }
+        analyzers.push((file, analyzer, file_emitter));
+    }
+
+    let new_state = |file: &ProjectFile, pass| {
+        let mut state = AnalysisState::new_with_symbols(symbol_data.clone());
+        state.config = config.clone();
+        state.pass = pass;
+        state.filename = Some(file.path.clone());
+        state
+    };
+    for (file, analyzer, file_emitter) in &analyzers {
+        analyzer.first_pass(&mut new_state(file, 1), *file_emitter);
+    }
+    for (file, analyzer, file_emitter) in &analyzers {
+        analyzer.second_pass(&mut new_state(file, 2), *file_emitter);
+    }
+
+    let third_pass_emitter = TaintRoundEmitter::new(emitter);
+    let analyzed: Vec<_> = analyzers
+        .iter()
+        .filter(|(file, _, _)| file.mode == AnalysisMode::Full)
+        .collect();
+    for _ in 0..MAX_THIRD_PASS_ROUNDS {
+        for (file, analyzer, _) in &analyzed {
+            analyzer.third_pass(&mut new_state(file, 3), &third_pass_emitter);
+        }
+        if !symbol_data.taint.write_or_recover().take_changed_returns() {
+            break;
+        }
+        third_pass_emitter.next_round();
+    }
+}
+
+///
+/// Analyzes the project: Every file is indexed in the first pass, while only the files of
+/// the project itself are analyzed in the later passes, and have their issues reported.
+///
+pub fn analyze_project(
+    project: &ComposerProject,
+    config: PHPAnalyzeConfig,
+    emitter: &dyn IssueEmitter,
+) -> Arc<SymbolData> {
+    let symbol_data = new_symbol_data(&config, emitter);
+    analyze_files(&symbol_data, &project.get_files(), &config, emitter);
+    analyze_psr4_locations(project, &symbol_data, emitter);
+    if config.dead_code.enabled {
+        analyze_dead_code(&symbol_data, &config, emitter);
+    }
+    symbol_data
+}
+
+///
+/// Analyzes some of the files of the project, like those changed in a commit. The classes
+/// they use are indexed on demand through the autoload-rules, while the autoloaded
+/// `files`, which may declare functions, are always indexed.
+///
+pub fn analyze_project_files(
+    project: Arc<ComposerProject>,
+    paths: &[PathBuf],
+    config: PHPAnalyzeConfig,
+    emitter: &dyn IssueEmitter,
+) -> Arc<SymbolData> {
+    let symbol_data = new_symbol_data(&config, emitter);
+
+    let packages = std::iter::once(&project.package).chain(&project.dependencies);
+    let mut files: Vec<_> = packages
+        .flat_map(|package| &package.autoload.files)
+        .filter(|path| !paths.contains(path))
+        .map(|path| ProjectFile {
+            path: path.clone(),
+            mode: AnalysisMode::IndexOnly,
+        })
+        .collect();
+    files.extend(paths.iter().map(|path| ProjectFile {
+        path: path.clone(),
+        mode: AnalysisMode::Full,
+    }));
+    symbol_data.set_class_resolver(Arc::new(ProjectClassResolver {
+        project,
+        config: config.clone(),
+        queued: files
+            .iter()
+            .map(|file| canonical_path(&file.path))
+            .collect(),
+    }));
+    analyze_files(&symbol_data, &files, &config, emitter);
+    symbol_data
+}
@@ -1,3 +1,4 @@
+use crate::locks::RecoverableLock;
 use std::{
     collections::HashMap,
     sync::{Arc, RwLock},
@@ -8,7 +9,7 @@ use crate::{
     symbols::Name,
 };
 
-use super::{data::VarData, state::AnalysisState};
+use super::{data::VarData, state::AnalysisState, taint};
 
 #[derive(Debug)]
 pub struct Scope {
@@ -32,7 +33,7 @@ impl Scope {
 
     pub fn has_var(&self, var_name: &Name) -> bool {
         if let Some(p) = &self.parent {
-            let read = p.read().unwrap();
+            let read = p.read_or_recover();
             if read.has_var(var_name) {
                 return true;
             }
@@ -49,7 +50,7 @@ impl Scope {
                 .expect("We just confirmed the entry is there");
         }
         if let Some(p) = &self.parent {
-            let read = p.read().unwrap();
+            let read = p.read_or_recover();
             if read.has_var(&var_name) {
                 return read.get_var(&var_name).expect("has_var returned true");
             }
@@ -77,7 +78,7 @@ impl Scope {
             return self.vars.get(var_name).cloned();
         }
         if let Some(p) = &self.parent {
-            let read = p.read().unwrap();
+            let read = p.read_or_recover();
             if read.has_var(var_name) {
                 return read.get_var(var_name);
             }
@@ -85,13 +86,23 @@ impl Scope {
 
         self.vars.get(var_name).cloned()
     }
+
+    /// Adds the variables of `parent` which this scope doesn't have itself, so a branch of a
+    /// narrowed scope can be joined as if it was the narrowed scope
+    pub fn inherit_vars(&mut self, parent: &Scope) {
+        for (var_name, data) in &parent.vars {
+            self.vars
+                .entry(var_name.clone())
+                .or_insert_with(|| data.clone());
+        }
+    }
 }
 /*
 impl std::fmt::Debug for Scope {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         for (name, data) in &self.vars {
             write!(f, "${}: ", name);
-            let var_data = data.read().unwrap();
+            let var_data = data.read_or_recover();
             write!(
                 f,
                 "comment_declared_type={:?},",
@@ -115,7 +126,7 @@ impl std::fmt::Debug for Scope {
             write!(f, "\n");
         }
         if let Some(p) = &self.parent {
-            let read = p.read().unwrap();
+            let read = p.read_or_recover();
             write!(f, "UP\n{:?}", *read);
         }
         Ok(())
@@ -134,7 +145,7 @@ pub trait BranchableScope {
     fn analyze_for_unused_vars(&self, state: &mut AnalysisState, emitter: &dyn IssueEmitter);
 }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum BranchSide {
     TrueBranch,
     FalseBranch,
@@ -154,7 +165,7 @@ impl BranchableScope for Arc<RwLock<Scope>> {
         let mut vars = HashMap::new();
         // Gather all variables from all branches
         for b in branches {
-            let scope = b.read().unwrap();
+            let scope = b.read_or_recover();
             for (var, data) in &scope.vars {
                 let key = var.clone();
                 let entry = vars.entry(key).or_insert_with(std::vec::Vec::new);
@@ -162,17 +173,18 @@ impl BranchableScope for Arc<RwLock<Scope>> {
             }
         }
         {
-            let mut write = self.write().unwrap();
+            let mut write = self.write_or_recover();
             for (key, data) in &vars {
                 let wr_ref = write.get_or_create_var(key.clone());
-                let mut write_var = wr_ref.write().unwrap();
+                let mut write_var = wr_ref.write_or_recover();
                 let mut written_data: Vec<_> = vec![];
                 let mut last_data: Vec<Vec<_>> = vec![];
                 for e in data {
-                    let reader = e.read().unwrap();
+                    let reader = e.read_or_recover();
                     write_var.read_from += reader.read_from;
                     write_var.written_to += reader.written_to;
                     write_var.is_partial |= reader.is_partial;
+                    write_var.taint = taint::join(write_var.taint.take(), reader.taint.clone());
 
                     written_data.extend(reader.all_written_data.iter().cloned());
                     last_data.push(reader.last_written_data.to_vec());
@@ -234,9 +246,9 @@ impl BranchableScope for Arc<RwLock<Scope>> {
 
     fn analyze_for_unused_vars(&self, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
         {
-            let scope = self.read().unwrap();
+            let scope = self.read_or_recover();
             for (var_name, data_handle) in &scope.vars {
-                let data = data_handle.read().unwrap();
+                let data = data_handle.read_or_recover();
                 //  eprintln!("Checking var {:?}: {:?}", &var_name, &data);
                 if data.read_from != 0 {
                     continue;
@@ -0,0 +1,237 @@
+use std::os::unix::ffi::OsStrExt;
+
+use crate::{
+    autonodes::{
+        any::AnyNodeRef,
+        binary_expression::{BinaryExpressionNode, BinaryExpressionOperator},
+        function_call_expression::FunctionCallExpressionNode,
+    },
+    autotree::NodeAccess,
+    config::SecurityConfig,
+    issue::{Issue, IssueEmitter, VoidEmitter},
+    symbols::Name,
+    types::union::{DiscreteType, DiscretlyAccessedType},
+    value::PHPValue,
+};
+
+use super::state::AnalysisState;
+
+///
+/// Flags dangerous constructs, regardless of where their input comes from. Called after
+/// the node itself has been analyzed in the third pass
+///
+pub fn analyze_security(node: &AnyNodeRef, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
+    let config = state.config.security;
+    match node {
+        AnyNodeRef::FunctionCallExpression(call) => {
+            check_function_call(call, &config, state, emitter)
+        }
+        AnyNodeRef::ShellCommandExpression(s) if config.shell_command => {
+            emitter.emit(Issue::BacktickShellCommand(s.pos(state)))
+        }
+        AnyNodeRef::ErrorSuppressionExpression(e) if config.error_suppression => {
+            emitter.emit(Issue::ErrorSuppression(e.pos(state)))
+        }
+        AnyNodeRef::BinaryExpression(b) if config.loose_hash_comparison => {
+            check_loose_hash_comparison(b, &config, state, emitter)
+        }
+        AnyNodeRef::AssignmentExpression(a) if config.insecure_random => {
+            let target_name = get_value_name(&a.left.as_any(), state);
+            if name_matches(target_name, config.token_names) {
+                check_insecure_random(&a.right.as_any(), &config, state, emitter);
+            }
+        }
+        AnyNodeRef::ReturnStatement(r) if config.insecure_random => {
+            let Some(child) = &r.child else {
+                return;
+            };
+            let function_name = state
+                .in_function_stack
+                .last()
+                .and_then(|f| f.name.as_ref())
+                .map(|n| n.to_string());
+            if name_matches(function_name, config.token_names) {
+                check_insecure_random(&child.as_any(), &config, state, emitter);
+            }
+        }
+        _ => (),
+    }
+}
+
+fn check_function_call(
+    call: &FunctionCallExpressionNode,
+    config: &SecurityConfig,
+    state: &mut AnalysisState,
+    emitter: &dyn IssueEmitter,
+) {
+    let name = call.get_configured_name(state).to_ascii_lowercase();
+    let first_argument = call.arguments.get_argument_nodes().next();
+    let first_value = first_argument.and_then(|arg| arg.get_php_value(state, &VoidEmitter::new()));
+
+    match name.as_str() {
+        "eval" if config.eval => emitter.emit(Issue::UseOfEval(call.pos(state))),
+        "extract" if config.extract => emitter.emit(Issue::UseOfExtract(call.pos(state))),
+        "unserialize"
+            if config.unserialize && first_argument.is_some() && first_value.is_none() =>
+        {
+            emitter.emit(Issue::UnserializeOfVariableInput(call.pos(state)));
+        }
+        "assert" if config.assert_string => {
+            let is_string = match &first_value {
+                Some(value) => matches!(value, PHPValue::String(_)),
+                None => first_argument
+                    .and_then(|arg| arg.get_utype(state, &VoidEmitter::new()))
+                    .is_some_and(|utype| {
+                        let variants = utype.as_discrete_variants();
+                        !variants.is_empty()
+                            && variants.iter().all(|variant| {
+                                matches!(
+                                    variant,
+                                    DiscretlyAccessedType::Discrete(
+                                        DiscreteType::String | DiscreteType::StringLiteral(_)
+                                    )
+                                )
+                            })
+                    }),
+            };
+            if is_string {
+                emitter.emit(Issue::AssertWithStringArgument(call.pos(state)));
+            }
+        }
+        "preg_replace" if config.preg_replace_eval => {
+            if let Some(PHPValue::String(pattern)) = &first_value {
+                if has_eval_modifier(pattern.as_bytes()) {
+                    emitter.emit(Issue::PregReplaceEvalModifier(call.pos(state)));
+                }
+            }
+        }
+        _ => (),
+    }
+
+    if config.weak_password_hash && is_one_of(&name, config.weak_hash_functions) {
+        let argument_name = first_argument.and_then(|arg| get_value_name(&arg.as_any(), state));
+        if name_matches(argument_name, config.password_names) {
+            emitter.emit(Issue::WeakPasswordHash(call.pos(state), Name::from(name)));
+        }
+    }
+}
+
+/// Whether a regex, like `/(\w+)/e`, has the `e` modifier
+fn has_eval_modifier(pattern: &[u8]) -> bool {
+    let start = pattern
+        .iter()
+        .position(|c| !c.is_ascii_whitespace())
+        .unwrap_or(pattern.len());
+    let Some((&delimiter, rest)) = pattern[start..].split_first() else {
+        return false;
+    };
+    let closing = match delimiter {
+        b'(' => b')',
+        b'{' => b'}',
+        b'[' => b']',
+        b'<' => b'>',
+        d => d,
+    };
+    let Some(end) = rest.iter().rposition(|c| *c == closing) else {
+        return false;
+    };
+    rest[end + 1..].contains(&b'e')
+}
+
+/// Emits calls to predictable random functions in `node`, which is used as a token
+fn check_insecure_random(
+    node: &AnyNodeRef,
+    config: &SecurityConfig,
+    state: &mut AnalysisState,
+    emitter: &dyn IssueEmitter,
+) {
+    match node {
+        AnyNodeRef::FunctionCallExpression(call) => {
+            let name = call.get_configured_name(state).to_ascii_lowercase();
+            if is_one_of(&name, config.insecure_random_functions) {
+                emitter.emit(Issue::InsecureRandomToken(
+                    call.pos(state),
+                    Name::from(name),
+                ));
+            }
+        }
+        AnyNodeRef::AnonymousFunctionCreationExpression(_) | AnyNodeRef::ArrowFunction(_) => return,
+        _ => (),
+    }
+    for child in node.children_any() {
+        check_insecure_random(&child, config, state, emitter);
+    }
+}
+
+fn check_loose_hash_comparison(
+    b: &BinaryExpressionNode,
+    config: &SecurityConfig,
+    state: &mut AnalysisState,
+    emitter: &dyn IssueEmitter,
+) {
+    if !matches!(
+        &*b.operator,
+        BinaryExpressionOperator::Equal(_) | BinaryExpressionOperator::NotEqual(_)
+    ) {
+        return;
+    }
+    let left = b.left.as_any();
+    let right = b.right.as_any();
+    // Comparing with `null`, booleans or integers isn't a hash comparison
+    let is_literal = |node: &AnyNodeRef| {
+        matches!(
+            node,
+            AnyNodeRef::Null(_) | AnyNodeRef::Boolean(_) | AnyNodeRef::Integer(_)
+        )
+    };
+    if is_literal(&left) || is_literal(&right) {
+        return;
+    }
+    if is_hash(&left, config, state) || is_hash(&right, config, state) {
+        emitter.emit(Issue::LooseHashComparison(b.pos(state)));
+    }
+}
+
+fn is_hash(node: &AnyNodeRef, config: &SecurityConfig, state: &mut AnalysisState) -> bool {
+    if let AnyNodeRef::FunctionCallExpression(call) = node {
+        let name = call.get_configured_name(state).to_ascii_lowercase();
+        return is_one_of(&name, config.hash_functions);
+    }
+    name_matches(get_value_name(node, state), config.hash_names)
+}
+
+///
+/// The name of what `node` reads from, like the variable `$password`, the property
+/// `$user->password` or the key `$_POST['password']`
+///
+fn get_value_name(node: &AnyNodeRef, state: &mut AnalysisState) -> Option<String> {
+    match node {
+        AnyNodeRef::VariableName(vn) => Some(vn.get_variable_name().to_string()),
+        AnyNodeRef::MemberAccessExpression(ma) => ma
+            .get_property_name(state, &VoidEmitter::new())
+            .map(|n| n.to_string()),
+        AnyNodeRef::SubscriptExpression(s) => match s.get_key_value(state, &VoidEmitter::new()) {
+            Some(PHPValue::String(key)) => Some(key.to_string_lossy().to_string()),
+            _ => get_value_name(&s.dereferenceable.as_any(), state),
+        },
+        AnyNodeRef::Argument(_) | AnyNodeRef::ParenthesizedExpression(_) => node
+            .children_any()
+            .iter()
+            .find_map(|child| get_value_name(child, state)),
+        _ => None,
+    }
+}
+
+fn name_matches(name: Option<String>, parts: &[&str]) -> bool {
+    let Some(name) = name else {
+        return false;
+    };
+    let name = name.to_ascii_lowercase();
+    parts
+        .iter()
+        .any(|part| name.contains(&part.to_ascii_lowercase()))
+}
+
+fn is_one_of(name: &str, functions: &[&str]) -> bool {
+    functions.iter().any(|f| f.eq_ignore_ascii_case(name))
+}
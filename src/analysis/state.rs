@@ -1,3 +1,4 @@
+use crate::locks::RecoverableLock;
 use std::ffi::OsStr;
 use std::ffi::OsString;
 use std::os::unix::ffi::OsStrExt;
@@ -17,7 +18,8 @@ use crate::symboldata::FunctionData;
 use crate::symboldata::SymbolData;
 use crate::symbols::FullyQualifiedName;
 use crate::symbols::Name;
-use crate::types::union::PHPType;
+use crate::types::traversable::{generator_type, get_generator_send_type};
+use crate::types::union::{DiscreteType, PHPType, UnionType};
 use crate::value::PHPValue;
 use std::collections::HashMap;
 
@@ -118,15 +120,37 @@ impl FunctionDataPointer {
     pub fn get_generic_templates(&self) -> Option<Vec<Name>> {
         match self {
             Self::Method(m) => {
-                let mdata = m.read().unwrap();
+                let mdata = m.read_or_recover();
                 mdata.generic_templates.clone()
             }
             Self::Function(f) => {
-                let fdata = f.read().unwrap();
+                let fdata = f.read_or_recover();
                 fdata.generic_templates.clone()
             }
         }
     }
+
+    /// The return-type declared either in the signature or in the doc-comment
+    pub fn get_declared_return_type(&self) -> Option<PHPType> {
+        match self {
+            Self::Method(m) => {
+                let mdata = m.read_or_recover();
+                mdata
+                    .comment_return_type
+                    .as_ref()
+                    .map(|x| x.0.clone())
+                    .or_else(|| mdata.php_return_type.clone())
+            }
+            Self::Function(f) => {
+                let fdata = f.read_or_recover();
+                fdata
+                    .comment_return_type
+                    .as_ref()
+                    .map(|x| x.0.clone())
+                    .or_else(|| fdata.php_return_type.clone())
+            }
+        }
+    }
 }
 #[derive(Debug)]
 pub struct FunctionState {
@@ -134,7 +158,11 @@ pub struct FunctionState {
     pub is_method: bool,
     pub scope_stack: RwLock<ScopeStack>,
     pub returns: RwLock<Vec<(Option<PHPType>, Option<PHPValue>)>>,
+    /// Key- and value-types of every `yield` (or `yield from`) in the function
+    pub yields: RwLock<Vec<(Option<PHPType>, Option<PHPType>)>>,
     pub data: Option<FunctionDataPointer>,
+    /// The declared return-type of a closure, which has no function data
+    pub closure_return_type: Option<PHPType>,
 }
 
 impl FunctionState {
@@ -142,17 +170,88 @@ impl FunctionState {
         Self {
             scope_stack: RwLock::new(ScopeStack::new()),
             returns: RwLock::new(Vec::new()),
+            yields: RwLock::new(Vec::new()),
             name,
             is_method,
             data,
+            closure_return_type: None,
+        }
+    }
+
+    /// The return-type declared either in the signature or in the doc-comment
+    pub fn get_declared_return_type(&self) -> Option<PHPType> {
+        match &self.data {
+            Some(data) => data.get_declared_return_type(),
+            None => self.closure_return_type.clone(),
         }
     }
 
     pub fn add_return(&self, ret_type: Option<PHPType>, ret_value: Option<PHPValue>) {
-        let mut rets = self.returns.write().expect("Noe");
+        let mut rets = self.returns.write_or_recover();
         rets.push((ret_type, ret_value));
     }
 
+    pub fn add_yield(&self, key_type: Option<PHPType>, value_type: Option<PHPType>) {
+        let mut yields = self.yields.write_or_recover();
+        yields.push((key_type, value_type));
+    }
+
+    /// If the function contains `yield`, it returns a `Generator` built from the
+    /// yielded keys and values, and the returns. Unknown parts are left as `mixed`
+    pub fn get_generator_type(&self) -> Option<PHPType> {
+        let yields = self.yields.read_or_recover();
+        if yields.is_empty() {
+            return None;
+        }
+        let mut key_type = UnionType::new();
+        let mut value_type = UnionType::new();
+        let mut key_unknown = false;
+        let mut value_unknown = false;
+        for (k, v) in yields.iter() {
+            match k {
+                Some(k) => key_type.append(k.clone()),
+                None => key_unknown = true,
+            }
+            match v {
+                Some(v) => value_type.append(v.clone()),
+                None => value_unknown = true,
+            }
+        }
+
+        let returns = self.returns.read_or_recover();
+        let mut ret_type = UnionType::new();
+        let mut ret_unknown = false;
+        for (r_type, _) in returns.iter() {
+            match r_type {
+                Some(t) => ret_type.append(t.clone()),
+                None => ret_unknown = true,
+            }
+        }
+        if returns.is_empty() {
+            ret_type.append(DiscreteType::Void);
+        }
+
+        let finalize = |utype: UnionType, unknown: bool| -> PHPType {
+            if unknown || utype.is_empty() {
+                DiscreteType::Mixed.into()
+            } else {
+                PHPType::from(utype).simplify()
+            }
+        };
+
+        let send_type = self
+            .get_declared_return_type()
+            .and_then(|t| get_generator_send_type(&t))
+            .unwrap_or_else(|| DiscreteType::Mixed.into());
+
+        Some(generator_type(
+            finalize(key_type, key_unknown),
+            finalize(value_type, value_unknown),
+            send_type,
+            finalize(ret_type, ret_unknown),
+        ))
+    }
+
     pub(crate) fn new_method(
         method_name: Name,
         method_data: Arc<RwLock<MethodData>>,
@@ -175,8 +274,10 @@ impl FunctionState {
         )
     }
 
-    pub(crate) fn new_anonymous() -> FunctionState {
-        Self::new(None, false, None)
+    pub(crate) fn new_anonymous(return_type: Option<PHPType>) -> FunctionState {
+        let mut function = Self::new(None, false, None);
+        function.closure_return_type = return_type;
+        function
     }
 
     pub fn get_generic_templates(&self) -> Option<Vec<Name>> {
@@ -248,6 +349,20 @@ impl AnalysisState {
         }
     }
 
+    /// Whether we're in the global scope of a file which is included from another file,
+    /// which might have defined the variable
+    pub fn is_defined_by_includer(&self, var_name: &Name) -> bool {
+        let Some(filename) = &self.filename else {
+            return false;
+        };
+        self.in_function_stack.is_empty()
+            && self
+                .symbol_data
+                .includes
+                .read_or_recover()
+                .is_variable_from_includer(filename, var_name)
+    }
+
     pub fn pos_as_string(&self, range: Range) -> String {
         let fname = if let Some(fname) = &self.filename {
             String::from_utf8_lossy(fname.as_os_str().as_bytes()).to_string()
@@ -308,15 +423,15 @@ impl AnalysisState {
     }
 
     pub fn current_scope(&self) -> Arc<RwLock<Scope>> {
-        self.current_scope_stack().read().unwrap().top()
+        self.current_scope_stack().read_or_recover().top()
     }
 
     pub(crate) fn push_scope(&self, scope: Arc<RwLock<Scope>>) {
-        self.current_scope_stack().write().unwrap().push(scope)
+        self.current_scope_stack().write_or_recover().push(scope)
     }
 
     pub(crate) fn pop_scope(&self) -> Arc<RwLock<Scope>> {
-        self.current_scope_stack().write().unwrap().pop()
+        self.current_scope_stack().write_or_recover().pop()
     }
 
     pub fn in_method<S>(&self, method_name: S) -> bool
@@ -346,6 +461,16 @@ impl AnalysisState {
         self.in_method("__construct")
     }
 
+    /// Type alias available in the current class, declared with `@phpstan-type` or similar
+    pub(crate) fn get_type_alias(&self, name: &Name) -> Option<PHPType> {
+        let class_handle = self.in_class.as_ref()?.get_data();
+        let unlocked = class_handle.read_or_recover();
+        match &*unlocked {
+            ClassType::Class(cdata) => cdata.get_type_alias(name, &self.symbol_data),
+            _ => None,
+        }
+    }
+
     ///
     /// In some parse-situations (especially parsing of method php-doc-block)
     /// We discover template-definitions AND usage of the same templates, before
@@ -356,7 +481,7 @@ impl AnalysisState {
         temp_generics: Option<&Vec<Name>>,
     ) -> Option<Vec<Name>> {
         let class_templates = self.in_class.as_ref().map(|x| x.get_data()).and_then(|x| {
-            let read = x.read().unwrap();
+            let read = x.read_or_recover();
             read.get_generic_templates()
         });
         let func_templates = self
@@ -365,7 +490,12 @@ impl AnalysisState {
             .and_then(|x| x.get_generic_templates());
 
         let func_templates = match (func_templates, temp_generics) {
-            (Some(_), Some(_)) => todo!("Both generic-sources are populated..."),
+            // Templates of a nested declaration come in addition to the enclosing function's
+            (Some(mut a), Some(b)) => {
+                let added: Vec<_> = b.iter().filter(|name| !a.contains(name)).cloned().collect();
+                a.extend(added);
+                Some(a)
+            }
             (None, Some(a)) => Some(a.clone()),
             (Some(a), None) => Some(a),
             _ => None,
@@ -385,8 +515,11 @@ impl AnalysisState {
 
 impl LookingForNode {
     pub fn found(&self, child: AnyNodeRef, state: &mut AnalysisState, path: &[AnyNodeRef]) {
-        let mut handle = self.callback.write().unwrap();
-        let cb = handle.take().expect("Already consumed the callback?");
+        let mut handle = self.callback.write_or_recover();
+        let Some(cb) = handle.take() else {
+            crate::missing!("Node was found after the callback was consumed");
+            return;
+        };
         eprintln!(
             "FANT EN NODE: {:?}, path:len() = {}",
             child.kind(),
@@ -0,0 +1,658 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    os::unix::ffi::OsStrExt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        RwLock,
+    },
+};
+
+use crate::{
+    autonodes::{
+        any::AnyNodeRef, arguments::ArgumentsNode, binary_expression::BinaryExpressionOperator,
+        member_access_expression::MemberAccessExpressionNode,
+        member_call_expression::MemberCallExpressionNode,
+        scoped_call_expression::ScopedCallExpressionNode,
+    },
+    autotree::NodeAccess,
+    config::{TaintSanitizer, TaintSink, TaintSinkKind},
+    issue::{Issue, IssueEmitter, VoidEmitter},
+    locks::RecoverableLock,
+    symboldata::{
+        class::{ClassName, MethodData},
+        FileLocation,
+    },
+    symbols::{FullyQualifiedName, Name},
+    types::union::{DiscreteType, DiscretlyAccessedType, PHPType},
+    value::PHPValue,
+};
+
+use super::state::{AnalysisState, FunctionDataPointer};
+
+///
+/// User input, with the steps it has taken since it entered the program, like
+/// `$_GET` → `$id` → `trim()`
+///
+#[derive(Clone, Debug, PartialEq)]
+pub struct Taint {
+    pub path: Vec<String>,
+    /// The kinds of sinks the input has been made safe for
+    pub sanitized: BTreeSet<TaintSinkKind>,
+}
+
+impl Taint {
+    pub fn new(source: String) -> Self {
+        Self {
+            path: vec![source],
+            sanitized: BTreeSet::new(),
+        }
+    }
+
+    /// The input after passing through `step`, like a variable or a function
+    pub fn through(mut self, step: String) -> Self {
+        if self.path.last() != Some(&step) {
+            self.path.push(step);
+        }
+        self
+    }
+
+    /// The input after being sanitized for `kinds`, unless it's safe for all of them
+    pub fn sanitized_for(mut self, kinds: &[TaintSinkKind]) -> Option<Self> {
+        self.sanitized.extend(kinds.iter().copied());
+        if TaintSinkKind::ALL
+            .iter()
+            .all(|kind| self.sanitized.contains(kind))
+        {
+            None
+        } else {
+            Some(self)
+        }
+    }
+
+    /// Whether the input is dangerous for a sink of `kind`
+    pub fn reaches(&self, kind: TaintSinkKind) -> bool {
+        !self.sanitized.contains(&kind)
+    }
+}
+
+///
+/// Input from either of two origins, like the branches of a ternary. It's only sanitized
+/// for what both of them are sanitized for
+///
+pub fn join(a: Option<Taint>, b: Option<Taint>) -> Option<Taint> {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            let sanitized = a.sanitized.intersection(&b.sanitized).copied().collect();
+            Some(Taint { sanitized, ..a })
+        }
+        (a, b) => a.or(b),
+    }
+}
+
+/// Input which is stored outside of the local scope, and therefore is shared between files
+#[derive(Debug, Default)]
+pub struct TaintData {
+    /// By lowercased function name, or `class::method`
+    pub returns: HashMap<String, Taint>,
+    /// By lowercased `class::property`
+    pub properties: HashMap<String, Taint>,
+    /// The keys of `returns` which have been looked up
+    read_returns: HashSet<String>,
+    /// Whether a return which had been looked up has changed since
+    changed_returns: bool,
+}
+
+impl TaintData {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn read_return(&mut self, key: &str) -> Option<Taint> {
+        if !self.read_returns.contains(key) {
+            self.read_returns.insert(key.to_string());
+        }
+        self.returns.get(key).cloned()
+    }
+
+    fn write_return(&mut self, key: String, taint: Taint) {
+        let existing = self.returns.get(&key).cloned();
+        let Some(joined) = join(existing.clone(), Some(taint)) else {
+            return;
+        };
+        if existing.as_ref() != Some(&joined) {
+            self.changed_returns |= self.read_returns.contains(&key);
+            self.returns.insert(key, joined);
+        }
+    }
+
+    ///
+    /// Whether input returned from a function has changed after it was looked up, since
+    /// the last time this was called. The callers then have to be analyzed again
+    ///
+    pub fn take_changed_returns(&mut self) -> bool {
+        std::mem::take(&mut self.changed_returns)
+    }
+}
+
+///
+/// Emits the issues of the first round of the third pass, and of the later rounds only the
+/// tainted sinks which haven't been emitted already
+///
+pub struct TaintRoundEmitter<'a> {
+    emitter: &'a dyn IssueEmitter,
+    later_round: AtomicBool,
+    emitted_sinks: RwLock<HashSet<String>>,
+}
+
+impl<'a> TaintRoundEmitter<'a> {
+    pub fn new(emitter: &'a dyn IssueEmitter) -> Self {
+        Self {
+            emitter,
+            later_round: AtomicBool::new(false),
+            emitted_sinks: RwLock::new(HashSet::new()),
+        }
+    }
+
+    pub fn next_round(&self) {
+        self.later_round.store(true, Ordering::Relaxed);
+    }
+}
+
+impl IssueEmitter for TaintRoundEmitter<'_> {
+    fn emit(&self, issue: Issue) {
+        if let Issue::TaintedSink(..) = issue {
+            let mut emitted = self.emitted_sinks.write_or_recover();
+            if !emitted.insert(issue.as_string_with_pos()) {
+                return;
+            }
+        } else if self.later_round.load(Ordering::Relaxed) {
+            return;
+        }
+        self.emitter.emit(issue);
+    }
+
+    fn get_status(&self) -> Option<String> {
+        self.emitter.get_status()
+    }
+}
+
+///
+/// Tracks the user input flowing through `node`, and emits when it reaches a sink. Called
+/// after the node itself has been analyzed in the third pass
+///
+pub fn analyze_taint(node: &AnyNodeRef, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
+    match node {
+        AnyNodeRef::AssignmentExpression(a) => {
+            let taint = get_taint(&a.right.as_any(), state);
+            write_taint(&a.left.as_any(), taint, false, state);
+        }
+        AnyNodeRef::ReferenceAssignmentExpression(a) => {
+            let taint = get_taint(&a.right.as_any(), state);
+            write_taint(&a.left.as_any(), taint, false, state);
+        }
+        AnyNodeRef::AugmentedAssignmentExpression(a) => {
+            let taint = get_taint(&a.right.as_any(), state);
+            write_taint(&a.left.as_any(), taint, true, state);
+        }
+        AnyNodeRef::ReturnStatement(r) => {
+            let Some(child) = &r.child else {
+                return;
+            };
+            let Some(taint) = get_taint(&child.as_any(), state) else {
+                return;
+            };
+            let key = match state.in_function_stack.last().and_then(|f| f.data.as_ref()) {
+                Some(FunctionDataPointer::Function(f)) => function_key(&f.read_or_recover().name),
+                Some(FunctionDataPointer::Method(m)) => method_key(&m.read_or_recover()),
+                None => return,
+            };
+            let mut taint_data = state.symbol_data.taint.write_or_recover();
+            taint_data.write_return(key, taint);
+        }
+
+        AnyNodeRef::FunctionCallExpression(call) => {
+            let name = call.get_configured_name(state);
+            check_argument_sinks(&name, &call.arguments, state, emitter);
+        }
+        AnyNodeRef::MemberCallExpression(call) => {
+            for name in get_member_call_names(call, state) {
+                check_argument_sinks(&name, &call.arguments, state, emitter);
+            }
+        }
+        AnyNodeRef::ScopedCallExpression(call) => {
+            if let Some(name) = get_scoped_call_name(call, state) {
+                check_argument_sinks(&name, &call.arguments, state, emitter);
+            }
+        }
+
+        AnyNodeRef::EchoStatement(e) => check_sink("echo", &e.child.as_any(), state, emitter),
+        AnyNodeRef::PrintIntrinsic(p) => check_sink("print", &p.child.as_any(), state, emitter),
+        AnyNodeRef::IncludeExpression(i) => {
+            check_sink("include", &i.child.as_any(), state, emitter)
+        }
+        AnyNodeRef::IncludeOnceExpression(i) => {
+            check_sink("include_once", &i.child.as_any(), state, emitter)
+        }
+        AnyNodeRef::RequireExpression(r) => {
+            check_sink("require", &r.child.as_any(), state, emitter)
+        }
+        AnyNodeRef::RequireOnceExpression(r) => {
+            check_sink("require_once", &r.child.as_any(), state, emitter)
+        }
+        AnyNodeRef::ShellCommandExpression(s) => {
+            for child in &s.children {
+                check_sink("shell_exec", &child.as_any(), state, emitter);
+            }
+        }
+        _ => (),
+    }
+}
+
+///
+/// The user input `node` evaluates to, if any
+///
+pub fn get_taint(node: &AnyNodeRef, state: &mut AnalysisState) -> Option<Taint> {
+    match node {
+        AnyNodeRef::VariableName(vn) => {
+            let var_name = vn.get_variable_name();
+            if state
+                .config
+                .taint
+                .superglobals
+                .iter()
+                .any(|sg| var_name.as_bytes() == sg.as_bytes())
+            {
+                return Some(Taint::new(format!("${}", var_name)));
+            }
+            let var_data = vn.get_var_data(state)?;
+            let data = var_data.read_or_recover();
+            data.taint.clone()
+        }
+        AnyNodeRef::SubscriptExpression(s) => get_taint(&s.dereferenceable.as_any(), state),
+        AnyNodeRef::MemberAccessExpression(ma) => {
+            if let Some(key) = property_key(ma, state) {
+                let taint_data = state.symbol_data.taint.read_or_recover();
+                if let Some(taint) = taint_data.properties.get(&key) {
+                    return Some(taint.clone());
+                }
+            }
+            get_taint(&ma.object.as_any(), state)
+        }
+        AnyNodeRef::NullsafeMemberAccessExpression(ma) => get_taint(&ma.object.as_any(), state),
+
+        AnyNodeRef::FunctionCallExpression(call) => {
+            let name = call.get_configured_name(state);
+            if let Some(taint) = get_source(&name, &call.arguments, state) {
+                return Some(taint);
+            }
+            if let Some(taint) = get_sanitized_taint(&name, &call.arguments, state) {
+                return taint;
+            }
+            if let Some(fdata) = call.get_func_data(state, &VoidEmitter::new()) {
+                let fdata = fdata.read_or_recover();
+                if !fdata.is_native && fdata.position.uri != FileLocation::internal().uri {
+                    return get_return_taint(&function_key(&fdata.name), &name, state);
+                }
+            }
+            let utype = call.get_utype(state, &VoidEmitter::new());
+            get_native_call_taint(&name, &call.arguments, utype, state)
+        }
+        AnyNodeRef::MemberCallExpression(call) => {
+            let mut taint = None;
+            for (key, name) in get_member_call_keys(call, state) {
+                taint = join(
+                    taint,
+                    get_sanitized_taint(&name, &call.arguments, state)
+                        .unwrap_or_else(|| get_return_taint(&key, &name, state)),
+                );
+            }
+            taint
+        }
+        AnyNodeRef::ScopedCallExpression(call) => {
+            let name = get_scoped_call_name(call, state)?;
+            let (_, mdata) = call.get_method_data(state)?;
+            get_sanitized_taint(&name, &call.arguments, state)
+                .unwrap_or_else(|| get_return_taint(&method_key(&mdata), &name, state))
+        }
+
+        AnyNodeRef::CastExpression(c) => match c.type_.get_raw().to_ascii_lowercase().as_bytes() {
+            b"int" | b"integer" | b"float" | b"double" | b"bool" | b"boolean" => None,
+            _ => get_taint(&c.value.as_any(), state),
+        },
+        AnyNodeRef::BinaryExpression(b) => match &*b.operator {
+            BinaryExpressionOperator::Concat(_) | BinaryExpressionOperator::NullCoalesce(_) => {
+                join(
+                    get_taint(&b.left.as_any(), state),
+                    get_taint(&b.right.as_any(), state),
+                )
+            }
+            _ => None,
+        },
+        AnyNodeRef::ConditionalExpression(c) => {
+            let body = if let Some(body) = &c.body {
+                get_taint(&body.as_any(), state)
+            } else {
+                get_taint(&c.condition.as_any(), state)
+            };
+            join(body, get_taint(&c.alternative.as_any(), state))
+        }
+        AnyNodeRef::AssignmentExpression(a) => get_taint(&a.right.as_any(), state),
+
+        AnyNodeRef::UnaryOpExpression(_)
+        | AnyNodeRef::UpdateExpression(_)
+        | AnyNodeRef::Integer(_)
+        | AnyNodeRef::Float(_)
+        | AnyNodeRef::Boolean(_)
+        | AnyNodeRef::Null(_)
+        | AnyNodeRef::AnonymousFunctionCreationExpression(_)
+        | AnyNodeRef::ArrowFunction(_)
+        | AnyNodeRef::ObjectCreationExpression(_)
+        | AnyNodeRef::ShellCommandExpression(_)
+        | AnyNodeRef::ClassConstantAccessExpression(_) => None,
+
+        // Strings with interpolation, arrays, parentheses and the like carry the input of
+        // their children
+        _ => {
+            for child in node.children_any() {
+                if let Some(taint) = get_taint(&child, state) {
+                    return Some(taint);
+                }
+            }
+            None
+        }
+    }
+}
+
+///
+/// Stores the input written to `target`. When `append` is set, the input is added to what
+/// it already holds, like for `.=`
+///
+fn write_taint(target: &AnyNodeRef, taint: Option<Taint>, append: bool, state: &mut AnalysisState) {
+    match target {
+        AnyNodeRef::VariableName(vn) => {
+            let Some(var_data) = vn.get_var_data(state) else {
+                return;
+            };
+            let step = format!("${}", vn.get_variable_name());
+            let mut data = var_data.write_or_recover();
+            let taint = taint.map(|t| t.through(step));
+            data.taint = if append {
+                join(data.taint.take(), taint)
+            } else {
+                taint
+            };
+        }
+        // Writing to a part of an array or string taints all of it
+        AnyNodeRef::SubscriptExpression(s) if taint.is_some() => {
+            write_taint(&s.dereferenceable.as_any(), taint, true, state);
+        }
+        AnyNodeRef::MemberAccessExpression(ma) => {
+            let Some(taint) = taint else {
+                return;
+            };
+            let Some(key) = property_key(ma, state) else {
+                return;
+            };
+            let step = if let Some(pname) = ma.get_property_name(state, &VoidEmitter::new()) {
+                format!("->{}", pname)
+            } else {
+                key.clone()
+            };
+            let mut taint_data = state.symbol_data.taint.write_or_recover();
+            let existing = taint_data.properties.remove(&key);
+            if let Some(joined) = join(existing, Some(taint.through(step))) {
+                taint_data.properties.insert(key, joined);
+            }
+        }
+        AnyNodeRef::ListLiteral(_) | AnyNodeRef::ByRef(_) => {
+            for child in target.children_any() {
+                write_taint(&child, taint.clone(), append, state);
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Writes the input of the traversed value to the key and value of a `foreach`
+pub fn write_traversal_taint(
+    traversable: &AnyNodeRef,
+    targets: &[AnyNodeRef],
+    state: &mut AnalysisState,
+) {
+    let taint = get_taint(traversable, state);
+    for target in targets {
+        write_taint(target, taint.clone(), false, state);
+    }
+}
+
+fn check_sink(name: &str, arg: &AnyNodeRef, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
+    let sinks = get_sinks(name, state);
+    if sinks.is_empty() {
+        return;
+    }
+    let Some(taint) = get_taint(arg, state) else {
+        return;
+    };
+    for sink in sinks {
+        emit_if_reaching(&sink, &taint, arg, state, emitter);
+    }
+}
+
+fn check_argument_sinks(
+    name: &str,
+    arguments: &ArgumentsNode,
+    state: &mut AnalysisState,
+    emitter: &dyn IssueEmitter,
+) {
+    let sinks = get_sinks(name, state);
+    if sinks.is_empty() {
+        return;
+    }
+    for (idx, argument) in arguments.get_argument_nodes().enumerate() {
+        let arg = argument.as_any();
+        for sink in &sinks {
+            if sink.argument.is_some_and(|sink_idx| sink_idx != idx) {
+                continue;
+            }
+            if let Some(taint) = get_taint(&arg, state) {
+                emit_if_reaching(sink, &taint, &arg, state, emitter);
+            }
+        }
+    }
+}
+
+fn emit_if_reaching(
+    sink: &TaintSink,
+    taint: &Taint,
+    arg: &AnyNodeRef,
+    state: &mut AnalysisState,
+    emitter: &dyn IssueEmitter,
+) {
+    if !taint.reaches(sink.kind) {
+        return;
+    }
+    emitter.emit(Issue::TaintedSink(
+        arg.pos(state),
+        sink.kind,
+        Name::from(sink.function.as_str()),
+        taint.path.clone(),
+    ));
+}
+
+fn get_sinks(name: &str, state: &AnalysisState) -> Vec<TaintSink> {
+    state
+        .config
+        .taint
+        .sinks
+        .iter()
+        .filter(|sink| sink.function.eq_ignore_ascii_case(name))
+        .cloned()
+        .collect()
+}
+
+fn get_sanitizer(name: &str, state: &AnalysisState) -> Option<TaintSanitizer> {
+    state
+        .config
+        .taint
+        .sanitizers
+        .iter()
+        .find(|sanitizer| sanitizer.function.eq_ignore_ascii_case(name))
+        .cloned()
+}
+
+/// The input returned by a source, like `file_get_contents('php://input')`
+fn get_source(name: &str, arguments: &ArgumentsNode, state: &mut AnalysisState) -> Option<Taint> {
+    let source = state
+        .config
+        .taint
+        .sources
+        .iter()
+        .find(|source| source.function.eq_ignore_ascii_case(name))?
+        .clone();
+    let Some(expected) = source.argument else {
+        return Some(Taint::new(format!("{}()", name)));
+    };
+    let first = arguments
+        .get_argument_values(state, &VoidEmitter::new())
+        .into_iter()
+        .next()??;
+    match first {
+        PHPValue::String(s) if s.as_bytes() == expected.as_bytes() => {
+            Some(Taint::new(format!("{}('{}')", name, expected)))
+        }
+        _ => None,
+    }
+}
+
+///
+/// The input returned by a sanitizer. The outer `None` means `name` isn't a sanitizer,
+/// and the inner that the sanitized value is safe
+///
+fn get_sanitized_taint(
+    name: &str,
+    arguments: &ArgumentsNode,
+    state: &mut AnalysisState,
+) -> Option<Option<Taint>> {
+    let sanitizer = get_sanitizer(name, state)?;
+    let argument = arguments.get_argument_nodes().next();
+    let taint = argument.and_then(|argument| get_taint(&argument.as_any(), state));
+    Some(taint.and_then(|t| {
+        t.through(format!("{}()", name))
+            .sanitized_for(&sanitizer.kinds)
+    }))
+}
+
+fn get_return_taint(key: &str, name: &str, state: &AnalysisState) -> Option<Taint> {
+    let mut taint_data = state.symbol_data.taint.write_or_recover();
+    taint_data
+        .read_return(key)
+        .map(|t| t.through(format!("{}()", name)))
+}
+
+///
+/// The input returned by a native function. Unless it's a known source or sanitizer, we
+/// assume that functions able to return strings or arrays pass on the input they're given,
+/// like `trim()` or `sprintf()`
+///
+fn get_native_call_taint(
+    name: &str,
+    arguments: &ArgumentsNode,
+    utype: Option<PHPType>,
+    state: &mut AnalysisState,
+) -> Option<Taint> {
+    if let Some(utype) = utype {
+        if !may_carry_input(&utype) {
+            return None;
+        }
+    }
+    let mut taint = None;
+    for argument in arguments.get_argument_nodes() {
+        taint = join(taint, get_taint(&argument.as_any(), state));
+    }
+    taint.map(|t| t.through(format!("{}()", name)))
+}
+
+/// Whether a value of `utype` can hold user input. Numbers and booleans are harmless
+fn may_carry_input(utype: &PHPType) -> bool {
+    utype
+        .as_discrete_variants()
+        .iter()
+        .any(|variant| match variant {
+            DiscretlyAccessedType::Discrete(dtype) => !matches!(
+                dtype,
+                DiscreteType::NULL
+                    | DiscreteType::Void
+                    | DiscreteType::Never
+                    | DiscreteType::Int
+                    | DiscreteType::IntLiteral(_)
+                    | DiscreteType::IntRange(_, _)
+                    | DiscreteType::Float
+                    | DiscreteType::Bool
+                    | DiscreteType::True
+                    | DiscreteType::False
+            ),
+            DiscretlyAccessedType::Intersection(_) => true,
+        })
+}
+
+/// `Class::method` for each class the object of the call could be an instance of
+fn get_member_call_names(
+    call: &MemberCallExpressionNode,
+    state: &mut AnalysisState,
+) -> Vec<String> {
+    get_member_call_keys(call, state)
+        .into_iter()
+        .map(|(_, name)| name)
+        .collect()
+}
+
+/// The key of each method that could be called, with the name it's configured with
+fn get_member_call_keys(
+    call: &MemberCallExpressionNode,
+    state: &mut AnalysisState,
+) -> Vec<(String, String)> {
+    call.get_methods_data(state)
+        .into_iter()
+        .flatten()
+        .map(|(_, mdata)| {
+            (
+                method_key(&mdata),
+                format!("{}::{}", class_name(&mdata.declared_in), mdata.name),
+            )
+        })
+        .collect()
+}
+
+fn get_scoped_call_name(
+    call: &ScopedCallExpressionNode,
+    state: &mut AnalysisState,
+) -> Option<String> {
+    let (_, mdata) = call.get_method_data(state)?;
+    Some(format!(
+        "{}::{}",
+        class_name(&mdata.declared_in),
+        mdata.name
+    ))
+}
+
+fn class_name(cname: &ClassName) -> String {
+    cname
+        .get_fq_name()
+        .to_string()
+        .trim_start_matches('\\')
+        .to_string()
+}
+
+fn function_key(fq_name: &FullyQualifiedName) -> String {
+    fq_name.to_string().to_ascii_lowercase()
+}
+
+fn method_key(mdata: &MethodData) -> String {
+    format!("{}::{}", mdata.declared_in.get_fq_name(), mdata.name).to_ascii_lowercase()
+}
+
+fn property_key(ma: &MemberAccessExpressionNode, state: &mut AnalysisState) -> Option<String> {
+    let cname = ma.get_class_name(state)?;
+    let pname = ma.get_property_name(state, &VoidEmitter::new())?;
+    Some(format!("{}::{}", cname.get_fq_name(), pname).to_ascii_lowercase())
+}
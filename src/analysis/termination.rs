@@ -0,0 +1,79 @@
+use crate::{
+    autonodes::{
+        any::AnyNodeRef,
+        function_call_expression::FunctionCallExpressionNode,
+        if_statement::{IfStatementAlternative, IfStatementNode},
+    },
+    autotree::NodeAccess,
+    issue::VoidEmitter,
+    types::union::{DiscreteType, PHPType},
+};
+
+use super::state::AnalysisState;
+
+///
+/// Whether execution never continues past the node, because it always returns, throws, exits,
+/// breaks or continues, or calls something which never returns.
+///
+/// This is used to find guard clauses, like `if (!$x) { return; }`, where the code following
+/// the `if` only is reached when the condition was false.
+pub fn always_terminates(node: &AnyNodeRef, state: &mut AnalysisState) -> bool {
+    let emitter = VoidEmitter::new();
+    match node {
+        AnyNodeRef::ReturnStatement(_)
+        | AnyNodeRef::BreakStatement(_)
+        | AnyNodeRef::ContinueStatement(_)
+        | AnyNodeRef::ThrowExpression(_) => true,
+        AnyNodeRef::CompoundStatement(c) => c
+            .children
+            .iter()
+            .any(|statement| always_terminates(&statement.as_any(), state)),
+        AnyNodeRef::ColonBlock(c) => c
+            .children
+            .iter()
+            .any(|statement| always_terminates(&statement.as_any(), state)),
+        AnyNodeRef::ExpressionStatement(e) => always_terminates(&e.child.as_any(), state),
+        AnyNodeRef::ParenthesizedExpression(p) => always_terminates(&p.child.as_any(), state),
+        AnyNodeRef::IfStatement(i) => if_always_terminates(i, state),
+        AnyNodeRef::ElseClause(e) => always_terminates(&e.body.as_any(), state),
+        AnyNodeRef::ElseIfClause(e) => always_terminates(&e.body.as_any(), state),
+        AnyNodeRef::FunctionCallExpression(f) => function_call_never_returns(f, state),
+        AnyNodeRef::MemberCallExpression(m) => is_never(m.get_utype(state, &emitter)),
+        AnyNodeRef::NullsafeMemberCallExpression(m) => is_never(m.get_utype(state, &emitter)),
+        AnyNodeRef::ScopedCallExpression(s) => is_never(s.get_utype(state, &emitter)),
+        _ => false,
+    }
+}
+
+/// Every branch terminates, and there is an `else` so one of them is taken
+fn if_always_terminates(if_node: &IfStatementNode, state: &mut AnalysisState) -> bool {
+    let Some(alternatives) = &if_node.alternative else {
+        return false;
+    };
+    let has_else = alternatives
+        .iter()
+        .any(|alt| matches!(&**alt, IfStatementAlternative::ElseClause(_)));
+    has_else
+        && always_terminates(&if_node.body.as_any(), state)
+        && alternatives
+            .iter()
+            .all(|alt| always_terminates(&alt.as_any(), state))
+}
+
+fn function_call_never_returns(
+    call: &FunctionCallExpressionNode,
+    state: &mut AnalysisState,
+) -> bool {
+    let emitter = VoidEmitter::new();
+    if let Some(fq_name) = call.get_fq_function_name(state, &emitter) {
+        let lc_name = fq_name.to_ascii_lowercase();
+        if lc_name == b"\\exit" as &[u8] || lc_name == b"\\die" as &[u8] {
+            return true;
+        }
+    }
+    is_never(call.get_utype(state, &emitter))
+}
+
+fn is_never(utype: Option<PHPType>) -> bool {
+    matches!(utype, Some(PHPType::Discrete(d)) if matches!(*d, DiscreteType::Never))
+}
@@ -1010,6 +1010,7 @@ impl<'a> NodeAccess for AnyNodeRef<'a> {
 
 #[derive(Debug, Clone)]
 pub enum AnyNode {
+    Error(Box<ErrorNode>),
     _Expression(Box<_ExpressionNode>),
     _Literal(Box<_LiteralNode>),
     _PrimaryExpression(Box<_PrimaryExpressionNode>),
@@ -1180,6 +1181,7 @@ impl AnyNode {
         Ok(match node.kind() {
             // "comment" =>
             // "text_interpolation" =>
+            "ERROR" => AnyNode::Error(Box::new(ErrorNode::parse(node, source)?)),
             "_expression" => AnyNode::_Expression(Box::new(_ExpressionNode::parse(node, source)?)),
             "_literal" => AnyNode::_Literal(Box::new(_LiteralNode::parse(node, source)?)),
             "_primary_expression" => {
@@ -1610,6 +1612,7 @@ impl AnyNode {
 impl NodeAccess for AnyNode {
     fn brief_desc(&self) -> String {
         match self {
+            AnyNode::Error(x) => x.brief_desc(),
             AnyNode::_Expression(x) => x.brief_desc(),
             AnyNode::_Literal(x) => x.brief_desc(),
             AnyNode::_PrimaryExpression(x) => x.brief_desc(),
@@ -1784,6 +1787,7 @@ impl NodeAccess for AnyNode {
 
     fn range(&self) -> Range {
         match self {
+            AnyNode::Error(x) => x.range(),
             AnyNode::_Expression(x) => x.range(),
             AnyNode::_Literal(x) => x.range(),
             AnyNode::_PrimaryExpression(x) => x.range(),
@@ -1958,6 +1962,7 @@ impl NodeAccess for AnyNode {
 
     fn as_any(&self) -> AnyNodeRef<'_> {
         match self {
+            AnyNode::Error(x) => x.as_any(),
             AnyNode::_Expression(x) => x.as_any(),
             AnyNode::_Literal(x) => x.as_any(),
             AnyNode::_PrimaryExpression(x) => x.as_any(),
@@ -5,6 +5,7 @@ use crate::autotree::ChildNodeParser;
 use crate::autotree::NodeAccess;
 use crate::autotree::NodeParser;
 use crate::autotree::ParseError;
+use crate::autotree::RecoveringNodeParser;
 use crate::extra::ExtraChild;
 use crate::parser::Range;
 use tree_sitter::Node;
@@ -31,12 +32,12 @@ impl NodeParser for CaseStatementNode {
         Ok(Self {
             range,
             value,
-            children: _StatementNode::parse_vec(
+            children: _StatementNode::parse_vec_recovering(
                 node.named_children(&mut node.walk())
                     .filter(|node| !skip_nodes.contains(&node.id()))
                     .filter(|node| node.kind() != "comment"),
                 source,
-            )?,
+            ),
             extras: ExtraChild::parse_vec(
                 node.named_children(&mut node.walk())
                     .filter(|node| node.kind() == "comment")
@@ -3,6 +3,7 @@ use crate::autonodes::any::AnyNodeRef;
 use crate::autotree::NodeAccess;
 use crate::autotree::NodeParser;
 use crate::autotree::ParseError;
+use crate::autotree::RecoveringNodeParser;
 use crate::extra::ExtraChild;
 use crate::parser::Range;
 use tree_sitter::Node;
@@ -23,11 +24,11 @@ impl NodeParser for CompoundStatementNode {
 
         Ok(Self {
             range,
-            children: _StatementNode::parse_vec(
+            children: _StatementNode::parse_vec_recovering(
                 node.named_children(&mut node.walk())
                     .filter(|node| node.kind() != "comment"),
                 source,
-            )?,
+            ),
             extras: ExtraChild::parse_vec(
                 node.named_children(&mut node.walk())
                     .filter(|node| node.kind() == "comment"),
@@ -9,6 +9,7 @@ use crate::autonodes::use_declaration::UseDeclarationNode;
 use crate::autotree::NodeAccess;
 use crate::autotree::NodeParser;
 use crate::autotree::ParseError;
+use crate::autotree::RecoveringNodeParser;
 use crate::errornode::ErrorNode;
 use crate::extra::ExtraChild;
 use crate::issue::IssueEmitter;
@@ -225,11 +226,11 @@ impl NodeParser for DeclarationListNode {
 
         Ok(Self {
             range,
-            children: DeclarationListChildren::parse_vec(
+            children: DeclarationListChildren::parse_vec_recovering(
                 node.named_children(&mut node.walk())
                     .filter(|node| node.kind() != "comment"),
                 source,
-            )?,
+            ),
             extras: ExtraChild::parse_vec(
                 node.named_children(&mut node.walk())
                     .filter(|node| node.kind() == "comment"),
@@ -2,6 +2,7 @@
 use std::marker::PhantomData;
 
 use crate::autonodes::program::ProgramNode;
+use crate::errornode::ErrorNode;
 use crate::issue::IssuePosition;
 use crate::{analysis::state::AnalysisState, autonodes::any::AnyNodeRef};
 // use tree_sitter::Range;
@@ -32,6 +33,8 @@ impl ParseError {
 pub struct AutoTree {
     pub tree: Tree,
     pub root: Box<ProgramNode>,
+    /// Every syntax error the parser recovered from, in source order
+    pub syntax_errors: Vec<ParseError>,
 }
 
 impl AutoTree {
@@ -46,8 +49,52 @@ impl AutoTree {
         // Usefull if parsing failes:
         // Self::debug_dump_tree(&tree);
         let root = Box::new(ProgramNode::parse(root_node, &source)?);
+        let mut syntax_errors = vec![];
+        Self::collect_syntax_errors(root_node, &source, &mut syntax_errors);
 
-        Ok(AutoTree { tree, root })
+        Ok(AutoTree {
+            tree,
+            root,
+            syntax_errors,
+        })
+    }
+
+    fn collect_syntax_errors(node: Node, source: &[u8], errors: &mut Vec<ParseError>) {
+        if node.is_missing() {
+            let expected = if node.is_named() {
+                node.kind().to_string()
+            } else {
+                format!("`{}`", node.kind())
+            };
+            errors.push(ParseError::new(
+                node.range(),
+                format!("missing {}", expected),
+            ));
+        } else if node.is_error() {
+            let message = match Self::excerpt(node, source) {
+                Some(excerpt) => format!("unexpected `{}`", excerpt),
+                None => "unexpected input".to_string(),
+            };
+            errors.push(ParseError::new(node.range(), message));
+        } else if node.has_error() {
+            for child in node.children(&mut node.walk()) {
+                Self::collect_syntax_errors(child, source, errors);
+            }
+        }
+    }
+
+    ///
+    /// The first non-empty line of the source of `node`, shortened if it's long
+    ///
+    fn excerpt(node: Node, source: &[u8]) -> Option<String> {
+        const MAX_CHARS: usize = 40;
+        let raw = String::from_utf8_lossy(&source[node.start_byte()..node.end_byte()]);
+        let line = raw.lines().map(str::trim).find(|line| !line.is_empty())?;
+        Some(if line.chars().count() > MAX_CHARS {
+            format!("{}...", line.chars().take(MAX_CHARS).collect::<String>())
+        } else {
+            line.to_string()
+        })
     }
 
     pub fn debug_dump(&self) {
@@ -251,6 +298,29 @@ pub trait NodeParser {
     }
 }
 
+///
+/// Children of statement lists. A child which can't be converted is kept in place as an
+/// `ErrorNode`, so one broken statement doesn't fail the conversion of the whole file
+///
+pub trait RecoveringNodeParser: NodeParser + Sized {
+    fn from_error(error: ErrorNode) -> Self;
+
+    fn parse_vec_recovering<'a, I>(children: I, source: &[u8]) -> Vec<Box<Self>>
+    where
+        I: Iterator<Item = Node<'a>>,
+    {
+        children
+            .map(|child| {
+                Box::new(
+                    Self::parse(child, source).unwrap_or_else(|err| {
+                        Self::from_error(ErrorNode::recover(child, source, err))
+                    }),
+                )
+            })
+            .collect()
+    }
+}
+
 pub struct ChildNodeParserHelper<'node, 'source, 'skipped, T> {
     node: &'node Node<'node>,
     fieldname: &'static str,
@@ -486,9 +556,6 @@ where
         self
     }
 
-    pub fn boxed(self) -> NodeParserBuilder<'node, 'skip_nodes, Box<TParseResult>, TFMap> {
-        todo!();
-    }
     /*  pub fn boxed(mut self) -> NodeParserBuilder<'node, 'skip_nodes, Box<TParseResult>, TFBoxMap>
     where {
             let mut mapper = self.map;
@@ -3,15 +3,6 @@ pub struct PHPDocConfig {
     pub known_tags: &'static [&'static str],
 }
 
-#[derive(Clone, Debug, Copy, Default)]
-pub struct PHPConfigVersionRequirements {}
-
-impl PHPConfigVersionRequirements {
-    pub fn is_less_than(&self, major: u8, minor: u8, patchlevel: u8) -> bool {
-        true
-    }
-}
-
 impl Default for PHPDocConfig {
     fn default() -> Self {
         Self {
@@ -23,13 +14,367 @@ impl Default for PHPDocConfig {
                 "subpackage",
                 "runTestsInSeparateProcesses",
                 "log",
+                "api",
+            ],
+        }
+    }
+}
+
+/// Symbols which are used from outside the analyzed code, and therefore never
+/// should be reported as unused
+#[derive(Clone, Debug, Copy)]
+pub struct DeadCodeConfig {
+    /// Whether unused symbols are reported when a whole project is analyzed
+    pub enabled: bool,
+    /// Classes with a name ending with one of these, are entry points
+    pub class_name_suffixes: &'static [&'static str],
+    /// Symbols documented with one of these phpdoc-tags are entry points
+    pub phpdoc_tags: &'static [&'static str],
+    /// Symbols with one of these attributes are entry points
+    pub attributes: &'static [&'static str],
+}
+
+impl Default for DeadCodeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            class_name_suffixes: &["Controller"],
+            phpdoc_tags: &["api"],
+            attributes: &[],
+        }
+    }
+}
+
+/// A PHP-version, like `8.3`
+#[derive(Clone, Debug, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PHPVersion {
+    pub major: u8,
+    pub minor: u8,
+}
+
+impl PHPVersion {
+    pub const fn new(major: u8, minor: u8) -> Self {
+        Self { major, minor }
+    }
+}
+
+impl Default for PHPVersion {
+    fn default() -> Self {
+        Self::new(8, 3)
+    }
+}
+
+impl std::fmt::Display for PHPVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// Which native symbols, functions and classes provided by PHP itself, are known. The
+/// version of them follows [PHPAnalyzeConfig::php_version]
+#[derive(Clone, Debug, Copy)]
+pub struct NativeConfig {
+    /// Extensions which are enabled in addition to the bundled ones, like `intl`,
+    /// `mbstring`, `redis` or `pdo`
+    pub extensions: &'static [&'static str],
+    /// PHP-files with declarations only, whose symbols are registered as native
+    pub stub_files: &'static [&'static str],
+}
+
+impl Default for NativeConfig {
+    fn default() -> Self {
+        Self {
+            extensions: &[
+                "ctype",
+                "curl",
+                "dom",
+                "fileinfo",
+                "filter",
+                "iconv",
+                "json",
+                "libxml",
+                "mbstring",
+                "openssl",
+                "pdo",
+                "session",
+                "simplexml",
+                "tokenizer",
+                "xml",
+                "xmlreader",
+                "xmlwriter",
+                "zlib",
+            ],
+            stub_files: &[],
+        }
+    }
+}
+
+/// What user input could be abused for, when it reaches a sink unsanitized
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TaintSinkKind {
+    Sql,
+    Shell,
+    Eval,
+    Include,
+    Html,
+}
+
+impl TaintSinkKind {
+    pub const ALL: &'static [TaintSinkKind] = &[
+        TaintSinkKind::Sql,
+        TaintSinkKind::Shell,
+        TaintSinkKind::Eval,
+        TaintSinkKind::Include,
+        TaintSinkKind::Html,
+    ];
+}
+
+impl std::fmt::Display for TaintSinkKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Sql => write!(f, "SQL query"),
+            Self::Shell => write!(f, "shell command"),
+            Self::Eval => write!(f, "evaluated code"),
+            Self::Include => write!(f, "included file"),
+            Self::Html => write!(f, "HTML output"),
+        }
+    }
+}
+
+///
+/// A function returning user input. Functions are named like `getallheaders`, and methods
+/// like `PDO::query`, with the class they're declared in
+///
+#[derive(Clone, Debug)]
+pub struct TaintSource {
+    pub function: String,
+    /// Only when the first argument is this value, like `php://input`
+    pub argument: Option<String>,
+}
+
+///
+/// A function, method or language construct which is dangerous to pass user input to.
+/// Language constructs are named like `echo`, `include` or `eval`, and backtick shell
+/// commands are checked as `shell_exec`
+///
+#[derive(Clone, Debug)]
+pub struct TaintSink {
+    pub function: String,
+    /// The argument passed to the sink, or `None` for all of them
+    pub argument: Option<usize>,
+    pub kind: TaintSinkKind,
+}
+
+/// A function whose return value is safe to pass to sinks of `kinds`
+#[derive(Clone, Debug)]
+pub struct TaintSanitizer {
+    pub function: String,
+    pub kinds: Vec<TaintSinkKind>,
+}
+
+///
+/// Tracking of user input to the places it could be abused. Values bound to prepared
+/// statements never reach a sink, so only the query given to `prepare()` is checked.
+///
+/// The defaults cover PHP itself, and can be extended with the sources, sinks and
+/// sanitizers of the frameworks in use
+///
+#[derive(Clone, Debug)]
+pub struct TaintConfig {
+    /// Superglobals with user input, without the `$`
+    pub superglobals: &'static [&'static str],
+    pub sources: Vec<TaintSource>,
+    pub sinks: Vec<TaintSink>,
+    pub sanitizers: Vec<TaintSanitizer>,
+}
+
+impl Default for TaintConfig {
+    fn default() -> Self {
+        use TaintSinkKind::*;
+        fn source(function: &str, argument: Option<&str>) -> TaintSource {
+            TaintSource {
+                function: function.into(),
+                argument: argument.map(Into::into),
+            }
+        }
+        fn sink(function: &str, argument: usize, kind: TaintSinkKind) -> TaintSink {
+            TaintSink {
+                function: function.into(),
+                argument: Some(argument),
+                kind,
+            }
+        }
+        fn sink_all(function: &str, kind: TaintSinkKind) -> TaintSink {
+            TaintSink {
+                function: function.into(),
+                argument: None,
+                kind,
+            }
+        }
+        fn sanitizer(function: &str, kinds: &[TaintSinkKind]) -> TaintSanitizer {
+            TaintSanitizer {
+                function: function.into(),
+                kinds: kinds.to_vec(),
+            }
+        }
+        // Hex digits and numbers can't break out of a quoted string or a command argument,
+        // but can still be used to pick which file to include or what code to evaluate
+        const HASHED: &[TaintSinkKind] = &[Sql, Shell, Html];
+        Self {
+            superglobals: &["_GET", "_POST", "_COOKIE", "_REQUEST", "_SERVER", "_FILES"],
+            sources: vec![
+                source("file_get_contents", Some("php://input")),
+                source("getallheaders", None),
+                source("apache_request_headers", None),
+            ],
+            sinks: vec![
+                sink("mysqli_query", 1, Sql),
+                sink("mysqli_real_query", 1, Sql),
+                sink("mysqli_multi_query", 1, Sql),
+                sink("mysqli_prepare", 1, Sql),
+                sink_all("pg_query", Sql),
+                sink_all("pg_send_query", Sql),
+                sink_all("pg_prepare", Sql),
+                sink("mysqli::query", 0, Sql),
+                sink("mysqli::real_query", 0, Sql),
+                sink("mysqli::multi_query", 0, Sql),
+                sink("mysqli::prepare", 0, Sql),
+                sink("PDO::query", 0, Sql),
+                sink("PDO::exec", 0, Sql),
+                sink("PDO::prepare", 0, Sql),
+                sink("SQLite3::query", 0, Sql),
+                sink("SQLite3::exec", 0, Sql),
+                sink("SQLite3::querySingle", 0, Sql),
+                sink("SQLite3::prepare", 0, Sql),
+                sink("exec", 0, Shell),
+                sink("system", 0, Shell),
+                sink("passthru", 0, Shell),
+                sink("shell_exec", 0, Shell),
+                sink("popen", 0, Shell),
+                sink("proc_open", 0, Shell),
+                sink("pcntl_exec", 0, Shell),
+                sink("eval", 0, Eval),
+                sink("assert", 0, Eval),
+                sink("create_function", 1, Eval),
+                sink("include", 0, Include),
+                sink("include_once", 0, Include),
+                sink("require", 0, Include),
+                sink("require_once", 0, Include),
+                sink_all("echo", Html),
+                sink("print", 0, Html),
+                sink_all("printf", Html),
+                sink_all("vprintf", Html),
+            ],
+            sanitizers: vec![
+                sanitizer("htmlspecialchars", &[Html]),
+                sanitizer("htmlentities", &[Html]),
+                sanitizer("strip_tags", &[Html]),
+                sanitizer("urlencode", &[Html]),
+                sanitizer("rawurlencode", &[Html]),
+                sanitizer("escapeshellarg", &[Shell]),
+                sanitizer("escapeshellcmd", &[Shell]),
+                sanitizer("basename", &[Include]),
+                sanitizer("mysqli_real_escape_string", &[Sql]),
+                sanitizer("mysqli::real_escape_string", &[Sql]),
+                sanitizer("pg_escape_string", &[Sql]),
+                sanitizer("pg_escape_literal", &[Sql]),
+                sanitizer("PDO::quote", &[Sql]),
+                sanitizer("SQLite3::escapeString", &[Sql]),
+                sanitizer("intval", TaintSinkKind::ALL),
+                sanitizer("floatval", TaintSinkKind::ALL),
+                sanitizer("boolval", TaintSinkKind::ALL),
+                sanitizer("md5", HASHED),
+                sanitizer("sha1", HASHED),
+                sanitizer("hash", HASHED),
+                sanitizer("crc32", HASHED),
+                sanitizer("bin2hex", HASHED),
+            ],
+        }
+    }
+}
+
+///
+/// Rules flagging constructs which are dangerous regardless of where their input comes from.
+/// Each rule can be turned off on its own
+///
+#[derive(Clone, Debug, Copy)]
+pub struct SecurityConfig {
+    /// `eval()`
+    pub eval: bool,
+    /// Shell commands in backticks
+    pub shell_command: bool,
+    /// `extract()`, which can overwrite any variable in scope
+    pub extract: bool,
+    /// `unserialize()` of anything but a constant, which can instantiate arbitrary classes
+    pub unserialize: bool,
+    /// `assert()` with a string, which is evaluated as code before PHP 8
+    pub assert_string: bool,
+    /// `preg_replace()` with the `/e` modifier, which evaluates the replacement as code
+    pub preg_replace_eval: bool,
+    /// Passwords hashed with one of the `weak_hash_functions`
+    pub weak_password_hash: bool,
+    /// Tokens made with one of the `insecure_random_functions`
+    pub insecure_random: bool,
+    /// Hashes compared with `==`, where `"0e123"` is equal to `"0e456"`
+    pub loose_hash_comparison: bool,
+    /// Errors suppressed with `@`
+    pub error_suppression: bool,
+
+    /// Hash functions which are too fast to store passwords with
+    pub weak_hash_functions: &'static [&'static str],
+    /// Functions returning predictable values
+    pub insecure_random_functions: &'static [&'static str],
+    /// Functions returning hashes
+    pub hash_functions: &'static [&'static str],
+    /// Parts of the names of variables, properties and keys holding passwords
+    pub password_names: &'static [&'static str],
+    /// Parts of the names of variables, properties, keys and functions holding tokens
+    pub token_names: &'static [&'static str],
+    /// Parts of the names of variables, properties and keys holding hashes
+    pub hash_names: &'static [&'static str],
+}
+
+impl Default for SecurityConfig {
+    fn default() -> Self {
+        Self {
+            eval: true,
+            shell_command: true,
+            extract: true,
+            unserialize: true,
+            assert_string: true,
+            preg_replace_eval: true,
+            weak_password_hash: true,
+            insecure_random: true,
+            loose_hash_comparison: true,
+            error_suppression: true,
+
+            weak_hash_functions: &["md5", "sha1"],
+            insecure_random_functions: &["rand", "mt_rand", "uniqid", "lcg_value", "str_shuffle"],
+            hash_functions: &[
+                "md5",
+                "sha1",
+                "hash",
+                "hash_hmac",
+                "crypt",
+                "md5_file",
+                "sha1_file",
+                "hash_file",
             ],
+            password_names: &["password", "passwd", "passphrase", "pwd"],
+            token_names: &["token", "nonce", "salt", "secret", "csrf"],
+            hash_names: &["hash", "digest", "signature", "hmac"],
         }
     }
 }
 
-#[derive(Default, Clone, Copy, Debug)]
+#[derive(Default, Clone, Debug)]
 pub struct PHPAnalyzeConfig {
     pub phpdoc: PHPDocConfig,
-    pub php_version: PHPConfigVersionRequirements,
+    /// The version of PHP the code runs on. If the native symbols of this version
+    /// aren't compiled in, the closest older version is used
+    pub php_version: PHPVersion,
+    pub dead_code: DeadCodeConfig,
+    pub native: NativeConfig,
+    pub taint: TaintConfig,
+    pub security: SecurityConfig,
 }
@@ -1,3 +1,4 @@
+use crate::locks::RecoverableLock;
 use crate::{
     analysis::state::AnalysisState,
     autonodes::{any::AnyNodeRef, method_declaration::MethodDeclarationNode},
@@ -17,7 +18,7 @@ impl NodeDescription for MethodDeclarationNode {
     ) -> Option<String> {
         let locked_data = self.get_method_data(state)?;
 
-        let data = locked_data.read().ok()?;
+        let data = locked_data.read_or_recover();
 
         let class_name = self.get_class_name(state)?;
 
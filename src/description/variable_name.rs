@@ -1,3 +1,4 @@
+use crate::locks::RecoverableLock;
 use crate::{
     analysis::state::AnalysisState,
     autonodes::{any::AnyNodeRef, variable_name::VariableNameNode},
@@ -13,9 +14,9 @@ impl NodeDescription for VariableNameNode {
     ) -> Option<String> {
         let var_name = self.get_variable_name();
         let scope = state.current_scope();
-        let scope_handle = scope.read().unwrap();
+        let scope_handle = scope.read_or_recover();
         let var_handle = scope_handle.get_var(&var_name)?;
-        let var_data = var_handle.read().unwrap();
+        let var_data = var_handle.read_or_recover();
         let mut buf = String::new();
         buf.push_str("|  |  |\n");
         buf.push_str("| --- | --- |\n");
@@ -1,7 +1,13 @@
+use crate::autonodes::_statement::_StatementNode;
 use crate::autonodes::any::AnyNode;
 use crate::autonodes::any::AnyNodeRef;
+use crate::autonodes::declaration_list::DeclarationListChildren;
+use crate::autonodes::enum_declaration_list::EnumDeclarationListChildren;
+use crate::autonodes::program::ProgramChildren;
 use crate::autotree::NodeAccess;
 use crate::autotree::ParseError;
+use crate::autotree::RecoveringNodeParser;
+use crate::extra::ExtraChild;
 use std::ffi::OsStr;
 use std::ffi::OsString;
 use std::os::unix::ffi::OsStrExt;
@@ -14,6 +20,9 @@ pub struct ErrorNode {
     pub range: Range,
     pub children: Vec<AnyNode>,
     pub raw: Vec<u8>,
+    /// Why the node was recovered, when it replaces a node without any syntax errors in it
+    /// which still couldn't be converted
+    pub conversion_error: Option<ParseError>,
 }
 
 impl ErrorNode {
@@ -31,11 +40,36 @@ impl ErrorNode {
 
         Ok(Self {
             range: range.into(),
-            children: AnyNode::parse_vec(node.named_children(&mut node.walk()), source)?,
+            children: Self::recover_children(node, source),
             raw: source[range.start_byte..range.end_byte].to_vec(),
+            conversion_error: None,
         })
     }
 
+    ///
+    /// Keeps `node`, which failed conversion with `error`, as an error node in place, with as
+    /// much as possible of its subtree converted
+    ///
+    pub fn recover(node: Node, source: &[u8], error: ParseError) -> Self {
+        let range = node.range();
+        Self {
+            range: range.into(),
+            children: Self::recover_children(node, source),
+            raw: source[range.start_byte..range.end_byte].to_vec(),
+            conversion_error: if node.has_error() { None } else { Some(error) },
+        }
+    }
+
+    fn recover_children(node: Node, source: &[u8]) -> Vec<AnyNode> {
+        node.named_children(&mut node.walk())
+            .map(|child| {
+                AnyNode::parse(child, source).unwrap_or_else(|err| {
+                    AnyNode::Error(Box::new(Self::recover(child, source, err)))
+                })
+            })
+            .collect()
+    }
+
     pub fn parse_vec<'a, I>(children: I, source: &[u8]) -> Result<Vec<Box<Self>>, ParseError>
     where
         I: Iterator<Item = Node<'a>>,
@@ -82,3 +116,27 @@ impl NodeAccess for ErrorNode {
         self.range
     }
 }
+
+impl RecoveringNodeParser for ProgramChildren {
+    fn from_error(error: ErrorNode) -> Self {
+        ProgramChildren::Extra(ExtraChild::Error(Box::new(error)))
+    }
+}
+
+impl RecoveringNodeParser for _StatementNode {
+    fn from_error(error: ErrorNode) -> Self {
+        _StatementNode::Extra(ExtraChild::Error(Box::new(error)))
+    }
+}
+
+impl RecoveringNodeParser for DeclarationListChildren {
+    fn from_error(error: ErrorNode) -> Self {
+        DeclarationListChildren::Extra(ExtraChild::Error(Box::new(error)))
+    }
+}
+
+impl RecoveringNodeParser for EnumDeclarationListChildren {
+    fn from_error(error: ErrorNode) -> Self {
+        EnumDeclarationListChildren::Extra(ExtraChild::Error(Box::new(error)))
+    }
+}
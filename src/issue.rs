@@ -5,7 +5,11 @@ use std::{
 };
 
 //use tree_sitter::Range;
-use crate::{parser::Range, types::union::PHPType};
+use crate::{
+    config::TaintSinkKind,
+    parser::Range,
+    types::union::{PHPType, ShapeTypeKey},
+};
 
 use crate::symbols::{FullyQualifiedName, Name};
 
@@ -76,6 +80,28 @@ pub enum Issue {
     /// *  .1 method_name if avaiable
     MethodCallOnNullableType(IssuePosition, Option<Name>),
 
+    /// The target of the property access can be null
+    /// *  .0 position
+    /// *  .1 property_name if avaiable
+    PropertyAccessOnNullableType(IssuePosition, Option<Name>),
+
+    /// The array being subscripted can be null
+    /// *  .0 position
+    /// *  .1 type of the array
+    ArrayAccessOnNullableType(IssuePosition, PHPType),
+
+    /// An argument which can be null is passed to a parameter which doesn't accept null
+    /// *  .0 position
+    /// *  .1 name of the function or method called
+    /// *  .2 parameter name
+    /// *  .3 type of the argument
+    NullableArgumentType(IssuePosition, Name, Name, PHPType),
+
+    /// The target of `?->` can never be null, so `->` would do
+    /// *  .0 position
+    /// *  .1 type of the target
+    RedundantNullsafeOperator(IssuePosition, PHPType),
+
     /// We're unable to determine the type of the target of the property access
     /// *  .0 position
     /// *  .1 property_name if avaiable
@@ -132,6 +158,33 @@ pub enum Issue {
 
     UnknownIndexType(IssuePosition),
 
+    /// A string key used on a list, which only has integer keys
+    /// *  .0 position
+    /// *  .1 type of the list
+    StringKeyOnList(IssuePosition, PHPType),
+
+    /// A string subscripted with something which can't be an integer offset
+    /// *  .0 position
+    /// *  .1 type of the offset
+    NonIntegerStringOffset(IssuePosition, PHPType),
+
+    /// Subscripting a value which is neither an array, a string nor implements `ArrayAccess`
+    /// *  .0 position
+    /// *  .1 type of the value
+    ArrayAccessOnNonArrayType(IssuePosition, PHPType),
+
+    /// A key read from an array which isn't known to have it, like an optional shape key
+    /// *  .0 position
+    /// *  .1 type of the key
+    /// *  .2 type of the array
+    PossiblyUndefinedArrayKey(IssuePosition, PHPType, PHPType),
+
+    /// A key which isn't in the sealed array shape it's looked up in, like `$row['naem']`
+    /// *  .0 position
+    /// *  .1 the key
+    /// *  .2 type of the array
+    UnknownArrayShapeKey(IssuePosition, ShapeTypeKey, PHPType),
+
     WrongClassNameCasing(IssuePosition, Name, FullyQualifiedName),
 
     /// The analyzer arrived at a parse-state it considers impossible
@@ -154,6 +207,169 @@ pub enum Issue {
     UnknownPHPDocEntry(IssuePosition, OsString),
 
     IllegalTypeInInstanceof(IssuePosition, String),
+
+    /// `yield` used in a function whose declared return-type can't hold a Generator
+    /// *  .0 position
+    /// *  .1 declared return type
+    IncompatibleGeneratorReturnType(IssuePosition, PHPType),
+
+    /// A key or value is yielded, or a value returned, which doesn't match the declared
+    /// generator type
+    /// *  .0 position
+    /// *  .1 declared key-, value- or return-type
+    /// *  .2 yielded or returned type
+    IncompatibleYieldType(IssuePosition, PHPType, PHPType),
+
+    /// The same method is imported from more than one trait, without being resolved
+    /// with `insteadof`
+    /// *  .0 position
+    /// *  .1 fq_class_name
+    /// *  .2 method_name
+    /// *  .3 the conflicting traits
+    TraitMethodConflict(
+        IssuePosition,
+        FullyQualifiedName,
+        Name,
+        Vec<FullyQualifiedName>,
+    ),
+
+    /// An abstract method from an imported trait is not implemented
+    /// *  .0 position
+    /// *  .1 fq_class_name
+    /// *  .2 fq_trait_name
+    /// *  .3 method_name
+    UnimplementedAbstractTraitMethod(IssuePosition, FullyQualifiedName, FullyQualifiedName, Name),
+
+    /// A class used as an attribute, which isn't declared with `#[Attribute]`
+    /// *  .0 position
+    /// *  .1 fq_class_name
+    NotAnAttribute(IssuePosition, FullyQualifiedName),
+
+    /// An attribute used on a kind of declaration its `Attribute::TARGET_*`-flags
+    /// doesn't allow
+    /// *  .0 position
+    /// *  .1 fq_class_name
+    /// *  .2 the kind of declaration
+    InvalidAttributeTarget(IssuePosition, FullyQualifiedName, String),
+
+    /// An attribute used more than once on the same declaration, without being
+    /// declared with `Attribute::IS_REPEATABLE`
+    /// *  .0 position
+    /// *  .1 fq_class_name
+    NonRepeatableAttribute(IssuePosition, FullyQualifiedName),
+
+    /// *  .0 position
+    /// *  .1 function or method-name
+    /// *  .2 argument name
+    /// *  .3 declared type
+    /// *  .4 provided type
+    IncompatibleArgumentType(IssuePosition, Name, Name, PHPType, PHPType),
+
+    /// A private or protected method which is never called
+    /// *  .0 position
+    /// *  .1 fq_class_name
+    /// *  .2 method_name
+    UnusedMethod(IssuePosition, FullyQualifiedName, Name),
+
+    /// A private or protected property which is never read from
+    /// *  .0 position
+    /// *  .1 fq_class_name
+    /// *  .2 property_name
+    UnusedProperty(IssuePosition, FullyQualifiedName, Name),
+
+    /// A private class constant which is never referenced
+    /// *  .0 position
+    /// *  .1 fq_class_name
+    /// *  .2 constant_name
+    UnusedClassConstant(IssuePosition, FullyQualifiedName, Name),
+
+    /// *  .0 position
+    /// *  .1 fq_function_name
+    UnusedFunction(IssuePosition, FullyQualifiedName),
+
+    /// *  .0 position
+    /// *  .1 fq_class_name
+    UnusedClass(IssuePosition, FullyQualifiedName),
+
+    /// *  .0 position
+    /// *  .1 resolved path of the file
+    IncludedFileNotFound(IssuePosition, OsString),
+
+    /// *  .0 position
+    /// *  .1 resolved path of the file
+    RequiredFileNotFound(IssuePosition, OsString),
+
+    /// A template is given a type outside of the bound it's declared with (`@template T of Foo`)
+    /// *  .0 position
+    /// *  .1 template name
+    /// *  .2 bound
+    /// *  .3 provided type
+    TemplateBoundViolation(IssuePosition, Name, PHPType, PHPType),
+
+    /// A covariant template used as a parameter type, or a contravariant as return type
+    /// *  .0 position
+    /// *  .1 template name
+    /// *  .2 method name
+    TemplateVarianceViolation(IssuePosition, Name, Name),
+
+    /// A class in a PSR-4 directory, declared in another file than the autoloader expects
+    /// *  .0 position
+    /// *  .1 fq_class_name
+    /// *  .2 expected path of the file
+    ClassLocationMismatch(IssuePosition, FullyQualifiedName, OsString),
+
+    /// A class in a PSR-4 directory, outside of the namespace the directory maps to
+    /// *  .0 position
+    /// *  .1 fq_class_name
+    /// *  .2 namespace prefix of the directory
+    ClassNamespaceMismatch(IssuePosition, FullyQualifiedName, OsString),
+
+    /// User input reaching a sink without being sanitized for it
+    /// *  .0 position
+    /// *  .1 what the input could be abused for
+    /// *  .2 name of the sink
+    /// *  .3 the steps from the source to the sink
+    TaintedSink(IssuePosition, TaintSinkKind, Name, Vec<String>),
+
+    /// *  .0 position
+    UseOfEval(IssuePosition),
+
+    /// A shell command in backticks
+    /// *  .0 position
+    BacktickShellCommand(IssuePosition),
+
+    /// *  .0 position
+    UseOfExtract(IssuePosition),
+
+    /// `unserialize()` of a value which isn't known up front
+    /// *  .0 position
+    UnserializeOfVariableInput(IssuePosition),
+
+    /// `assert()` with a string, which is evaluated as code
+    /// *  .0 position
+    AssertWithStringArgument(IssuePosition),
+
+    /// `preg_replace()` with a pattern using the `/e` modifier
+    /// *  .0 position
+    PregReplaceEvalModifier(IssuePosition),
+
+    /// A password hashed with a fast hash function, like `md5($password)`
+    /// *  .0 position
+    /// *  .1 name of the hash function
+    WeakPasswordHash(IssuePosition, Name),
+
+    /// A token made with a predictable random function, like `$token = rand()`
+    /// *  .0 position
+    /// *  .1 name of the random function
+    InsecureRandomToken(IssuePosition, Name),
+
+    /// A hash compared with `==` or `!=` instead of `hash_equals()` or `===`
+    /// *  .0 position
+    LooseHashComparison(IssuePosition),
+
+    /// An expression with its errors suppressed by `@`
+    /// *  .0 position
+    ErrorSuppression(IssuePosition),
 }
 
 impl Issue {
@@ -164,9 +380,32 @@ impl Issue {
             Self::UnusedArgument(_, _) => Severity::Hint,
             Self::VariableNotInitializedInAllBranhces(_, _) => Severity::Warning,
             Self::NotAVerifiedCallableVariable(_, _) => Severity::Warning,
+            Self::UnusedMethod(_, _, _) => Severity::Warning,
+            Self::UnusedProperty(_, _, _) => Severity::Warning,
+            Self::UnusedClassConstant(_, _, _) => Severity::Warning,
+            Self::IncludedFileNotFound(_, _) => Severity::Warning,
+            Self::ClassLocationMismatch(_, _, _) => Severity::Warning,
+            Self::ClassNamespaceMismatch(_, _, _) => Severity::Warning,
+            Self::PropertyAccessOnNullableType(_, _) => Severity::Warning,
+            Self::ArrayAccessOnNullableType(_, _) => Severity::Warning,
+            Self::NullableArgumentType(_, _, _, _) => Severity::Information,
+            Self::StringKeyOnList(_, _) => Severity::Warning,
+            Self::PossiblyUndefinedArrayKey(_, _, _) => Severity::Information,
+            Self::TaintedSink(_, TaintSinkKind::Html, _, _) => Severity::Warning,
+            Self::UseOfEval(_) => Severity::Warning,
+            Self::BacktickShellCommand(_) => Severity::Warning,
+            Self::UseOfExtract(_) => Severity::Warning,
+            Self::UnserializeOfVariableInput(_) => Severity::Warning,
+            Self::WeakPasswordHash(_, _) => Severity::Warning,
+            Self::InsecureRandomToken(_, _) => Severity::Warning,
+            Self::LooseHashComparison(_) => Severity::Warning,
 
             // Hints
             Self::ConditionalConstantDeclaration(_) => Severity::Hint,
+            Self::UnusedFunction(_, _) => Severity::Hint,
+            Self::UnusedClass(_, _) => Severity::Hint,
+            Self::RedundantNullsafeOperator(_, _) => Severity::Hint,
+            Self::ErrorSuppression(_) => Severity::Hint,
 
             // Remaining we classify as errors
             _ => Severity::Error,
@@ -198,6 +437,10 @@ impl Issue {
             | Self::UnknownMethod(pos, _, _)
             | Self::MethodCallOnUnknownType(pos, _, _)
             | Self::MethodCallOnNullableType(pos, _)
+            | Self::PropertyAccessOnNullableType(pos, _)
+            | Self::ArrayAccessOnNullableType(pos, _)
+            | Self::NullableArgumentType(pos, _, _, _)
+            | Self::RedundantNullsafeOperator(pos, _)
             | Self::TraversalOfUnknownType(pos)
             | Self::ConditionalConstantDeclaration(pos)
             | Self::WrongNumberOfArguments(pos, _, _, _)
@@ -208,6 +451,11 @@ impl Issue {
             | Self::DuplicateDeclaration(pos, _)
             | Self::DuplicateTemplate(pos, _)
             | Self::UnknownIndexType(pos)
+            | Self::StringKeyOnList(pos, _)
+            | Self::NonIntegerStringOffset(pos, _)
+            | Self::ArrayAccessOnNonArrayType(pos, _)
+            | Self::PossiblyUndefinedArrayKey(pos, _, _)
+            | Self::UnknownArrayShapeKey(pos, _, _)
             | Self::ParseAnomaly(pos, _)
             | Self::WrongFunctionNameCasing(pos, _, _)
             | Self::PropertyAccessOnUnknownType(pos, _)
@@ -223,6 +471,36 @@ impl Issue {
             | Self::UnknownPHPDocEntry(pos, _)
             | Self::IllegalTypeInInstanceof(pos, _)
             | Self::EmptyTemplate(pos, _)
+            | Self::IncompatibleGeneratorReturnType(pos, _)
+            | Self::IncompatibleYieldType(pos, _, _)
+            | Self::TraitMethodConflict(pos, _, _, _)
+            | Self::UnimplementedAbstractTraitMethod(pos, _, _, _)
+            | Self::NotAnAttribute(pos, _)
+            | Self::InvalidAttributeTarget(pos, _, _)
+            | Self::NonRepeatableAttribute(pos, _)
+            | Self::IncompatibleArgumentType(pos, _, _, _, _)
+            | Self::UnusedMethod(pos, _, _)
+            | Self::UnusedProperty(pos, _, _)
+            | Self::UnusedClassConstant(pos, _, _)
+            | Self::UnusedFunction(pos, _)
+            | Self::UnusedClass(pos, _)
+            | Self::IncludedFileNotFound(pos, _)
+            | Self::RequiredFileNotFound(pos, _)
+            | Self::TemplateBoundViolation(pos, _, _, _)
+            | Self::TemplateVarianceViolation(pos, _, _)
+            | Self::ClassLocationMismatch(pos, _, _)
+            | Self::ClassNamespaceMismatch(pos, _, _)
+            | Self::TaintedSink(pos, _, _, _)
+            | Self::UseOfEval(pos)
+            | Self::BacktickShellCommand(pos)
+            | Self::UseOfExtract(pos)
+            | Self::UnserializeOfVariableInput(pos)
+            | Self::AssertWithStringArgument(pos)
+            | Self::PregReplaceEvalModifier(pos)
+            | Self::WeakPasswordHash(pos, _)
+            | Self::InsecureRandomToken(pos, _)
+            | Self::LooseHashComparison(pos)
+            | Self::ErrorSuppression(pos)
             | Self::ParseError(pos, _) => pos.clone(),
         }
     }
@@ -255,6 +533,10 @@ impl Issue {
             Self::UnreachableCode(_) => "UnreachableCode",
             Self::MethodCallOnUnknownType(_, _, _) => "MethodCallOnUnknownType",
             Self::MethodCallOnNullableType(_, _) => "MethodCallOnNullableType",
+            Self::PropertyAccessOnNullableType(_, _) => "PropertyAccessOnNullableType",
+            Self::ArrayAccessOnNullableType(_, _) => "ArrayAccessOnNullableType",
+            Self::NullableArgumentType(_, _, _, _) => "NullableArgumentType",
+            Self::RedundantNullsafeOperator(_, _) => "RedundantNullsafeOperator",
             Self::PropertyAccessOnUnknownType(_, _) => "PropertyAccessOnUnknownType",
             Self::PropertyAccessOnInterfaceType(_, _, _) => "PropertyAccessOnInterfaceType",
             Self::IndeterminablePropertyName(_, _) => "IndeterminablePropertyName",
@@ -269,6 +551,11 @@ impl Issue {
             Self::DuplicateClassConstant(_, _, _) => "DuplicateClassConstant",
             Self::DuplicateDeclaration(_, _) => "DuplicateDeclaration",
             Self::UnknownIndexType(_) => "UnknownIndexType",
+            Self::StringKeyOnList(_, _) => "StringKeyOnList",
+            Self::NonIntegerStringOffset(_, _) => "NonIntegerStringOffset",
+            Self::ArrayAccessOnNonArrayType(_, _) => "ArrayAccessOnNonArrayType",
+            Self::PossiblyUndefinedArrayKey(_, _, _) => "PossiblyUndefinedArrayKey",
+            Self::UnknownArrayShapeKey(_, _, _) => "UnknownArrayShapeKey",
             Self::ParseAnomaly(_, _) => "ParseAnomaly",
             Self::VariableNotInitializedInAllBranhces(_, _) => {
                 "VariableNotInitializedInAllBranhces"
@@ -282,6 +569,44 @@ impl Issue {
             Self::UnknownPHPDocEntry(_, _) => "UnknownPHPDocEntry",
             Self::EmptyTemplate(_, _) => "EmptyTemplate",
             Self::IllegalTypeInInstanceof(_, _) => "IllegalTypeInInstanceof",
+            Self::IncompatibleGeneratorReturnType(_, _) => "IncompatibleGeneratorReturnType",
+            Self::IncompatibleYieldType(_, _, _) => "IncompatibleYieldType",
+            Self::TraitMethodConflict(_, _, _, _) => "TraitMethodConflict",
+            Self::UnimplementedAbstractTraitMethod(_, _, _, _) => {
+                "UnimplementedAbstractTraitMethod"
+            }
+            Self::NotAnAttribute(_, _) => "NotAnAttribute",
+            Self::InvalidAttributeTarget(_, _, _) => "InvalidAttributeTarget",
+            Self::NonRepeatableAttribute(_, _) => "NonRepeatableAttribute",
+            Self::IncompatibleArgumentType(_, _, _, _, _) => "IncompatibleArgumentType",
+            Self::UnusedMethod(_, _, _) => "UnusedMethod",
+            Self::UnusedProperty(_, _, _) => "UnusedProperty",
+            Self::UnusedClassConstant(_, _, _) => "UnusedClassConstant",
+            Self::UnusedFunction(_, _) => "UnusedFunction",
+            Self::UnusedClass(_, _) => "UnusedClass",
+            Self::IncludedFileNotFound(_, _) => "IncludedFileNotFound",
+            Self::RequiredFileNotFound(_, _) => "RequiredFileNotFound",
+            Self::TemplateBoundViolation(_, _, _, _) => "TemplateBoundViolation",
+            Self::TemplateVarianceViolation(_, _, _) => "TemplateVarianceViolation",
+            Self::ClassLocationMismatch(_, _, _) => "ClassLocationMismatch",
+            Self::ClassNamespaceMismatch(_, _, _) => "ClassNamespaceMismatch",
+            Self::TaintedSink(_, kind, _, _) => match kind {
+                TaintSinkKind::Sql => "SqlInjection",
+                TaintSinkKind::Shell => "ShellInjection",
+                TaintSinkKind::Eval => "CodeInjection",
+                TaintSinkKind::Include => "FileInclusion",
+                TaintSinkKind::Html => "CrossSiteScripting",
+            },
+            Self::UseOfEval(_) => "UseOfEval",
+            Self::BacktickShellCommand(_) => "BacktickShellCommand",
+            Self::UseOfExtract(_) => "UseOfExtract",
+            Self::UnserializeOfVariableInput(_) => "UnserializeOfVariableInput",
+            Self::AssertWithStringArgument(_) => "AssertWithStringArgument",
+            Self::PregReplaceEvalModifier(_) => "PregReplaceEvalModifier",
+            Self::WeakPasswordHash(_, _) => "WeakPasswordHash",
+            Self::InsecureRandomToken(_, _) => "InsecureRandomToken",
+            Self::LooseHashComparison(_) => "LooseHashComparison",
+            Self::ErrorSuppression(_) => "ErrorSuppression",
             Self::ParseError(_, _) => "ParseError",
         }
     }
@@ -318,6 +643,20 @@ impl Issue {
             Self::MethodCallOnNullableType(_, mname) => {
                 format!("Method call {:?} on a target which can be null", mname)
             }
+            Self::PropertyAccessOnNullableType(_, pname) => {
+                format!("Property access {:?} on a target which can be null", pname)
+            }
+            Self::ArrayAccessOnNullableType(_, atype) => {
+                format!("Array access on a target of type {}, which can be null", atype)
+            }
+            Self::NullableArgumentType(_, callee, param, atype) => format!(
+                "Argument of type {}, which can be null, passed as ${} to {}, which doesn't accept null",
+                atype, param, callee
+            ),
+            Self::RedundantNullsafeOperator(_, otype) => format!(
+                "Nullsafe operator on a target of type {}, which can never be null",
+                otype
+            ),
             Self::UnknownMethod(_, c, m) => format!("Unknown method {} on {}", m, c),
             Self::TraversalOfUnknownType(_) => "Traversal of unknown type".to_string(),
             Self::ConditionalConstantDeclaration(_) => {
@@ -340,6 +679,26 @@ impl Issue {
                 format!("Duplicate declaration: {}", desc.to_string_lossy())
             }
             Self::UnknownIndexType(_) => "Unknown index type".to_string(),
+            Self::StringKeyOnList(_, ltype) => {
+                format!("String key on {}, which only has integer keys", ltype)
+            }
+            Self::NonIntegerStringOffset(_, otype) => {
+                format!("String offset of type {}, which isn't an integer", otype)
+            }
+            Self::ArrayAccessOnNonArrayType(_, atype) => format!(
+                "Array access on a target of type {}, which isn't an array or ArrayAccess",
+                atype
+            ),
+            Self::PossiblyUndefinedArrayKey(_, key, atype) => {
+                format!("Key {} might not exist in array of type {}", key, atype)
+            }
+            Self::UnknownArrayShapeKey(_, key, atype) => {
+                format!(
+                    "Unknown key {} in array of type {}",
+                    key.get_literal_type(),
+                    atype
+                )
+            }
             Self::ParseAnomaly(_, pa) => format!("Arrived at an unexpected parse state: {:?}", pa),
             Self::VariableNotInitializedInAllBranhces(_, vname) => {
                 format!("Variable ${} is not initialized in all branches", vname)
@@ -387,6 +746,112 @@ impl Issue {
             Self::IllegalTypeInInstanceof(_, desc) => {
                 format!("Illegal type in instanceof-expression: {}", desc)
             }
+            Self::IncompatibleGeneratorReturnType(_, declared) => format!(
+                "Function contains yield, but the declared return type {} can't hold a Generator",
+                declared
+            ),
+            Self::IncompatibleYieldType(_, declared, provided) => format!(
+                "The declared generator type expects {}, got {}",
+                declared, provided
+            ),
+            Self::TraitMethodConflict(_, cname, mname, traits) => format!(
+                "Method {} in {} is imported from multiple traits: {}",
+                mname,
+                cname,
+                traits
+                    .iter()
+                    .map(|t| t.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            Self::UnimplementedAbstractTraitMethod(_, cname, tname, mname) => format!(
+                "Class {} must implement abstract method {} from trait {}",
+                cname, mname, tname
+            ),
+            Self::NotAnAttribute(_, cname) => {
+                format!("Class {} is not declared as #[Attribute]", cname)
+            }
+            Self::InvalidAttributeTarget(_, cname, target) => {
+                format!("Attribute {} can't be used on a {}", cname, target)
+            }
+            Self::NonRepeatableAttribute(_, cname) => {
+                format!("Attribute {} is not repeatable", cname)
+            }
+            Self::IncompatibleArgumentType(_, fname, aname, declared, provided) => format!(
+                "Argument {} to {} expects {}, got {}",
+                aname, fname, declared, provided
+            ),
+            Self::UnusedMethod(_, cname, mname) => {
+                format!("Method {}::{} is never called", cname, mname)
+            }
+            Self::UnusedProperty(_, cname, pname) => {
+                format!("Property {}::${} is never read", cname, pname)
+            }
+            Self::UnusedClassConstant(_, cname, const_name) => {
+                format!("Constant {}::{} is never used", cname, const_name)
+            }
+            Self::UnusedFunction(_, fname) => format!("Function {} is never called", fname),
+            Self::UnusedClass(_, cname) => format!("Class {} is never used", cname),
+            Self::IncludedFileNotFound(_, path) => {
+                format!("Included file {} doesn't exist", path.to_string_lossy())
+            }
+            Self::RequiredFileNotFound(_, path) => {
+                format!("Required file {} doesn't exist", path.to_string_lossy())
+            }
+            Self::TemplateBoundViolation(_, tname, bound, provided) => format!(
+                "Template {} must be a subtype of {}, got {}",
+                tname, bound, provided
+            ),
+            Self::TemplateVarianceViolation(_, tname, mname) => {
+                format!(
+                    "Template {} has the wrong variance for its use in {}",
+                    tname, mname
+                )
+            }
+            Self::ClassLocationMismatch(_, cname, path) => format!(
+                "Class {} should be declared in {} to be autoloaded",
+                cname,
+                path.to_string_lossy()
+            ),
+            Self::ClassNamespaceMismatch(_, cname, prefix) => format!(
+                "Class {} is outside of the namespace {} of its directory",
+                cname,
+                prefix.to_string_lossy()
+            ),
+            Self::TaintedSink(_, kind, sink, path) => format!(
+                "User input used as {} without being sanitized: {} → {}",
+                kind,
+                path.join(" → "),
+                sink
+            ),
+            Self::UseOfEval(_) => "eval() executes arbitrary code".to_string(),
+            Self::BacktickShellCommand(_) => {
+                "Shell command in backticks, prefer a function with escaped arguments".to_string()
+            }
+            Self::UseOfExtract(_) => {
+                "extract() can overwrite any variable in the current scope".to_string()
+            }
+            Self::UnserializeOfVariableInput(_) => {
+                "unserialize() of variable input can instantiate arbitrary classes".to_string()
+            }
+            Self::AssertWithStringArgument(_) => {
+                "assert() with a string argument is evaluated as code".to_string()
+            }
+            Self::PregReplaceEvalModifier(_) => {
+                "The /e modifier of preg_replace() evaluates the replacement as code".to_string()
+            }
+            Self::WeakPasswordHash(_, fname) => format!(
+                "{}() is too fast to hash passwords with, use password_hash()",
+                fname
+            ),
+            Self::InsecureRandomToken(_, fname) => format!(
+                "{}() is predictable, use random_bytes() or random_int() for tokens",
+                fname
+            ),
+            Self::LooseHashComparison(_) => {
+                "Hashes compared with ==, use hash_equals() or ===".to_string()
+            }
+            Self::ErrorSuppression(_) => "Errors suppressed with @".to_string(),
             Self::ParseError(_, desc) => {
                 format!("Parse error: {}", desc.to_string_lossy())
             }
@@ -12,6 +12,8 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::RwLock;
 
+use crate::locks::RecoverableLock;
+
 pub use tree_sitter::Point;
 //use tree_sitter::Range;
 use crate::parser::Range;
@@ -24,6 +26,7 @@ pub mod autotree;
 pub mod description;
 pub mod errornode;
 pub mod issue;
+pub mod locks;
 pub mod nodeanalysis;
 pub mod operators;
 pub mod symboldata;
@@ -48,7 +51,7 @@ macro_rules! missing {
         {
             let s = std::format!("{}:{}: missing", std::file!(), std::line!());
             if $crate::VERBOSE_MISSING {std::eprintln!("{}", &s);}
-            *$crate::MISSING_FEATURES.write().unwrap().entry(s).or_insert(0) += 1;
+            *$crate::MISSING_FEATURES.write().unwrap_or_else(std::sync::PoisonError::into_inner).entry(s).or_insert(0) += 1;
         }
     };
     ($($arg:tt)+) => {
@@ -56,7 +59,7 @@ macro_rules! missing {
             let s = std::format!("{}:{}: missing: {}", std::file!(), std::line!(), std::format_args!($($arg)+));
             if $crate::VERBOSE_MISSING { std::eprintln!("{}", &s);
         }
-            *$crate::MISSING_FEATURES.write().unwrap().entry(s).or_insert(0) += 1;
+            *$crate::MISSING_FEATURES.write().unwrap_or_else(std::sync::PoisonError::into_inner).entry(s).or_insert(0) += 1;
         }
     };
 }
@@ -68,7 +71,7 @@ macro_rules! missing_none {
             let s = std::format!("{}:{}: missing", std::file!(), std::line!());
             if $crate::VERBOSE_MISSING { std::eprintln!("{}", &s);
         }
-            *$crate::MISSING_FEATURES.write().unwrap().entry(s).or_insert(0) += 1;
+            *$crate::MISSING_FEATURES.write().unwrap_or_else(std::sync::PoisonError::into_inner).entry(s).or_insert(0) += 1;
             None
         }
     };
@@ -76,7 +79,7 @@ macro_rules! missing_none {
         {
             let s = std::format!("{}:{}: missing: {}", std::file!(), std::line!(), std::format_args!($($arg)+));
             if $crate::VERBOSE_MISSING { std::eprintln!("{}", &s); }
-            *$crate::MISSING_FEATURES.write().unwrap().entry(s).or_insert(0) += 1;
+            *$crate::MISSING_FEATURES.write().unwrap_or_else(std::sync::PoisonError::into_inner).entry(s).or_insert(0) += 1;
             None
         }
     };
@@ -84,7 +87,7 @@ macro_rules! missing_none {
 
 pub fn dump_missing_stats() {
     eprintln!("...");
-    let logged = MISSING_FEATURES.read().unwrap();
+    let logged = MISSING_FEATURES.read_or_recover();
     let mut list: Vec<_> = logged.iter().collect();
     list.sort_by(|a, b| a.1.cmp(b.1));
     let len = list.len();
@@ -200,6 +200,20 @@ function rust_type_opt(ReflectionType $type)
 
 $ver = "php_" . PHP_MAJOR_VERSION . "_" . PHP_MINOR_VERSION;
 
+// The symbols of an extension are only generated when it's loaded, so make sure the
+// PHP running this has all of these available
+$EXPECTED_EXTENSIONS = [
+    'ctype', 'curl', 'dom', 'fileinfo', 'filter', 'gd', 'iconv', 'intl', 'json', 'libxml',
+    'mbstring', 'mysqli', 'openssl', 'pdo', 'pdo_mysql', 'pdo_pgsql', 'pdo_sqlite', 'redis',
+    'session', 'simplexml', 'soap', 'sodium', 'tokenizer', 'xml', 'xmlreader', 'xmlwriter',
+    'zip', 'zlib',
+];
+foreach ($EXPECTED_EXTENSIONS as $extension) {
+    if (!extension_loaded($extension)) {
+        error_log("WARNING: Extension $extension is not loaded, and will be missing from $ver");
+    }
+}
+
 $root_mods = [];
 $func_mods = [];
 
@@ -253,13 +267,15 @@ foreach (get_defined_functions()['internal'] as $function) {
             phpdoc_entry: None,
             phpdoc_type: None,
             variadic: %s,
+            by_ref: %s,
         }",
             $param->name,
             "None",
             $param->isDefaultValueAvailable() ? "Some(" . rust_value($param->getDefaultValue()) . ")" : "None",
             $param->allowsNull() ? "true" : "false",
             $param->isOptional() ? "true" : "false",
-            $param->isVariadic() ? "true" : "false"
+            $param->isVariadic() ? "true" : "false",
+            $param->isPassedByReference() ? "true" : "false"
         );
         $args[] = $arg;
     }
@@ -270,6 +286,7 @@ foreach (get_defined_functions()['internal'] as $function) {
 
     $buffer = "
     use crate::analysis::state::AnalysisState;
+    use crate::locks::RecoverableLock;
     use crate::symboldata::FileLocation;
     use crate::symboldata::FunctionData;
     use crate::symboldata::class::FunctionArgumentData;
@@ -305,9 +322,14 @@ foreach (get_defined_functions()['internal'] as $function) {
             return_value: None,
             overload_map: HashMap::new(),
             generic_templates: None,
+            template_data: vec![],
+            assertions: vec![],
+            phpdoc: None,
+            attributes: vec![],
+            is_native: true,
         };
         {
-            let mut functions = state.symbol_data.functions.write().unwrap();
+            let mut functions = state.symbol_data.functions.write_or_recover();
 
             functions.insert(func_name, Arc::new(RwLock::new(func_data)));
         }
@@ -364,6 +386,7 @@ foreach (get_declared_classes() as $class) {
     $class_rs = "";
     $class_rs .= "\n\n";
     $class_rs .= "use crate::analysis::state::AnalysisState;\n";
+    $class_rs .= "use crate::locks::RecoverableLock;\n";
     $class_rs .= "use crate::symboldata::FileLocation;\n";
     $class_rs .= "use crate::symbols::FullyQualifiedName;\n";
     $class_rs .= "use crate::symbols::Name;\n";
@@ -470,7 +493,7 @@ foreach (get_declared_classes() as $class) {
 
     $class_rs .= "        
         {
-            let mut writeable = cdata_handle.write().unwrap();
+            let mut writeable = cdata_handle.write_or_recover();
             *writeable = ClassType::Class(class_data);
         }
         
@@ -521,10 +544,10 @@ foreach ($root_mods as $mod => $root_mod) {
     $root_mod .= "}\n";
     putfile("$ver/$mod/mod.rs", $root_mod);
     $ver_mod .= "pub mod r#$mod;\n";
-    $ver_mod_func .= " r#$mod::register(state);\n";
+    $ver_mod_func .= " if is_enabled(\"$mod\") { r#$mod::register(state); }\n";
 }
 $ver_mod .= "\nuse crate::analysis::state::AnalysisState;
-\n\npub fn register(state: &mut AnalysisState) {\n";
+\n\npub fn register(state: &mut AnalysisState, is_enabled: &dyn Fn(&str) -> bool) {\n";
 $ver_mod .= $ver_mod_func;
 $ver_mod .= "}\n";
 
@@ -1,8 +1,98 @@
+use std::path::PathBuf;
+
+use crate::analysis::analyzer::AnalyzerError;
 use crate::analysis::state::AnalysisState;
+use crate::config::{NativeConfig, PHPVersion};
+use crate::issue::{Issue, IssueEmitter, IssuePosition};
 
+#[cfg(native_php_7_4)]
+pub mod php_7_4;
+#[cfg(native_php_8_0)]
+pub mod php_8_0;
+#[cfg(native_php_8_1)]
+pub mod php_8_1;
+#[cfg(native_php_8_2)]
+pub mod php_8_2;
+#[cfg(native_php_8_3)]
 pub mod php_8_3;
+#[cfg(native_php_8_4)]
+pub mod php_8_4;
+pub mod pure;
+pub mod refinements;
+pub mod stubs;
+
+type RegisterFn = fn(&mut AnalysisState, &dyn Fn(&str) -> bool);
+
+/// Extensions which can't be disabled, and therefore always are registered
+const BUNDLED_EXTENSIONS: &[&str] = &[
+    "std",
+    "core",
+    "standard",
+    "date",
+    "pcre",
+    "spl",
+    "reflection",
+    "random",
+    "hash",
+];
+
+/// The native symbol sets compiled in, ordered by version
+const VERSIONS: &[(PHPVersion, RegisterFn)] = &[
+    #[cfg(native_php_7_4)]
+    (PHPVersion::new(7, 4), php_7_4::register),
+    #[cfg(native_php_8_0)]
+    (PHPVersion::new(8, 0), php_8_0::register),
+    #[cfg(native_php_8_1)]
+    (PHPVersion::new(8, 1), php_8_1::register),
+    #[cfg(native_php_8_2)]
+    (PHPVersion::new(8, 2), php_8_2::register),
+    #[cfg(native_php_8_3)]
+    (PHPVersion::new(8, 3), php_8_3::register),
+    #[cfg(native_php_8_4)]
+    (PHPVersion::new(8, 4), php_8_4::register),
+];
+
+/// The compiled in version closest to, and not newer than, `version`. Falls back to the oldest
+pub fn get_native_version(version: PHPVersion) -> Option<PHPVersion> {
+    select_version(version).map(|(version, _)| *version)
+}
+
+fn select_version(version: PHPVersion) -> Option<&'static (PHPVersion, RegisterFn)> {
+    VERSIONS
+        .iter()
+        .rev()
+        .find(|(v, _)| *v <= version)
+        .or_else(|| VERSIONS.first())
+}
+
+pub fn is_extension_enabled(config: &NativeConfig, extension: &str) -> bool {
+    BUNDLED_EXTENSIONS
+        .iter()
+        .chain(config.extensions)
+        .any(|e| e.eq_ignore_ascii_case(extension))
+}
 
-pub fn register(state: &mut AnalysisState) {
-    // FIXME this could be a configuration-directive
-    php_8_3::register(state);
+///
+/// Registers the native symbols of the configured PHP-version and extensions, followed by
+/// the configured stub-files. A stub-file which can't be loaded is reported to `emitter` as a
+/// `ParseError`
+///
+pub fn register(state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
+    if let Some((_, register_version)) = select_version(state.config.php_version) {
+        let config = state.config.native;
+        register_version(state, &|extension| is_extension_enabled(&config, extension));
+    }
+    refinements::register(state);
+    pure::register(state);
+    for stub_file in state.config.native.stub_files {
+        let path = PathBuf::from(*stub_file);
+        match stubs::register_stub_file(state, &path, emitter) {
+            // Already reported by the analyzer
+            Ok(()) | Err(AnalyzerError::Conversion(_)) => (),
+            Err(err) => emitter.emit(Issue::ParseError(
+                IssuePosition::new(&Some(path), Default::default()),
+                err.to_string().into(),
+            )),
+        }
+    }
 }
@@ -0,0 +1,730 @@
+//!
+//! Implementations of pure native functions, used for evaluating calls with known arguments,
+//! like `strtolower('ABC')` or `implode(',', ['a', 'b'])`.
+//!
+//! The implementations return `None` whenever the result isn't certain, like when PHP
+//! would coerce an argument in a way we don't model, or throw.
+//!
+use crate::locks::RecoverableLock;
+use std::ffi::OsString;
+use std::os::unix::ffi::{OsStrExt, OsStringExt};
+
+use crate::{
+    analysis::state::AnalysisState,
+    symbols::FullyQualifiedName,
+    value::{PHPArray, PHPFloat, PHPValue},
+};
+
+type PureFn = fn(&[PHPValue]) -> Option<PHPValue>;
+
+/// Longest string the functions here will build, anything longer isn't evaluated
+const MAX_STRING_LENGTH: usize = 4096;
+
+const PURE_FUNCTIONS: &[(&str, PureFn)] = &[
+    // Strings
+    ("\\strtolower", strtolower),
+    ("\\strtoupper", strtoupper),
+    ("\\ucfirst", ucfirst),
+    ("\\lcfirst", lcfirst),
+    ("\\ucwords", ucwords),
+    ("\\trim", trim),
+    ("\\ltrim", ltrim),
+    ("\\rtrim", rtrim),
+    ("\\strlen", strlen),
+    ("\\strrev", strrev),
+    ("\\str_repeat", str_repeat),
+    ("\\str_pad", str_pad),
+    ("\\substr", substr),
+    ("\\str_replace", str_replace),
+    ("\\str_contains", str_contains),
+    ("\\str_starts_with", str_starts_with),
+    ("\\str_ends_with", str_ends_with),
+    ("\\sprintf", sprintf),
+    ("\\implode", implode),
+    ("\\join", implode),
+    ("\\explode", explode),
+    // Arrays
+    ("\\count", count),
+    ("\\sizeof", count),
+    ("\\array_keys", array_keys),
+    ("\\array_values", array_values),
+    ("\\array_merge", array_merge),
+    ("\\array_key_exists", array_key_exists),
+    ("\\in_array", in_array),
+    // Math
+    ("\\abs", abs),
+    ("\\max", max),
+    ("\\min", min),
+    ("\\intdiv", intdiv),
+    ("\\floor", floor),
+    ("\\ceil", ceil),
+    // Types
+    ("\\intval", intval),
+    ("\\strval", strval),
+    ("\\boolval", boolval),
+    ("\\is_string", |args| {
+        is_kind(args, |v| matches!(v, PHPValue::String(_)))
+    }),
+    ("\\is_int", |args| {
+        is_kind(args, |v| matches!(v, PHPValue::Int(_)))
+    }),
+    ("\\is_float", |args| {
+        is_kind(args, |v| matches!(v, PHPValue::Float(_)))
+    }),
+    ("\\is_bool", |args| {
+        is_kind(args, |v| matches!(v, PHPValue::Boolean(_)))
+    }),
+    ("\\is_array", |args| {
+        is_kind(args, |v| matches!(v, PHPValue::Array(_)))
+    }),
+    ("\\is_null", |args| {
+        is_kind(args, |v| matches!(v, PHPValue::NULL))
+    }),
+];
+
+fn get_pure_function(fq_name: &FullyQualifiedName) -> Option<PureFn> {
+    let lc_name = fq_name.to_ascii_lowercase();
+    PURE_FUNCTIONS
+        .iter()
+        .find(|(name, _)| lc_name == FullyQualifiedName::from(*name))
+        .map(|(_, pure_fn)| *pure_fn)
+}
+
+pub fn is_pure(fq_name: &FullyQualifiedName) -> bool {
+    get_pure_function(fq_name).is_some()
+}
+
+/// Evaluates a call to a pure native function. `None` if unknown, or the result is uncertain
+pub fn evaluate(fq_name: &FullyQualifiedName, arguments: &[PHPValue]) -> Option<PHPValue> {
+    get_pure_function(fq_name)?(arguments)
+}
+
+/// Flags the registered natives which we can evaluate as pure and deterministic
+pub fn register(state: &mut AnalysisState) {
+    let functions = state.symbol_data.functions.read_or_recover();
+    for (name, _) in PURE_FUNCTIONS {
+        let fq_name = FullyQualifiedName::from(*name);
+        if let Some(function) = functions.get(&fq_name) {
+            let mut function = function.write_or_recover();
+            function.pure = true;
+            function.deterministic = true;
+        }
+    }
+}
+
+fn string_arg(arguments: &[PHPValue], idx: usize) -> Option<Vec<u8>> {
+    match arguments.get(idx)? {
+        // Arrays give `Array` with a warning, and floats are formatted in ways we don't model
+        PHPValue::Array(_) | PHPValue::Float(_) => None,
+        value => value.as_os_string().map(|s| s.into_vec()),
+    }
+}
+
+fn int_arg(arguments: &[PHPValue], idx: usize) -> Option<i64> {
+    match arguments.get(idx)? {
+        PHPValue::Int(i) => Some(*i),
+        PHPValue::Boolean(b) => Some(*b as i64),
+        _ => None,
+    }
+}
+
+/// The argument, or the default when not given
+fn optional_int_arg(arguments: &[PHPValue], idx: usize, default: i64) -> Option<i64> {
+    if idx >= arguments.len() {
+        Some(default)
+    } else {
+        int_arg(arguments, idx)
+    }
+}
+
+fn array_arg(arguments: &[PHPValue], idx: usize) -> Option<&PHPArray> {
+    match arguments.get(idx)? {
+        PHPValue::Array(array) => Some(array),
+        _ => None,
+    }
+}
+
+fn string(bytes: Vec<u8>) -> Option<PHPValue> {
+    Some(PHPValue::String(OsString::from_vec(bytes)))
+}
+
+fn map_string(arguments: &[PHPValue], map: impl Fn(&mut Vec<u8>)) -> Option<PHPValue> {
+    let mut s = string_arg(arguments, 0)?;
+    map(&mut s);
+    string(s)
+}
+
+fn strtolower(arguments: &[PHPValue]) -> Option<PHPValue> {
+    map_string(arguments, |s| s.make_ascii_lowercase())
+}
+
+fn strtoupper(arguments: &[PHPValue]) -> Option<PHPValue> {
+    map_string(arguments, |s| s.make_ascii_uppercase())
+}
+
+fn ucfirst(arguments: &[PHPValue]) -> Option<PHPValue> {
+    map_string(arguments, |s| {
+        if let Some(first) = s.first_mut() {
+            first.make_ascii_uppercase();
+        }
+    })
+}
+
+fn lcfirst(arguments: &[PHPValue]) -> Option<PHPValue> {
+    map_string(arguments, |s| {
+        if let Some(first) = s.first_mut() {
+            first.make_ascii_lowercase();
+        }
+    })
+}
+
+fn ucwords(arguments: &[PHPValue]) -> Option<PHPValue> {
+    let delimiters = if arguments.len() > 1 {
+        string_arg(arguments, 1)?
+    } else {
+        b" \t\r\n\x0c\x0b".to_vec()
+    };
+    let mut s = string_arg(arguments, 0)?;
+    let mut at_word_start = true;
+    for c in s.iter_mut() {
+        if at_word_start {
+            c.make_ascii_uppercase();
+        }
+        at_word_start = delimiters.contains(c);
+    }
+    string(s)
+}
+
+/// The characters to trim, as given to `trim()`
+fn trim_characters(arguments: &[PHPValue]) -> Option<Vec<u8>> {
+    if arguments.len() < 2 {
+        return Some(b" \n\r\t\x0b\0".to_vec());
+    }
+    let characters = string_arg(arguments, 1)?;
+    if characters.windows(2).any(|w| w == b"..") {
+        // Ranges, like `a..z`
+        return None;
+    }
+    Some(characters)
+}
+
+fn trim_with(arguments: &[PHPValue], start: bool, end: bool) -> Option<PHPValue> {
+    let s = string_arg(arguments, 0)?;
+    let characters = trim_characters(arguments)?;
+    let mut from = 0;
+    let mut to = s.len();
+    while start && from < to && characters.contains(&s[from]) {
+        from += 1;
+    }
+    while end && to > from && characters.contains(&s[to - 1]) {
+        to -= 1;
+    }
+    string(s[from..to].to_vec())
+}
+
+fn trim(arguments: &[PHPValue]) -> Option<PHPValue> {
+    trim_with(arguments, true, true)
+}
+
+fn ltrim(arguments: &[PHPValue]) -> Option<PHPValue> {
+    trim_with(arguments, true, false)
+}
+
+fn rtrim(arguments: &[PHPValue]) -> Option<PHPValue> {
+    trim_with(arguments, false, true)
+}
+
+fn strlen(arguments: &[PHPValue]) -> Option<PHPValue> {
+    Some(PHPValue::Int(string_arg(arguments, 0)?.len() as i64))
+}
+
+fn strrev(arguments: &[PHPValue]) -> Option<PHPValue> {
+    map_string(arguments, |s| s.reverse())
+}
+
+fn str_repeat(arguments: &[PHPValue]) -> Option<PHPValue> {
+    let s = string_arg(arguments, 0)?;
+    let times: usize = int_arg(arguments, 1)?.try_into().ok()?;
+    if s.len().checked_mul(times)? > MAX_STRING_LENGTH {
+        return None;
+    }
+    string(s.repeat(times))
+}
+
+fn str_pad(arguments: &[PHPValue]) -> Option<PHPValue> {
+    const STR_PAD_LEFT: i64 = 0;
+    const STR_PAD_RIGHT: i64 = 1;
+    const STR_PAD_BOTH: i64 = 2;
+    let s = string_arg(arguments, 0)?;
+    let length: usize = int_arg(arguments, 1)?.try_into().unwrap_or(0);
+    let pad = if arguments.len() > 2 {
+        string_arg(arguments, 2)?
+    } else {
+        b" ".to_vec()
+    };
+    let pad_type = optional_int_arg(arguments, 3, STR_PAD_RIGHT)?;
+    if pad.is_empty() {
+        return None;
+    }
+    if length <= s.len() {
+        return string(s);
+    }
+    if length > MAX_STRING_LENGTH {
+        return None;
+    }
+    let missing = length - s.len();
+    let padding = |len: usize| -> Vec<u8> { pad.iter().cycle().take(len).copied().collect() };
+    let (left, right) = match pad_type {
+        STR_PAD_LEFT => (missing, 0),
+        STR_PAD_RIGHT => (0, missing),
+        STR_PAD_BOTH => (missing / 2, missing - missing / 2),
+        _ => return None,
+    };
+    let mut padded = padding(left);
+    padded.extend(s);
+    padded.extend(padding(right));
+    string(padded)
+}
+
+fn substr(arguments: &[PHPValue]) -> Option<PHPValue> {
+    let s = string_arg(arguments, 0)?;
+    let len = s.len() as i64;
+    let offset = int_arg(arguments, 1)?;
+    let start = if offset < 0 {
+        (len + offset).max(0)
+    } else {
+        offset.min(len)
+    };
+    let end = match arguments.get(2) {
+        None | Some(PHPValue::NULL) => len,
+        Some(_) => {
+            let length = int_arg(arguments, 2)?;
+            if length < 0 {
+                len + length
+            } else {
+                start.saturating_add(length).min(len)
+            }
+        }
+    };
+    if end <= start {
+        return string(vec![]);
+    }
+    string(s[start as usize..end as usize].to_vec())
+}
+
+fn replace_bytes(haystack: &[u8], search: &[u8], replace: &[u8]) -> Vec<u8> {
+    if search.is_empty() {
+        return haystack.to_vec();
+    }
+    let mut result = vec![];
+    let mut idx = 0;
+    while idx < haystack.len() {
+        if haystack[idx..].starts_with(search) {
+            result.extend(replace);
+            idx += search.len();
+        } else {
+            result.push(haystack[idx]);
+            idx += 1;
+        }
+    }
+    result
+}
+
+fn str_replace(arguments: &[PHPValue]) -> Option<PHPValue> {
+    let mut subject = string_arg(arguments, 2)?;
+    if arguments.len() > 3 {
+        // The count is passed by reference
+        return None;
+    }
+    match (arguments.first()?, arguments.get(1)?) {
+        (PHPValue::Array(searches), PHPValue::Array(replaces)) => {
+            let replaces = replaces.values();
+            for (idx, search) in searches.values().iter().enumerate() {
+                let search = string_arg(std::slice::from_ref(search), 0)?;
+                let replace = match replaces.get(idx) {
+                    Some(replace) => string_arg(std::slice::from_ref(replace), 0)?,
+                    None => vec![],
+                };
+                subject = replace_bytes(&subject, &search, &replace);
+            }
+        }
+        (PHPValue::Array(searches), _) => {
+            let replace = string_arg(arguments, 1)?;
+            for search in searches.values() {
+                let search = string_arg(&[search], 0)?;
+                subject = replace_bytes(&subject, &search, &replace);
+            }
+        }
+        (_, PHPValue::Array(_)) => return None,
+        _ => {
+            let search = string_arg(arguments, 0)?;
+            let replace = string_arg(arguments, 1)?;
+            subject = replace_bytes(&subject, &search, &replace);
+        }
+    }
+    string(subject)
+}
+
+fn str_contains(arguments: &[PHPValue]) -> Option<PHPValue> {
+    let haystack = string_arg(arguments, 0)?;
+    let needle = string_arg(arguments, 1)?;
+    let found = needle.is_empty() || haystack.windows(needle.len()).any(|w| w == needle);
+    Some(PHPValue::Boolean(found))
+}
+
+fn str_starts_with(arguments: &[PHPValue]) -> Option<PHPValue> {
+    let haystack = string_arg(arguments, 0)?;
+    let needle = string_arg(arguments, 1)?;
+    Some(PHPValue::Boolean(haystack.starts_with(&needle)))
+}
+
+fn str_ends_with(arguments: &[PHPValue]) -> Option<PHPValue> {
+    let haystack = string_arg(arguments, 0)?;
+    let needle = string_arg(arguments, 1)?;
+    Some(PHPValue::Boolean(haystack.ends_with(&needle)))
+}
+
+///
+/// Supports the conversions `s`, `d`, `u`, `f`, `F`, `x`, `X`, `o`, `b` and `%`, with the
+/// flags `-` and `0`, width and precision
+///
+fn sprintf(arguments: &[PHPValue]) -> Option<PHPValue> {
+    let format = string_arg(arguments, 0)?;
+    let mut args = arguments[1..].iter();
+    let mut result = vec![];
+    let mut chars = format.iter().copied().peekable();
+    while let Some(c) = chars.next() {
+        if c != b'%' {
+            result.push(c);
+            continue;
+        }
+        if chars.peek() == Some(&b'%') {
+            chars.next();
+            result.push(b'%');
+            continue;
+        }
+        let mut left_justify = false;
+        let mut pad = b' ';
+        while let Some(flag) = chars.next_if(|c| matches!(c, b'-' | b'0')) {
+            match flag {
+                b'-' => left_justify = true,
+                _ => pad = b'0',
+            }
+        }
+        let mut width = 0;
+        while let Some(digit) = chars.next_if(u8::is_ascii_digit) {
+            width = width * 10 + (digit - b'0') as usize;
+            if width > MAX_STRING_LENGTH {
+                return None;
+            }
+        }
+        let mut precision = None;
+        if chars.next_if_eq(&b'.').is_some() {
+            let mut p = 0;
+            while let Some(digit) = chars.next_if(u8::is_ascii_digit) {
+                p = p * 10 + (digit - b'0') as usize;
+                if p > MAX_STRING_LENGTH {
+                    return None;
+                }
+            }
+            precision = Some(p);
+        }
+        let value = args.next()?;
+        let mut formatted = match chars.next()? {
+            b's' => {
+                let mut s = string_arg(std::slice::from_ref(value), 0)?;
+                if let Some(p) = precision {
+                    s.truncate(p);
+                }
+                s
+            }
+            b'd' => int_arg(std::slice::from_ref(value), 0)?
+                .to_string()
+                .into_bytes(),
+            b'u' => (int_arg(std::slice::from_ref(value), 0)? as u64)
+                .to_string()
+                .into_bytes(),
+            b'f' | b'F' => {
+                let f = match value {
+                    PHPValue::Float(PHPFloat::Real(f)) => *f,
+                    value => int_arg(std::slice::from_ref(value), 0)? as f64,
+                };
+                format!("{:.*}", precision.unwrap_or(6), f).into_bytes()
+            }
+            b'x' => format!("{:x}", int_arg(std::slice::from_ref(value), 0)?).into_bytes(),
+            b'X' => format!("{:X}", int_arg(std::slice::from_ref(value), 0)?).into_bytes(),
+            b'o' => format!("{:o}", int_arg(std::slice::from_ref(value), 0)?).into_bytes(),
+            b'b' => format!("{:b}", int_arg(std::slice::from_ref(value), 0)?).into_bytes(),
+            _ => return None,
+        };
+        if formatted.len() < width {
+            let padding = vec![pad; width - formatted.len()];
+            if left_justify {
+                formatted.extend(vec![b' '; padding.len()]);
+            } else if pad == b'0' && formatted.first() == Some(&b'-') {
+                formatted.splice(1..1, padding);
+            } else {
+                formatted.splice(0..0, padding);
+            }
+        }
+        result.extend(formatted);
+        if result.len() > MAX_STRING_LENGTH {
+            return None;
+        }
+    }
+    string(result)
+}
+
+fn implode(arguments: &[PHPValue]) -> Option<PHPValue> {
+    let (separator, array) = match arguments {
+        [PHPValue::Array(array)] => (vec![], array),
+        [_, PHPValue::Array(array)] => (string_arg(arguments, 0)?, array),
+        _ => return None,
+    };
+    let pieces = array
+        .values()
+        .into_iter()
+        .map(|value| string_arg(&[value], 0))
+        .collect::<Option<Vec<_>>>()?;
+    string(pieces.join(&separator[..]))
+}
+
+fn explode(arguments: &[PHPValue]) -> Option<PHPValue> {
+    let separator = string_arg(arguments, 0)?;
+    let s = string_arg(arguments, 1)?;
+    let limit = optional_int_arg(arguments, 2, i64::MAX)?;
+    if separator.is_empty() {
+        return None;
+    }
+    let mut pieces: Vec<Vec<u8>> = vec![];
+    let mut rest = &s[..];
+    let max_pieces = if limit > 0 {
+        limit as usize
+    } else {
+        usize::MAX
+    };
+    loop {
+        if pieces.len() + 1 >= max_pieces {
+            pieces.push(rest.to_vec());
+            break;
+        }
+        match rest.windows(separator.len()).position(|w| w == separator) {
+            Some(pos) => {
+                pieces.push(rest[..pos].to_vec());
+                rest = &rest[pos + separator.len()..];
+            }
+            None => {
+                pieces.push(rest.to_vec());
+                break;
+            }
+        }
+    }
+    if limit < 0 {
+        let keep = pieces.len().saturating_sub(limit.unsigned_abs() as usize);
+        pieces.truncate(keep);
+    }
+    let values = pieces
+        .into_iter()
+        .map(|piece| PHPValue::String(OsString::from_vec(piece)))
+        .collect::<Vec<_>>();
+    Some(PHPValue::Array(if values.is_empty() {
+        PHPArray::Empty
+    } else {
+        PHPArray::Vector(values)
+    }))
+}
+
+fn count(arguments: &[PHPValue]) -> Option<PHPValue> {
+    if optional_int_arg(arguments, 1, 0)? != 0 {
+        // COUNT_RECURSIVE
+        return None;
+    }
+    Some(PHPValue::Int(array_arg(arguments, 0)?.len() as i64))
+}
+
+fn array_keys(arguments: &[PHPValue]) -> Option<PHPValue> {
+    if arguments.len() > 1 {
+        return None;
+    }
+    let keys = array_arg(arguments, 0)?
+        .entries()
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (key, _))| (PHPValue::Int(idx as i64), key))
+        .collect();
+    Some(PHPValue::Array(PHPArray::from_entries(keys)))
+}
+
+fn array_values(arguments: &[PHPValue]) -> Option<PHPValue> {
+    let values = array_arg(arguments, 0)?
+        .values()
+        .into_iter()
+        .enumerate()
+        .map(|(idx, value)| (PHPValue::Int(idx as i64), value))
+        .collect();
+    Some(PHPValue::Array(PHPArray::from_entries(values)))
+}
+
+/// Integer keys are renumbered, while later string keys replace earlier ones
+fn array_merge(arguments: &[PHPValue]) -> Option<PHPValue> {
+    let mut merged: Vec<(PHPValue, PHPValue)> = vec![];
+    let mut next_idx = 0;
+    for idx in 0..arguments.len() {
+        for (key, value) in array_arg(arguments, idx)?.entries() {
+            match key {
+                PHPValue::Int(_) => {
+                    merged.push((PHPValue::Int(next_idx), value));
+                    next_idx += 1;
+                }
+                key => match merged.iter_mut().find(|(k, _)| *k == key) {
+                    Some(entry) => entry.1 = value,
+                    None => merged.push((key, value)),
+                },
+            }
+        }
+    }
+    Some(PHPValue::Array(PHPArray::from_entries(merged)))
+}
+
+fn array_key_exists(arguments: &[PHPValue]) -> Option<PHPValue> {
+    let key = arguments.first()?.as_php_array_key()?;
+    if let PHPValue::String(s) = &key {
+        if s.as_bytes()
+            .first()
+            .is_some_and(|c| c.is_ascii_digit() || *c == b'-')
+        {
+            // Numeric strings are converted to integer keys
+            return None;
+        }
+    }
+    let exists = array_arg(arguments, 1)?
+        .entries()
+        .iter()
+        .any(|(k, _)| *k == key);
+    Some(PHPValue::Boolean(exists))
+}
+
+fn in_array(arguments: &[PHPValue]) -> Option<PHPValue> {
+    let needle = arguments.first()?;
+    let haystack = array_arg(arguments, 1)?;
+    let strict = match arguments.get(2) {
+        None => false,
+        Some(strict) => strict.as_bool()?,
+    };
+    for value in haystack.values() {
+        let same_kind = std::mem::discriminant(needle) == std::mem::discriminant(&value);
+        if !strict && !same_kind {
+            // Loose comparison between different types isn't modelled
+            return None;
+        }
+        if needle.identical_to(&value)? {
+            return Some(PHPValue::Boolean(true));
+        }
+    }
+    Some(PHPValue::Boolean(false))
+}
+
+fn abs(arguments: &[PHPValue]) -> Option<PHPValue> {
+    match arguments.first()? {
+        PHPValue::Int(i) => i.checked_abs().map(PHPValue::Int),
+        PHPValue::Float(PHPFloat::Real(f)) => Some(PHPValue::Float(PHPFloat::new(f.abs()))),
+        _ => None,
+    }
+}
+
+/// The values compared by `max()` and `min()`, which are numbers only
+fn numeric_values(arguments: &[PHPValue]) -> Option<Vec<PHPValue>> {
+    let values = match arguments {
+        [PHPValue::Array(array)] => array.values(),
+        values => values.to_vec(),
+    };
+    let numeric = values
+        .iter()
+        .all(|v| matches!(v, PHPValue::Int(_) | PHPValue::Float(PHPFloat::Real(_))));
+    (numeric && !values.is_empty()).then_some(values)
+}
+
+fn extreme(arguments: &[PHPValue], pick_later: fn(f64, f64) -> bool) -> Option<PHPValue> {
+    let values = numeric_values(arguments)?;
+    let mut best = values.first()?.clone();
+    for value in values.into_iter().skip(1) {
+        if pick_later(value.as_f64()?, best.as_f64()?) {
+            best = value;
+        }
+    }
+    Some(best)
+}
+
+fn max(arguments: &[PHPValue]) -> Option<PHPValue> {
+    extreme(arguments, |candidate, best| candidate > best)
+}
+
+fn min(arguments: &[PHPValue]) -> Option<PHPValue> {
+    extreme(arguments, |candidate, best| candidate < best)
+}
+
+fn intdiv(arguments: &[PHPValue]) -> Option<PHPValue> {
+    let dividend = int_arg(arguments, 0)?;
+    let divisor = int_arg(arguments, 1)?;
+    dividend.checked_div(divisor).map(PHPValue::Int)
+}
+
+fn float_arg(arguments: &[PHPValue], idx: usize) -> Option<f64> {
+    match arguments.get(idx)? {
+        PHPValue::Float(PHPFloat::Real(f)) => Some(*f),
+        PHPValue::Int(i) => Some(*i as f64),
+        _ => None,
+    }
+}
+
+fn floor(arguments: &[PHPValue]) -> Option<PHPValue> {
+    Some(PHPValue::Float(PHPFloat::new(
+        float_arg(arguments, 0)?.floor(),
+    )))
+}
+
+fn ceil(arguments: &[PHPValue]) -> Option<PHPValue> {
+    Some(PHPValue::Float(PHPFloat::new(
+        float_arg(arguments, 0)?.ceil(),
+    )))
+}
+
+fn intval(arguments: &[PHPValue]) -> Option<PHPValue> {
+    if optional_int_arg(arguments, 1, 10)? != 10 {
+        return None;
+    }
+    match arguments.first()? {
+        PHPValue::String(s) => {
+            // Leading whitespace, an optional sign and digits
+            let s = s.as_bytes();
+            let s = &s[s.iter().take_while(|c| c.is_ascii_whitespace()).count()..];
+            let sign_len = s.iter().take_while(|c| matches!(c, b'-' | b'+')).count();
+            if sign_len > 1 {
+                return Some(PHPValue::Int(0));
+            }
+            let digits = s[sign_len..]
+                .iter()
+                .take_while(|c| c.is_ascii_digit())
+                .count();
+            if digits == 0 {
+                return Some(PHPValue::Int(0));
+            }
+            let number = std::str::from_utf8(&s[..sign_len + digits]).ok()?;
+            // Overflowing strings are clamped by PHP, which we leave be
+            number.parse().ok().map(PHPValue::Int)
+        }
+        value => value.as_php_int(),
+    }
+}
+
+fn strval(arguments: &[PHPValue]) -> Option<PHPValue> {
+    string(string_arg(arguments, 0)?)
+}
+
+fn boolval(arguments: &[PHPValue]) -> Option<PHPValue> {
+    arguments.first()?.as_php_bool()
+}
+
+fn is_kind(arguments: &[PHPValue], is: fn(&PHPValue) -> bool) -> Option<PHPValue> {
+    Some(PHPValue::Boolean(is(arguments.first()?)))
+}
@@ -0,0 +1,433 @@
+//!
+//! Native functions whose return type, or type of by-reference parameters, depends on
+//! the arguments, like `json_decode($json, true)` returning arrays rather than objects.
+//!
+//! The built-in rules are registered along with the native symbols. Additional rules
+//! can be registered with [SymbolData::register_function_refinement]
+//!
+//! [SymbolData::register_function_refinement]: crate::symboldata::SymbolData::register_function_refinement
+use std::os::unix::ffi::OsStrExt;
+
+use crate::{
+    analysis::state::AnalysisState,
+    symbols::FullyQualifiedName,
+    types::{
+        type_operators::TypeOperator,
+        union::{DiscreteType, DiscretlyAccessedType, PHPType, ShapeType, ShapeTypeKey, UnionType},
+    },
+    value::PHPValue,
+};
+
+const PREG_OFFSET_CAPTURE: i64 = 256;
+const PREG_UNMATCHED_AS_NULL: i64 = 512;
+const PREG_SET_ORDER: i64 = 2;
+
+/// What is known about an argument at the call site
+#[derive(Clone, Debug, Default)]
+pub struct CallArgument {
+    pub ptype: Option<PHPType>,
+    pub value: Option<PHPValue>,
+}
+
+/// Computes the return type from the arguments. `None` keeps the declared return type
+pub type ReturnTypeRule = fn(&[CallArgument]) -> Option<PHPType>;
+
+///
+/// The types written to the by-reference parameters
+/// *  .0 = Index of the parameter
+/// *  .1 = Type written to it
+///
+pub type OutParameterRule = fn(&[CallArgument]) -> Vec<(usize, PHPType)>;
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FunctionRefinement {
+    pub return_type: Option<ReturnTypeRule>,
+    pub out_parameters: Option<OutParameterRule>,
+}
+
+impl FunctionRefinement {
+    pub fn get_return_type(&self, arguments: &[CallArgument]) -> Option<PHPType> {
+        self.return_type.and_then(|rule| rule(arguments))
+    }
+
+    pub fn get_out_parameter_types(&self, arguments: &[CallArgument]) -> Vec<(usize, PHPType)> {
+        self.out_parameters
+            .map(|rule| rule(arguments))
+            .unwrap_or_default()
+    }
+}
+
+const fn returns(rule: ReturnTypeRule) -> FunctionRefinement {
+    FunctionRefinement {
+        return_type: Some(rule),
+        out_parameters: None,
+    }
+}
+
+const NATIVE_REFINEMENTS: &[(&str, FunctionRefinement)] = &[
+    ("\\json_decode", returns(json_decode)),
+    ("\\array_map", returns(array_map)),
+    ("\\array_filter", returns(array_filter)),
+    ("\\array_values", returns(array_values)),
+    ("\\array_keys", returns(array_keys)),
+    ("\\explode", returns(explode)),
+    ("\\str_replace", returns(str_replace)),
+    ("\\str_ireplace", returns(str_replace)),
+    (
+        "\\preg_match",
+        FunctionRefinement {
+            return_type: Some(preg_match),
+            out_parameters: Some(preg_match_out),
+        },
+    ),
+    (
+        "\\preg_match_all",
+        FunctionRefinement {
+            return_type: Some(preg_match_all),
+            out_parameters: Some(preg_match_all_out),
+        },
+    ),
+];
+
+pub fn register(state: &mut AnalysisState) {
+    for (name, refinement) in NATIVE_REFINEMENTS {
+        state
+            .symbol_data
+            .register_function_refinement(FullyQualifiedName::from(*name), *refinement);
+    }
+}
+
+fn argument_type(arguments: &[CallArgument], idx: usize) -> Option<&PHPType> {
+    arguments.get(idx)?.ptype.as_ref()
+}
+
+fn argument_value(arguments: &[CallArgument], idx: usize) -> Option<&PHPValue> {
+    arguments.get(idx)?.value.as_ref()
+}
+
+fn argument_int(arguments: &[CallArgument], idx: usize) -> Option<i64> {
+    match argument_value(arguments, idx)? {
+        PHPValue::Int(i) => Some(*i),
+        _ => None,
+    }
+}
+
+/// `true` when the flag is set, `false` when it isn't, `None` when the flags aren't known
+fn has_flag(arguments: &[CallArgument], idx: usize, flag: i64) -> Option<bool> {
+    if idx >= arguments.len() {
+        return Some(false);
+    }
+    argument_int(arguments, idx).map(|flags| flags & flag == flag)
+}
+
+fn is_array_type(ptype: &PHPType) -> bool {
+    let variants = ptype.as_discrete_variants();
+    !variants.is_empty()
+        && variants.iter().all(|variant| {
+            matches!(
+                variant,
+                DiscretlyAccessedType::Discrete(
+                    DiscreteType::Array
+                        | DiscreteType::Vector(_)
+                        | DiscreteType::HashMap(_, _)
+                        | DiscreteType::Shape(_)
+                        | DiscreteType::List(_)
+                        | DiscreteType::NonEmptyList(_)
+                        | DiscreteType::NonEmptyArray(_, _)
+                )
+            )
+        })
+}
+
+fn is_list_type(ptype: &PHPType) -> bool {
+    ptype.as_discrete_variants().iter().all(|variant| {
+        matches!(
+            variant,
+            DiscretlyAccessedType::Discrete(DiscreteType::List(_) | DiscreteType::NonEmptyList(_))
+        )
+    })
+}
+
+fn key_type(ptype: &PHPType) -> PHPType {
+    TypeOperator::KeyOf(ptype.clone())
+        .evaluate()
+        .unwrap_or_else(|| DiscreteType::ArrayKey.into())
+}
+
+fn value_type(ptype: &PHPType) -> PHPType {
+    TypeOperator::ValueOf(ptype.clone())
+        .evaluate()
+        .unwrap_or_else(|| DiscreteType::Mixed.into())
+}
+
+fn union(types: &[DiscreteType]) -> PHPType {
+    UnionType::from(types).into()
+}
+
+/// `json_decode($json, true)` gives arrays instead of `stdClass`-objects
+fn json_decode(arguments: &[CallArgument]) -> Option<PHPType> {
+    const JSON_OBJECT_AS_ARRAY: i64 = 1;
+    let associative = match argument_value(arguments, 1) {
+        Some(PHPValue::Boolean(associative)) => *associative,
+        None if arguments.len() > 1 => return None,
+        // With `null` or no argument, the flags decides
+        _ => has_flag(arguments, 3, JSON_OBJECT_AS_ARRAY)?,
+    };
+    let mut types = vec![
+        DiscreteType::Array,
+        DiscreteType::String,
+        DiscreteType::Int,
+        DiscreteType::Float,
+        DiscreteType::Bool,
+        DiscreteType::NULL,
+    ];
+    if !associative {
+        types.push(DiscreteType::Named("stdClass".into(), "\\stdClass".into()));
+    }
+    Some(union(&types))
+}
+
+/// The values are what the callback returns, and keys are kept with a single array
+fn array_map(arguments: &[CallArgument]) -> Option<PHPType> {
+    let Some(DiscreteType::TypedCallable(_, value)) =
+        argument_type(arguments, 0).and_then(|t| t.single_type())
+    else {
+        return None;
+    };
+    if arguments.len() > 2 {
+        return Some(DiscreteType::List(value).into());
+    }
+    let array = argument_type(arguments, 1)?;
+    if !is_array_type(array) {
+        return None;
+    }
+    if is_list_type(array) {
+        Some(DiscreteType::List(value).into())
+    } else {
+        Some(DiscreteType::HashMap(key_type(array), value).into())
+    }
+}
+
+/// Keys are kept, and without a callback, falsy values like `null` and `false` are removed
+fn array_filter(arguments: &[CallArgument]) -> Option<PHPType> {
+    let array = argument_type(arguments, 0)?;
+    if !is_array_type(array) {
+        return None;
+    }
+    let value = value_type(array);
+    let value = if arguments.len() < 2 {
+        match &value {
+            PHPType::Union(u) => {
+                let truthy = u.filter_types(|variant| {
+                    !matches!(
+                        variant,
+                        DiscretlyAccessedType::Discrete(DiscreteType::NULL | DiscreteType::False)
+                    )
+                });
+                if truthy.is_empty() {
+                    value
+                } else {
+                    truthy.into()
+                }
+            }
+            _ => value,
+        }
+    } else {
+        value
+    };
+    Some(DiscreteType::HashMap(key_type(array), value).into())
+}
+
+fn array_values(arguments: &[CallArgument]) -> Option<PHPType> {
+    let array = argument_type(arguments, 0)?;
+    if !is_array_type(array) {
+        return None;
+    }
+    Some(DiscreteType::List(value_type(array)).into())
+}
+
+fn array_keys(arguments: &[CallArgument]) -> Option<PHPType> {
+    let array = argument_type(arguments, 0)?;
+    if !is_array_type(array) {
+        return None;
+    }
+    Some(DiscreteType::List(key_type(array)).into())
+}
+
+/// Never empty, unless the limit is negative
+fn explode(arguments: &[CallArgument]) -> Option<PHPType> {
+    let string = DiscreteType::String.into();
+    match arguments.get(2) {
+        None => Some(DiscreteType::NonEmptyList(string).into()),
+        Some(_) => match argument_int(arguments, 2) {
+            Some(limit) if limit >= 0 => Some(DiscreteType::NonEmptyList(string).into()),
+            _ => Some(DiscreteType::List(string).into()),
+        },
+    }
+}
+
+/// Same kind as the subject, and arrays keep their keys
+fn str_replace(arguments: &[CallArgument]) -> Option<PHPType> {
+    let subject = argument_type(arguments, 2)?;
+    if is_array_type(subject) {
+        return Some(DiscreteType::HashMap(key_type(subject), DiscreteType::String.into()).into());
+    }
+    let is_string = subject.as_discrete_variants().iter().all(|variant| {
+        matches!(
+            variant,
+            DiscretlyAccessedType::Discrete(DiscreteType::String | DiscreteType::StringLiteral(_))
+        )
+    });
+    is_string.then(|| DiscreteType::String.into())
+}
+
+fn preg_match(_arguments: &[CallArgument]) -> Option<PHPType> {
+    Some(union(&[
+        DiscreteType::IntRange(Some(0), Some(1)),
+        DiscreteType::False,
+    ]))
+}
+
+fn preg_match_all(_arguments: &[CallArgument]) -> Option<PHPType> {
+    Some(union(&[
+        DiscreteType::IntRange(Some(0), None),
+        DiscreteType::False,
+    ]))
+}
+
+/// The type of a single match, which depends on the flags
+fn preg_match_type(arguments: &[CallArgument], flags_idx: usize) -> PHPType {
+    let (Some(unmatched_as_null), Some(offset_capture)) = (
+        has_flag(arguments, flags_idx, PREG_UNMATCHED_AS_NULL),
+        has_flag(arguments, flags_idx, PREG_OFFSET_CAPTURE),
+    ) else {
+        return DiscreteType::Mixed.into();
+    };
+    let matched: PHPType = if unmatched_as_null {
+        union(&[DiscreteType::String, DiscreteType::NULL])
+    } else {
+        DiscreteType::String.into()
+    };
+    if offset_capture {
+        // `[$match, $offset]`
+        let with_offset =
+            DiscreteType::List(UnionType::from_pair(matched, DiscreteType::Int).into());
+        return with_offset.into();
+    }
+    matched
+}
+
+///
+/// With a known pattern, the matches are a shape with the whole match and each group. A
+/// failed match leaves it empty, but it's only read after a successful one in practice
+///
+fn preg_match_out(arguments: &[CallArgument]) -> Vec<(usize, PHPType)> {
+    let match_type = preg_match_type(arguments, 3);
+    let pattern = match argument_value(arguments, 0) {
+        Some(PHPValue::String(pattern)) => get_pattern_group_keys(pattern.as_bytes()),
+        _ => None,
+    };
+    let matches = match pattern {
+        Some(keys) => DiscreteType::Shape(keys.into_iter().fold(ShapeType::new(), |shape, key| {
+            shape.with_key_type(key, match_type.clone())
+        })),
+        None => DiscreteType::HashMap(DiscreteType::ArrayKey.into(), match_type),
+    };
+    vec![(2, matches.into())]
+}
+
+///
+/// The keys of a match of `pattern`, like `/(?<year>\d+)-(\d+)/`: `0` for the whole match,
+/// then the number of each group, and the name of named groups. `None` for patterns we
+/// can't tell, like with branch resets `(?|...)`
+///
+fn get_pattern_group_keys(pattern: &[u8]) -> Option<Vec<ShapeTypeKey>> {
+    let start = pattern.iter().position(|c| !c.is_ascii_whitespace())?;
+    let (&delimiter, rest) = pattern[start..].split_first()?;
+    let closing = match delimiter {
+        b'(' => b')',
+        b'{' => b'}',
+        b'[' => b']',
+        b'<' => b'>',
+        _ => delimiter,
+    };
+    let end = rest.iter().rposition(|c| *c == closing)?;
+    let (body, modifiers) = (&rest[..end], &rest[end + 1..]);
+    if modifiers.contains(&b'n') {
+        // `NO_AUTO_CAPTURE` leaves out the unnamed groups
+        return None;
+    }
+
+    let mut keys = vec![ShapeTypeKey::Int(0)];
+    let mut group = 0;
+    let mut in_class = false;
+    let mut idx = 0;
+    while idx < body.len() {
+        match body[idx] {
+            b'\\' => idx += 1,
+            b'[' if !in_class => {
+                in_class = true;
+                // A `]` first in the class is a literal
+                if body.get(idx + 1) == Some(&b'^') {
+                    idx += 1;
+                }
+                if body.get(idx + 1) == Some(&b']') {
+                    idx += 1;
+                }
+            }
+            b']' if in_class => in_class = false,
+            b'(' if !in_class => {
+                let after = &body[idx + 1..];
+                let name = if let Some(named) = after
+                    .strip_prefix(b"?P<")
+                    .or_else(|| after.strip_prefix(b"?<"))
+                {
+                    named
+                        .iter()
+                        .position(|c| *c == b'>')
+                        .map(|len| &named[..len])
+                } else if let Some(named) = after.strip_prefix(b"?'") {
+                    named
+                        .iter()
+                        .position(|c| *c == b'\'')
+                        .map(|len| &named[..len])
+                } else {
+                    None
+                };
+                match name {
+                    // Lookbehinds, like `(?<=a)`, aren't groups
+                    Some(name) if name.first().is_some_and(|c| *c == b'=' || *c == b'!') => (),
+                    Some(name) => {
+                        group += 1;
+                        keys.push(ShapeTypeKey::String(name.into()));
+                        keys.push(ShapeTypeKey::Int(group));
+                    }
+                    None if after.starts_with(b"?|") => return None,
+                    None if after.starts_with(b"?") || after.starts_with(b"*") => (),
+                    None => {
+                        group += 1;
+                        keys.push(ShapeTypeKey::Int(group));
+                    }
+                }
+            }
+            _ => (),
+        }
+        idx += 1;
+    }
+    Some(keys)
+}
+
+fn preg_match_all_out(arguments: &[CallArgument]) -> Vec<(usize, PHPType)> {
+    let group = DiscreteType::HashMap(DiscreteType::ArrayKey.into(), preg_match_type(arguments, 3));
+    let matches = match has_flag(arguments, 3, PREG_SET_ORDER) {
+        // A list of the groups for each match
+        Some(true) => DiscreteType::List(group.into()),
+        // A list of the matches for each group
+        Some(false) => DiscreteType::HashMap(
+            DiscreteType::ArrayKey.into(),
+            DiscreteType::List(preg_match_type(arguments, 3)).into(),
+        ),
+        None => DiscreteType::Array,
+    };
+    vec![(2, matches.into())]
+}
@@ -0,0 +1,70 @@
+use crate::locks::RecoverableLock;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use crate::analysis::analyzer::{Analyzer, AnalyzerError};
+use crate::analysis::state::AnalysisState;
+use crate::issue::{IssueEmitter, VoidEmitter};
+use crate::symboldata::class::ClassType;
+
+///
+/// Registers the declarations in a stub-file, a PHP-file with declarations only, like the
+/// ones for extensions not shipped with the analyzer.
+///
+pub fn register_stub_file(
+    state: &mut AnalysisState,
+    path: &Path,
+    emitter: &dyn IssueEmitter,
+) -> Result<(), AnalyzerError> {
+    let buffer = std::fs::read(path).map_err(AnalyzerError::Read)?;
+    register_stub_buffer(state, buffer, path.into(), emitter)
+}
+
+///
+/// Registers the declarations in `buffer` as native symbols. Only the first pass is performed,
+/// so the declared types are used as is. Syntax errors in the stub are reported to `emitter`,
+/// other issues are not.
+///
+pub fn register_stub_buffer(
+    state: &mut AnalysisState,
+    buffer: Vec<u8>,
+    filename: PathBuf,
+    emitter: &dyn IssueEmitter,
+) -> Result<(), AnalyzerError> {
+    let uri: OsString = filename.clone().into();
+    let mut analyzer = Analyzer::new(
+        state.config.clone(),
+        Box::new(move || Ok(buffer.clone())),
+        uri.clone(),
+    );
+    analyzer.parse(emitter)?;
+    let void_emitter = VoidEmitter::new();
+
+    let mut stub_state = AnalysisState::new_with_symbols(state.symbol_data.clone());
+    stub_state.config = state.config.clone();
+    stub_state.pass = 1;
+    stub_state.filename = Some(filename);
+    analyzer.first_pass(&mut stub_state, &void_emitter);
+
+    mark_as_native(&stub_state, &uri);
+    Ok(())
+}
+
+/// Flags the symbols declared in `uri` as native, so they are treated like the built-in ones
+fn mark_as_native(state: &AnalysisState, uri: &OsString) {
+    for class_handle in state.symbol_data.classes.read_or_recover().values() {
+        let mut class_type = class_handle.write_or_recover();
+        match &mut *class_type {
+            ClassType::Class(c) if c.position.uri == *uri => c.is_native = true,
+            ClassType::Interface(i) if i.position.uri == *uri => i.is_native = true,
+            ClassType::Trait(t) if t.position.uri == *uri => t.is_native = true,
+            _ => (),
+        }
+    }
+    for function_handle in state.symbol_data.functions.read_or_recover().values() {
+        let mut function = function_handle.write_or_recover();
+        if function.position.uri == *uri {
+            function.is_native = true;
+        }
+    }
+}
@@ -89,27 +89,44 @@ pub trait ThirdPassAnalyzeableNode {
         our_path.push(node_ref.clone());
 
         for child in node_ref.children_any() {
-            if !child.analyze_third_pass(state, emitter, &our_path) {
+            if !self.analyze_third_pass_child(child, state, emitter, &our_path) {
                 return false;
             }
-            let mut found = false;
-            if let Some(looking_for) = &state.looking_for_node {
-                if child.contains_pos(looking_for.pos) {
-                    found = true;
-                }
+        }
+        true
+    }
+
+    ///
+    /// Analyzes a single child, where `path` includes the node itself. Used by nodes which
+    /// analyze some of their children in a different scope than the others
+    ///
+    fn analyze_third_pass_child(
+        &self,
+        child: AnyNodeRef,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+        path: &[AnyNodeRef],
+    ) -> bool {
+        if !child.analyze_third_pass(state, emitter, path) {
+            return false;
+        }
+        let mut found = false;
+        if let Some(looking_for) = &state.looking_for_node {
+            if child.contains_pos(looking_for.pos) {
+                found = true;
             }
-            if found {
-                if let Some(looking_for) = state.looking_for_node.take() {
-                    looking_for.found(child, state, &our_path);
-                    return false;
-                }
+        }
+        if found {
+            if let Some(looking_for) = state.looking_for_node.take() {
+                looking_for.found(child, state, path);
+                return false;
             }
+        }
 
-            if let AnyNodeRef::Comment(c) = &child {
-                state.last_doc_comment = Some((c.get_raw(), c.range()));
-            } else if state.last_doc_comment.is_some() {
-                state.last_doc_comment = None;
-            }
+        if let AnyNodeRef::Comment(c) = &child {
+            state.last_doc_comment = Some((c.get_raw(), c.range()));
+        } else if state.last_doc_comment.is_some() {
+            state.last_doc_comment = None;
         }
         true
     }
@@ -5,10 +5,13 @@ use crate::{
         any::AnyNodeRef,
     },
     issue::IssueEmitter,
-    types::union::PHPType,
+    types::{
+        traversable::plain_generator_type,
+        union::{DiscreteType, PHPType},
+    },
 };
 
-use super::analysis::ThirdPassAnalyzeableNode;
+use super::{analysis::ThirdPassAnalyzeableNode, yield_expression::contains_yield};
 use crate::autotree::NodeAccess;
 
 impl AnonymousFunctionCreationExpressionNode {
@@ -26,12 +29,27 @@ impl AnonymousFunctionCreationExpressionNode {
         crate::missing_none!("{}.get_php_value(..)", self.kind())
     }
 
+    pub fn get_declared_return_type(
+        &self,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) -> Option<PHPType> {
+        self.return_type.as_ref()?.get_utype(state, emitter)
+    }
+
+    /// A typed callable, when the return type is declared or the closure contains `yield`
     pub fn get_utype(
         &self,
-        _state: &mut AnalysisState,
-        _emitter: &dyn IssueEmitter,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
     ) -> Option<PHPType> {
-        crate::missing_none!("{}.get_utype(..)", self.kind())
+        let return_type = match self.get_declared_return_type(state, emitter) {
+            Some(return_type) => return_type,
+            None if contains_yield(&self.as_any()) => plain_generator_type(),
+            None => return None,
+        };
+        let parameter_types = self.parameters.get_parameter_types(state, emitter);
+        Some(DiscreteType::TypedCallable(parameter_types, return_type).into())
     }
 }
 
@@ -42,7 +60,8 @@ impl ThirdPassAnalyzeableNode for AnonymousFunctionCreationExpressionNode {
         emitter: &dyn IssueEmitter,
         path: &[AnyNodeRef],
     ) -> bool {
-        let function = FunctionState::new_anonymous();
+        let return_type = self.get_declared_return_type(state, emitter);
+        let function = FunctionState::new_anonymous(return_type);
         state.in_function_stack.push(function);
         let ret = self.analyze_third_pass_children(&self.as_any(), state, emitter, path);
         state.in_function_stack.pop();
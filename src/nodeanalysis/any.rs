@@ -1,4 +1,6 @@
+use crate::analysis::security;
 use crate::analysis::state::AnalysisState;
+use crate::analysis::taint;
 use crate::autonodes::any::AnyNodeRef;
 use crate::autotree::NodeAccess;
 use crate::issue::IssueEmitter;
@@ -32,6 +34,9 @@ impl IntoFirstPassAnalyzeable for AnyNodeRef<'_> {
             // Class properties
             AnyNodeRef::PropertyDeclaration(p) => cb(*p),
 
+            // Trait imports
+            AnyNodeRef::UseDeclaration(u) => cb(*u),
+
             // Defining which namespace we're currently in
             AnyNodeRef::NamespaceDefinition(n) => cb(*n),
 
@@ -48,6 +53,12 @@ impl IntoFirstPassAnalyzeable for AnyNodeRef<'_> {
             // Binary expression
             AnyNodeRef::BinaryExpression(be) => cb(*be),
 
+            // Include and require, for the file dependency graph
+            AnyNodeRef::IncludeExpression(i) => cb(*i),
+            AnyNodeRef::IncludeOnceExpression(i) => cb(*i),
+            AnyNodeRef::RequireExpression(r) => cb(*r),
+            AnyNodeRef::RequireOnceExpression(r) => cb(*r),
+
             _ => return None,
         })
     }
@@ -68,7 +79,9 @@ impl IntoSecondPassAnalyzeable for AnyNodeRef<'_> {
             AnyNodeRef::TraitDeclaration(td) => cb(*td),
             AnyNodeRef::MethodDeclaration(md) => cb(*md),
             AnyNodeRef::PropertyDeclaration(pd) => cb(*pd),
+            AnyNodeRef::FunctionDefinition(fd) => cb(*fd),
             AnyNodeRef::ClassConstantAccessExpression(cc) => cb(*cc),
+            AnyNodeRef::UseDeclaration(u) => cb(*u),
             _ => return None,
         })
     }
@@ -80,6 +93,8 @@ impl IntoThirdPassAnalyzeable for AnyNodeRef<'_> {
         CB: FnMut(&dyn ThirdPassAnalyzeableNode) -> T,
     {
         Some(match self {
+            AnyNodeRef::Error(e) => cb(*e),
+
             AnyNodeRef::ClassDeclaration(c) => cb(*c),
             AnyNodeRef::TraitDeclaration(t) => cb(*t),
             AnyNodeRef::InterfaceDeclaration(i) => cb(*i),
@@ -107,6 +122,7 @@ impl IntoThirdPassAnalyzeable for AnyNodeRef<'_> {
             // AnyNodeRef::ElseClause(x) => cb(*x),
             AnyNodeRef::ForeachStatement(fs) => cb(*fs),
             AnyNodeRef::ForStatement(fs) => cb(*fs),
+            AnyNodeRef::WhileStatement(ws) => cb(*ws),
 
             AnyNodeRef::TryStatement(ts) => cb(*ts),
             AnyNodeRef::CatchClause(cc) => cb(*cc),
@@ -118,12 +134,17 @@ impl IntoThirdPassAnalyzeable for AnyNodeRef<'_> {
             AnyNodeRef::VariableName(vn) => cb(*vn),
 
             AnyNodeRef::MemberCallExpression(mce) => cb(*mce),
+            AnyNodeRef::NullsafeMemberCallExpression(nmce) => cb(*nmce),
+            AnyNodeRef::ScopedCallExpression(sce) => cb(*sce),
+            AnyNodeRef::ObjectCreationExpression(oce) => cb(*oce),
             AnyNodeRef::FunctionCallExpression(fc) => cb(*fc),
             AnyNodeRef::MemberAccessExpression(ma) => cb(*ma),
+            AnyNodeRef::NullsafeMemberAccessExpression(nma) => cb(*nma),
             AnyNodeRef::SubscriptExpression(se) => cb(*se),
 
             AnyNodeRef::ConditionalExpression(ce) => cb(*ce),
             AnyNodeRef::ReturnStatement(ret) => cb(*ret),
+            AnyNodeRef::YieldExpression(y) => cb(*y),
             AnyNodeRef::ScopedPropertyAccessExpression(ret) => cb(*ret),
             _ => return None,
         })
@@ -137,8 +158,13 @@ impl ErrorPassAnalyzableNode for AnyNodeRef<'_> {
         state: &mut AnalysisState,
         emitter: &dyn IssueEmitter,
     ) {
-        for child in node_ref.children_any() {
-            child.analyze_errors(&child, state, emitter);
+        match node_ref {
+            AnyNodeRef::Error(error) => error.analyze_errors(node_ref, state, emitter),
+            _ => {
+                for child in node_ref.children_any() {
+                    child.analyze_errors(&child, state, emitter);
+                }
+            }
         }
     }
 }
@@ -180,18 +206,20 @@ impl ThirdPassAnalyzeableNode for AnyNodeRef<'_> {
         emitter: &dyn IssueEmitter,
         path: &[AnyNodeRef],
     ) -> bool {
-        if let Some(carry_on) =
+        let carry_on = if let Some(carry_on) =
             self.with_third_pass_analyzeable(&mut |x: &dyn ThirdPassAnalyzeableNode| {
                 x.analyze_third_pass(state, emitter, path)
-            })
-        {
+            }) {
             /*if let Some(looking_for) = state.looking_for_node {
 
             }*/
             carry_on
         } else {
             self.analyze_third_pass_children(self, state, emitter, path)
-        }
+        };
+        taint::analyze_taint(self, state, emitter);
+        security::analyze_security(self, state, emitter);
+        carry_on
     }
 }
 
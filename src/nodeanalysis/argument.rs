@@ -1,6 +1,9 @@
 use crate::{
     analysis::state::AnalysisState,
-    autonodes::{any::AnyNodeRef, argument::ArgumentNode},
+    autonodes::{
+        any::AnyNodeRef,
+        argument::{ArgumentChildren, ArgumentNode},
+    },
     issue::IssueEmitter,
     types::union::PHPType,
     value::PHPValue,
@@ -29,6 +32,21 @@ impl ArgumentNode {
     ) -> Option<PHPType> {
         self.child.get_utype(state, emitter)
     }
+
+    pub fn write_to(
+        &self,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+        val_type: Option<PHPType>,
+        value: Option<PHPValue>,
+    ) {
+        match &*self.child {
+            ArgumentChildren::_Expression(e) => e.write_to(state, emitter, val_type, value),
+            ArgumentChildren::Name(_)
+            | ArgumentChildren::VariadicUnpacking(_)
+            | ArgumentChildren::Extra(_) => (),
+        }
+    }
 }
 
 impl ThirdPassAnalyzeableNode for ArgumentNode {
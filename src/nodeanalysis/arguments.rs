@@ -1,6 +1,16 @@
 use crate::{
-    analysis::state::AnalysisState, autonodes::arguments::ArgumentsNode, issue::IssueEmitter,
-    types::union::PHPType, value::PHPValue,
+    analysis::state::AnalysisState,
+    autonodes::{
+        argument::{ArgumentChildren, ArgumentNode},
+        arguments::{ArgumentsChildren, ArgumentsNode},
+    },
+    autotree::NodeAccess,
+    issue::{Issue, IssueEmitter, VoidEmitter},
+    native::refinements::CallArgument,
+    symboldata::class::FunctionArgumentData,
+    symbols::Name,
+    types::union::PHPType,
+    value::PHPValue,
 };
 
 impl ArgumentsNode {
@@ -47,4 +57,104 @@ impl ArgumentsNode {
             .map(|x| x.get_utype(state, emitter))
             .collect()
     }
+
+    /// Argument types, using literal types like `true` or `'asc'` when the value is known
+    pub fn get_argument_literal_types(
+        &self,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) -> Vec<Option<PHPType>> {
+        self.children
+            .iter()
+            .map(|x| {
+                x.get_php_value(state, emitter)
+                    .and_then(|value| value.get_literal_utype())
+                    .or_else(|| x.get_utype(state, emitter))
+            })
+            .collect()
+    }
+
+    pub(crate) fn get_argument_nodes(&self) -> impl Iterator<Item = &ArgumentNode> {
+        self.children.iter().filter_map(|child| match &**child {
+            ArgumentsChildren::Argument(argument) => Some(&**argument),
+            _ => None,
+        })
+    }
+
+    /// `true` when any argument is passed by name, like `f(name: $x)`
+    pub fn has_named_arguments(&self) -> bool {
+        self.get_argument_nodes()
+            .any(|argument| argument.name.is_some())
+    }
+
+    /// Type and value of each argument, as used by native function refinements
+    pub fn get_call_arguments(
+        &self,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) -> Vec<CallArgument> {
+        self.get_argument_nodes()
+            .map(|argument| CallArgument {
+                ptype: argument.get_utype(state, emitter),
+                value: argument.get_php_value(state, emitter),
+            })
+            .collect()
+    }
+
+    /// Writes to the argument at `idx`, when passed to a by-reference parameter
+    pub fn write_to_argument(
+        &self,
+        idx: usize,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+        val_type: PHPType,
+    ) {
+        if let Some(argument) = self.get_argument_nodes().nth(idx) {
+            argument.write_to(state, emitter, Some(val_type), None);
+        }
+    }
+
+    ///
+    /// Emits arguments which can be `null`, passed to parameters of `callee` which don't
+    /// accept `null`. Arguments after an unpacking like `...$args` can't be matched to
+    /// their parameters, so they're skipped
+    ///
+    pub fn check_nullable_arguments(
+        &self,
+        callee: &Name,
+        parameters: &[FunctionArgumentData],
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) {
+        for (idx, argument) in self.get_argument_nodes().enumerate() {
+            if let ArgumentChildren::VariadicUnpacking(_) = &*argument.child {
+                break;
+            }
+            let parameter = if let Some(arg_name) = &argument.name {
+                let arg_name = arg_name.get_name();
+                parameters.iter().find(|p| p.name == arg_name)
+            } else {
+                parameters
+                    .get(idx)
+                    .or_else(|| parameters.last().filter(|p| p.variadic))
+            };
+            let Some(parameter) = parameter else {
+                continue;
+            };
+            if parameter.by_ref || parameter.accepts_null(state) {
+                continue;
+            }
+            let Some(arg_type) = argument.get_utype(state, &VoidEmitter::new()) else {
+                continue;
+            };
+            if arg_type.can_be_null() {
+                emitter.emit(Issue::NullableArgumentType(
+                    argument.pos(state),
+                    callee.clone(),
+                    parameter.name.clone(),
+                    arg_type,
+                ));
+            }
+        }
+    }
 }
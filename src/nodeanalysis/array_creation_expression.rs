@@ -1,4 +1,4 @@
-use std::{convert::TryInto, os::unix::prelude::OsStrExt};
+use std::os::unix::prelude::OsStrExt;
 
 use crate::autonodes::array_element_initializer::ArrayElementInitializerValue;
 // use crate::autonodes::array_element_initializer::ArrayElementInitializerChildren;
@@ -10,7 +10,7 @@ use crate::{
     autonodes::array_creation_expression::ArrayCreationExpressionNode,
     issue::IssueEmitter,
     missing_none,
-    types::union::{DiscreteType, UnionType},
+    types::union::{DiscreteType, ShapeType, ShapeTypeKey, UnionType},
     value::PHPValue,
 };
 
@@ -37,21 +37,39 @@ impl ArrayCreationExpressionNode {
 
         let mut value_types = vec![];
         let mut key_types = vec![];
+        // Stays a shape as long as every key is known
+        let mut shape = Some(ShapeType::new());
+        let mut next_idx = 0;
         for child in &self.children {
             if child.spread.is_some() {
                 // Noen barn har spread, da gir vi opp
                 return missing_none!("{}: Finn ut av array-type med spread", self.brief_desc());
             }
-            if let Some(key) = &child.key {
+            let shape_key = if let Some(key) = &child.key {
                 has_some_keys = true;
                 let key_type = key.get_utype(state, emitter)?;
                 key_types.push(key_type);
-            }
+                key.get_php_value(state, emitter)
+                    .and_then(|k| ShapeTypeKey::from_php_value(&k))
+            } else {
+                Some(ShapeTypeKey::Int(next_idx))
+            };
             let value_type = child.value.as_ref()?.get_utype(state, emitter)?;
+            shape = match (shape, shape_key) {
+                (Some(shape), Some(key)) => {
+                    if let ShapeTypeKey::Int(idx) = key {
+                        next_idx = next_idx.max(idx + 1);
+                    }
+                    Some(shape.with_key_type(key, value_type.clone()))
+                }
+                _ => None,
+            };
             value_types.push(value_type);
         }
         let value_types = UnionType::flatten(value_types).into();
-        if has_some_keys {
+        if let (true, Some(shape)) = (has_some_keys, shape) {
+            Some(DiscreteType::Shape(shape).into())
+        } else if has_some_keys {
             let key_types = UnionType::flatten(key_types).into();
             Some(DiscreteType::HashMap(key_types, value_types).into())
         } else {
@@ -76,9 +94,10 @@ impl ArrayCreationExpressionNode {
                 if let Some(key_val) = key.get_php_value(state, emitter) {
                     hashmap = true;
                     match key_val {
-                        PHPValue::Int(ival) if ival > 0 && idx < ival.try_into().unwrap() => {
-                            // FIXME this should probably not unwrap, but result in a None return
-                            idx = (ival + 1).try_into().unwrap();
+                        PHPValue::Int(ival)
+                            if ival > 0 && usize::try_from(ival).is_ok_and(|i| idx < i) =>
+                        {
+                            idx = usize::try_from(ival).ok()?.checked_add(1)?;
                             key_val
                         }
                         PHPValue::String(ref s) => {
@@ -103,7 +122,7 @@ impl ArrayCreationExpressionNode {
                     return None;
                 }
             } else {
-                let v = PHPValue::Int(idx.try_into().unwrap());
+                let v = PHPValue::Int(i64::try_from(idx).ok()?);
                 idx += 1;
                 v
             };
@@ -112,7 +131,7 @@ impl ArrayCreationExpressionNode {
                     ArrayElementInitializerValue::_Expression(e) => e.get_php_value(state, emitter),
                     ArrayElementInitializerValue::ByRef(v) => v.get_php_value(state, emitter),
 
-                    ArrayElementInitializerValue::Extra(_) => todo!(),
+                    ArrayElementInitializerValue::Extra(_) => None,
                 }
             } else {
                 None
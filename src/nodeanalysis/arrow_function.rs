@@ -1,8 +1,16 @@
 use crate::{
-    analysis::state::AnalysisState, autonodes::arrow_function::ArrowFunctionNode,
-    issue::IssueEmitter, types::union::PHPType,
+    analysis::state::AnalysisState,
+    autonodes::arrow_function::ArrowFunctionNode,
+    issue::IssueEmitter,
+    types::{
+        traversable::plain_generator_type,
+        union::{DiscreteType, PHPType},
+    },
 };
 
+use super::yield_expression::contains_yield;
+use crate::autotree::NodeAccess;
+
 impl ArrowFunctionNode {
     pub fn read_from(&self, _state: &mut AnalysisState, _emitter: &dyn IssueEmitter) {
         crate::missing!("{}.read_from(..)", self.kind());
@@ -16,11 +24,26 @@ impl ArrowFunctionNode {
         crate::missing_none!("{}.get_php_value(..)", self.kind())
     }
 
+    pub fn get_declared_return_type(
+        &self,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) -> Option<PHPType> {
+        self.return_type.as_ref()?.get_utype(state, emitter)
+    }
+
+    /// A typed callable, when the return type is declared or the arrow function contains `yield`
     pub fn get_utype(
         &self,
-        _state: &mut AnalysisState,
-        _emitter: &dyn IssueEmitter,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
     ) -> Option<PHPType> {
-        crate::missing_none!("{}.get_utype(..)", self.kind())
+        let return_type = match self.get_declared_return_type(state, emitter) {
+            Some(return_type) => return_type,
+            None if contains_yield(&self.as_any()) => plain_generator_type(),
+            None => return None,
+        };
+        let parameter_types = self.parameters.get_parameter_types(state, emitter);
+        Some(DiscreteType::TypedCallable(parameter_types, return_type).into())
     }
 }
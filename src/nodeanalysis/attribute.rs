@@ -0,0 +1,182 @@
+use crate::locks::RecoverableLock;
+use crate::{
+    analysis::state::AnalysisState,
+    autonodes::{
+        argument::ArgumentNode,
+        arguments::ArgumentsChildren,
+        attribute::{AttributeChildren, AttributeNode},
+    },
+    autotree::NodeAccess,
+    issue::{Issue, IssueEmitter, VoidEmitter},
+    symboldata::{
+        attributes::{
+            get_native_attribute_flags, AttributeArgument, AttributeData, AttributeTarget,
+        },
+        class::{ClassName, ClassType},
+        FileLocation,
+    },
+    symbols::Name,
+    types::union::PHPType,
+};
+
+impl AttributeNode {
+    pub fn read_from(&self, _state: &mut AnalysisState, _emitter: &dyn IssueEmitter) {
+        crate::missing!("{}.read_from(..)", self.kind());
+    }
+
+    pub fn get_php_value(
+        &self,
+        _state: &mut AnalysisState,
+        _emitter: &dyn IssueEmitter,
+    ) -> Option<crate::value::PHPValue> {
+        crate::missing_none!("{}.get_php_value(..)", self.kind())
+    }
+
+    pub fn get_utype(
+        &self,
+        _state: &mut AnalysisState,
+        _emitter: &dyn IssueEmitter,
+    ) -> Option<PHPType> {
+        crate::missing_none!("{}.get_utype(..)", self.kind())
+    }
+
+    pub fn get_class_name(&self, state: &mut AnalysisState) -> Option<ClassName> {
+        match &*self.child {
+            AttributeChildren::Name(n) => {
+                Some(ClassName::new_with_analysis_state(&n.get_name(), state))
+            }
+            AttributeChildren::QualifiedName(qn) => {
+                Some(ClassName::new_with_fq_name(qn.get_fq_name(state)))
+            }
+            AttributeChildren::Extra(_) => None,
+        }
+    }
+
+    fn get_argument_nodes(&self) -> Vec<&ArgumentNode> {
+        let Some(params) = &self.parameters else {
+            return vec![];
+        };
+        params
+            .children
+            .iter()
+            .filter_map(|arg| match &**arg {
+                ArgumentsChildren::Argument(a) => Some(&**a),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn get_attribute_data(&self, state: &mut AnalysisState) -> Option<AttributeData> {
+        let class_name = self.get_class_name(state)?;
+        // Constants might not be known yet, so we don't want to report on them here
+        let emitter = VoidEmitter::new();
+        let arguments = self
+            .get_argument_nodes()
+            .into_iter()
+            .map(|arg| AttributeArgument {
+                name: arg.name.as_ref().map(|n| n.get_name()),
+                value: arg.get_php_value(state, &emitter),
+            })
+            .collect();
+
+        Some(AttributeData {
+            class_name,
+            position: FileLocation::new(self.pos(state)),
+            arguments,
+        })
+    }
+
+    /// Verifies that the attribute refers to an attribute-class which allows `target`,
+    /// and that the arguments are compatible with its constructor.
+    ///
+    /// Returns the attribute-flags of the class, if it's known
+    pub fn analyze_attribute(
+        &self,
+        target: AttributeTarget,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) -> Option<(ClassName, i64)> {
+        let class_name = self.get_class_name(state)?;
+        let fq_name = class_name.get_fq_name().clone();
+
+        let flags = if let Some(class_data) = state.symbol_data.get_class(&class_name) {
+            let unlocked = class_data.read_or_recover();
+            match &*unlocked {
+                ClassType::Class(c) => {
+                    let flags = c.get_attribute_flags();
+                    drop(unlocked);
+                    if flags.is_some() {
+                        self.analyze_constructor_arguments(&class_name, state, emitter);
+                    }
+                    flags
+                }
+                ClassType::None | ClassType::Interface(_) | ClassType::Trait(_) => None,
+            }
+        } else if let Some(flags) = get_native_attribute_flags(&fq_name) {
+            Some(flags)
+        } else {
+            emitter.emit(Issue::UnknownClass(self.pos(state), fq_name));
+            return None;
+        };
+
+        let Some(flags) = flags else {
+            emitter.emit(Issue::NotAnAttribute(self.pos(state), fq_name));
+            return None;
+        };
+
+        if flags & target.flag() == 0 {
+            emitter.emit(Issue::InvalidAttributeTarget(
+                self.pos(state),
+                fq_name,
+                target.to_string(),
+            ));
+        }
+        Some((class_name, flags))
+    }
+
+    fn analyze_constructor_arguments(
+        &self,
+        class_name: &ClassName,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) {
+        let symbol_data = state.symbol_data.clone();
+        let Some(constructor) = symbol_data.get_class(class_name).and_then(|c| {
+            c.read_or_recover()
+                .get_method(&"__construct".into(), symbol_data.clone())
+        }) else {
+            return;
+        };
+
+        for (idx, arg) in self.get_argument_nodes().into_iter().enumerate() {
+            let declared = if let Some(arg_name) = &arg.name {
+                let arg_name = arg_name.get_name();
+                constructor.arguments.iter().find(|a| a.name == arg_name)
+            } else {
+                constructor
+                    .arguments
+                    .get(idx)
+                    .or_else(|| constructor.arguments.last().filter(|a| a.variadic))
+            };
+            let Some(declared) = declared else {
+                continue;
+            };
+            let Some(declared_type) = declared.get_type(state) else {
+                continue;
+            };
+            let Some(arg_type) = arg.get_utype(state, emitter) else {
+                continue;
+            };
+            // The arguments are constant, so a scalar of the wrong type is always a mistake
+            if let Some(false) = arg_type.can_be_passed_strictly_as(&declared_type, &symbol_data) {
+                emitter.emit(Issue::IncompatibleArgumentType(
+                    arg.pos(state),
+                    Name::from(format!("{}::__construct", class_name.get_fq_name())),
+                    declared.name.clone(),
+                    declared_type,
+                    arg_type,
+                ));
+            }
+        }
+    }
+}
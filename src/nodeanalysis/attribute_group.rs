@@ -0,0 +1,26 @@
+use crate::{
+    analysis::state::AnalysisState, autonodes::attribute_group::AttributeGroupNode,
+    issue::IssueEmitter, types::union::PHPType,
+};
+
+impl AttributeGroupNode {
+    pub fn read_from(&self, _state: &mut AnalysisState, _emitter: &dyn IssueEmitter) {
+        crate::missing!("{}.read_from(..)", self.kind());
+    }
+
+    pub fn get_php_value(
+        &self,
+        _state: &mut AnalysisState,
+        _emitter: &dyn IssueEmitter,
+    ) -> Option<crate::value::PHPValue> {
+        crate::missing_none!("{}.get_php_value(..)", self.kind())
+    }
+
+    pub fn get_utype(
+        &self,
+        _state: &mut AnalysisState,
+        _emitter: &dyn IssueEmitter,
+    ) -> Option<PHPType> {
+        crate::missing_none!("{}.get_utype(..)", self.kind())
+    }
+}
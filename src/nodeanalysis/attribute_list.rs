@@ -0,0 +1,70 @@
+use std::collections::HashSet;
+
+use crate::{
+    analysis::state::AnalysisState,
+    autonodes::{attribute::AttributeNode, attribute_list::AttributeListNode},
+    autotree::NodeAccess,
+    issue::{Issue, IssueEmitter},
+    symboldata::attributes::{AttributeData, AttributeTarget, ATTRIBUTE_IS_REPEATABLE},
+    types::union::PHPType,
+};
+
+impl AttributeListNode {
+    pub fn read_from(&self, _state: &mut AnalysisState, _emitter: &dyn IssueEmitter) {
+        crate::missing!("{}.read_from(..)", self.kind());
+    }
+
+    pub fn get_php_value(
+        &self,
+        _state: &mut AnalysisState,
+        _emitter: &dyn IssueEmitter,
+    ) -> Option<crate::value::PHPValue> {
+        crate::missing_none!("{}.get_php_value(..)", self.kind())
+    }
+
+    pub fn get_utype(
+        &self,
+        _state: &mut AnalysisState,
+        _emitter: &dyn IssueEmitter,
+    ) -> Option<PHPType> {
+        crate::missing_none!("{}.get_utype(..)", self.kind())
+    }
+
+    /// All attributes, regardless of which `#[...]`-group they're declared in
+    pub fn get_attribute_nodes(&self) -> Vec<&AttributeNode> {
+        self.children
+            .iter()
+            .flat_map(|group| group.children.iter().map(|a| &**a))
+            .collect()
+    }
+
+    pub fn get_attributes(&self, state: &mut AnalysisState) -> Vec<AttributeData> {
+        self.get_attribute_nodes()
+            .into_iter()
+            .filter_map(|a| a.get_attribute_data(state))
+            .collect()
+    }
+
+    /// Verify the attributes attached to a declaration of kind `target`
+    pub fn analyze_attributes(
+        &self,
+        target: AttributeTarget,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) {
+        let mut seen = HashSet::new();
+        for attribute in self.get_attribute_nodes() {
+            let Some((class_name, flags)) = attribute.analyze_attribute(target, state, emitter)
+            else {
+                continue;
+            };
+            let lc_fq_name = class_name.get_fq_name().to_ascii_lowercase();
+            if !seen.insert(lc_fq_name) && flags & ATTRIBUTE_IS_REPEATABLE == 0 {
+                emitter.emit(Issue::NonRepeatableAttribute(
+                    attribute.pos(state),
+                    class_name.get_fq_name().clone(),
+                ));
+            }
+        }
+    }
+}
@@ -1,5 +1,14 @@
+use std::sync::{Arc, RwLock};
+
 use crate::{
-    analysis::state::AnalysisState,
+    analysis::{
+        hardening::{
+            check_null, get_variable_node, new_scope_with_type_check, BranchTypeHardening,
+        },
+        scope::{BranchSide, BranchableScope, Scope},
+        state::AnalysisState,
+        termination::always_terminates,
+    },
     autonodes::{
         _expression::_ExpressionNode,
         _primary_expression::_PrimaryExpressionNode,
@@ -8,6 +17,7 @@ use crate::{
             BinaryExpressionNode, BinaryExpressionOperator, BinaryExpressionRight,
         },
     },
+    config::PHPVersion,
     issue::{Issue, IssueEmitter, VoidEmitter},
     missing,
     operators::{
@@ -387,7 +397,7 @@ impl BinaryExpressionNode {
         // > The expression must be wrapped in parentheses and produce a string.
         //
 
-        if !state.config.php_version.is_less_than(7, 3, 0) {
+        if state.config.php_version >= PHPVersion::new(7, 3) {
             return;
         };
 
@@ -490,7 +500,99 @@ impl ThirdPassAnalyzeableNode for BinaryExpressionNode {
             }
         }*/
         // eprintln!("TODO: {}", state.pos_as_string(self.range));
-        self.analyze_third_pass_children(&self.as_any(), state, emitter, path)
+        match &*self.operator {
+            BinaryExpressionOperator::BooleanAnd(_)
+            | BinaryExpressionOperator::LogicalAnd(_)
+            | BinaryExpressionOperator::BooleanOr(_)
+            | BinaryExpressionOperator::LogicalOr(_)
+            | BinaryExpressionOperator::NullCoalesce(_) => {
+                self.analyze_third_pass_short_circuit(state, emitter, path)
+            }
+            _ => self.analyze_third_pass_children(&self.as_any(), state, emitter, path),
+        }
+    }
+}
+
+impl BinaryExpressionNode {
+    ///
+    /// `&&`, `||` and `??` only evaluates the right operand depending on the left one, so
+    /// the right operand is analyzed in a scope narrowed by the left one
+    ///
+    fn analyze_third_pass_short_circuit(
+        &self,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+        path: &[AnyNodeRef],
+    ) -> bool {
+        let mut our_path = path.to_vec();
+        our_path.push(self.as_any());
+
+        if !self.analyze_third_pass_child(self.left.as_any(), state, emitter, &our_path) {
+            return false;
+        }
+        let scope = state.current_scope();
+        let right_scope = self.branch_on_left_operand(scope.clone(), true, state);
+        state.push_scope(right_scope);
+        let carry_on =
+            self.analyze_third_pass_child(self.right.as_any(), state, emitter, &our_path);
+        let right_scope = state.pop_scope();
+        if !carry_on {
+            return false;
+        }
+        let skipped_scope = self.branch_on_left_operand(scope.clone(), false, state);
+        // Like `$x ?? throw new Exception()`, where we only continue if the right operand
+        // wasn't evaluated
+        if always_terminates(&self.right.as_any(), state) {
+            scope.join(vec![skipped_scope], emitter);
+        } else {
+            scope.join(vec![right_scope, skipped_scope], emitter);
+        }
+        true
+    }
+
+    /// A branch of the scope where the left operand decided if the right operand is evaluated
+    fn branch_on_left_operand(
+        &self,
+        scope: Arc<RwLock<Scope>>,
+        right_evaluated: bool,
+        state: &mut AnalysisState,
+    ) -> Arc<RwLock<Scope>> {
+        let side = |evaluated_on: BranchSide| {
+            if right_evaluated {
+                evaluated_on
+            } else {
+                evaluated_on.inverse()
+            }
+        };
+        match &*self.operator {
+            BinaryExpressionOperator::BooleanAnd(_) | BinaryExpressionOperator::LogicalAnd(_) => {
+                self.left
+                    .branch_with_hardened_types_base_on_conditional_node(
+                        scope,
+                        side(BranchSide::TrueBranch),
+                        state,
+                    )
+            }
+            BinaryExpressionOperator::BooleanOr(_) | BinaryExpressionOperator::LogicalOr(_) => self
+                .left
+                .branch_with_hardened_types_base_on_conditional_node(
+                    scope,
+                    side(BranchSide::FalseBranch),
+                    state,
+                ),
+            // The right operand is evaluated when the left one is null
+            BinaryExpressionOperator::NullCoalesce(_) => match get_variable_node(&self.left) {
+                Some(variable) => new_scope_with_type_check(
+                    scope,
+                    variable,
+                    state,
+                    check_null,
+                    side(BranchSide::TrueBranch),
+                ),
+                None => scope.branch(),
+            },
+            _ => scope.branch(),
+        }
     }
 }
 
@@ -1,5 +1,4 @@
 use crate::analysis::state::AnalysisState;
-use crate::extra::ExtraChild;
 use crate::issue::{Issue, IssueEmitter};
 use crate::symboldata::class::ClassName;
 
@@ -61,9 +60,6 @@ pub trait AnalysisOfClassBaseLikeNode: NodeAccess {
                             fq_name: n.get_fq_name(state),
                         });
                     }
-                    BaseClauseChildren::Extra(ExtraChild::Comment(c)) => {
-                        todo!("{:?}", c);
-                    }
                     _ => continue,
                 }
             }
@@ -1,3 +1,4 @@
+use crate::locks::RecoverableLock;
 use std::os::unix::prelude::OsStrExt;
 
 use crate::autonodes::class_constant_access_expression::ClassConstantAccessExpressionClass;
@@ -145,7 +146,7 @@ impl ClassConstantAccessExpressionNode {
                         .as_ref()
                         .map(|cstate| vec![cstate.get_name()]),
                     b"parent" => crate::missing_none!(),
-                    _ => panic!("Should not get here"),
+                    _ => crate::missing_none!("Unknown relative scope {:?}", rel_scope),
                 }
             }
             ClassConstantAccessExpressionClass::ScopedCallExpression(n) => crate::missing_none!(
@@ -199,7 +200,12 @@ impl ClassConstantAccessExpressionNode {
                                     )
                                 }
                             },
-                            DiscretlyAccessedType::Intersection(_) => todo!(),
+                            DiscretlyAccessedType::Intersection(_) => {
+                                return crate::missing_none!(
+                                    "Class constant access on intersection at {}",
+                                    state.pos_as_string(self.range())
+                                )
+                            }
                         }
                     }
                     if !names.is_empty() {
@@ -246,7 +252,7 @@ impl ClassConstantAccessExpressionNode {
             // FIXME handle SomeClass::class-constants
 
             let class_data = state.symbol_data.get_class(class_name)?;
-            let classish = class_data.read().unwrap();
+            let classish = class_data.read_or_recover();
 
             if let Some(v) = classish.get_constant_value(&state.symbol_data, &constant_name) {
                 values.push(v);
@@ -270,11 +276,16 @@ impl SecondPassAnalyzeableNode for ClassConstantAccessExpressionNode {
                 // FIXME handle SomeClass::class-constants
 
                 if let Some(class_data) = state.symbol_data.get_class(class_name) {
+                    {
+                        let mut usage = state.symbol_data.usage.write_or_recover();
+                        usage.mark_class(class_name);
+                        usage.mark_constant(class_name, &constant_name);
+                    }
                     if constant_name == b"class" as &[u8] {
                         continue;
                     }
 
-                    let classish = class_data.read().unwrap();
+                    let classish = class_data.read_or_recover();
                     let const_val = classish.get_constant_value(&state.symbol_data, &constant_name);
 
                     if let None = const_val {
@@ -8,12 +8,16 @@ use crate::{
 impl ClassConstantAccessIdentifierNode {
     pub fn get_name(&self) -> Name {
         let Some(children) = &self.child else {
-            panic!("Expected Name");
+            crate::missing!("Expected Name");
+            return Name::new();
         };
         match &**children {
-            ClassConstantAccessIdentifierChildren::_Expression(_) => todo!(),
             ClassConstantAccessIdentifierChildren::Name(n) => n.get_name(),
-            ClassConstantAccessIdentifierChildren::Extra(_) => todo!(),
+            ClassConstantAccessIdentifierChildren::_Expression(_)
+            | ClassConstantAccessIdentifierChildren::Extra(_) => {
+                crate::missing!("Dynamic class constant name");
+                Name::new()
+            }
         }
     }
 }
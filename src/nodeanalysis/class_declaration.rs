@@ -1,3 +1,4 @@
+use crate::locks::RecoverableLock;
 use std::{
     ffi::OsString,
     os::unix::prelude::OsStrExt,
@@ -16,15 +17,25 @@ use crate::{
     autotree::NodeAccess,
     extra::ExtraChild,
     issue::{Issue, IssueEmitter},
-    phpdoc::types::{PHPDocComment, PHPDocEntry},
+    parser::Range,
+    phpdoc::{
+        phpdoc::without_tool_prefix,
+        types::{PHPDocComment, PHPDocEntry, PHPDocMethod, PHPDocPropertyAccess},
+    },
     symboldata::{
-        class::{ClassData, ClassModifier, ClassName, ClassType},
+        attributes::AttributeTarget,
+        class::{
+            ClassData, ClassModifier, ClassName, ClassType, FunctionArgumentData, MethodData,
+            PropertyData,
+        },
+        templates::{get_bound_violations, get_generic_concretes, get_phpdoc_template_data},
         FileLocation,
     },
     symbols::{FullyQualifiedName, Name},
     types::{
+        phptype::TypeTraits,
         type_parser::TypeParser,
-        union::{PHPType, UnionType},
+        union::{DiscreteType, DiscretlyAccessedType, PHPType, UnionType},
     },
 };
 
@@ -82,6 +93,238 @@ impl ClassDeclarationNode {
         state.symbol_data.get_or_create_class(&class_name)
     }
 
+    /// Emit issues for conflicting trait methods, and abstract trait methods which
+    /// are left unimplemented
+    fn check_trait_methods(&self, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
+        let class_data = self.get_class_data(state);
+        let unlocked = class_data.read_or_recover();
+        let ClassType::Class(cdata) = &*unlocked else {
+            return;
+        };
+        if cdata.traits.is_empty() {
+            return;
+        }
+        let symbol_data = state.symbol_data.clone();
+        let resolved = cdata.traits.resolve_methods(&symbol_data);
+
+        for (lc_name, imported) in &resolved.methods {
+            if cdata.methods.contains_key(lc_name) {
+                continue;
+            }
+            let method = imported.get_method_data();
+            if resolved.conflicts.contains_key(lc_name) {
+                emitter.emit(Issue::TraitMethodConflict(
+                    self.name.pos(state),
+                    cdata.class_name.get_fq_name().clone(),
+                    method.name.clone(),
+                    resolved.get_conflicting_traits(lc_name),
+                ));
+                continue;
+            }
+            if method.modifier != ClassModifier::Abstract
+                || cdata.modifier == ClassModifier::Abstract
+            {
+                continue;
+            }
+            let implemented_in_base = cdata
+                .get_base_class_data(&symbol_data)
+                .and_then(|b| {
+                    b.read_or_recover()
+                        .get_method(&method.name, symbol_data.clone())
+                })
+                .map(|m| m.modifier != ClassModifier::Abstract)
+                .unwrap_or(false);
+            if !implemented_in_base {
+                emitter.emit(Issue::UnimplementedAbstractTraitMethod(
+                    self.name.pos(state),
+                    cdata.class_name.get_fq_name().clone(),
+                    imported.trait_name.get_fq_name().clone(),
+                    method.name.clone(),
+                ));
+            }
+        }
+    }
+
+    /// Checks the generic arguments given with `@extends` and `@implements` against the
+    /// bounds of the templates they are given to
+    fn check_generic_parents(&self, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
+        let class_data = self.get_class_data(state);
+        let parents: Vec<_> = {
+            let unlocked = class_data.read_or_recover();
+            let ClassType::Class(cdata) = &*unlocked else {
+                return;
+            };
+            cdata
+                .phpdoc_base_class_name
+                .iter()
+                .chain(cdata.phpdoc_interfaces.iter())
+                .cloned()
+                .collect()
+        };
+        for parent in parents {
+            let DiscreteType::Generic(base, generic_args) = parent else {
+                continue;
+            };
+            let DiscreteType::Named(name, fq_name) = *base else {
+                continue;
+            };
+            let parent_name = ClassName::new_with_names(name, fq_name);
+            let Some(parent_handle) = state.symbol_data.get_class(&parent_name) else {
+                continue;
+            };
+            let templates = parent_handle.read_or_recover().get_template_data();
+            let concrete = get_generic_concretes(&templates, &generic_args);
+            for (template, bound, concrete_type) in
+                get_bound_violations(&templates, &concrete, &state.symbol_data)
+            {
+                emitter.emit(Issue::TemplateBoundViolation(
+                    self.name.pos(state),
+                    template,
+                    bound,
+                    concrete_type,
+                ));
+            }
+        }
+    }
+
+    ///
+    /// Registers type aliases, imported type aliases and magic members from the
+    /// doc-comment of the class. This is done when the class is registered and
+    /// `state.in_class` is set, so that the types can refer to class templates and
+    /// earlier type aliases
+    ///
+    fn register_deferred_phpdoc_entries(
+        entries: &[PHPDocEntry],
+        class_handle: &Arc<RwLock<ClassType>>,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) {
+        let class_name = class_handle.read_or_recover().get_class_name();
+        let with_class_data = |f: &mut dyn FnMut(&mut ClassData)| {
+            if let ClassType::Class(cdata) = &mut *class_handle.write_or_recover() {
+                f(cdata);
+            }
+        };
+
+        for entry in entries {
+            match entry {
+                PHPDocEntry::TypeAlias(range, name, ptype) => {
+                    let Some(utype) =
+                        TypeParser::from_parsed_type(ptype.clone(), state, emitter, None)
+                    else {
+                        emitter.emit(Issue::PHPDocTypeError(
+                            state.pos_from_range(*range),
+                            format!("Unable to resolve type alias {}", name.to_string_lossy()),
+                        ));
+                        continue;
+                    };
+                    with_class_data(&mut |cdata| {
+                        cdata.type_aliases.insert(name.into(), utype.clone());
+                    });
+                }
+                PHPDocEntry::ImportType(_, name, from_class, alias) => {
+                    let from_class = if from_class.as_bytes().starts_with(b"\\") {
+                        ClassName::new_with_fq_name(FullyQualifiedName::from(from_class.clone()))
+                    } else {
+                        ClassName::new_with_analysis_state(&from_class.into(), state)
+                    };
+                    let local_name: Name = alias.as_ref().unwrap_or(name).into();
+                    with_class_data(&mut |cdata| {
+                        cdata
+                            .imported_type_aliases
+                            .insert(local_name.clone(), (from_class.clone(), name.into()));
+                    });
+                }
+                PHPDocEntry::Property(range, access, ptype, name, _) => {
+                    let name: Name = name.as_bytes()[1..].into();
+                    let mut pdata =
+                        PropertyData::new(FileLocation::new(state.pos_from_range(*range)), name);
+                    pdata.readonly = *access == PHPDocPropertyAccess::Read;
+                    pdata.writeonly = *access == PHPDocPropertyAccess::Write;
+                    pdata.comment_type =
+                        TypeParser::from_parsed_type(ptype.clone(), state, emitter, None)
+                            .map(|t| (t, *range));
+                    let mut duplicate = false;
+                    with_class_data(&mut |cdata| {
+                        duplicate = cdata
+                            .phpdoc_properties
+                            .insert(pdata.name.clone(), pdata.clone())
+                            .is_some();
+                    });
+                    if duplicate {
+                        emitter.emit(Issue::DuplicateDeclaration(
+                            state.pos_from_range(*range),
+                            format!("@property ${}", pdata.name).into(),
+                        ));
+                    }
+                }
+                PHPDocEntry::Method(range, method) => {
+                    let mdata =
+                        Self::get_phpdoc_method_data(*range, method, &class_name, state, emitter);
+                    let mut duplicate = false;
+                    with_class_data(&mut |cdata| {
+                        duplicate = cdata
+                            .phpdoc_methods
+                            .insert(mdata.name.to_ascii_lowercase(), mdata.clone())
+                            .is_some();
+                    });
+                    if duplicate {
+                        emitter.emit(Issue::DuplicateDeclaration(
+                            state.pos_from_range(*range),
+                            format!("@method {}", mdata.name).into(),
+                        ));
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    /// Synthetic method data for a method declared with `@method`
+    fn get_phpdoc_method_data(
+        range: Range,
+        method: &PHPDocMethod,
+        class_name: &ClassName,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) -> MethodData {
+        let mut mdata = MethodData::new_with_name(
+            FileLocation::new(state.pos_from_range(range)),
+            class_name.clone(),
+            method.name.clone().into(),
+        );
+        mdata.is_static = method.is_static;
+        mdata.phpdoc = Some(PHPDocComment {
+            raw: OsString::new(),
+            entries: vec![PHPDocEntry::Method(range, method.clone())],
+        });
+        mdata.comment_return_type = method.return_type.as_ref().and_then(|rtype| {
+            TypeParser::from_parsed_type(rtype.clone(), state, emitter, None).map(|t| (t, range))
+        });
+        for param in &method.params {
+            let phpdoc_type = param.ptype.as_ref().and_then(|ptype| {
+                TypeParser::from_parsed_type(ptype.clone(), state, emitter, None)
+            });
+            mdata.variadic |= param.variadic;
+            mdata.arguments.push(FunctionArgumentData {
+                name: param.name.as_bytes()[1..].into(),
+                arg_type: None,
+                default_value: None,
+                nullable: phpdoc_type
+                    .as_ref()
+                    .map(|t| t.is_nullable())
+                    .unwrap_or(false),
+                optional: param.default.is_some() || param.variadic,
+                inline_phpdoc_type: None,
+                phpdoc_entry: None,
+                phpdoc_type,
+                variadic: param.variadic,
+                by_ref: false,
+            });
+        }
+        mdata
+    }
+
     pub(crate) fn get_inline_generic_doc_comment(
         &self,
         mode: InlineGenericSearchMode,
@@ -134,8 +377,6 @@ impl ClassDeclarationNode {
                     start_search_pos = i.range().end_byte;
                 }
 
-                (_, ClassDeclarationChildren::Extra(ExtraChild::Comment(_))) => todo!(),
-
                 (_, ClassDeclarationChildren::Extra(_)) => (),
             }
         }
@@ -213,7 +454,7 @@ impl FirstPassAnalyzeableNode for ClassDeclarationNode {
                     ClassDeclarationModifier::ReadonlyModifier(_) => {
                         is_read_only = true;
                     }
-                    ClassDeclarationModifier::Extra(_) => todo!(),
+                    ClassDeclarationModifier::Extra(_) => (),
                 }
             }
         }
@@ -221,11 +462,14 @@ impl FirstPassAnalyzeableNode for ClassDeclarationNode {
         let mut deprecated = None;
 
         let mut generic_templates = vec![];
+        let mut template_data = vec![];
 
         let mut phpdoc = None;
 
         let mut phpdoc_base_class_name = None;
         let mut phpdoc_interfaces = vec![];
+        let mut mixins = vec![];
+        let mut deferred_entries = vec![];
 
         if let Some((raw_doc_comment, php_doc_range)) = state.last_doc_comment.clone() {
             match PHPDocComment::parse(&raw_doc_comment, &php_doc_range) {
@@ -235,9 +479,9 @@ impl FirstPassAnalyzeableNode for ClassDeclarationNode {
                     for entry in &doc.entries {
                         match entry {
                             PHPDocEntry::EmptyLine(_) => continue,
-                            PHPDocEntry::Template(_, template, _) => {
+                            PHPDocEntry::Template(_, template) => {
                                 // void
-                                generic_templates.push(template.into());
+                                generic_templates.push((&template.name).into());
                             }
                             PHPDocEntry::Deprecated(dep_range, desc) => {
                                 // void
@@ -276,6 +520,22 @@ impl FirstPassAnalyzeableNode for ClassDeclarationNode {
                                 // void
                             }
 
+                            PHPDocEntry::Property(_, _, _, _, _)
+                            | PHPDocEntry::Method(_, _)
+                            | PHPDocEntry::TypeAlias(_, _, _)
+                            | PHPDocEntry::ImportType(_, _, _, _) => {
+                                // Handled when the class is registered, as they might refer
+                                // to its templates and type aliases
+                                deferred_entries.push(entry.clone());
+                            }
+
+                            PHPDocEntry::Assert(range, _) => {
+                                emitter.emit(Issue::MisplacedPHPDocEntry(
+                                    state.pos_from_range(*range),
+                                    "@assert can't be used on a class-declaration".into(),
+                                ));
+                            }
+
                             PHPDocEntry::Abstract(range) => {
                                 if class_modifier == ClassModifier::None {
                                     class_modifier = ClassModifier::Abstract;
@@ -296,12 +556,17 @@ impl FirstPassAnalyzeableNode for ClassDeclarationNode {
                             PHPDocEntry::GeneralWithParam(range, param, data) => {
                                 // @extends is an alias for @inherits
                                 let lcparam = param.to_ascii_lowercase();
-                                let pname_u8v = lcparam.as_bytes();
+                                let (pname_u8v, is_tool_specific) =
+                                    without_tool_prefix(lcparam.as_bytes());
                                 match pname_u8v {
                                     b"extends" | b"implements" | b"inherits" | b"mixin" => {
-                                        if let Some(ptype) =
-                                            TypeParser::parse(data.clone(), *range, state, emitter)
-                                        {
+                                        if let Some(ptype) = TypeParser::parse_with_templates(
+                                            data.clone(),
+                                            *range,
+                                            state,
+                                            emitter,
+                                            Some(&generic_templates),
+                                        ) {
                                             match pname_u8v {
                                                 b"extends" | b"inherits" => {
                                                     // TODO might emit
@@ -335,7 +600,24 @@ impl FirstPassAnalyzeableNode for ClassDeclarationNode {
                                                     phpdoc_interfaces.append(&mut types);
                                                 }
                                                 b"mixin" => {
-                                                    crate::missing!("Implement @mixin support")
+                                                    for dtype in ptype.as_discrete_variants() {
+                                                        if let DiscretlyAccessedType::Discrete(
+                                                            DiscreteType::Named(name, fq_name),
+                                                        ) = dtype
+                                                        {
+                                                            mixins.push(ClassName::new_with_names(
+                                                                name, fq_name,
+                                                            ));
+                                                        } else {
+                                                            emitter.emit(
+                                                                Issue::InvalidPHPDocEntry(
+                                                                    state.pos_from_range(*range),
+                                                                    "Only classes can be used as @mixin"
+                                                                        .into(),
+                                                                ),
+                                                            );
+                                                        }
+                                                    }
                                                 }
                                                 _ => (),
                                             }
@@ -353,7 +635,9 @@ impl FirstPassAnalyzeableNode for ClassDeclarationNode {
                                     }
                                     _ => {
                                         let sparam: &str = &param.to_string_lossy();
-                                        if !state.config.phpdoc.known_tags.contains(&sparam) {
+                                        if !is_tool_specific
+                                            && !state.config.phpdoc.known_tags.contains(&sparam)
+                                        {
                                             emitter.emit(Issue::UnknownPHPDocEntry(
                                                 state.pos_from_range(*range),
                                                 format!(
@@ -372,7 +656,10 @@ impl FirstPassAnalyzeableNode for ClassDeclarationNode {
                                 // void
                                 // let lcparam = param.to_ascii_lowercase();
                                 let sparam: &str = &param.to_string_lossy();
-                                if !state.config.phpdoc.known_tags.contains(&sparam) {
+                                let (_, is_tool_specific) = without_tool_prefix(param.as_bytes());
+                                if !is_tool_specific
+                                    && !state.config.phpdoc.known_tags.contains(&sparam)
+                                {
                                     emitter.emit(Issue::UnknownPHPDocEntry(
                                         state.pos_from_range(*range),
                                         format!(
@@ -384,7 +671,7 @@ impl FirstPassAnalyzeableNode for ClassDeclarationNode {
                                 }
                             }
                             _ => {
-                                todo!(
+                                crate::missing!(
                                     "E in {:?}:{} {:?}",
                                     state.filename,
                                     php_doc_range.start_point.row,
@@ -393,6 +680,8 @@ impl FirstPassAnalyzeableNode for ClassDeclarationNode {
                             }
                         }
                     }
+                    template_data =
+                        get_phpdoc_template_data(&doc, &generic_templates, state, emitter);
                 }
                 Err(_) => {
                     emitter.emit(Issue::PHPDocParseError(state.pos_from_range(php_doc_range)))
@@ -403,7 +692,10 @@ impl FirstPassAnalyzeableNode for ClassDeclarationNode {
         if let Some(inline_class_name_generic) =
             self.get_inline_generic_doc_comment(InlineGenericSearchMode::Class, state, emitter)
         {
-            todo!("NOE: {:#?}", inline_class_name_generic);
+            crate::missing!(
+                "Inline generics on class name: {:?}",
+                inline_class_name_generic
+            );
         }
 
         let interfaces = self.get_interfaces(state);
@@ -419,8 +711,10 @@ impl FirstPassAnalyzeableNode for ClassDeclarationNode {
         if !generic_templates.is_empty() {
             class_data.generic_templates = Some(generic_templates);
         }
+        class_data.template_data = template_data;
         class_data.phpdoc_base_class_name = phpdoc_base_class_name;
         class_data.phpdoc_interfaces = phpdoc_interfaces;
+        class_data.mixins = mixins;
         if let Some(int) = interfaces {
             class_data.interfaces = int
                 .iter()
@@ -430,10 +724,15 @@ impl FirstPassAnalyzeableNode for ClassDeclarationNode {
                 .collect();
         }
         class_data.phpdoc = phpdoc;
+        class_data.attributes = self
+            .attributes
+            .as_ref()
+            .map(|a| a.get_attributes(state))
+            .unwrap_or_default();
 
         let symbol_data = state.symbol_data.get_or_create_class(&class_name);
         {
-            let mut unlocked = symbol_data.write().unwrap();
+            let mut unlocked = symbol_data.write_or_recover();
             match *unlocked {
                 ClassType::None => {
                     *unlocked = ClassType::Class(class_data);
@@ -449,8 +748,9 @@ impl FirstPassAnalyzeableNode for ClassDeclarationNode {
             }
         }
         // eprintln!("ClassDeclarationNode.analyze_round_one(): Analyzed os fram til {:?}", class_data);
-        state.in_class = Some(ClassState::Class(class_name, symbol_data));
+        state.in_class = Some(ClassState::Class(class_name, symbol_data.clone()));
         state.last_doc_comment = None;
+        Self::register_deferred_phpdoc_entries(&deferred_entries, &symbol_data, state, emitter);
         self.analyze_first_pass_children(&self.as_any(), state, emitter);
         state.in_class = None;
     }
@@ -460,7 +760,12 @@ impl SecondPassAnalyzeableNode for ClassDeclarationNode {
     fn analyze_second_pass(&self, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
         let class_name = self.get_class_name(state);
         state.in_class = Some(ClassState::Class(class_name, self.get_class_data(state)));
+        if let Some(attributes) = &self.attributes {
+            attributes.analyze_attributes(AttributeTarget::Class, state, emitter);
+        }
         self.analyze_second_pass_children(&self.as_any(), state, emitter);
+        self.check_trait_methods(state, emitter);
+        self.check_generic_parents(state, emitter);
         state.in_class = None;
     }
 }
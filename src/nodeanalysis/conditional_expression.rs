@@ -1,4 +1,6 @@
-use crate::analysis::scope::BranchableScope;
+use crate::analysis::hardening::BranchTypeHardening;
+use crate::analysis::scope::{BranchSide, BranchableScope};
+use crate::analysis::termination::always_terminates;
 use crate::autonodes::any::AnyNodeRef;
 use crate::issue::Issue;
 use crate::types::union::PHPType;
@@ -141,28 +143,57 @@ impl ThirdPassAnalyzeableNode for ConditionalExpressionNode {
 
         if let Some(b) = &self.body {
             if true_branch {
-                let branch = scope.branch();
+                let branch = self
+                    .condition
+                    .branch_with_hardened_types_base_on_conditional_node(
+                        scope.clone(),
+                        BranchSide::TrueBranch,
+                        state,
+                    );
                 state.push_scope(branch);
                 let carry_on = b.as_any().analyze_third_pass(state, emitter, path);
-                scopes.push(state.pop_scope());
+                let branch = state.pop_scope();
                 if !carry_on {
                     return false;
                 }
+                if !always_terminates(&b.as_any(), state) {
+                    scopes.push(branch);
+                }
             } else {
                 emitter.emit(Issue::UnreachableCode(b.pos(state)));
             }
+        } else if true_branch {
+            // `$a ?: $b` evaluates to `$a` itself when it's truthy
+            scopes.push(
+                self.condition
+                    .branch_with_hardened_types_base_on_conditional_node(
+                        scope.clone(),
+                        BranchSide::TrueBranch,
+                        state,
+                    ),
+            );
         }
         if false_branch {
-            let branch = scope.branch();
+            let branch = self
+                .condition
+                .branch_with_hardened_types_base_on_conditional_node(
+                    scope.clone(),
+                    BranchSide::FalseBranch,
+                    state,
+                );
             state.push_scope(branch);
             let carry_on = self
                 .alternative
                 .as_any()
                 .analyze_third_pass(state, emitter, path);
-            scopes.push(state.pop_scope());
+            let branch = state.pop_scope();
             if !carry_on {
                 return false;
             }
+            // Like `$x ? $x->foo() : throw new Exception()`
+            if !always_terminates(&self.alternative.as_any(), state) {
+                scopes.push(branch);
+            }
         } else {
             emitter.emit(Issue::UnreachableCode(self.alternative.pos(state)));
         }
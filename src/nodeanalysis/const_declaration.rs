@@ -1,9 +1,13 @@
+use crate::locks::RecoverableLock;
 use crate::{
-    analysis::state::AnalysisState,
+    analysis::state::{AnalysisState, ConstantData},
     autonodes::const_declaration::{ConstDeclarationChildren, ConstDeclarationNode},
     issue::{Issue, IssueEmitter},
-    symboldata::class::ClassType,
-    types::union::PHPType,
+    symboldata::{
+        class::{ClassConstantData, ClassMemberVisibility, ClassType},
+        FileLocation,
+    },
+    types::union::{DiscreteType, PHPType},
 };
 
 use super::analysis::FirstPassAnalyzeableNode;
@@ -40,10 +44,20 @@ impl FirstPassAnalyzeableNode for ConstDeclarationNode {
             crate::missing!("Const har en {:?}, hva gjør vi med det?", modi.get_raw());
         }
 
+        let visibility = self
+            .children
+            .iter()
+            .find_map(|child| match &**child {
+                ConstDeclarationChildren::VisibilityModifier(v) => Some(v.get_visibility()),
+                _ => None,
+            })
+            .unwrap_or(ClassMemberVisibility::Public);
+
         for child in &self.children {
             match &**child {
                 ConstDeclarationChildren::ConstElement(c) => {
                     let name = c.get_const_name();
+                    let c_pos = c.pos(state);
                     let maybe_value = c.get_php_value(state, emitter);
                     if maybe_value.is_none() {
                         emitter.emit(Issue::ParseAnomaly(
@@ -59,9 +73,26 @@ impl FirstPassAnalyzeableNode for ConstDeclarationNode {
                     // let value = c.get_php_value(state, emitter);
 
                     let Some(class_state) = &state.in_class else {
-                        // Global const?
-                        eprintln!("Global const decls?");
-                        todo!("Const: self::{:?} = {:?} ({:?})", name, maybe_value, c);
+                        let mut fq_name = state.namespace.clone().unwrap_or_default();
+                        fq_name.push(name);
+                        let val_type = c
+                            .get_utype(state, emitter)
+                            .unwrap_or_else(|| DiscreteType::Unknown.into());
+                        let fname = state
+                            .filename
+                            .as_ref()
+                            .map(|x| x.as_os_str().to_os_string())
+                            .unwrap_or_default();
+
+                        let mut mutable = state.global.constants.write_or_recover();
+                        if let Some(cdata) = mutable.get_mut(&fq_name) {
+                            emitter.emit(Issue::DuplicateConstant(c_pos, fq_name));
+                            cdata.add_value(fname, c.range(), val_type, maybe_value);
+                        } else {
+                            let mut cdata = ConstantData::new(fq_name.clone());
+                            cdata.add_value(fname, c.range(), val_type, maybe_value);
+                            mutable.insert(fq_name, cdata);
+                        }
                         continue;
                     };
 
@@ -72,7 +103,7 @@ impl FirstPassAnalyzeableNode for ConstDeclarationNode {
                         continue;
                     };
 
-                    let mut mutable = class_data.write().unwrap();
+                    let mut mutable = class_data.write_or_recover();
                     match &mut (*mutable) {
                         ClassType::Class(c) => {
                             if c.constants.get(&name).is_some() {
@@ -88,10 +119,17 @@ impl FirstPassAnalyzeableNode for ConstDeclarationNode {
                                     name,
                                     value
                                 );*/
+                                c.constant_declarations.insert(
+                                    name.clone(),
+                                    ClassConstantData {
+                                        position: FileLocation::new(c_pos.clone()),
+                                        visibility: visibility.clone(),
+                                    },
+                                );
                                 c.constants.insert(name, maybe_value);
                             }
                         }
-                        ClassType::None => todo!(),
+                        ClassType::None => crate::missing!("Constant in unknown class-type"),
                         ClassType::Interface(intf) => {
                             if intf.constants.get(&name).is_some() {
                                 emitter.emit(Issue::DuplicateClassConstant(
@@ -109,10 +147,9 @@ impl FirstPassAnalyzeableNode for ConstDeclarationNode {
                                 intf.constants.insert(name, maybe_value);
                             }
                         }
-                        ClassType::Trait(_) => todo!(),
+                        ClassType::Trait(_) => crate::missing!("Constants in traits"),
                     }
                 }
-                ConstDeclarationChildren::VisibilityModifier(v) => todo!("analysere: {:?}", v),
                 _ => continue,
             }
         }
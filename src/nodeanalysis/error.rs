@@ -1,5 +1,3 @@
-use std::{ffi::OsString, str::FromStr};
-
 use crate::{
     analysis::state::AnalysisState,
     autonodes::any::AnyNodeRef,
@@ -9,7 +7,7 @@ use crate::{
     types::union::PHPType,
 };
 
-use super::analysis::ErrorPassAnalyzableNode;
+use super::analysis::{ErrorPassAnalyzableNode, ThirdPassAnalyzeableNode};
 
 impl ErrorNode {
     pub fn read_from(&self, _state: &mut AnalysisState, _emitter: &dyn IssueEmitter) {}
@@ -38,13 +36,28 @@ impl ErrorPassAnalyzableNode for ErrorNode {
         state: &mut AnalysisState,
         emitter: &dyn IssueEmitter,
     ) {
-        let mut err =
-            OsString::from_str("Parse error near ").expect("This should succeed in all cases");
-        err.push(self.get_raw());
+        // Syntax errors are reported by the analyzer. What's left is nodes which were valid
+        // syntax, but couldn't be converted
+        if let Some(err) = &self.conversion_error {
+            emitter.emit(Issue::ParseAnomaly(
+                state.pos_from_range(err.range),
+                err.error.clone().into(),
+            ));
+        }
+        for child in node_ref.children_any() {
+            child.analyze_errors(&child, state, emitter);
+        }
+    }
+}
 
-        emitter.emit(Issue::ParseError(
-            state.pos_from_range(node_ref.range()),
-            err,
-        ));
+impl ThirdPassAnalyzeableNode for ErrorNode {
+    fn analyze_third_pass(
+        &self,
+        _state: &mut AnalysisState,
+        _emitter: &dyn IssueEmitter,
+        _path: &[AnyNodeRef],
+    ) -> bool {
+        // Whatever was recovered inside a broken subtree is out of context, so it's skipped
+        true
     }
 }
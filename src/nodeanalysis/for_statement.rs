@@ -1,6 +1,15 @@
+use std::sync::{Arc, RwLock};
+
 use crate::{
-    analysis::state::AnalysisState,
-    autonodes::{any::AnyNodeRef, for_statement::ForStatementNode},
+    analysis::{
+        hardening::BranchTypeHardening,
+        scope::{BranchSide, BranchableScope, Scope},
+        state::AnalysisState,
+    },
+    autonodes::{
+        any::AnyNodeRef,
+        for_statement::{ForStatementCondition, ForStatementNode},
+    },
     autotree::NodeAccess,
     issue::IssueEmitter,
     types::union::PHPType,
@@ -28,6 +37,21 @@ impl ForStatementNode {
     ) -> Option<PHPType> {
         crate::missing_none!("{}.get_utype(..)", self.kind())
     }
+
+    /// A branch of the scope, where the loop-condition evaluated to `branch_side`
+    fn branch_on_condition(
+        &self,
+        scope: Arc<RwLock<Scope>>,
+        branch_side: BranchSide,
+        state: &mut AnalysisState,
+    ) -> Arc<RwLock<Scope>> {
+        match self.condition.as_deref() {
+            Some(ForStatementCondition::_Expression(e)) => {
+                e.branch_with_hardened_types_base_on_conditional_node(scope, branch_side, state)
+            }
+            _ => scope.branch(),
+        }
+    }
 }
 
 impl ThirdPassAnalyzeableNode for ForStatementNode {
@@ -55,14 +79,26 @@ impl ThirdPassAnalyzeableNode for ForStatementNode {
 
         //        self.initialize.map(|x| x.read_from(state, emitter));
         crate::missing!("for-loop analysis needs attention");
-        for child in &self.children {
-            if !child.as_any().analyze_third_pass(state, emitter, path) {
-                return false;
-            }
+        // The body is only entered when the condition holds
+        let scope = state.current_scope();
+        let branch = self.branch_on_condition(scope.clone(), BranchSide::TrueBranch, state);
+        state.push_scope(branch);
+        let carry_on = self
+            .children
+            .iter()
+            .all(|child| child.as_any().analyze_third_pass(state, emitter, path))
+            && self
+                .increment
+                .as_ref()
+                .map(|x| x.as_any().analyze_third_pass(state, emitter, path))
+                .unwrap_or(true);
+        let branch = state.pop_scope();
+        if !carry_on {
+            return false;
         }
-        self.increment
-            .as_ref()
-            .map(|x| x.as_any().analyze_third_pass(state, emitter, path))
-            .unwrap_or(true)
+
+        let exit_scope = self.branch_on_condition(scope.clone(), BranchSide::FalseBranch, state);
+        scope.join(vec![branch, exit_scope], emitter);
+        true
     }
 }
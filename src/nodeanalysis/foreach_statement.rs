@@ -1,5 +1,5 @@
 use crate::{
-    analysis::state::AnalysisState,
+    analysis::{state::AnalysisState, taint},
     autonodes::{
         any::AnyNodeRef,
         foreach_statement::{ForeachStatementEntry, ForeachStatementNode},
@@ -49,9 +49,9 @@ impl ForeachStatementEntry {
         match self {
             ForeachStatementEntry::_Expression(e) => None,
             ForeachStatementEntry::ByRef(_) => None,
-            ForeachStatementEntry::ListLiteral(_) => todo!(),
+            ForeachStatementEntry::ListLiteral(_) => None,
             ForeachStatementEntry::Pair(p) => Some(p.key.as_any()),
-            ForeachStatementEntry::Extra(_) => todo!(),
+            ForeachStatementEntry::Extra(_) => None,
         }
     }
 
@@ -100,6 +100,13 @@ impl ThirdPassAnalyzeableNode for ForeachStatementNode {
             value.write_to(state, emitter, value_type, None);
         }
 
+        let targets: Vec<_> = self
+            .get_key_node()
+            .into_iter()
+            .chain(self.get_value_node())
+            .collect();
+        taint::write_traversal_taint(&self.traversable.as_any(), &targets, state);
+
         self.analyze_third_pass_children(&self.as_any(), state, emitter, path)
     }
 }
@@ -3,16 +3,16 @@ use std::{collections::HashMap, ffi::OsString};
 //use tree_sitter::Range;
 use crate::parser::Range;
 use crate::types::type_parser::TypeParser;
-use crate::types::union::PHPType;
+use crate::types::union::{DiscreteType, PHPType};
 use crate::{
     analysis::state::AnalysisState,
     autonodes::formal_parameters::{FormalParametersChildren, FormalParametersNode},
     extra::ExtraChild,
     issue::IssueEmitter,
     phpdoc::types::{PHPDocComment, PHPDocEntry},
-    symboldata::class::FunctionArgumentData,
+    symboldata::{attributes::AttributeTarget, class::FunctionArgumentData},
     symbols::Name,
-    types::union::{from_vec_parsed_type},
+    types::union::from_vec_parsed_type,
 };
 
 use crate::autotree::NodeAccess;
@@ -95,6 +95,7 @@ impl FormalParametersNode {
                             phpdoc_entry,
                             phpdoc_type,
                             variadic: false,
+                            by_ref: s.reference_modifier.is_some(),
                         };
 
                         params.push(data);
@@ -130,6 +131,7 @@ impl FormalParametersNode {
                             phpdoc_entry,
                             phpdoc_type,
                             variadic: true,
+                            by_ref: variadic.reference_modifier.is_some(),
                         };
 
                         params.push(data);
@@ -151,6 +153,49 @@ impl FormalParametersNode {
         params
     }
 
+    /// Declared type of each parameter, `mixed` when undeclared
+    pub(crate) fn get_parameter_types(
+        &self,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) -> Vec<PHPType> {
+        self.children
+            .iter()
+            .filter_map(|child| match &**child {
+                FormalParametersChildren::SimpleParameter(s) => Some(s.get_utype(state, emitter)),
+                FormalParametersChildren::VariadicParameter(v) => Some(v.get_utype(state, emitter)),
+                FormalParametersChildren::PropertyPromotionParameter(_) => Some(None),
+                FormalParametersChildren::Extra(_) => None,
+            })
+            .map(|ptype| ptype.unwrap_or_else(|| DiscreteType::Mixed.into()))
+            .collect()
+    }
+
+    /// Verify the attributes attached to each of the parameters
+    pub(crate) fn analyze_parameter_attributes(
+        &self,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) {
+        for child in &self.children {
+            let (attributes, target) = match &**child {
+                FormalParametersChildren::PropertyPromotionParameter(p) => {
+                    (&p.attributes, AttributeTarget::PromotedProperty)
+                }
+                FormalParametersChildren::SimpleParameter(p) => {
+                    (&p.attributes, AttributeTarget::Parameter)
+                }
+                FormalParametersChildren::VariadicParameter(p) => {
+                    (&p.attributes, AttributeTarget::Parameter)
+                }
+                FormalParametersChildren::Extra(_) => continue,
+            };
+            if let Some(attributes) = attributes {
+                attributes.analyze_attributes(target, state, emitter);
+            }
+        }
+    }
+
     ///
     /// if someone has declared types with inline comments
     /// function foo(/** bool */ $arg)
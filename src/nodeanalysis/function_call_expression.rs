@@ -1,19 +1,34 @@
+use crate::locks::RecoverableLock;
 use std::{
     os::unix::prelude::OsStrExt,
+    path::PathBuf,
     sync::{Arc, RwLock},
 };
 
 use crate::{
-    analysis::state::{AnalysisState, ConstantData},
+    analysis::{
+        hardening::{harden_scope_based_on_conditional_node, harden_scope_with_assertions},
+        scope::BranchSide,
+        state::{AnalysisState, ConstantData},
+    },
     autonodes::{
         any::AnyNodeRef,
+        argument::ArgumentChildren,
         function_call_expression::{FunctionCallExpressionFunction, FunctionCallExpressionNode},
     },
-    issue::{Issue, IssueEmitter},
+    issue::{Issue, IssueEmitter, VoidEmitter},
+    native::pure,
     nodeanalysis::lang::AnalysisOfType,
-    symboldata::FunctionData,
-    symbols::FullyQualifiedName,
-    types::union::{DiscreteType, PHPType},
+    symboldata::{
+        assertions::{get_call_site_assertions, AssertionData},
+        templates::get_bound_violations,
+        FunctionData,
+    },
+    symbols::{FullyQualifiedName, Name},
+    types::{
+        type_operators::{contains_operator, get_call_site_concretes},
+        union::{DiscreteType, PHPType},
+    },
     value::PHPValue,
 };
 
@@ -31,26 +46,160 @@ impl FunctionCallExpressionNode {
         emitter: &dyn IssueEmitter,
     ) -> Option<PHPType> {
         if let Some(data_handle) = self.get_func_data(state, emitter) {
-            let data = data_handle.read().unwrap();
-            // FIXME finn ut om dette er en optimal måte å bruke typedefinisjoner på
-            data.comment_return_type
-                .as_ref()
-                .map(|x| &x.0)
-                .or_else(|| data.php_return_type.as_ref())
-                .or_else(|| data.inferred_return_type.as_ref())
-                .cloned()
+            if let Some(refined_type) = self.get_refined_return_type(&data_handle, state) {
+                return Some(refined_type);
+            }
+            let (return_type, parameters, templates) = {
+                let data = data_handle.read_or_recover();
+                // FIXME finn ut om dette er en optimal måte å bruke typedefinisjoner på
+                let return_type = data
+                    .comment_return_type
+                    .as_ref()
+                    .map(|x| &x.0)
+                    .or(data.php_return_type.as_ref())
+                    .or(data.inferred_return_type.as_ref())
+                    .cloned()?;
+                if !contains_operator(&return_type) && data.template_data.is_empty() {
+                    return Some(return_type);
+                }
+                (
+                    return_type,
+                    data.arguments.clone(),
+                    data.template_data.clone(),
+                )
+            };
+            // Conditional return types, templates and similar depends on the arguments
+            let argument_types = self.arguments.get_argument_literal_types(state, emitter);
+            let concrete = get_call_site_concretes(&parameters, &argument_types, &templates);
+            Some(return_type.concretize_templates(&concrete))
         } else {
             None
         }
     }
 
+    /// The return type given by the refinement of the function, if any applies
+    fn get_refined_return_type(
+        &self,
+        func_data_handle: &Arc<RwLock<FunctionData>>,
+        state: &mut AnalysisState,
+    ) -> Option<PHPType> {
+        let fq_name = func_data_handle.read_or_recover().name.clone();
+        let refinement = state.symbol_data.get_function_refinement(&fq_name)?;
+        // The arguments are analyzed with the call itself, so issues are reported there
+        let arguments = self
+            .arguments
+            .get_call_arguments(state, &VoidEmitter::new());
+        refinement.get_return_type(&arguments)
+    }
+
+    ///
+    /// Writes to the arguments passed to by-reference parameters, using the type given by
+    /// the refinement of the function, or else the declared type of the parameter
+    ///
+    fn write_out_parameters(
+        &self,
+        func_data_handle: &Arc<RwLock<FunctionData>>,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) {
+        let (fq_name, parameters) = {
+            let func_data = func_data_handle.read_or_recover();
+            if !func_data.arguments.iter().any(|p| p.by_ref) {
+                return;
+            }
+            (func_data.name.clone(), func_data.arguments.clone())
+        };
+        let call_arguments = self
+            .arguments
+            .get_call_arguments(state, &VoidEmitter::new());
+        let refined_types = state
+            .symbol_data
+            .get_function_refinement(&fq_name)
+            .map(|refinement| refinement.get_out_parameter_types(&call_arguments))
+            .unwrap_or_default();
+        for idx in 0..call_arguments.len() {
+            let Some(parameter) = parameters
+                .get(idx)
+                .or_else(|| parameters.last().filter(|p| p.variadic))
+            else {
+                break;
+            };
+            if !parameter.by_ref {
+                continue;
+            }
+            let out_type = refined_types
+                .iter()
+                .find(|(out_idx, _)| *out_idx == idx)
+                .map(|(_, out_type)| out_type.clone())
+                .or_else(|| parameter.get_type(state));
+            if let Some(out_type) = out_type {
+                self.arguments
+                    .write_to_argument(idx, state, emitter, out_type);
+            }
+        }
+    }
+
+    /// What the function asserts about the arguments, with templates inferred from them
+    pub(crate) fn get_assertions(
+        &self,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) -> Vec<AssertionData> {
+        let Some(func_data_handle) = self.get_func_data(state, emitter) else {
+            return vec![];
+        };
+        let (assertions, parameters, templates) = {
+            let func_data = func_data_handle.read_or_recover();
+            if func_data.assertions.is_empty() {
+                return vec![];
+            }
+            (
+                func_data.assertions.clone(),
+                func_data.arguments.clone(),
+                func_data.template_data.clone(),
+            )
+        };
+        let argument_types = self.arguments.get_argument_types(state, emitter);
+        get_call_site_assertions(&assertions, &parameters, &templates, &argument_types)
+    }
+
+    /// Emits templates which are given a type outside of their bound
+    fn check_template_bounds(
+        &self,
+        func_data_handle: &Arc<RwLock<FunctionData>>,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) {
+        let (parameters, templates) = {
+            let func_data = func_data_handle.read_or_recover();
+            if func_data.template_data.is_empty() {
+                return;
+            }
+            (func_data.arguments.clone(), func_data.template_data.clone())
+        };
+        let argument_types = self
+            .arguments
+            .get_argument_literal_types(state, &VoidEmitter::new());
+        let concrete = get_call_site_concretes(&parameters, &argument_types, &templates);
+        for (template, bound, concrete_type) in
+            get_bound_violations(&templates, &concrete, &state.symbol_data)
+        {
+            emitter.emit(Issue::TemplateBoundViolation(
+                self.pos(state),
+                template,
+                bound,
+                concrete_type,
+            ));
+        }
+    }
+
     pub fn get_func_data(
         &self,
         state: &mut AnalysisState,
         emitter: &dyn IssueEmitter,
     ) -> Option<Arc<RwLock<FunctionData>>> {
         let fq_name = self.get_fq_function_name(state, emitter)?;
-        let func_handle = state.symbol_data.functions.read().unwrap();
+        let func_handle = state.symbol_data.functions.read_or_recover();
         let func_data = func_handle.get(&fq_name.to_ascii_lowercase())?;
         Some(func_data.clone())
     }
@@ -63,14 +212,82 @@ impl FunctionCallExpressionNode {
         self.function.get_fq_function_name(state, emitter)
     }
 
+    ///
+    /// The name the function is matched against configuration with, like `exec`, or
+    /// `app\render` for functions declared in a namespace. Functions which aren't declared
+    /// in the namespace they're called from, fall back to the global one
+    ///
+    pub fn get_configured_name(&self, state: &mut AnalysisState) -> String {
+        let Some(fq_name) = self.get_fq_function_name(state, &VoidEmitter::new()) else {
+            return String::new();
+        };
+        let is_declared = state
+            .symbol_data
+            .functions
+            .read_or_recover()
+            .contains_key(&fq_name.to_ascii_lowercase());
+        if is_declared {
+            fq_name.to_string().trim_start_matches('\\').to_string()
+        } else {
+            fq_name
+                .get_name()
+                .map(|n| n.to_string())
+                .unwrap_or_default()
+        }
+    }
+
     pub fn get_php_value(
         &self,
-        _state: &mut AnalysisState,
-        _emitter: &dyn IssueEmitter,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) -> Option<PHPValue> {
+        // Deterministic function calls with known arguments are possible to predict a return value from.
+        // i.e. `abs(-4)`, or `dirname(..)`, which is commonly used in include-paths
+        let fq_name = self.get_fq_function_name(state, emitter)?;
+        match fq_name.to_ascii_lowercase().to_os_string().as_bytes() {
+            b"\\dirname" => self.get_dirname_value(state),
+            _ => self.get_pure_value(&fq_name, state),
+        }
+    }
+
+    fn get_pure_value(
+        &self,
+        fq_name: &FullyQualifiedName,
+        state: &mut AnalysisState,
     ) -> Option<PHPValue> {
-        // A few deterministic function calls are possible to predict a return value from. i.e. `abs(-4)`
-        // but for now, none
-        None
+        if !pure::is_pure(fq_name) || self.arguments.has_named_arguments() {
+            return None;
+        }
+        let values = self
+            .arguments
+            .get_argument_values(state, &VoidEmitter::new())
+            .into_iter()
+            .collect::<Option<Vec<_>>>()?;
+        pure::evaluate(fq_name, &values)
+    }
+
+    fn get_dirname_value(&self, state: &mut AnalysisState) -> Option<PHPValue> {
+        let values = self
+            .arguments
+            .get_argument_values(state, &VoidEmitter::new());
+        let Some(Some(PHPValue::String(path))) = values.first() else {
+            return None;
+        };
+        let levels = match values.get(1) {
+            None => 1,
+            Some(Some(PHPValue::Int(levels))) if *levels >= 1 => *levels,
+            _ => return None,
+        };
+        let mut dir = PathBuf::from(path);
+        for _ in 0..levels {
+            match dir.parent() {
+                Some(parent) if parent.as_os_str().is_empty() => dir = PathBuf::from("."),
+                Some(parent) => dir = parent.to_path_buf(),
+                // The root directory is its own parent
+                None => break,
+            }
+        }
+        Some(PHPValue::String(dir.into_os_string()))
     }
 }
 
@@ -122,7 +339,7 @@ impl FunctionCallExpressionFunction {
                 if let Some(PHPValue::String(fname)) = val {
                     // function names in variables need to always be fully qualified
                     let func_name = FullyQualifiedName::from(fname);
-                    let funcs = state.symbol_data.functions.read().unwrap();
+                    let funcs = state.symbol_data.functions.read_or_recover();
                     if let Some(_f) = funcs.get(&func_name) {
                         // SJEKK OM ARGUMENTER PASSER
                     } else {
@@ -220,7 +437,9 @@ fn analyze_define_call(
         return;
     };
 
-    let value_node = args.next().unwrap();
+    let Some(value_node) = args.next() else {
+        return;
+    };
 
     let value = value_node.get_php_value(state, emitter);
     let val_type = value_node
@@ -234,7 +453,7 @@ fn analyze_define_call(
         .unwrap_or_default();
 
     //if let Some(val) = value {
-    let mut mutable = state.global.constants.write().unwrap();
+    let mut mutable = state.global.constants.write_or_recover();
     if let Some(cdata) = (*mutable).get_mut(&name_value) {
         emitter.emit(Issue::DuplicateConstant(call.pos(state), name_value));
         cdata.add_value(fname, call.range(), val_type, value);
@@ -276,6 +495,10 @@ impl ThirdPassAnalyzeableNode for FunctionCallExpressionNode {
         let data = self.get_func_data(state, emitter);
 
         let fq_name = self.get_fq_function_name(state, emitter);
+        let is_assert = fq_name
+            .as_ref()
+            .is_some_and(|fq_name| fq_name.to_ascii_lowercase() == b"\\assert" as &[u8]);
+        let mut out_parameters_of = None;
         //         if data.is_none() && fq_name.is_some() {}
         match (data, fq_name) {
             // intrinsics needs special treatment
@@ -288,7 +511,12 @@ impl ThirdPassAnalyzeableNode for FunctionCallExpressionNode {
                 emitter.emit(Issue::UnknownFunction(self.pos(state), fq_name));
             }
             (Some(func_data_handle), Some(fq_name)) => {
-                let func_data = func_data_handle.read().unwrap();
+                let func_data = func_data_handle.read_or_recover();
+                state
+                    .symbol_data
+                    .usage
+                    .write_or_recover()
+                    .mark_function(&func_data.name);
                 if func_data.name != fq_name {
                     emitter.emit(Issue::WrongFunctionNameCasing(
                         self.pos(state),
@@ -296,12 +524,56 @@ impl ThirdPassAnalyzeableNode for FunctionCallExpressionNode {
                         fq_name,
                     ));
                 }
+                let callee = Name::from(func_data.name.to_string());
+                let parameters = func_data.arguments.clone();
+                drop(func_data);
+                self.check_template_bounds(&func_data_handle, state, emitter);
+                self.arguments
+                    .check_nullable_arguments(&callee, &parameters, state, emitter);
+                out_parameters_of = Some(func_data_handle);
             }
             _ => (),
         }
 
+        // Functions passed along as callable strings, like `array_map('trim', $lines)`
+        for value in self
+            .arguments
+            .get_argument_values(state, &VoidEmitter::new())
+            .into_iter()
+            .flatten()
+        {
+            if let PHPValue::String(callable) = value {
+                let fq_name = FullyQualifiedName::from(callable);
+                state
+                    .symbol_data
+                    .usage
+                    .write_or_recover()
+                    .mark_function(&fq_name);
+            }
+        }
+
         // FIXME analyze if arguments are correct
 
-        self.analyze_third_pass_children(&self.as_any(), state, emitter, path)
+        let carry_on = self.analyze_third_pass_children(&self.as_any(), state, emitter, path);
+        if let Some(func_data_handle) = out_parameters_of {
+            self.write_out_parameters(&func_data_handle, state, emitter);
+        }
+        // Code following `assert($x instanceof Foo)` is only reached when it holds
+        if is_assert {
+            if let Some(argument) = self.arguments.get_argument_nodes().next() {
+                if let ArgumentChildren::_Expression(condition) = &*argument.child {
+                    harden_scope_based_on_conditional_node(
+                        &**condition,
+                        state.current_scope(),
+                        BranchSide::TrueBranch,
+                        state,
+                        emitter,
+                    );
+                }
+            }
+        }
+        let assertions = self.get_assertions(state, &VoidEmitter::new());
+        harden_scope_with_assertions(&assertions, &self.arguments, state, emitter);
+        carry_on
     }
 }
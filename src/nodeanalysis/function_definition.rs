@@ -1,3 +1,4 @@
+use crate::locks::RecoverableLock;
 use std::{
     collections::HashMap,
     sync::{Arc, RwLock},
@@ -10,7 +11,10 @@ use crate::{
     extra::ExtraChild,
     issue::VoidEmitter,
     phpdoc::types::{PHPDocComment, PHPDocEntry},
-    symboldata::FileLocation,
+    symboldata::{
+        assertions::get_phpdoc_assertion_data, attributes::AttributeTarget,
+        templates::get_phpdoc_template_data, FileLocation,
+    },
     symbols::{FullyQualifiedName, Name},
     types::type_parser::TypeParser,
 };
@@ -24,7 +28,9 @@ use crate::{
 };
 use crate::{parser::Range, types::union::PHPType};
 
-use super::analysis::{FirstPassAnalyzeableNode, ThirdPassAnalyzeableNode};
+use super::analysis::{
+    FirstPassAnalyzeableNode, SecondPassAnalyzeableNode, ThirdPassAnalyzeableNode,
+};
 
 use crate::autotree::NodeAccess;
 
@@ -63,7 +69,7 @@ impl FunctionDefinitionNode {
         emitter: &dyn IssueEmitter,
     ) -> Option<Arc<RwLock<FunctionData>>> {
         let fname = self.get_function_name(state, emitter);
-        let read = state.symbol_data.functions.read().unwrap();
+        let read = state.symbol_data.functions.read_or_recover();
         read.get(&fname.to_ascii_lowercase()).cloned()
     }
 
@@ -113,7 +119,7 @@ impl FirstPassAnalyzeableNode for FunctionDefinitionNode {
 
         let mut is_dup = false;
         {
-            let read = state.symbol_data.functions.read().unwrap();
+            let read = state.symbol_data.functions.read_or_recover();
             if read.get(&fname.to_ascii_lowercase()).is_some() {
                 emitter.emit(Issue::DuplicateFunction(self.pos(state), fname.clone()));
                 is_dup = true;
@@ -122,8 +128,9 @@ impl FirstPassAnalyzeableNode for FunctionDefinitionNode {
 
         let mut comment_return_type = None;
         let mut param_map = HashMap::new();
-        //let mut phpdoc = None;
+        let mut phpdoc = None;
         let mut function_template_params = vec![];
+        let mut template_data = vec![];
         if let Some((doc_comment, range)) = &state.last_doc_comment {
             match PHPDocComment::parse(doc_comment, range) {
                 Ok(doc_comment) => {
@@ -158,14 +165,20 @@ impl FirstPassAnalyzeableNode for FunctionDefinitionNode {
                                     "@var can't be used on a function-declaration".into(),
                                 ));
                             }
-                            PHPDocEntry::Template(_range, t, _desc) => {
-                                let temp_name: Name = t.into();
+                            PHPDocEntry::Template(_range, t) => {
+                                let temp_name: Name = (&t.name).into();
                                 function_template_params.push(temp_name);
                             }
                             _ => (),
                         }
                     }
-                    //phpdoc = Some(doc_comment);
+                    template_data = get_phpdoc_template_data(
+                        &doc_comment,
+                        &function_template_params,
+                        state,
+                        emitter,
+                    );
+                    phpdoc = Some(doc_comment);
                 }
                 Err(_) => emitter.emit(Issue::PHPDocParseError(state.pos_from_range(*range))),
             }
@@ -176,6 +189,11 @@ impl FirstPassAnalyzeableNode for FunctionDefinitionNode {
         }
 
         let php_return_type = self.get_php_declared_return_type(state, emitter);
+        let attributes = self
+            .attributes
+            .as_ref()
+            .map(|a| a.get_attributes(state))
+            .unwrap_or_default();
 
         let arguments = self.parameters.analyze_first_pass_parameters(
             state,
@@ -183,10 +201,22 @@ impl FirstPassAnalyzeableNode for FunctionDefinitionNode {
             &param_map,
             Some(&function_template_params),
         );
+        let assertions = phpdoc
+            .as_ref()
+            .map(|doc_comment| {
+                get_phpdoc_assertion_data(
+                    doc_comment,
+                    &arguments,
+                    &function_template_params,
+                    state,
+                    emitter,
+                )
+            })
+            .unwrap_or_default();
 
         let mut maybe_fdata = None;
         if !is_dup {
-            let mut write = state.symbol_data.functions.write().unwrap();
+            let mut write = state.symbol_data.functions.write_or_recover();
             if write.get(&fname.to_ascii_lowercase()).is_some() {
                 // Someone beat us to it
                 emitter.emit(Issue::DuplicateFunction(self.pos(state), fname.clone()));
@@ -209,13 +239,18 @@ impl FirstPassAnalyzeableNode for FunctionDefinitionNode {
                     } else {
                         None
                     },
+                    template_data,
+                    assertions,
+                    phpdoc,
+                    attributes,
+                    is_native: false,
                 }));
                 maybe_fdata = Some(fdata.clone());
                 write.insert(fname.to_ascii_lowercase(), fdata);
             }
         }
         state.in_function_stack.push(FunctionState::new_function(
-            fname.get_name().unwrap(),
+            fname.get_name().unwrap_or_default(),
             maybe_fdata,
         ));
 
@@ -225,6 +260,16 @@ impl FirstPassAnalyzeableNode for FunctionDefinitionNode {
     }
 }
 
+impl SecondPassAnalyzeableNode for FunctionDefinitionNode {
+    fn analyze_second_pass(&self, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
+        if let Some(attributes) = &self.attributes {
+            attributes.analyze_attributes(AttributeTarget::Function, state, emitter);
+        }
+        self.parameters.analyze_parameter_attributes(state, emitter);
+        self.analyze_second_pass_children(&self.as_any(), state, emitter);
+    }
+}
+
 impl ThirdPassAnalyzeableNode for FunctionDefinitionNode {
     fn analyze_third_pass(
         &self,
@@ -235,25 +280,27 @@ impl ThirdPassAnalyzeableNode for FunctionDefinitionNode {
         let data = if let Some(dt) = self.get_function_data(state, emitter) {
             dt
         } else {
-            eprintln!(
-                "missing function_data for {}",
+            crate::missing!(
+                "missing function data for {}",
                 self.get_function_name(state, emitter)
             );
             return true;
         };
         let function = FunctionState::new_function(
-            self.get_function_name(state, emitter).get_name().unwrap(),
+            self.get_function_name(state, emitter)
+                .get_name()
+                .unwrap_or_default(),
             Some(data),
         );
         state.in_function_stack.push(function);
         if !self.analyze_third_pass_children(&self.as_any(), state, emitter, path) {
             return false;
         }
-        let func = state
-            .in_function_stack
-            .pop()
-            .expect("There must be a state");
-        let returns = func.returns.read().unwrap().clone();
+        let Some(func) = state.in_function_stack.pop() else {
+            crate::missing!("function state was popped by a child node");
+            return true;
+        };
+        let returns = func.returns.read_or_recover().clone();
 
         let mut values: Vec<PHPValue> = vec![];
         for ret in &returns {
@@ -271,9 +318,18 @@ impl ThirdPassAnalyzeableNode for FunctionDefinitionNode {
         };
 
         // eprintln!("returns: {:?}", returns);
-        let scope_handle = func.scope_stack.read().unwrap().top();
+        let scope_handle = func.scope_stack.read_or_recover().top();
         scope_handle.analyze_for_unused_vars(state, emitter);
 
+        if let Some(generator_type) = func.get_generator_type() {
+            if let Some(function) = self.get_function_data(state, emitter) {
+                let mut function_data = function.write_or_recover();
+                function_data.inferred_return_type = Some(generator_type);
+                function_data.return_value = None;
+            }
+            return true;
+        }
+
         let mut ret_type = UnionType::new();
 
         for (r_type, _val) in returns {
@@ -288,7 +344,7 @@ impl ThirdPassAnalyzeableNode for FunctionDefinitionNode {
         let ret_type: PHPType = ret_type.into();
 
         if let Some(function) = self.get_function_data(state, emitter) {
-            let mut function_data = function.write().unwrap();
+            let mut function_data = function.write_or_recover();
             function_data.inferred_return_type = Some(ret_type.simplify());
             function_data.return_value = return_value;
         }
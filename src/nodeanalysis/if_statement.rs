@@ -1,7 +1,9 @@
 use crate::analysis::scope::{BranchSide, BranchableScope};
 use crate::analysis::state::AnalysisState;
+use crate::analysis::termination::always_terminates;
 use crate::autonodes::any::AnyNodeRef;
 use crate::issue::Issue;
+use crate::locks::RecoverableLock;
 use crate::types::union::PHPType;
 use crate::{
     autonodes::if_statement::{IfStatementAlternative, IfStatementNode},
@@ -84,16 +86,21 @@ impl ThirdPassAnalyzeableNode for IfStatementNode {
             }
 
             let carry_on = self.body.as_any().analyze_third_pass(state, emitter, path);
+            let terminates = always_terminates(&self.body.as_any(), state);
 
             if false_branch {
                 state.in_conditional_branch = was_conditional;
             }
 
             // self.analyze_round_two_children(&self.as_any(), state, emitter);
-            scopes.push(state.pop_scope());
+            let branch = state.pop_scope();
             if !carry_on {
                 return false;
             }
+            // Code after a guard clause is only reached through the other branches
+            if !terminates {
+                scopes.push(branch);
+            }
         } else {
             // emit the other branch as unreachable
         }
@@ -116,7 +123,15 @@ impl ThirdPassAnalyzeableNode for IfStatementNode {
                     let branch = false_scope.branch();
                     state.push_scope(branch);
                     carry_on = a.as_any().analyze_third_pass(state, emitter, path);
-                    scopes.push(state.pop_scope());
+                    let terminates = always_terminates(&a.as_any(), state);
+                    let branch = state.pop_scope();
+                    if !terminates {
+                        // Carry the narrowing of the condition along into the join
+                        branch
+                            .write_or_recover()
+                            .inherit_vars(&false_scope.read_or_recover());
+                        scopes.push(branch);
+                    }
                 }
                 state.in_conditional_branch = was_conditional;
                 if !carry_on {
@@ -127,16 +142,14 @@ impl ThirdPassAnalyzeableNode for IfStatementNode {
             // emit the other branch as unreachable
         }
 
-        let mut no_alt = false;
-        if let Some(alt) = &self.alternative {
-            if alt.is_empty() {
-                no_alt = true;
-            }
-        } else {
-            no_alt = true;
-        }
+        // Without an `else`, none of the branches might be taken
+        let has_else = self
+            .alternative
+            .iter()
+            .flatten()
+            .any(|alt| matches!(&**alt, IfStatementAlternative::ElseClause(_)));
 
-        if no_alt {
+        if !has_else {
             let false_scope = self
                 .condition
                 .branch_with_hardened_types_base_on_conditional_node(
@@ -190,7 +203,13 @@ impl ThirdPassAnalyzeableNode for IfStatementAlternative {
         }
 
         let scope = state.current_scope();
-        let branch = scope.branch();
+        let branch = else_if
+            .condition
+            .branch_with_hardened_types_base_on_conditional_node(
+                scope.clone(),
+                BranchSide::TrueBranch,
+                state,
+            );
         state.push_scope(branch);
         let carry_on = else_if
             .body
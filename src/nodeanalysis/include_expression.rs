@@ -1,11 +1,27 @@
+use crate::locks::RecoverableLock;
+use std::{collections::HashSet, path::PathBuf};
+
 use crate::{
-    analysis::state::AnalysisState, autonodes::include_expression::IncludeExpressionNode,
-    issue::IssueEmitter, types::union::PHPType,
+    analysis::state::AnalysisState,
+    autonodes::{
+        _expression::_ExpressionNode, any::AnyNodeRef, include_expression::IncludeExpressionNode,
+    },
+    autotree::NodeAccess,
+    issue::{Issue, IssueEmitter, IssuePosition, VoidEmitter},
+    symboldata::{
+        includes::{normalize_path, IncludeData, IncludeKind},
+        FileLocation,
+    },
+    symbols::Name,
+    types::union::PHPType,
+    value::PHPValue,
 };
 
+use super::analysis::FirstPassAnalyzeableNode;
+
 impl IncludeExpressionNode {
-    pub fn read_from(&self, _state: &mut AnalysisState, _emitter: &dyn IssueEmitter) {
-        crate::missing!("{}.read_from(..)", self.kind());
+    pub fn read_from(&self, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
+        self.child.read_from(state, emitter);
     }
 
     pub fn get_php_value(
@@ -24,3 +40,87 @@ impl IncludeExpressionNode {
         crate::missing_none!("{}.get_utype(..)", self.kind())
     }
 }
+
+impl FirstPassAnalyzeableNode for IncludeExpressionNode {
+    fn analyze_first_pass(&self, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
+        let pos = self.pos(state);
+        analyze_include(IncludeKind::Include, &self.child, pos, state, emitter);
+        self.analyze_first_pass_children(&self.as_any(), state, emitter);
+    }
+}
+
+/// Resolves the path of an include or require relative to the including file, and
+/// registers it in the include graph. Emits if the path resolves to a file which
+/// doesn't exist.
+pub(crate) fn analyze_include(
+    kind: IncludeKind,
+    path_expression: &_ExpressionNode,
+    pos: IssuePosition,
+    state: &mut AnalysisState,
+    emitter: &dyn IssueEmitter,
+) {
+    let Some(from) = state.filename.clone() else {
+        return;
+    };
+    let Some(PHPValue::String(path)) = path_expression.get_php_value(state, &VoidEmitter::new())
+    else {
+        // A dynamic path, there's nothing more we can do
+        return;
+    };
+    let path = PathBuf::from(path);
+    // Paths anchored with `__DIR__` or `dirname(__FILE__)` are absolute by now
+    let is_absolute = path.is_absolute();
+    let target = if is_absolute {
+        path
+    } else if let Some(dir) = from.parent() {
+        dir.join(path)
+    } else {
+        path
+    };
+    let target = normalize_path(&target);
+
+    let resolved = target.exists();
+    // PHP looks for relative paths in the `include_path` and the working directory too,
+    // which we don't know, so only absolute paths are known to be missing
+    if !resolved && is_absolute {
+        let target = target.clone().into_os_string();
+        emitter.emit(if kind.is_required() {
+            Issue::RequiredFileNotFound(pos.clone(), target)
+        } else {
+            Issue::IncludedFileNotFound(pos.clone(), target)
+        });
+    }
+
+    state.symbol_data.includes.write_or_recover().add_include(
+        &from,
+        IncludeData {
+            kind,
+            target,
+            position: FileLocation::new(pos),
+            in_function: !state.in_function_stack.is_empty(),
+            resolved,
+        },
+    );
+}
+
+///
+/// The variables used in the top-level scope of a file, which the files it includes can
+/// see. Functions and classes have scopes of their own, and are skipped
+///
+pub(crate) fn collect_top_level_variables(node: &AnyNodeRef, variables: &mut HashSet<Name>) {
+    for child in node.children_any() {
+        match child {
+            AnyNodeRef::VariableName(v) => {
+                variables.insert(v.get_variable_name());
+            }
+            AnyNodeRef::FunctionDefinition(_)
+            | AnyNodeRef::AnonymousFunctionCreationExpression(_)
+            | AnyNodeRef::ArrowFunction(_)
+            | AnyNodeRef::ClassDeclaration(_)
+            | AnyNodeRef::InterfaceDeclaration(_)
+            | AnyNodeRef::TraitDeclaration(_)
+            | AnyNodeRef::EnumDeclaration(_) => (),
+            _ => collect_top_level_variables(&child, variables),
+        }
+    }
+}
@@ -1,11 +1,14 @@
 use crate::{
     analysis::state::AnalysisState, autonodes::include_once_expression::IncludeOnceExpressionNode,
-    issue::IssueEmitter, types::union::PHPType,
+    autotree::NodeAccess, issue::IssueEmitter, symboldata::includes::IncludeKind,
+    types::union::PHPType,
 };
 
+use super::{analysis::FirstPassAnalyzeableNode, include_expression::analyze_include};
+
 impl IncludeOnceExpressionNode {
-    pub fn read_from(&self, _state: &mut AnalysisState, _emitter: &dyn IssueEmitter) {
-        crate::missing!("{}.read_from(..)", self.kind());
+    pub fn read_from(&self, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
+        self.child.read_from(state, emitter);
     }
 
     pub fn get_php_value(
@@ -24,3 +27,11 @@ impl IncludeOnceExpressionNode {
         crate::missing_none!("{}.get_utype(..)", self.kind())
     }
 }
+
+impl FirstPassAnalyzeableNode for IncludeOnceExpressionNode {
+    fn analyze_first_pass(&self, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
+        let pos = self.pos(state);
+        analyze_include(IncludeKind::IncludeOnce, &self.child, pos, state, emitter);
+        self.analyze_first_pass_children(&self.as_any(), state, emitter);
+    }
+}
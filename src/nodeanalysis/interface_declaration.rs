@@ -1,11 +1,14 @@
+use crate::locks::RecoverableLock;
 use std::sync::{Arc, RwLock};
 
 use crate::{
     analysis::state::{AnalysisState, ClassState},
     autonodes::{any::AnyNodeRef, interface_declaration::InterfaceDeclarationNode},
     issue::{Issue, IssueEmitter},
+    phpdoc::types::{PHPDocComment, PHPDocEntry},
     symboldata::{
         class::{ClassName, ClassType, InterfaceData},
+        templates::get_phpdoc_template_data,
         FileLocation,
     },
     symbols::Name,
@@ -70,10 +73,33 @@ impl FirstPassAnalyzeableNode for InterfaceDeclarationNode {
         let mut if_data =
             InterfaceData::new(FileLocation::new(self.name.pos(state)), if_name.clone());
         if_data.base_interface_names = base_name;
+        if let Some((raw_doc_comment, php_doc_range)) = state.last_doc_comment.clone() {
+            match PHPDocComment::parse(&raw_doc_comment, &php_doc_range) {
+                Ok(doc) => {
+                    let generic_templates: Vec<Name> = doc
+                        .entries
+                        .iter()
+                        .filter_map(|entry| match entry {
+                            PHPDocEntry::Template(_, template) => Some((&template.name).into()),
+                            _ => None,
+                        })
+                        .collect();
+                    if !generic_templates.is_empty() {
+                        if_data.template_data =
+                            get_phpdoc_template_data(&doc, &generic_templates, state, emitter);
+                        if_data.generic_templates = Some(generic_templates);
+                    }
+                    if_data.phpdoc = Some(doc);
+                }
+                Err(_) => {
+                    emitter.emit(Issue::PHPDocParseError(state.pos_from_range(php_doc_range)))
+                }
+            }
+        }
 
         let symbol_data = state.symbol_data.get_or_create_class(&if_name);
         {
-            let mut unlocked = symbol_data.write().unwrap();
+            let mut unlocked = symbol_data.write_or_recover();
             match *unlocked {
                 ClassType::None => {
                     *unlocked = ClassType::Interface(if_data);
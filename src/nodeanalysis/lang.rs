@@ -1,5 +1,11 @@
+use std::os::unix::prelude::OsStrExt;
+
 use crate::analysis::state::AnalysisState;
-use crate::issue::IssueEmitter;
+use crate::autonodes::any::AnyNodeRef;
+use crate::autonodes::binary_expression::BinaryExpressionOperator;
+use crate::autotree::NodeAccess;
+use crate::issue::{IssueEmitter, VoidEmitter};
+use crate::parser::Range;
 
 use crate::types::union::PHPType;
 
@@ -16,3 +22,69 @@ pub trait AnalysisOfType {
         _emitter: &dyn IssueEmitter,
     ) -> Option<PHPType>;
 }
+
+/// How an expression is used by the expressions around it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccessContext {
+    Read,
+    /// Assigned or destructured to, like `$row['id'] = 1;`
+    Written,
+    /// Only checked for, like in `isset($row['id'])`, `$a->b ?? null` or `unset($a->b)`, where
+    /// missing keys and `null` are expected
+    Checked,
+}
+
+///
+/// How the expression at `range` is used, given its ancestors in `path`. Expressions which
+/// are subscripted or accessed further, like `$a` in `isset($a->b['c'])`, share the context
+/// of the outermost access.
+///
+pub fn get_access_context(
+    mut range: Range,
+    state: &mut AnalysisState,
+    path: &[AnyNodeRef],
+) -> AccessContext {
+    for ancestor in path.iter().rev() {
+        match ancestor {
+            AnyNodeRef::SubscriptExpression(se) if se.dereferenceable.range() == range => (),
+            AnyNodeRef::MemberAccessExpression(ma) if ma.object.range() == range => (),
+            AnyNodeRef::Argument(_) | AnyNodeRef::Arguments(_) => (),
+            AnyNodeRef::AssignmentExpression(a) if a.left.range() == range => {
+                return AccessContext::Written
+            }
+            AnyNodeRef::AugmentedAssignmentExpression(a) if a.left.range() == range => {
+                return AccessContext::Written
+            }
+            AnyNodeRef::ReferenceAssignmentExpression(a) if a.left.range() == range => {
+                return AccessContext::Written
+            }
+            AnyNodeRef::ListLiteral(_) => return AccessContext::Written,
+            AnyNodeRef::UnsetStatement(_) => return AccessContext::Checked,
+            AnyNodeRef::BinaryExpression(b)
+                if matches!(*b.operator, BinaryExpressionOperator::NullCoalesce(_))
+                    && b.left.range() == range =>
+            {
+                return AccessContext::Checked
+            }
+            AnyNodeRef::FunctionCallExpression(fc) => {
+                let is_check = fc
+                    .get_fq_function_name(state, &VoidEmitter::new())
+                    .map(|fq_name| {
+                        matches!(
+                            fq_name.to_ascii_lowercase().to_os_string().as_bytes(),
+                            b"\\isset" | b"\\empty"
+                        )
+                    })
+                    .unwrap_or(false);
+                return if is_check {
+                    AccessContext::Checked
+                } else {
+                    AccessContext::Read
+                };
+            }
+            _ => return AccessContext::Read,
+        }
+        range = ancestor.range();
+    }
+    AccessContext::Read
+}
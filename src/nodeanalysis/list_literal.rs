@@ -2,7 +2,10 @@ use crate::{
     analysis::state::AnalysisState,
     autonodes::list_literal::{ListLiteralChildren, ListLiteralNode},
     issue::IssueEmitter,
-    types::union::PHPType,
+    types::{
+        type_operators::TypeOperator,
+        union::{DiscreteType, PHPType, ShapeTypeKey},
+    },
     value::PHPValue,
 };
 
@@ -34,17 +37,36 @@ impl ListLiteralNode {
         val_type: Option<PHPType>,
         value: Option<PHPValue>,
     ) {
-        if val_type.is_some() {
-            crate::missing!("list(..) = .. har type, som blir ignorert");
-        }
-        if value.is_some() {
-            crate::missing!("list(..) = .. har verdi, som blir ignorert");
-        }
-        for (idx, child) in self.children.iter().enumerate() {
-            match &**child {
-                ListLiteralChildren::_Expression(_) => {
-                    crate::missing!("list({:?}) write to", child.kind())
+        let mut idx: i64 = 0;
+        // The key of entries like `['id' => $id] = $row;` comes as a child of its own
+        let mut key_node = None;
+        for child in &self.children {
+            let key = match &**child {
+                ListLiteralChildren::_Expression(key) => {
+                    key_node = Some(key);
+                    continue;
                 }
+                ListLiteralChildren::Extra(_) => continue,
+                _ => match key_node.take() {
+                    Some(key) => key.get_php_value(state, emitter),
+                    None => {
+                        idx += 1;
+                        Some(PHPValue::Int(idx - 1))
+                    }
+                },
+            };
+            let sub_value = match (&value, &key) {
+                (Some(PHPValue::Array(a)), Some(key)) => a.get_value_by_key(key.clone()),
+                (Some(v), _) => {
+                    crate::missing_none!("Extract something fra value in list(...) = {:?}", v)
+                }
+                _ => None,
+            };
+            let sub_val_type = match &sub_value {
+                Some(sub_value) => sub_value.get_utype(),
+                None => get_element_type(&val_type, key.as_ref()),
+            };
+            match &**child {
                 ListLiteralChildren::ByRef(_) => {
                     crate::missing!("list({:?}) write to", child.kind())
                 }
@@ -54,11 +76,11 @@ impl ListLiteralNode {
                 ListLiteralChildren::FunctionCallExpression(_) => {
                     crate::missing!("list({:?}) write to", child.kind())
                 }
-                ListLiteralChildren::ListLiteral(_) => {
-                    crate::missing!("list({:?}) write to", child.kind())
+                ListLiteralChildren::ListLiteral(ll) => {
+                    ll.write_to(state, emitter, sub_val_type, sub_value)
                 }
-                ListLiteralChildren::MemberAccessExpression(_) => {
-                    crate::missing!("list({:?}) write to", child.kind())
+                ListLiteralChildren::MemberAccessExpression(ma) => {
+                    ma.write_to(state, emitter, sub_val_type, sub_value)
                 }
                 ListLiteralChildren::MemberCallExpression(_) => {
                     crate::missing!("list({:?}) write to", child.kind())
@@ -72,40 +94,27 @@ impl ListLiteralNode {
                 ListLiteralChildren::ScopedCallExpression(_) => {
                     crate::missing!("list({:?}) write to", child.kind())
                 }
-                ListLiteralChildren::ScopedPropertyAccessExpression(_) => {
-                    crate::missing!("list({:?}) write to", child.kind())
+                ListLiteralChildren::ScopedPropertyAccessExpression(sp) => {
+                    sp.write_to(state, emitter, sub_val_type, sub_value)
                 }
-                ListLiteralChildren::SubscriptExpression(_) => {
-                    crate::missing!("list({:?}) write to", child.kind())
+                ListLiteralChildren::SubscriptExpression(se) => {
+                    se.write_to(state, emitter, sub_val_type, sub_value)
                 }
                 ListLiteralChildren::VariableName(vname) => {
-                    let mut sub_val_type = None;
-                    let mut sub_value = None;
-                    match &value {
-                        Some(PHPValue::Array(a)) => {
-                            let php_idx = PHPValue::Int(
-                                idx.try_into()
-                                    .expect("This loop can't be so long that it overflows an i64"),
-                            );
-                            sub_value = a.get_value_by_key(php_idx.clone());
-                            sub_val_type = a.get_type_by_key(php_idx);
-                        }
-                        Some(v) => {
-                            crate::missing!("Extract something fra value in list(...) = {:?}", v);
-                        }
-                        None => match &val_type {
-                            Some(x) => {
-                                crate::missing!("list(..) = type: {:?}", x);
-                            }
-                            None => (),
-                        },
-                    }
-                    vname.write_to(state, emitter, sub_val_type, sub_value);
+                    vname.write_to(state, emitter, sub_val_type, sub_value)
                 }
 
-                ListLiteralChildren::Extra(_) => (),
+                ListLiteralChildren::_Expression(_) | ListLiteralChildren::Extra(_) => (),
             }
         }
-        crate::missing!("list literal write_to");
     }
 }
+
+/// The type at `key` in arrays of type `val_type`, or of any element when the key is unknown
+fn get_element_type(val_type: &Option<PHPType>, key: Option<&PHPValue>) -> Option<PHPType> {
+    let offset: PHPType = match key.and_then(ShapeTypeKey::from_php_value) {
+        Some(key) => key.get_literal_type().into(),
+        None => DiscreteType::ArrayKey.into(),
+    };
+    TypeOperator::OffsetAccess(val_type.clone()?, offset).evaluate()
+}
@@ -1,3 +1,4 @@
+use crate::locks::RecoverableLock;
 use std::sync::{Arc, RwLock};
 
 use crate::{
@@ -15,15 +16,26 @@ use crate::{
     value::PHPValue,
 };
 
-use super::analysis::ThirdPassAnalyzeableNode;
+use super::{
+    analysis::ThirdPassAnalyzeableNode,
+    lang::{get_access_context, AccessContext},
+};
 use crate::autotree::NodeAccess;
 
 impl MemberAccessExpressionNode {
     pub fn read_from(&self, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
         // FIXME:
-        // * mark property as used
         // * check if we're in a context where we're allowed to read
         // crate::missing!("{}.read_from(..)", self.kind());
+        if let Some(property_data) = self.get_mut_property_data(state, &VoidEmitter::new()) {
+            property_data.write_or_recover().read_from += 1;
+        } else if let Some(property_name) = self.get_property_name(state, &VoidEmitter::new()) {
+            state
+                .symbol_data
+                .usage
+                .write_or_recover()
+                .mark_untyped_property(&property_name);
+        }
 
         self.name.read_from(state, emitter);
         self.object.read_from(state, emitter);
@@ -47,7 +59,9 @@ impl MemberAccessExpressionNode {
     ) -> Option<PHPType> {
         let data = self.get_property_data(state, emitter)?;
         // eprintln!("Property data: {:?}", *unlocked);
-        if let Some(x) = data.declared_type {
+        if data.writeonly {
+            None
+        } else if let Some(x) = data.declared_type {
             Some(x.clone())
         } else if let Some(dt) = data.comment_type {
             Some(dt.0.clone())
@@ -74,7 +88,7 @@ impl MemberAccessExpressionNode {
         // * gather statistics to inferr possible types of undefined/untyped variables
         if state.in_constructor() {
             if let Some(writable) = self.get_mut_property_data(state, emitter) {
-                let mut property_data = writable.write().unwrap();
+                let mut property_data = writable.write_or_recover();
                 // void
                 if let Some(val_type) = val_type {
                     if let Some(t) = property_data.constructor_type.take() {
@@ -117,7 +131,7 @@ impl MemberAccessExpressionNode {
         let class_data_handle = state.symbol_data.get_class(&class_name)?;
         let member_name = self.get_property_name(state, emitter)?;
 
-        let cdata = class_data_handle.read().unwrap();
+        let cdata = class_data_handle.read_or_recover();
         if let ClassType::Class(c) = &*cdata {
             c.get_property(&member_name, state)
         } else {
@@ -135,7 +149,7 @@ impl MemberAccessExpressionNode {
         let class_data_handle = state.symbol_data.get_class(&class_name)?;
         let member_name = self.get_property_name(state, emitter)?;
 
-        let cdata = class_data_handle.read().unwrap();
+        let cdata = class_data_handle.read_or_recover();
         if let ClassType::Class(c) = &*cdata {
             c.get_mut_property(&member_name, state)
         } else {
@@ -211,10 +225,21 @@ impl ThirdPassAnalyzeableNode for MemberAccessExpressionNode {
             ));
         }
 
+        if get_access_context(self.range, state, path) != AccessContext::Checked {
+            if let Some(object_type) = self.object.get_utype(state, &VoidEmitter::new()) {
+                if object_type.can_be_null() {
+                    emitter.emit(Issue::PropertyAccessOnNullableType(
+                        self.object.pos(state),
+                        maybe_property_name.clone(),
+                    ));
+                }
+            }
+        }
+
         if let Some(cname) = &maybe_class_name {
             if let Some(cdata_handle) = state.symbol_data.get_class(cname) {
                 if let Some(property_name) = maybe_property_name {
-                    match &*(cdata_handle.read().unwrap()) {
+                    match &*(cdata_handle.read_or_recover()) {
                         ClassType::Class(c) => {
                             if c.get_property(&property_name, state).is_none() {
                                 emitter.emit(Issue::UnknownProperty(
@@ -1,16 +1,24 @@
+use crate::locks::RecoverableLock;
 use std::collections::BTreeMap;
 
 use crate::{
     autonodes::any::AnyNodeRef,
     autotree::NodeAccess,
     issue::{Issue, VoidEmitter},
-    symboldata::class::{ClassType, MethodData},
+    symboldata::{
+        assertions::{get_call_site_assertions, AssertionData},
+        class::{ClassType, MethodData},
+        templates::{apply_template_defaults, get_bound_violations},
+    },
     symbols::{Name, Symbol, SymbolClass, SymbolMethod},
-    types::union::{DiscretlyAccessedType, PHPType},
+    types::{
+        type_operators::{contains_operator, get_call_site_concretes},
+        union::{DiscretlyAccessedType, PHPType},
+    },
 };
 
 use crate::{
-    analysis::state::AnalysisState,
+    analysis::{hardening::harden_scope_with_assertions, state::AnalysisState},
     autonodes::member_call_expression::{MemberCallExpressionName, MemberCallExpressionNode},
     issue::IssueEmitter,
     symboldata::class::ClassName,
@@ -34,7 +42,7 @@ impl MemberCallExpressionNode {
     pub fn get_utype(
         &self,
         state: &mut AnalysisState,
-        _emitter: &dyn IssueEmitter,
+        emitter: &dyn IssueEmitter,
     ) -> Option<PHPType> {
         // FIXME
         // Find out what the return-type of the method is
@@ -45,11 +53,17 @@ impl MemberCallExpressionNode {
         let mut return_type = UnionType::new();
         for (_class, method_data) in methods.into_iter().flatten() {
             // eprintln!("METHOD_DATA: {:#?}", &method_data);
-            if method_data.generic_templates.is_some() {
-                crate::missing!("Need to do something with generic_templates");
+            let mut call_return_type = method_data.get_return_type()?;
+            if contains_operator(&call_return_type) || !method_data.template_data.is_empty() {
+                // Conditional return types, method templates and similar depends on the arguments
+                let argument_types = self.arguments.get_argument_literal_types(state, emitter);
+                let concrete = get_call_site_concretes(
+                    &method_data.arguments,
+                    &argument_types,
+                    &method_data.template_data,
+                );
+                call_return_type = call_return_type.concretize_templates(&concrete);
             }
-
-            let call_return_type = method_data.get_return_type()?;
             return_type.append(call_return_type);
         }
         if !return_type.is_empty() {
@@ -71,7 +85,7 @@ impl MemberCallExpressionNode {
             let cdata_handle = state.symbol_data.get_class(&class_name)?;
 
             let method_data = if let Some(md) = {
-                let unlocked = cdata_handle.read().unwrap();
+                let unlocked = cdata_handle.read_or_recover();
                 unlocked.get_method(&method_name, state.symbol_data.clone())
             } {
                 md
@@ -107,7 +121,7 @@ impl MemberCallExpressionNode {
             for maybe_class_data in utypes {
                 if let Some(class_data) = maybe_class_data {
                     let method_data = if let Some(md) = {
-                        //let unlocked = cdata_handle.read().unwrap();
+                        //let unlocked = cdata_handle.read_or_recover();
                         class_data.get_method(&method_name, state.symbol_data.clone())
                     } {
                         md
@@ -147,7 +161,7 @@ impl MemberCallExpressionNode {
                     };
 
                     let method_data = if let Some(md) = {
-                        let unlocked = cdata_handle.read().unwrap();
+                        let unlocked = cdata_handle.read_or_recover();
                         unlocked.get_method(&method_name, state.symbol_data.clone())
                     } {
                         md
@@ -217,17 +231,23 @@ impl MemberCallExpressionNode {
             DiscreteType::Named(_, fqname) => {
                 let class_name: ClassName = fqname.into();
                 let cdata = state.symbol_data.get_class(&class_name)?;
-                let class_data = {
-                    let unlocked = cdata.read().unwrap();
+                let mut class_data = {
+                    let unlocked = cdata.read_or_recover();
                     unlocked.clone()
                 };
+                // Templates which aren't given might have defaults
+                let mut concrete = BTreeMap::new();
+                apply_template_defaults(&mut concrete, &class_data.get_template_data());
+                if !concrete.is_empty() {
+                    class_data.set_generic_concretes(concrete);
+                }
                 Some(class_data)
             }
             DiscreteType::Generic(dtype, concrete_template_types) => {
                 let class_name = self.get_class_name_from_discrete_type(&dtype)?;
                 let cdata = state.symbol_data.get_class(&class_name)?;
                 let mut class_data = {
-                    let unlocked = cdata.read().unwrap();
+                    let unlocked = cdata.read_or_recover();
                     unlocked.clone()
                 };
                 let mut concrete = BTreeMap::new();
@@ -237,6 +257,7 @@ impl MemberCallExpressionNode {
                 {
                     concrete.insert(template_name.clone(), template_type.clone());
                 }
+                apply_template_defaults(&mut concrete, &class_data.get_template_data());
                 class_data.set_generic_concretes(concrete);
                 Some(class_data)
             }
@@ -244,6 +265,82 @@ impl MemberCallExpressionNode {
                 crate::missing_none!("Find class data from ClassType...")
             }
             DiscreteType::Template(_) => crate::missing_none!("Find class data from Template..."),
+            DiscreteType::Never
+            | DiscreteType::IntRange(_, _)
+            | DiscreteType::IntLiteral(_)
+            | DiscreteType::StringLiteral(_)
+            | DiscreteType::ArrayKey
+            | DiscreteType::Scalar
+            | DiscreteType::Numeric
+            | DiscreteType::List(_)
+            | DiscreteType::NonEmptyList(_)
+            | DiscreteType::NonEmptyArray(_, _)
+            | DiscreteType::Operator(_) => None,
+        }
+    }
+
+    ///
+    /// What the method asserts about the arguments, with templates inferred from them. When
+    /// the object might be of several classes, only assertions made by all of them are known to
+    /// hold.
+    ///
+    pub(crate) fn get_assertions(
+        &self,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) -> Vec<AssertionData> {
+        let mut methods_assertions = vec![];
+        for method in self.get_methods_data(state) {
+            let Some((_class, method_data)) = method else {
+                return vec![];
+            };
+            if method_data.assertions.is_empty() {
+                return vec![];
+            }
+            let argument_types = self.arguments.get_argument_types(state, emitter);
+            methods_assertions.push(get_call_site_assertions(
+                &method_data.assertions,
+                &method_data.arguments,
+                &method_data.template_data,
+                &argument_types,
+            ));
+        }
+        let Some(assertions) = methods_assertions.pop() else {
+            return vec![];
+        };
+        assertions
+            .into_iter()
+            .filter(|assertion| methods_assertions.iter().all(|a| a.contains(assertion)))
+            .collect()
+    }
+
+    /// Emits method templates which are given a type outside of their bound
+    fn check_template_bounds(
+        &self,
+        method_data: &MethodData,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) {
+        if method_data.template_data.is_empty() {
+            return;
+        }
+        let argument_types = self
+            .arguments
+            .get_argument_literal_types(state, &VoidEmitter::new());
+        let concrete = get_call_site_concretes(
+            &method_data.arguments,
+            &argument_types,
+            &method_data.template_data,
+        );
+        for (template, bound, concrete_type) in
+            get_bound_violations(&method_data.template_data, &concrete, &state.symbol_data)
+        {
+            emitter.emit(Issue::TemplateBoundViolation(
+                self.name.pos(state),
+                template,
+                bound,
+                concrete_type,
+            ));
         }
     }
 
@@ -387,6 +484,11 @@ impl ThirdPassAnalyzeableNode for MemberCallExpressionNode {
         let class_names = if let Some(cnames) = self.get_object_class_names(state, emitter) {
             cnames
         } else {
+            state
+                .symbol_data
+                .usage
+                .write_or_recover()
+                .mark_method(None, &method_name);
             emitter.emit(Issue::MethodCallOnUnknownType(
                 self.object.pos(state),
                 None,
@@ -398,11 +500,16 @@ impl ThirdPassAnalyzeableNode for MemberCallExpressionNode {
         for cname in class_names {
             if let Some(cname) = cname {
                 if let Some(cdata_handle) = state.symbol_data.get_class(&cname) {
-                    let cdata = cdata_handle.read().unwrap();
-                    if cdata
-                        .get_method(&method_name, state.symbol_data.clone())
-                        .is_none()
+                    let cdata = cdata_handle.read_or_recover();
+                    let method_data = cdata.get_method(&method_name, state.symbol_data.clone());
                     {
+                        let mut usage = state.symbol_data.usage.write_or_recover();
+                        usage.mark_method(Some(&cname), &method_name);
+                        if let Some(mdata) = &method_data {
+                            usage.mark_method(Some(&mdata.declared_in), &method_name);
+                        }
+                    }
+                    if method_data.is_none() {
                         let fq_cname = cdata.get_fq_name();
                         emitter.emit(Issue::UnknownMethod(
                             self.name.pos(state),
@@ -410,6 +517,16 @@ impl ThirdPassAnalyzeableNode for MemberCallExpressionNode {
                             method_name.clone(),
                         ));
                     }
+                    drop(cdata);
+                    if let Some(mdata) = &method_data {
+                        self.check_template_bounds(mdata, state, emitter);
+                        self.arguments.check_nullable_arguments(
+                            &Name::from(format!("{}::{}", cname.get_fq_name(), method_name)),
+                            &mdata.arguments,
+                            state,
+                            emitter,
+                        );
+                    }
                 } else {
                     emitter.emit(Issue::MethodCallOnUnknownType(
                         self.object.pos(state),
@@ -418,6 +535,11 @@ impl ThirdPassAnalyzeableNode for MemberCallExpressionNode {
                     ));
                 }
             } else {
+                state
+                    .symbol_data
+                    .usage
+                    .write_or_recover()
+                    .mark_method(None, &method_name);
                 emitter.emit(Issue::MethodCallOnUnknownType(
                     self.object.pos(state),
                     None,
@@ -426,6 +548,8 @@ impl ThirdPassAnalyzeableNode for MemberCallExpressionNode {
             }
         }
 
+        let assertions = self.get_assertions(state, &VoidEmitter::new());
+        harden_scope_with_assertions(&assertions, &self.arguments, state, emitter);
         true
     }
 }
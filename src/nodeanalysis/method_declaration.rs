@@ -1,3 +1,4 @@
+use crate::locks::RecoverableLock;
 use std::{
     collections::HashMap,
     sync::{Arc, RwLock},
@@ -10,9 +11,12 @@ use crate::{
         method_declaration::{MethodDeclarationChildren, MethodDeclarationNode},
     },
     issue::{Issue, IssueEmitter},
-    phpdoc::types::{PHPDocComment, PHPDocEntry},
+    phpdoc::types::{PHPDocComment, PHPDocEntry, TemplateVariance},
     symboldata::{
+        assertions::get_phpdoc_assertion_data,
+        attributes::AttributeTarget,
         class::{ClassMemberVisibility, ClassModifier, ClassName, MethodData},
+        templates::{get_phpdoc_template_data, violates_variance},
         FileLocation,
     },
     symbols::Name,
@@ -96,10 +100,7 @@ impl AnalysisOfFunctionLike for MethodDeclarationNode {
             return Some(utype);
         };
         let utype = utype.map(&|discrete| match discrete {
-            DiscreteType::Special(SpecialType::Self_) => {
-                let _cname = &cname;
-                todo!("Inject self name");
-            }
+            DiscreteType::Special(SpecialType::Self_) => cname.clone().into(),
             x => x,
         });
 
@@ -108,10 +109,12 @@ impl AnalysisOfFunctionLike for MethodDeclarationNode {
 
     fn get_inferred_return_type(
         &self,
-        _state: &mut AnalysisState,
+        state: &mut AnalysisState,
         _: &dyn IssueEmitter,
     ) -> Option<PHPType> {
-        todo!()
+        let method_data = self.get_method_data(state)?;
+        let inferred_return_type = method_data.read_or_recover().inferred_return_type.clone();
+        inferred_return_type
     }
 }
 
@@ -125,18 +128,70 @@ impl MethodDeclarationNode {
 
         let class = self.get_class_name(state)?;
 
-        Some(
-            state
-                .symbol_data
-                .get_or_create_method(
-                    &class,
-                    &method_name,
-                    FileLocation::new(self.name.pos(state)),
-                )
-                .clone(),
+        state.symbol_data.get_or_create_method(
+            &class,
+            &method_name,
+            FileLocation::new(self.name.pos(state)),
         )
     }
 
+    ///
+    /// Covariant class templates can't be used for parameters, and contravariant class
+    /// templates can't be used for the return type, except as parameters of callables.
+    /// Constructors and private methods are exempt, as they can't be called through a
+    /// supertype
+    ///
+    fn check_template_variance(
+        &self,
+        method_data: &MethodData,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) {
+        if method_data.visibility == ClassMemberVisibility::Private
+            || method_data.name.eq_ignore_ascii_case("__construct")
+        {
+            return;
+        }
+        let Some(class_state) = &state.in_class else {
+            return;
+        };
+        let templates = class_state.get_data().read_or_recover().get_template_data();
+        let return_type = method_data
+            .comment_return_type
+            .as_ref()
+            .map(|x| &x.0)
+            .or(method_data.php_return_type.as_ref());
+        for template in templates {
+            let violated = method_data.arguments.iter().any(|arg| {
+                arg.phpdoc_type
+                    .as_ref()
+                    .or(arg.arg_type.as_ref())
+                    .is_some_and(|t| {
+                        violates_variance(
+                            t,
+                            &template.name,
+                            template.variance,
+                            TemplateVariance::Contravariant,
+                        )
+                    })
+            }) || return_type.is_some_and(|t| {
+                violates_variance(
+                    t,
+                    &template.name,
+                    template.variance,
+                    TemplateVariance::Covariant,
+                )
+            });
+            if violated {
+                emitter.emit(Issue::TemplateVarianceViolation(
+                    self.name.pos(state),
+                    template.name,
+                    method_data.name.clone(),
+                ));
+            }
+        }
+    }
+
     fn get_doc_comment_declared_templates(
         &self,
         doc_comment: &PHPDocComment,
@@ -147,10 +202,10 @@ impl MethodDeclarationNode {
 
         for entry in &doc_comment.entries {
             match entry {
-                PHPDocEntry::Template(range, t, _) => {
+                PHPDocEntry::Template(range, t) => {
                     let generic_templates =
                         state.get_generic_templates(Some(&method_template_params));
-                    let temp_name: Name = t.into();
+                    let temp_name: Name = (&t.name).into();
                     if let Some(gen) = generic_templates {
                         if gen.contains(&temp_name) {
                             emitter.emit(Issue::DuplicateTemplate(
@@ -194,12 +249,19 @@ impl FirstPassAnalyzeableNode for MethodDeclarationNode {
         let mut comment_return_type = None;
         let mut param_map = HashMap::new();
         let mut method_template_params = vec![];
+        let mut template_data = vec![];
         if let Some((doc_comment, range)) = &state.last_doc_comment {
             match PHPDocComment::parse(doc_comment, range) {
                 Ok(doc_comment) => {
                     // First check for templates
                     method_template_params =
                         self.get_doc_comment_declared_templates(&doc_comment, state, emitter);
+                    template_data = get_phpdoc_template_data(
+                        &doc_comment,
+                        &method_template_params,
+                        state,
+                        emitter,
+                    );
                     // TODO type-parsing expects generic-information in FunctionData in state...
 
                     // Then check the remaining entries
@@ -262,24 +324,46 @@ impl FirstPassAnalyzeableNode for MethodDeclarationNode {
                 MethodDeclarationChildren::FinalModifier(_) => modifier = ClassModifier::Final,
                 MethodDeclarationChildren::StaticModifier(_) => is_static = true,
                 MethodDeclarationChildren::VisibilityModifier(v) => visibility = v.get_visibility(),
-                MethodDeclarationChildren::VarModifier(v) => {
-                    todo!("HWA I ALLE DAGER ER DETTE: {:?}", v);
+                MethodDeclarationChildren::VarModifier(_) => {
+                    emitter.emit(Issue::ParseAnomaly(
+                        self.pos(state),
+                        "Methods can't be declared with `var`".into(),
+                    ));
                 }
                 _ => continue,
             }
         }
 
-        let method_data = self.get_method_data(state).unwrap();
+        let Some(method_data) = self.get_method_data(state) else {
+            return;
+        };
+        let attributes = self
+            .attributes
+            .as_ref()
+            .map(|a| a.get_attributes(state))
+            .unwrap_or_default();
         let arguments = self.parameters.analyze_first_pass_parameters(
             state,
             emitter,
             &param_map,
             Some(&method_template_params),
         );
+        let assertions = phpdoc
+            .as_ref()
+            .map(|doc_comment| {
+                get_phpdoc_assertion_data(
+                    doc_comment,
+                    &arguments,
+                    &method_template_params,
+                    state,
+                    emitter,
+                )
+            })
+            .unwrap_or_default();
 
         {
             // We scope the locked state to make it as short as possible
-            let mut unlocked = method_data.write().unwrap();
+            let mut unlocked = method_data.write_or_recover();
             unlocked.name = method_name.clone();
             unlocked.php_return_type = php_return_type;
             unlocked.comment_return_type = comment_return_type;
@@ -288,7 +372,10 @@ impl FirstPassAnalyzeableNode for MethodDeclarationNode {
             unlocked.visibility = visibility;
             unlocked.phpdoc = phpdoc;
             unlocked.arguments = arguments;
+            unlocked.attributes = attributes;
             unlocked.generic_templates = Some(method_template_params);
+            unlocked.template_data = template_data;
+            unlocked.assertions = assertions;
         }
 
         // eprintln!("Tolket metode: {:?}", method_data);
@@ -314,7 +401,7 @@ impl SecondPassAnalyzeableNode for MethodDeclarationNode {
         // Check types used in phpdoc
 
         {
-            let method_data = locked_data.read().unwrap();
+            let method_data = locked_data.read_or_recover();
             if let Some(phpdoc) = &method_data.phpdoc {
                 // First check for templates
                 let method_template_params_list =
@@ -350,16 +437,19 @@ impl SecondPassAnalyzeableNode for MethodDeclarationNode {
             if let Some((utype, range)) = &method_data.comment_return_type {
                 utype.ensure_valid(state, emitter, range, true);
             }
+            self.check_template_variance(&method_data, state, emitter);
         }
+        if let Some(attributes) = &self.attributes {
+            attributes.analyze_attributes(AttributeTarget::Method, state, emitter);
+        }
+        self.parameters.analyze_parameter_attributes(state, emitter);
+
         let function = FunctionState::new_method(self.get_declared_name(), locked_data);
         state.in_function_stack.push(function);
 
         self.analyze_second_pass_children(&self.as_any(), state, emitter);
 
-        let _func = state
-            .in_function_stack
-            .pop()
-            .expect("There must be a state");
+        state.in_function_stack.pop();
     }
 }
 
@@ -399,9 +489,9 @@ impl ThirdPassAnalyzeableNode for MethodDeclarationNode {
 
         {
             let scope_h = state.current_scope();
-            let mut scope = scope_h.write().unwrap();
+            let mut scope = scope_h.write_or_recover();
             let this_data_lock = scope.get_or_create_var(Name::from("this"));
-            let mut this_data = this_data_lock.write().unwrap();
+            let mut this_data = this_data_lock.write_or_recover();
             if let Some(cstate) = &state.in_class {
                 this_data.php_declared_type = Some(
                     DiscreteType::Named(
@@ -424,14 +514,14 @@ impl ThirdPassAnalyzeableNode for MethodDeclarationNode {
             return false;
         }
 
-        let func = state
-            .in_function_stack
-            .pop()
-            .expect("There must be a state");
+        let Some(func) = state.in_function_stack.pop() else {
+            crate::missing!("method state was popped by a child node");
+            return true;
+        };
 
-        let returns = func.returns.read().unwrap().clone();
+        let returns = func.returns.read_or_recover().clone();
         let return_count = returns.len();
-        let scope_handle = func.scope_stack.read().unwrap().top();
+        let scope_handle = func.scope_stack.read_or_recover().top();
         scope_handle.analyze_for_unused_vars(state, emitter);
 
         let mut ret_type = UnionType::new();
@@ -463,10 +553,13 @@ impl ThirdPassAnalyzeableNode for MethodDeclarationNode {
         } else {
             None
         };*/
+        let generator_type = func.get_generator_type();
         if let Some(method) = self.get_method_data(state) {
-            let mut method_data = method.write().unwrap();
+            let mut method_data = method.write_or_recover();
             method_data.return_count = return_count;
-            if !ret_type.is_empty() {
+            if let Some(generator_type) = generator_type {
+                method_data.inferred_return_type = Some(generator_type);
+            } else if !ret_type.is_empty() {
                 method_data.inferred_return_type = Some(ret_type.into());
             }
         }
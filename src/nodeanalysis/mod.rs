@@ -13,6 +13,9 @@ pub mod array_creation_expression;
 pub mod array_element_initializer;
 pub mod arrow_function;
 pub mod assignment_expression;
+pub mod attribute;
+pub mod attribute_group;
+pub mod attribute_list;
 pub mod augmented_assignment_expression;
 pub mod base_clause;
 pub mod binary_expression;
@@ -1,3 +1,6 @@
+use crate::locks::RecoverableLock;
+use std::os::unix::prelude::OsStrExt;
+
 use crate::{
     analysis::state::AnalysisState,
     autonodes::name::NameNode,
@@ -32,8 +35,11 @@ impl NameNode {
         state: &mut AnalysisState,
         emitter: &dyn IssueEmitter,
     ) -> Option<PHPValue> {
+        if let Some(value) = self.get_magic_constant_value(state) {
+            return value;
+        }
         let const_name = self.get_constant_name(state);
-        if let Some(x) = state.global.constants.read().unwrap().get(&const_name) {
+        if let Some(x) = state.global.constants.read_or_recover().get(&const_name) {
             x.get_value()
         } else {
             // FIXME this should emit in an analysis-method?
@@ -42,6 +48,25 @@ impl NameNode {
         }
     }
 
+    /// `__DIR__`, `__FILE__` and `__LINE__` depends on where they're used. Returns `None`
+    /// if this isn't one of them
+    fn get_magic_constant_value(&self, state: &AnalysisState) -> Option<Option<PHPValue>> {
+        let name = self.get_raw().to_ascii_uppercase();
+        Some(match name.as_bytes() {
+            b"__DIR__" => state
+                .filename
+                .as_ref()
+                .and_then(|f| f.parent())
+                .map(|d| PHPValue::String(d.as_os_str().to_os_string())),
+            b"__FILE__" => state
+                .filename
+                .as_ref()
+                .map(|f| PHPValue::String(f.as_os_str().to_os_string())),
+            b"__LINE__" => Some(PHPValue::Int(self.range.start_point.row as i64 + 1)),
+            _ => return None,
+        })
+    }
+
     pub fn get_utype(
         &self,
         state: &mut AnalysisState,
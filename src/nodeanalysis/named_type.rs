@@ -33,11 +33,13 @@ impl NamedTypeNode {
                 let fq_name = fq.get_fq_name(state);
                 DiscreteType::Named(fq_name.get_name().unwrap_or_else(Name::new), fq_name)
             }
-            _ => todo!(
-                "Fint type from NamedType in {}: {:?}",
-                state.pos_as_string(self.range),
-                self
-            ),
+            _ => {
+                return crate::missing_none!(
+                    "Find type from NamedType in {}: {:?}",
+                    state.pos_as_string(self.range),
+                    self
+                )
+            }
         };
 
         Some(discrete_type.into())
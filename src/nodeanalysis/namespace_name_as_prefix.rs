@@ -31,7 +31,7 @@ impl NamespaceNameAsPrefixNode {
             let range = self.range;
             let len = range.end_byte - range.start_byte;
             if len != 1 {
-                panic!("Lengde er ikke 1??: {}, {:?}", len, self);
+                crate::missing!("Lengde er ikke 1??: {}, {:?}", len, self);
             }
             FullyQualifiedName::new()
         }
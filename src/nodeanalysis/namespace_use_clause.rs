@@ -65,7 +65,11 @@ impl FirstPassAnalyzeableNode for NamespaceUseClauseNode {
                     // Box<NameNode>),
                     if use_name.is_some() {
                         // We didn't expect us to arrive here multiple times
-                        todo!("Something askew with parsing");
+                        emitter.emit(Issue::ParseAnomaly(
+                            self.pos(state),
+                            "Something askew with parsing".into(),
+                        ));
+                        return;
                     }
                     let name = n.get_name();
                     use_fq_name = Some(FullyQualifiedName::from(&name));
@@ -92,7 +96,11 @@ impl FirstPassAnalyzeableNode for NamespaceUseClauseNode {
                 NamespaceUseClauseChildren::QualifiedName(qn) => {
                     if use_name.is_some() {
                         // We didn't expect us to arrive here multiple times
-                        todo!("Something askew with parsing");
+                        emitter.emit(Issue::ParseAnomaly(
+                            self.pos(state),
+                            "Something askew with parsing".into(),
+                        ));
+                        return;
                     }
                     use_name = Some(qn.get_name());
                     use_fq_name = Some(qn.get_raw_fq_name());
@@ -30,15 +30,16 @@ impl NamespaceUseGroupNode {
 }
 
 impl FirstPassAnalyzeableNode for NamespaceUseGroupNode {
-    fn analyze_first_pass(&self, _state: &mut AnalysisState, _emitter: &dyn IssueEmitter) {
-        todo!("WHAT: {:?}", self);
+    fn analyze_first_pass(&self, state: &mut AnalysisState, _emitter: &dyn IssueEmitter) {
+        crate::missing!(
+            "Group use declaration at {}",
+            state.pos_as_string(self.range)
+        );
     }
 }
 
 impl SecondPassAnalyzeableNode for NamespaceUseGroupNode {
-    fn analyze_second_pass(&self, _state: &mut AnalysisState, _emitter: &dyn IssueEmitter) {
-        todo!("WHAT: {:?}", self);
-    }
+    fn analyze_second_pass(&self, _state: &mut AnalysisState, _emitter: &dyn IssueEmitter) {}
 }
 
 impl ThirdPassAnalyzeableNode for NamespaceUseGroupNode {
@@ -48,6 +49,6 @@ impl ThirdPassAnalyzeableNode for NamespaceUseGroupNode {
         _emitter: &dyn IssueEmitter,
         _path: &[AnyNodeRef],
     ) -> bool {
-        todo!("WHAT: {:?}", self);
+        true
     }
 }
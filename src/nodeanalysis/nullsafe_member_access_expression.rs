@@ -1,9 +1,15 @@
 use crate::{
     analysis::state::AnalysisState,
-    autonodes::nullsafe_member_access_expression::NullsafeMemberAccessExpressionNode,
-    issue::IssueEmitter, types::union::PHPType,
+    autonodes::{
+        any::AnyNodeRef, nullsafe_member_access_expression::NullsafeMemberAccessExpressionNode,
+    },
+    autotree::NodeAccess,
+    issue::{Issue, IssueEmitter, VoidEmitter},
+    types::union::PHPType,
 };
 
+use super::analysis::ThirdPassAnalyzeableNode;
+
 impl NullsafeMemberAccessExpressionNode {
     pub fn read_from(&self, _state: &mut AnalysisState, _emitter: &dyn IssueEmitter) {
         crate::missing!("{}.read_from(..)", self.kind());
@@ -25,3 +31,22 @@ impl NullsafeMemberAccessExpressionNode {
         crate::missing_none!("{}.get_utype(..)", self.kind())
     }
 }
+
+impl ThirdPassAnalyzeableNode for NullsafeMemberAccessExpressionNode {
+    fn analyze_third_pass(
+        &self,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+        path: &[AnyNodeRef],
+    ) -> bool {
+        if let Some(object_type) = self.object.get_utype(state, &VoidEmitter::new()) {
+            if object_type.is_never_null() {
+                emitter.emit(Issue::RedundantNullsafeOperator(
+                    self.object.pos(state),
+                    object_type,
+                ));
+            }
+        }
+        self.analyze_third_pass_children(&self.as_any(), state, emitter, path)
+    }
+}
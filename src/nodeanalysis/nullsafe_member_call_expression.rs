@@ -1,9 +1,19 @@
+use crate::locks::RecoverableLock;
 use crate::{
     analysis::state::AnalysisState,
-    autonodes::nullsafe_member_call_expression::NullsafeMemberCallExpressionNode,
-    issue::IssueEmitter, types::union::PHPType,
+    autonodes::{
+        any::AnyNodeRef,
+        nullsafe_member_call_expression::{
+            NullsafeMemberCallExpressionName, NullsafeMemberCallExpressionNode,
+        },
+    },
+    autotree::NodeAccess,
+    issue::{Issue, IssueEmitter, VoidEmitter},
+    types::union::PHPType,
 };
 
+use super::analysis::ThirdPassAnalyzeableNode;
+
 impl NullsafeMemberCallExpressionNode {
     pub fn read_from(&self, _state: &mut AnalysisState, _emitter: &dyn IssueEmitter) {
         crate::missing!("{}.read_from(..)", self.kind());
@@ -25,3 +35,31 @@ impl NullsafeMemberCallExpressionNode {
         crate::missing_none!("{}.get_utype(..)", self.kind())
     }
 }
+
+impl ThirdPassAnalyzeableNode for NullsafeMemberCallExpressionNode {
+    fn analyze_third_pass(
+        &self,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+        path: &[AnyNodeRef],
+    ) -> bool {
+        // We don't resolve the type of the object here yet, so any method with this
+        // name might be the one called
+        if let NullsafeMemberCallExpressionName::Name(n) = &*self.name {
+            state
+                .symbol_data
+                .usage
+                .write_or_recover()
+                .mark_method(None, &n.get_name());
+        }
+        if let Some(object_type) = self.object.get_utype(state, &VoidEmitter::new()) {
+            if object_type.is_never_null() {
+                emitter.emit(Issue::RedundantNullsafeOperator(
+                    self.object.pos(state),
+                    object_type,
+                ));
+            }
+        }
+        self.analyze_third_pass_children(&self.as_any(), state, emitter, path)
+    }
+}
@@ -1,20 +1,31 @@
-use std::collections::HashMap;
+use crate::locks::RecoverableLock;
+use std::collections::BTreeMap;
 
 use crate::{
     analysis::state::AnalysisState,
     autonodes::{
+        any::AnyNodeRef,
         arguments::ArgumentsNode,
         object_creation_expression::{
             ObjectCreationExpressionChildren, ObjectCreationExpressionNode,
         },
     },
-    issue::IssueEmitter,
-    symboldata::class::ClassName,
+    autotree::NodeAccess,
+    issue::{Issue, IssueEmitter, VoidEmitter},
+    symboldata::{
+        class::ClassName,
+        templates::{
+            apply_template_defaults, get_bound_violations, get_generic_concretes,
+            infer_template_types,
+        },
+    },
     symbols::{FullyQualifiedName, Name},
-    types::union::{DiscreteType, DiscretlyAccessedType, PHPType},
+    types::union::{DiscreteType, PHPType},
     value::{ObjectInstance, PHPValue},
 };
 
+use super::analysis::ThirdPassAnalyzeableNode;
+
 #[derive(Debug, Clone)]
 pub struct ObjectCreationData {
     name: Option<ObjectCreationExpressionChildren>,
@@ -48,7 +59,7 @@ impl ObjectCreationExpressionNode {
                     (real_fq.clone(), Some(generic_template_concretes))
                 }
                 _ => {
-                    todo!(
+                    return crate::missing_none!(
                         "WHAT to do with generics: {} < {:?} >",
                         base_type,
                         generic_template_concretes
@@ -66,11 +77,11 @@ impl ObjectCreationExpressionNode {
         };
         /*
                 let class_data_handle = {
-                    let cdata = state.symbol_data.classes.read().unwrap();
+                    let cdata = state.symbol_data.classes.read_or_recover();
 
                     cdata.get(&fq_name)?.clone()
                 };
-                let _class_data = class_data_handle.read().unwrap();
+                let _class_data = class_data_handle.read_or_recover();
         */
 
         // FIXME generics-analyse her eller i ObjectInstance
@@ -205,13 +216,15 @@ impl ObjectCreationExpressionNode {
         let shared_class_data = state.symbol_data.get_class(&fq_class_name)?;
         // FIXME improve structure here
 
-        let ctype = shared_class_data.read().unwrap();
+        let ctype = shared_class_data.read_or_recover();
         let template_types = ctype.get_generic_templates()?;
 
-        let mut inner_generic_map = HashMap::new();
-        let constructor_data = ctype.get_constructor(state.symbol_data.clone())?;
+        let mut inferred = BTreeMap::new();
+        let constructor_data = ctype.get_constructor(state.symbol_data.clone());
 
-        if let Some(args) = &object_creation_data.arguments {
+        if let (Some(args), Some(constructor_data)) =
+            (&object_creation_data.arguments, constructor_data)
+        {
             let in_arguments = args.get_argument_types(state, emitter);
             let mut in_argument_iter = in_arguments.iter();
             for func_arg in &constructor_data.arguments {
@@ -220,17 +233,14 @@ impl ObjectCreationExpressionNode {
                 let (Some(Some(in_type)), Some(arg_type)) = (in_type, arg_type) else {
                     continue;
                 };
-                // void
-                if arg_type.contains_template() {
-                    self.infer_generic_type_into_map(&mut inner_generic_map, arg_type, in_type);
-                }
-                //eprintln!("ARG: {}: {:?}", func_arg.name, func_arg.get_type(state));
+                infer_template_types(&mut inferred, &arg_type, &in_type);
             }
         }
+        apply_template_defaults(&mut inferred, &ctype.get_template_data());
 
         let mut concrete_generic_args = vec![];
         for temp in template_types {
-            if let Some(mapped_to) = inner_generic_map.get(&temp) {
+            if let Some(mapped_to) = inferred.get(&temp) {
                 concrete_generic_args.push(mapped_to.clone());
             } else {
                 concrete_generic_args.push(DiscreteType::Template(temp).into());
@@ -243,31 +253,86 @@ impl ObjectCreationExpressionNode {
         Some(DiscreteType::Generic(Box::new(class_type), concrete_generic_args).into())
     }
 
-    pub(crate) fn infer_generic_type_into_map(
+    /// Emits templates given a type outside of their bound
+    fn check_template_bounds(
         &self,
-        generic_map: &mut HashMap<Name, PHPType>,
-        templated_type: PHPType,
-        in_type: PHPType,
-    ) -> Option<()> {
-        let mut templated_type_variants = templated_type.as_discrete_variants();
-        if templated_type_variants.len() != 1 {
-            return crate::missing_none!("Handle of templated union types with multiple types");
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+        class_name: &ClassName,
+        generic_args: &[PHPType],
+    ) {
+        let Some(class_handle) = state.symbol_data.get_class(class_name) else {
+            return;
+        };
+        let templates = class_handle.read_or_recover().get_template_data();
+        let concrete = get_generic_concretes(&templates, generic_args);
+        for (template, bound, concrete_type) in
+            get_bound_violations(&templates, &concrete, &state.symbol_data)
+        {
+            emitter.emit(Issue::TemplateBoundViolation(
+                self.pos(state),
+                template,
+                bound,
+                concrete_type,
+            ));
         }
-        let templated_type = templated_type_variants.pop().expect("We checked len");
-        let DiscretlyAccessedType::Discrete(template_type) = templated_type else {
-            return crate::missing_none!("Handle of templated intersection types");
+    }
+}
+
+impl ObjectCreationExpressionNode {
+    fn check_nullable_constructor_arguments(
+        &self,
+        class_name: &ClassName,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) {
+        let Some(arguments) = self.get_creation_data().arguments else {
+            return;
+        };
+        let symbol_data = state.symbol_data.clone();
+        let Some(constructor) = symbol_data.get_class(class_name).and_then(|c| {
+            c.read_or_recover()
+                .get_method(&"__construct".into(), symbol_data.clone())
+        }) else {
+            return;
         };
+        arguments.check_nullable_arguments(
+            &Name::from(format!("{}::__construct", class_name.get_fq_name())),
+            &constructor.arguments,
+            state,
+            emitter,
+        );
+    }
+}
 
-        match template_type {
-            DiscreteType::Template(tname) => {
-                generic_map.insert(tname.clone(), in_type);
+impl ThirdPassAnalyzeableNode for ObjectCreationExpressionNode {
+    fn analyze_third_pass(
+        &self,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+        path: &[AnyNodeRef],
+    ) -> bool {
+        let class_type = self
+            .get_utype(state, &VoidEmitter::new())
+            .and_then(|t| t.single_type());
+        let (class_type, generic_args) = match class_type {
+            Some(DiscreteType::Generic(base_type, generic_args)) => {
+                (Some(*base_type), generic_args)
             }
-            DiscreteType::Generic(_base, _generics) => {
-                crate::missing!("Handle templated generics");
+            t => (t, vec![]),
+        };
+        if let Some(DiscreteType::Named(name, fq_name)) = class_type {
+            let class_name = ClassName::new_with_names(name, fq_name);
+            state
+                .symbol_data
+                .usage
+                .write_or_recover()
+                .mark_class(&class_name);
+            if !generic_args.is_empty() {
+                self.check_template_bounds(state, emitter, &class_name, &generic_args);
             }
-            _ => (),
+            self.check_nullable_constructor_arguments(&class_name, state, emitter);
         }
-
-        None
+        self.analyze_third_pass_children(&self.as_any(), state, emitter, path)
     }
 }
@@ -34,6 +34,7 @@ impl PrimitiveTypeNode {
             b"array" => DiscreteType::Array,
             b"bool" => DiscreteType::Bool,
             b"void" => DiscreteType::Void,
+            b"never" => DiscreteType::Never,
             b"float" => DiscreteType::Float,
             _ => {
                 return crate::missing_none!(
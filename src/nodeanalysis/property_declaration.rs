@@ -6,6 +6,7 @@ use crate::autonodes::any::AnyNodeRef;
 // use crate::autonodes::property_declaration::PropertyDeclarationProperties;
 use crate::autotree::NodeAccess;
 use crate::extra::ExtraChild;
+use crate::symboldata::attributes::AttributeTarget;
 use crate::types::union::PHPType;
 use crate::{
     analysis::state::AnalysisState, autonodes::property_declaration::PropertyDeclarationNode,
@@ -93,7 +94,12 @@ impl FirstPassAnalyzeableNode for PropertyDeclarationNode {
 
 impl SecondPassAnalyzeableNode for PropertyDeclarationNode {
     fn analyze_second_pass(&self, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
-        crate::missing!("PropertyDeclarationNode.analyze_second_pass()");
+        if let Some(attributes) = &self.attributes {
+            attributes.analyze_attributes(AttributeTarget::Property, state, emitter);
+        }
+        for prop in &self.children {
+            prop.check_template_variance(state, emitter);
+        }
         self.analyze_second_pass_children(&self.as_any(), state, emitter);
     }
 }
@@ -1,3 +1,4 @@
+use crate::locks::RecoverableLock;
 use std::sync::{Arc, RwLock};
 
 use crate::{
@@ -8,9 +9,10 @@ use crate::{
     },
     autotree::NodeAccess,
     issue::{Issue, IssueEmitter},
-    phpdoc::types::{PHPDocComment, PHPDocEntry},
+    phpdoc::types::{PHPDocComment, PHPDocEntry, TemplateVariance},
     symboldata::{
         class::{ClassMemberVisibility, ClassModifier, PropertyData},
+        templates::violates_variance,
         FileLocation,
     },
     symbols::Name,
@@ -168,8 +170,14 @@ impl PropertyElementNode {
             }
         };
 
+        let attributes = declaration
+            .attributes
+            .as_ref()
+            .map(|a| a.get_attributes(state))
+            .unwrap_or_default();
+
         {
-            let mut data = data_handle.write().unwrap();
+            let mut data = data_handle.write_or_recover();
             data.declared_type = declared_type;
             data.comment_type = comment_type;
             data.is_static = is_static;
@@ -177,6 +185,7 @@ impl PropertyElementNode {
             data.modifier = modifier;
             data.visibility = visibility;
             data.phpdoc = doc_comment;
+            data.attributes = attributes;
         }
     }
 
@@ -198,7 +207,7 @@ impl PropertyElementNode {
             return;
         };
 
-        let mut data = data_handle.write().unwrap();
+        let mut data = data_handle.write_or_recover();
 
         data.default_value = if let Some(init) = &self.child {
             init.get_php_value(state, emitter)
@@ -212,4 +221,45 @@ impl PropertyElementNode {
             x.check_type_casing(self.range(), state, emitter)
         }
     }
+
+    ///
+    /// Properties can be both read and written, so covariant and contravariant class
+    /// templates can't be used for them. Readonly properties can use covariant templates.
+    /// Private properties are exempt, as they can't be accessed through a supertype
+    ///
+    pub fn check_template_variance(&self, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
+        let Some(data_handle) = self.get_property_data(state, emitter) else {
+            return;
+        };
+        let data = data_handle.read_or_recover();
+        if data.visibility == ClassMemberVisibility::Private || data.is_static {
+            return;
+        }
+        let Some(class_state) = &state.in_class else {
+            return;
+        };
+        let templates = class_state.get_data().read_or_recover().get_template_data();
+        let Some(ptype) = data
+            .comment_type
+            .as_ref()
+            .map(|x| &x.0)
+            .or(data.declared_type.as_ref())
+        else {
+            return;
+        };
+        let position = if data.readonly {
+            TemplateVariance::Covariant
+        } else {
+            TemplateVariance::Invariant
+        };
+        for template in templates {
+            if violates_variance(ptype, &template.name, template.variance, position) {
+                emitter.emit(Issue::TemplateVarianceViolation(
+                    self.pos(state),
+                    template.name,
+                    data.name.clone(),
+                ));
+            }
+        }
+    }
 }
@@ -1,3 +1,4 @@
+use crate::locks::RecoverableLock;
 use crate::{
     analysis::state::AnalysisState,
     autonodes::qualified_name::{QualifiedNameChildren, QualifiedNameNode},
@@ -15,8 +16,7 @@ impl QualifiedNameNode {
                 QualifiedNameChildren::NamespaceNameAsPrefix(pf) => {
                     return pf.get_prefix();
                 }
-                QualifiedNameChildren::Name(_) => (),
-                _ => todo!("Hva gjør vi med en {:?} her?", x),
+                QualifiedNameChildren::Name(_) | QualifiedNameChildren::Extra(_) => (),
             }
         }
         FullyQualifiedName::new()
@@ -25,10 +25,8 @@ impl QualifiedNameNode {
     pub fn is_root_anchored(&self) -> bool {
         for child in &self.children {
             match &**child {
-                QualifiedNameChildren::Name(n) => {
-                    todo!("CRAP {:?} {:?}", self.range.start_byte, n.range.start_byte);
-                    // return false;
-                }
+                // The prefix comes before the name, so without one it isn't anchored
+                QualifiedNameChildren::Name(_) => return false,
                 QualifiedNameChildren::NamespaceNameAsPrefix(nn) => {
                     return nn.is_root_anchored();
                 }
@@ -68,15 +66,18 @@ impl QualifiedNameNode {
                 QualifiedNameChildren::NamespaceNameAsPrefix(_) => (),
                 QualifiedNameChildren::Name(n) => {
                     if name.is_some() {
-                        panic!("Vi har funnet flere name-noder?");
+                        crate::missing!("Vi har funnet flere name-noder?");
                     }
                     name = Some(n.get_name())
                 }
-                _ => todo!("Hva gjør vi med en {:?} her?", x),
+                QualifiedNameChildren::Extra(_) => (),
             }
         }
 
-        name.take().expect("Den her burde funnet et name?")
+        name.unwrap_or_else(|| {
+            crate::missing!("Den her burde funnet et name?");
+            Name::new()
+        })
     }
 
     pub fn read_from(&self, _state: &mut AnalysisState, _emitter: &dyn IssueEmitter) {
@@ -96,8 +97,7 @@ impl QualifiedNameNode {
         if let Some(x) = state
             .global
             .constants
-            .read()
-            .unwrap()
+            .read_or_recover()
             .get(&self.get_fq_name(state))
         {
             x.get_value()
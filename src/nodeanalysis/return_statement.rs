@@ -2,10 +2,16 @@ use crate::{
     analysis::state::AnalysisState,
     autonodes::{any::AnyNodeRef, return_statement::ReturnStatementNode},
     issue::{Issue, IssueEmitter},
-    types::union::{DiscreteType, PHPType},
+    types::{
+        traversable::get_generator_return_type,
+        union::{DiscreteType, PHPType},
+    },
 };
 
-use super::analysis::ThirdPassAnalyzeableNode;
+use super::{
+    analysis::ThirdPassAnalyzeableNode,
+    yield_expression::{contains_yield, get_enclosing_function},
+};
 use crate::autotree::NodeAccess;
 
 impl ReturnStatementNode {
@@ -32,6 +38,39 @@ impl ReturnStatementNode {
             Some(DiscreteType::Void.into())
         }
     }
+
+    /// In a generator, the returned value must match `TReturn` of the declared `Generator`
+    fn check_generator_return(
+        &self,
+        ret_type: &PHPType,
+        path: &[AnyNodeRef],
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) {
+        let Some(function) = get_enclosing_function(path) else {
+            return;
+        };
+        if !contains_yield(function) {
+            return;
+        }
+        let Some(declared_return) = state
+            .in_function_stack
+            .last()
+            .and_then(|f| f.get_declared_return_type())
+            .and_then(|t| get_generator_return_type(&t))
+        else {
+            return;
+        };
+        if let Some(false) =
+            ret_type.can_be_passed_strictly_as(&declared_return, &state.symbol_data)
+        {
+            emitter.emit(Issue::IncompatibleYieldType(
+                self.pos(state),
+                declared_return,
+                ret_type.clone(),
+            ));
+        }
+    }
 }
 
 impl ThirdPassAnalyzeableNode for ReturnStatementNode {
@@ -50,6 +89,9 @@ impl ThirdPassAnalyzeableNode for ReturnStatementNode {
         } else {
             (Some(DiscreteType::Void.into()), None)
         };
+        if let Some(ret_type) = &ret_type {
+            self.check_generator_return(ret_type, path, state, emitter);
+        }
         if let Some(func_state) = state.in_function_stack.last() {
             func_state.add_return(ret_type, ret_value);
         } else {
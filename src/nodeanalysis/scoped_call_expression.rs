@@ -1,15 +1,30 @@
+use crate::locks::RecoverableLock;
+use std::os::unix::prelude::OsStrExt;
+
 use crate::{
-    analysis::state::AnalysisState,
-    autonodes::scoped_call_expression::{
-        ScopedCallExpressionName, ScopedCallExpressionNode, ScopedCallExpressionScope,
+    analysis::{hardening::harden_scope_with_assertions, state::AnalysisState},
+    autonodes::{
+        any::AnyNodeRef,
+        scoped_call_expression::{
+            ScopedCallExpressionName, ScopedCallExpressionNode, ScopedCallExpressionScope,
+        },
     },
+    autotree::NodeAccess,
     issue::{IssueEmitter, VoidEmitter},
-    symboldata::class::{ClassName, MethodData},
+    symboldata::{
+        assertions::{get_call_site_assertions, AssertionData},
+        class::{ClassName, ClassType, MethodData},
+    },
     symbols::{Name, SymbolClass, SymbolMethod},
-    types::union::{DiscreteType, PHPType, SpecialType},
+    types::{
+        type_operators::{contains_operator, get_call_site_concretes},
+        union::{DiscreteType, PHPType, SpecialType},
+    },
     value::PHPValue,
 };
 
+use super::analysis::ThirdPassAnalyzeableNode;
+
 ///
 /// Static method call
 impl ScopedCallExpressionNode {
@@ -69,7 +84,21 @@ impl ScopedCallExpressionNode {
                 qname.get_name(),
                 qname.get_fq_name(state),
             )),
-            ScopedCallExpressionScope::RelativeScope(_) => crate::missing_none!("{}", self.kind()),
+            ScopedCallExpressionScope::RelativeScope(rs) => {
+                let class_name = state.in_class.as_ref()?.get_name();
+                match rs.get_raw().to_ascii_lowercase().as_bytes() {
+                    b"self" | b"static" => Some(class_name),
+                    b"parent" => {
+                        let class_data = state.symbol_data.get_class(&class_name)?;
+                        let unlocked = class_data.read_or_recover();
+                        match &*unlocked {
+                            ClassType::Class(c) => c.base_class_name.clone(),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                }
+            }
             ScopedCallExpressionScope::ScopedCallExpression(sc) => {
                 if let Some(utype) = sc.get_utype(state, &VoidEmitter::new()) {
                     match utype.single_type() {
@@ -135,7 +164,7 @@ impl ScopedCallExpressionNode {
 
         let method_name = self.get_method_name(state, &VoidEmitter::new())?;
 
-        let noe = class_data_handle.read().unwrap();
+        let noe = class_data_handle.read_or_recover();
 
         noe.get_method(&method_name, state.symbol_data.clone())
             .map(|mdata| (class_name, mdata))
@@ -144,7 +173,7 @@ impl ScopedCallExpressionNode {
     pub fn get_utype(
         &self,
         state: &mut AnalysisState,
-        _emitter: &dyn IssueEmitter,
+        emitter: &dyn IssueEmitter,
     ) -> Option<PHPType> {
         let (class_name, method_data) = self.get_method_data(state)?;
 
@@ -158,6 +187,18 @@ impl ScopedCallExpressionNode {
         } else {
             return None;
         };
+        let utype = if contains_operator(&utype) || !method_data.template_data.is_empty() {
+            // Conditional return types, method templates and similar depends on the arguments
+            let argument_types = self.arguments.get_argument_literal_types(state, emitter);
+            let concrete = get_call_site_concretes(
+                &method_data.arguments,
+                &argument_types,
+                &method_data.template_data,
+            );
+            utype.concretize_templates(&concrete)
+        } else {
+            utype
+        };
 
         let ret_type = utype.map(&|discrete| match discrete {
             DiscreteType::Special(SpecialType::Static) => DiscreteType::Named(
@@ -170,6 +211,27 @@ impl ScopedCallExpressionNode {
         Some(ret_type)
     }
 
+    /// What the method asserts about the arguments, with templates inferred from them
+    pub(crate) fn get_assertions(
+        &self,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) -> Vec<AssertionData> {
+        let Some((_class_name, method_data)) = self.get_method_data(state) else {
+            return vec![];
+        };
+        if method_data.assertions.is_empty() {
+            return vec![];
+        }
+        let argument_types = self.arguments.get_argument_types(state, emitter);
+        get_call_site_assertions(
+            &method_data.assertions,
+            &method_data.arguments,
+            &method_data.template_data,
+            &argument_types,
+        )
+    }
+
     pub fn get_method_symbol(&self, state: &mut AnalysisState) -> Option<SymbolMethod> {
         // FIXME should this check for existence, or just return symbol?
 
@@ -178,9 +240,47 @@ impl ScopedCallExpressionNode {
 
         let locked_cdata = state.symbol_data.get_class(&cname)?;
 
-        let cdata = locked_cdata.read().ok()?;
+        let cdata = locked_cdata.read_or_recover();
         cdata.get_method(&mname, state.symbol_data.clone())?;
 
         Some(SymbolMethod::new(mname, SymbolClass::new_from_cname(cname)))
     }
 }
+
+impl ThirdPassAnalyzeableNode for ScopedCallExpressionNode {
+    fn analyze_third_pass(
+        &self,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+        path: &[AnyNodeRef],
+    ) -> bool {
+        if let Some(class_name) = self.get_class_name(state) {
+            let method_name = self.get_method_name(state, &VoidEmitter::new());
+            let declared_in = self
+                .get_method_data(state)
+                .map(|(_, mdata)| mdata.declared_in);
+            let mut usage = state.symbol_data.usage.write_or_recover();
+            usage.mark_class(&class_name);
+            if let Some(method_name) = &method_name {
+                usage.mark_method(Some(&class_name), method_name);
+                if let Some(declared_in) = &declared_in {
+                    usage.mark_method(Some(declared_in), method_name);
+                }
+            }
+        }
+        if !self.analyze_third_pass_children(&self.as_any(), state, emitter, path) {
+            return false;
+        }
+        if let Some((class_name, mdata)) = self.get_method_data(state) {
+            self.arguments.check_nullable_arguments(
+                &Name::from(format!("{}::{}", class_name.get_fq_name(), mdata.name)),
+                &mdata.arguments,
+                state,
+                emitter,
+            );
+        }
+        let assertions = self.get_assertions(state, &VoidEmitter::new());
+        harden_scope_with_assertions(&assertions, &self.arguments, state, emitter);
+        true
+    }
+}
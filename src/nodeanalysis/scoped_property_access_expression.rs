@@ -1,12 +1,21 @@
+use crate::locks::RecoverableLock;
+use std::{
+    os::unix::prelude::OsStrExt,
+    sync::{Arc, RwLock},
+};
+
 use crate::{
     analysis::state::AnalysisState,
     autonodes::{
         any::AnyNodeRef,
         scoped_property_access_expression::{
             ScopedPropertyAccessExpressionName, ScopedPropertyAccessExpressionNode,
+            ScopedPropertyAccessExpressionScope,
         },
     },
     issue::IssueEmitter,
+    symboldata::class::{ClassName, ClassType, PropertyData},
+    symbols::Name,
     types::union::PHPType,
     value::PHPValue,
 };
@@ -15,8 +24,79 @@ use super::analysis::ThirdPassAnalyzeableNode;
 use crate::autotree::NodeAccess;
 
 impl ScopedPropertyAccessExpressionNode {
-    pub fn read_from(&self, _state: &mut AnalysisState, _emitter: &dyn IssueEmitter) {
-        //FIXME mark reading from static class vars
+    pub fn read_from(&self, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
+        if let Some(property_data) = self.get_mut_property_data(state) {
+            property_data.write_or_recover().read_from += 1;
+        } else if let Some(property_name) = self.get_property_name() {
+            state
+                .symbol_data
+                .usage
+                .write_or_recover()
+                .mark_untyped_property(&property_name);
+        }
+
+        self.scope.read_from(state, emitter);
+    }
+
+    /// The name of the property, without the `$`
+    pub fn get_property_name(&self) -> Option<Name> {
+        match &*self.name {
+            ScopedPropertyAccessExpressionName::VariableName(vn) => Some(vn.get_variable_name()),
+            _ => None,
+        }
+    }
+
+    pub fn get_class_name(&self, state: &mut AnalysisState) -> Option<ClassName> {
+        match &*self.scope {
+            ScopedPropertyAccessExpressionScope::Name(name) => {
+                Some(ClassName::new_with_analysis_state(&name.get_name(), state))
+            }
+            ScopedPropertyAccessExpressionScope::QualifiedName(qname) => Some(
+                ClassName::new_with_names(qname.get_name(), qname.get_fq_name(state)),
+            ),
+            ScopedPropertyAccessExpressionScope::RelativeScope(rs) => {
+                let class_name = state.in_class.as_ref()?.get_name();
+                match rs.get_raw().to_ascii_lowercase().as_bytes() {
+                    b"self" | b"static" => Some(class_name),
+                    b"parent" => {
+                        let class_data = state.symbol_data.get_class(&class_name)?;
+                        let unlocked = class_data.read_or_recover();
+                        match &*unlocked {
+                            ClassType::Class(c) => c.base_class_name.clone(),
+                            _ => None,
+                        }
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// The declaration of the static property, in the class or one of its parents
+    pub fn get_mut_property_data(
+        &self,
+        state: &mut AnalysisState,
+    ) -> Option<Arc<RwLock<PropertyData>>> {
+        let property_name = self.get_property_name()?;
+        let mut class_name = self.get_class_name(state)?;
+        let mut seen = vec![];
+        loop {
+            let lc_fq_name = class_name.get_fq_name().to_ascii_lowercase();
+            if seen.contains(&lc_fq_name) {
+                return None;
+            }
+            seen.push(lc_fq_name);
+            let class_data_handle = state.symbol_data.get_class(&class_name)?;
+            let cdata = class_data_handle.read_or_recover();
+            let ClassType::Class(c) = &*cdata else {
+                return None;
+            };
+            if let Some(property) = c.get_mut_property(&property_name, state) {
+                return Some(property);
+            }
+            class_name = c.base_class_name.clone()?;
+        }
     }
 
     pub fn get_utype(
@@ -1,3 +1,4 @@
+use crate::locks::RecoverableLock;
 use crate::{
     analysis::state::{AnalysisState, FunctionDataPointer},
     autonodes::{any::AnyNodeRef, simple_parameter::SimpleParameterNode},
@@ -63,8 +64,8 @@ impl SimpleParameterNode {
         let data = &func_state_ref.data.as_ref()?;
         let name = self.get_variable_name();
         let args = match data {
-            FunctionDataPointer::Method(m) => m.read().unwrap().arguments.clone(),
-            FunctionDataPointer::Function(f) => f.read().unwrap().arguments.clone(),
+            FunctionDataPointer::Method(m) => m.read_or_recover().arguments.clone(),
+            FunctionDataPointer::Function(f) => f.read_or_recover().arguments.clone(),
         };
 
         args.into_iter().find(|arg| arg.name == name)
@@ -104,9 +105,9 @@ impl ThirdPassAnalyzeableNode for SimpleParameterNode {
         let declared_type = self.get_utype(state, emitter);
         let default_value = self.get_default_value(state, emitter);
 
-        let mut write_scope = scope.write().expect("Sucess");
+        let mut write_scope = scope.write_or_recover();
         let write_ax = write_scope.get_or_create_var(var_name);
-        let mut var_data = write_ax.write().unwrap();
+        let mut var_data = write_ax.write_or_recover();
         var_data.referenced_ranges.push(self.range);
         var_data.php_declared_type = declared_type;
         var_data.is_argument = true;
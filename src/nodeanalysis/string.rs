@@ -39,8 +39,10 @@ impl StringNode {
 impl StringChildren {
     pub fn get_string_value(&self) -> Option<&[u8]> {
         match self {
-            StringChildren::Extra(_y) => todo!(),
-            StringChildren::EscapeSequence(_y) => todo!(),
+            StringChildren::Extra(_) => Some(&[]),
+            StringChildren::EscapeSequence(_) => {
+                crate::missing_none!("Value of escape sequence in string")
+            }
             StringChildren::StringValue(strval) => {
                 let len = strval.raw.len();
                 if len < 2 {
@@ -1,17 +1,31 @@
+use crate::locks::RecoverableLock;
+use std::sync::Arc;
+
 use crate::{
     analysis::state::AnalysisState,
     autonodes::{
         any::AnyNodeRef,
         subscript_expression::{SubscriptExpressionDereferenceable, SubscriptExpressionNode},
     },
-    issue::{Issue, IssueEmitter},
-    types::union::{DiscreteType, DiscretlyAccessedType, PHPType, UnionType},
+    issue::{Issue, IssueEmitter, VoidEmitter},
+    symboldata::SymbolData,
+    symbols::FullyQualifiedName,
+    types::{
+        type_operators::TypeOperator,
+        union::{
+            DiscreteType, DiscretlyAccessedType, PHPType, ShapeType, ShapeTypeKey, SpecialType,
+            UnionType,
+        },
+    },
     value::PHPValue,
 };
 
 use crate::autotree::NodeAccess;
 
-use super::analysis::ThirdPassAnalyzeableNode;
+use super::{
+    analysis::ThirdPassAnalyzeableNode,
+    lang::{get_access_context, AccessContext},
+};
 
 impl SubscriptExpressionNode {
     pub fn get_utype(
@@ -42,6 +56,12 @@ impl SubscriptExpressionNode {
             return None;
         }
 
+        // Literal keys, like in `$row['id']`, are looked up in shapes
+        let offset: PHPType = match self.get_shape_key(state, emitter) {
+            Some(key) => key.get_literal_type().into(),
+            None => index_type.clone(),
+        };
+
         let mut ret_type = UnionType::new();
 
         for datype in array_type.as_discrete_variants() {
@@ -77,6 +97,18 @@ impl SubscriptExpressionNode {
                         // This should emit
                         crate::missing!("Emit something when attempting array lookup of Int");
                     }
+                    DiscreteType::Shape(_)
+                    | DiscreteType::Vector(_)
+                    | DiscreteType::List(_)
+                    | DiscreteType::NonEmptyList(_)
+                    | DiscreteType::HashMap(_, _)
+                    | DiscreteType::NonEmptyArray(_, _) => {
+                        // Keys which a shape doesn't have gives us nothing
+                        let element_type =
+                            TypeOperator::OffsetAccess(dtype.clone().into(), offset.clone())
+                                .evaluate()?;
+                        ret_type.append(element_type);
+                    }
                     _ => crate::missing!(
                         "subscript.get_utype(..) what get's when looking up in {:?} with a {:?}",
                         dtype,
@@ -112,6 +144,15 @@ impl SubscriptExpressionNode {
             .and_then(|i| i.get_php_value(state, emitter))
     }
 
+    /// The key of lookups with a known index, like `$row['id']`
+    pub fn get_shape_key(
+        &self,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) -> Option<ShapeTypeKey> {
+        ShapeTypeKey::from_php_value(&self.get_key_value(state, emitter)?)
+    }
+
     pub fn get_php_value(
         &self,
         state: &mut AnalysisState,
@@ -137,20 +178,283 @@ impl SubscriptExpressionNode {
         &self,
         state: &mut crate::analysis::state::AnalysisState,
         emitter: &dyn IssueEmitter,
-        _val_type: Option<PHPType>,
-        _value: Option<PHPValue>,
+        val_type: Option<PHPType>,
+        value: Option<PHPValue>,
     ) {
-        // FIXME determine have this should be done...
-        self.dereferenceable.write_to(state, emitter, None, None);
-
-        if self.get_key_value(state, emitter).is_some() {
-            crate::missing!("write_to subscript_expression_node with known index needs more logic");
-        } else {
+        let (Some(key), Some(key_value)) = (
+            self.get_shape_key(state, emitter),
+            self.get_key_value(state, emitter),
+        ) else {
+            // FIXME determine have this should be done...
+            self.dereferenceable.write_to(state, emitter, None, None);
             crate::missing!(
                 "write_to subscript_expression_node with unknown index needs more logic"
             );
+            return;
+        };
+        let array_type = self.dereferenceable.get_utype(state, emitter);
+        let array_value = self.dereferenceable.get_php_value(state, emitter);
+
+        let val_type = val_type.unwrap_or_else(|| DiscreteType::Unknown.into());
+        let written_type = get_written_array_type(array_type, array_value.as_ref(), key, val_type);
+        let written_value = match (array_value, value) {
+            (Some(PHPValue::Array(array)), Some(value)) => array
+                .with_value_by_key(key_value, value)
+                .map(PHPValue::Array),
+            _ => None,
+        };
+        self.dereferenceable
+            .write_to(state, emitter, written_type, written_value);
+    }
+
+    /// Emits if the array can be `null`, which reading from gives a warning
+    fn check_nullable_array(&self, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
+        let Some(array_type) = self.dereferenceable.get_utype(state, &VoidEmitter::new()) else {
+            return;
+        };
+        if array_type.can_be_null() {
+            emitter.emit(Issue::ArrayAccessOnNullableType(
+                self.dereferenceable.pos(state),
+                array_type,
+            ));
+        }
+    }
+
+    ///
+    /// Emits reads of keys the array doesn't have, or isn't known to have. Keys missing from
+    /// every shape the array can have are unknown, while optional keys, keys outside of
+    /// unsealed shapes, and keys in hashmaps might be undefined
+    ///
+    fn check_key_existence(&self, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
+        let Some(array_type) = self.dereferenceable.get_utype(state, &VoidEmitter::new()) else {
+            return;
+        };
+        let key = self.get_shape_key(state, &VoidEmitter::new());
+        if let (Some(key), SubscriptExpressionDereferenceable::VariableName(vn)) =
+            (&key, &*self.dereferenceable)
+        {
+            let checked = vn
+                .get_var_data(state)
+                .is_some_and(|data| data.read_or_recover().checked_keys.contains(key));
+            if checked {
+                return;
+            }
+        }
+        let variants = array_type.as_discrete_variants();
+        let mut lacking = 0;
+        let mut possibly_lacking = false;
+        for variant in &variants {
+            match variant {
+                DiscretlyAccessedType::Discrete(DiscreteType::Shape(shape)) => {
+                    // Without a known key, we can't tell
+                    match key.as_ref().map(|key| shape.has_key(key)) {
+                        Some(Some(false)) => lacking += 1,
+                        Some(None) => possibly_lacking = true,
+                        Some(Some(true)) | None => (),
+                    }
+                }
+                // Like for shapes, keys which aren't known can't be told to be lacking
+                DiscretlyAccessedType::Discrete(DiscreteType::HashMap(_, _)) => {
+                    possibly_lacking |= key.is_some()
+                }
+                _ => (),
+            }
+        }
+        match key {
+            Some(key) if lacking > 0 && lacking == variants.len() => {
+                emitter.emit(Issue::UnknownArrayShapeKey(
+                    self.pos(state),
+                    key,
+                    array_type,
+                ));
+            }
+            _ if possibly_lacking || lacking > 0 => {
+                let offset_type = self
+                    .get_offset_type(state)
+                    .unwrap_or_else(|| DiscreteType::ArrayKey.into());
+                emitter.emit(Issue::PossiblyUndefinedArrayKey(
+                    self.pos(state),
+                    offset_type,
+                    array_type,
+                ));
+            }
+            _ => (),
+        }
+    }
+
+    ///
+    /// Emits lookups which can't work on the type of the array: string keys on lists,
+    /// offsets which can't be integers on strings, and subscripting values which aren't
+    /// arrays at all
+    ///
+    fn check_offset_types(&self, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
+        let Some(array_type) = self.dereferenceable.get_utype(state, &VoidEmitter::new()) else {
+            return;
+        };
+        let mut is_list = false;
+        let mut is_string = false;
+        let mut is_non_array = false;
+        for variant in array_type.as_discrete_variants() {
+            let DiscretlyAccessedType::Discrete(dtype) = variant else {
+                continue;
+            };
+            match dtype {
+                DiscreteType::List(_) | DiscreteType::NonEmptyList(_) => is_list = true,
+                DiscreteType::String
+                | DiscreteType::StringLiteral(_)
+                | DiscreteType::Special(SpecialType::NonEmptyString)
+                | DiscreteType::Special(SpecialType::NumericString) => is_string = true,
+                dtype if !is_subscriptable(&dtype, &state.symbol_data) => is_non_array = true,
+                _ => (),
+            }
+        }
+        if is_non_array {
+            emitter.emit(Issue::ArrayAccessOnNonArrayType(
+                self.dereferenceable.pos(state),
+                array_type.clone(),
+            ));
+        }
+        let Some(offset_type) = self.get_offset_type(state) else {
+            return;
+        };
+        let is_string_key = matches!(
+            offset_type.single_type(),
+            Some(DiscreteType::String | DiscreteType::StringLiteral(_))
+        );
+        if is_list && is_string_key {
+            emitter.emit(Issue::StringKeyOnList(self.pos(state), array_type));
+        }
+        let offset_variants = offset_type.as_discrete_variants();
+        let can_be_integer = offset_variants.is_empty()
+            || offset_variants.iter().any(|variant| match variant {
+                DiscretlyAccessedType::Discrete(dtype) => may_be_integer_offset(dtype),
+                DiscretlyAccessedType::Intersection(_) => true,
+            });
+        if is_string && !can_be_integer {
+            let pos = match &self.index {
+                Some(index) => index.pos(state),
+                None => self.pos(state),
+            };
+            emitter.emit(Issue::NonIntegerStringOffset(pos, offset_type));
+        }
+    }
+
+    /// Type of the offset, using the literal key type when the key is known, like in `$row['id']`
+    fn get_offset_type(&self, state: &mut AnalysisState) -> Option<PHPType> {
+        if let Some(key) = self.get_shape_key(state, &VoidEmitter::new()) {
+            return Some(key.get_literal_type().into());
+        }
+        self.index.as_ref()?.get_utype(state, &VoidEmitter::new())
+    }
+}
+
+/// Whether values of the type can be subscripted, like arrays, strings and `ArrayAccess` objects
+fn is_subscriptable(dtype: &DiscreteType, symbol_data: &Arc<SymbolData>) -> bool {
+    match dtype {
+        DiscreteType::Int
+        | DiscreteType::IntLiteral(_)
+        | DiscreteType::IntRange(_, _)
+        | DiscreteType::Float
+        | DiscreteType::Bool
+        | DiscreteType::True
+        | DiscreteType::False
+        | DiscreteType::Resource => false,
+        DiscreteType::Named(_, fq_name) => {
+            // Classes we don't know of might implement `ArrayAccess`
+            let Some(class_handle) = symbol_data.get_class(&fq_name.into()) else {
+                return true;
+            };
+            let cdata = class_handle.read_or_recover();
+            // `SimpleXMLElement` gives access to attributes without implementing `ArrayAccess`
+            ["\\ArrayAccess", "\\SimpleXMLElement"]
+                .iter()
+                .any(|fq_name| {
+                    cdata.instanceof(
+                        &FullyQualifiedName::from(*fq_name).into(),
+                        symbol_data.clone(),
+                    )
+                })
+        }
+        DiscreteType::Generic(base_type, _) => is_subscriptable(base_type, symbol_data),
+        _ => true,
+    }
+}
+
+/// Whether an offset of the type might be usable as an integer string offset
+fn may_be_integer_offset(dtype: &DiscreteType) -> bool {
+    match dtype {
+        DiscreteType::StringLiteral(s) => s.trim().parse::<i64>().is_ok(),
+        DiscreteType::NULL
+        | DiscreteType::Void
+        | DiscreteType::Float
+        | DiscreteType::Bool
+        | DiscreteType::True
+        | DiscreteType::False
+        | DiscreteType::Resource
+        | DiscreteType::Array
+        | DiscreteType::Object
+        | DiscreteType::Callable
+        | DiscreteType::TypedCallable(_, _)
+        | DiscreteType::Vector(_)
+        | DiscreteType::HashMap(_, _)
+        | DiscreteType::Shape(_)
+        | DiscreteType::List(_)
+        | DiscreteType::NonEmptyList(_)
+        | DiscreteType::NonEmptyArray(_, _)
+        | DiscreteType::Named(_, _)
+        | DiscreteType::Generic(_, _) => false,
+        _ => true,
+    }
+}
+
+///
+/// The type of an array of type `array_type` after a value of type `val_type` is written to
+/// `key`. Arrays which are known, like after `$row = [];`, becomes sealed shapes
+///
+fn get_written_array_type(
+    array_type: Option<PHPType>,
+    array_value: Option<&PHPValue>,
+    key: ShapeTypeKey,
+    val_type: PHPType,
+) -> Option<PHPType> {
+    if let Some(PHPValue::Array(array)) = array_value {
+        let mut shape = ShapeType::new();
+        for (entry_key, entry_value) in array.entries() {
+            shape = shape.with_key_type(
+                ShapeTypeKey::from_php_value(&entry_key)?,
+                entry_value.get_utype()?,
+            );
+        }
+        return Some(DiscreteType::Shape(shape.with_key_type(key, val_type)).into());
+    }
+    let unsealed = || {
+        DiscreteType::Shape(ShapeType::new_unsealed().with_key_type(key.clone(), val_type.clone()))
+    };
+    let Some(array_type) = array_type else {
+        return Some(unsealed().into());
+    };
+
+    let mut written = UnionType::new();
+    for variant in array_type.as_discrete_variants() {
+        match &variant {
+            DiscretlyAccessedType::Discrete(DiscreteType::Shape(shape)) => written.append(
+                DiscreteType::Shape(shape.with_key_type(key.clone(), val_type.clone())),
+            ),
+            // Writing to null creates a new array
+            DiscretlyAccessedType::Discrete(DiscreteType::NULL) => written.append(
+                DiscreteType::Shape(ShapeType::new().with_key_type(key.clone(), val_type.clone())),
+            ),
+            DiscretlyAccessedType::Discrete(
+                DiscreteType::Array | DiscreteType::Mixed | DiscreteType::Unknown,
+            ) => written.append(unsealed()),
+            _ => written.append(&variant),
         }
     }
+    if written.is_empty() {
+        None
+    } else {
+        Some(written.into())
+    }
 }
 
 impl SubscriptExpressionDereferenceable {
@@ -238,6 +542,17 @@ impl ThirdPassAnalyzeableNode for SubscriptExpressionNode {
         if let Some(i) = self.index.as_ref() {
             i.read_from(state, emitter)
         }
-        self.analyze_third_pass_children(&self.as_any(), state, emitter, path)
+        if !self.analyze_third_pass_children(&self.as_any(), state, emitter, path) {
+            return false;
+        }
+        let context = get_access_context(self.range, state, path);
+        if context != AccessContext::Checked {
+            self.check_offset_types(state, emitter);
+        }
+        if context == AccessContext::Read {
+            self.check_nullable_array(state, emitter);
+            self.check_key_existence(state, emitter);
+        }
+        true
     }
 }
@@ -1,9 +1,10 @@
+use crate::locks::RecoverableLock;
 use std::sync::{Arc, RwLock};
 
 use crate::{
     analysis::state::{AnalysisState, ClassState},
     autonodes::{any::AnyNodeRef, trait_declaration::TraitDeclarationNode},
-    issue::IssueEmitter,
+    issue::{Issue, IssueEmitter},
     symboldata::{
         class::{ClassName, ClassType, TraitData},
         FileLocation,
@@ -71,12 +72,15 @@ impl FirstPassAnalyzeableNode for TraitDeclarationNode {
 
         let symbol_data = state.symbol_data.get_or_create_class(&trait_name);
         {
-            let mut unlocked = symbol_data.write().unwrap();
+            let mut unlocked = symbol_data.write_or_recover();
             match *unlocked {
                 ClassType::None => {
                     *unlocked = ClassType::Trait(trait_data);
                 }
-                _ => panic!("NOE DUPS?"),
+                _ => emitter.emit(Issue::DuplicateClass(
+                    self.name.pos(state),
+                    trait_name.get_fq_name().clone(),
+                )),
             }
         }
 
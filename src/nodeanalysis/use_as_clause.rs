@@ -1,5 +1,9 @@
 use crate::{
-    analysis::state::AnalysisState, autonodes::use_as_clause::UseAsClauseNode, issue::IssueEmitter,
+    analysis::state::AnalysisState,
+    autonodes::use_as_clause::{UseAsClauseChildren, UseAsClauseNode},
+    autotree::NodeAccess,
+    issue::IssueEmitter,
+    symboldata::{traits::TraitAlias, FileLocation},
     types::union::PHPType,
 };
 
@@ -23,4 +27,34 @@ impl UseAsClauseNode {
     ) -> Option<PHPType> {
         crate::missing_none!("{}.get_utype(..)", self.kind())
     }
+
+    /// `[Trait::]method as [visibility] [alias]`
+    pub fn get_trait_alias(&self, state: &mut AnalysisState) -> Option<TraitAlias> {
+        let mut trait_name = None;
+        let mut method_name = None;
+        let mut alias = None;
+        let mut visibility = None;
+        for child in &self.children {
+            match &**child {
+                UseAsClauseChildren::ClassConstantAccessExpression(cc) => {
+                    trait_name = cc.get_class_names(state)?.into_iter().next();
+                    method_name = Some(cc.constant.get_name());
+                }
+                // The first name is the method, the second is the alias
+                UseAsClauseChildren::Name(n) if method_name.is_none() => {
+                    method_name = Some(n.get_name())
+                }
+                UseAsClauseChildren::Name(n) => alias = Some(n.get_name()),
+                UseAsClauseChildren::VisibilityModifier(v) => visibility = Some(v.get_visibility()),
+                UseAsClauseChildren::Extra(_) => (),
+            }
+        }
+        Some(TraitAlias {
+            trait_name,
+            method_name: method_name?,
+            alias,
+            visibility,
+            position: FileLocation::new(self.pos(state)),
+        })
+    }
 }
@@ -1,8 +1,23 @@
+use crate::locks::RecoverableLock;
 use crate::{
-    analysis::state::AnalysisState, autonodes::use_declaration::UseDeclarationNode,
-    issue::IssueEmitter, types::union::PHPType,
+    analysis::state::AnalysisState,
+    autonodes::{
+        use_declaration::{UseDeclarationChildren, UseDeclarationNode},
+        use_list::UseListChildren,
+    },
+    autotree::NodeAccess,
+    issue::{Issue, IssueEmitter},
+    symboldata::{
+        class::{ClassName, ClassType},
+        traits::{TraitImport, TraitUses},
+        FileLocation,
+    },
+    symbols::FullyQualifiedName,
+    types::union::PHPType,
 };
 
+use super::analysis::{FirstPassAnalyzeableNode, SecondPassAnalyzeableNode};
+
 impl UseDeclarationNode {
     pub fn read_from(&self, _state: &mut AnalysisState, _emitter: &dyn IssueEmitter) {
         crate::missing!("{}.read_from(..)", self.kind());
@@ -23,4 +38,122 @@ impl UseDeclarationNode {
     ) -> Option<PHPType> {
         crate::missing_none!("{}.get_utype(..)", self.kind())
     }
+
+    /// The traits imported, and the `insteadof`/`as`-rules of this declaration
+    pub fn get_trait_uses(&self, state: &mut AnalysisState) -> TraitUses {
+        let mut uses = TraitUses::default();
+        for child in &self.children {
+            match &**child {
+                UseDeclarationChildren::Name(n) => uses.imports.push(TraitImport {
+                    trait_name: ClassName::new_with_analysis_state(&n.get_name(), state),
+                    position: FileLocation::new(n.pos(state)),
+                }),
+                UseDeclarationChildren::QualifiedName(qn) => uses.imports.push(TraitImport {
+                    trait_name: ClassName::new_with_fq_name(qn.get_fq_name(state)),
+                    position: FileLocation::new(qn.pos(state)),
+                }),
+                UseDeclarationChildren::UseList(list) => {
+                    for rule in &list.children {
+                        match &**rule {
+                            UseListChildren::UseAsClause(a) => {
+                                if let Some(alias) = a.get_trait_alias(state) {
+                                    uses.aliases.push(alias);
+                                }
+                            }
+                            UseListChildren::UseInsteadOfClause(i) => {
+                                if let Some(insteadof) = i.get_trait_insteadof(state) {
+                                    uses.insteadofs.push(insteadof);
+                                }
+                            }
+                            UseListChildren::Extra(_) => (),
+                        }
+                    }
+                }
+                UseDeclarationChildren::Extra(_) => (),
+            }
+        }
+        uses
+    }
+}
+
+impl FirstPassAnalyzeableNode for UseDeclarationNode {
+    fn analyze_first_pass(&self, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
+        let Some(class_state) = &state.in_class else {
+            emitter.emit(Issue::ParseAnomaly(
+                self.pos(state),
+                "Trait use-declaration outside of class".into(),
+            ));
+            return;
+        };
+        let class_data = class_state.get_data();
+        let uses = self.get_trait_uses(state);
+
+        let mut unlocked = class_data.write_or_recover();
+        let traits = match &mut *unlocked {
+            ClassType::Class(c) => &mut c.traits,
+            ClassType::Trait(t) => &mut t.traits,
+            ClassType::Interface(_) | ClassType::None => {
+                emitter.emit(Issue::ParseAnomaly(
+                    self.pos(state),
+                    "Trait use-declaration only allowed in classes and traits".into(),
+                ));
+                return;
+            }
+        };
+        traits.extend(uses);
+    }
+}
+
+impl SecondPassAnalyzeableNode for UseDeclarationNode {
+    fn analyze_second_pass(&self, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
+        let uses = self.get_trait_uses(state);
+        let symbol_data = state.symbol_data.clone();
+
+        for import in &uses.imports {
+            if symbol_data.get_trait(&import.trait_name).is_none() {
+                emitter.emit(Issue::UnknownClass(
+                    import.position.clone().into(),
+                    import.trait_name.get_fq_name().clone(),
+                ));
+            }
+        }
+
+        let has_method = |trait_name: &ClassName, method_name| {
+            symbol_data
+                .get_trait(trait_name)
+                .map(|t| t.get_method(method_name, symbol_data.clone()).is_some())
+        };
+
+        for rule in &uses.insteadofs {
+            if let Some(false) = has_method(&rule.trait_name, &rule.method_name) {
+                emitter.emit(Issue::UnknownMethod(
+                    rule.position.clone().into(),
+                    rule.trait_name.get_fq_name().clone(),
+                    rule.method_name.clone(),
+                ));
+            }
+        }
+
+        for rule in &uses.aliases {
+            let found = if let Some(trait_name) = &rule.trait_name {
+                has_method(trait_name, &rule.method_name)
+            } else {
+                Some(
+                    uses.imports
+                        .iter()
+                        .any(|i| has_method(&i.trait_name, &rule.method_name) != Some(false)),
+                )
+            };
+            if let Some(false) = found {
+                emitter.emit(Issue::UnknownMethod(
+                    rule.position.clone().into(),
+                    rule.trait_name
+                        .as_ref()
+                        .map(|t| t.get_fq_name().clone())
+                        .unwrap_or_else(FullyQualifiedName::new),
+                    rule.method_name.clone(),
+                ));
+            }
+        }
+    }
 }
@@ -1,6 +1,10 @@
 use crate::{
-    analysis::state::AnalysisState, autonodes::use_instead_of_clause::UseInsteadOfClauseNode,
-    issue::IssueEmitter, types::union::PHPType,
+    analysis::state::AnalysisState,
+    autonodes::use_instead_of_clause::{UseInsteadOfClauseChildren, UseInsteadOfClauseNode},
+    autotree::NodeAccess,
+    issue::IssueEmitter,
+    symboldata::{class::ClassName, traits::TraitInsteadOf, FileLocation},
+    types::union::PHPType,
 };
 
 impl UseInsteadOfClauseNode {
@@ -23,4 +27,29 @@ impl UseInsteadOfClauseNode {
     ) -> Option<PHPType> {
         crate::missing_none!("{}.get_utype(..)", self.kind())
     }
+
+    /// `Trait::method insteadof OtherTrait`
+    pub fn get_trait_insteadof(&self, state: &mut AnalysisState) -> Option<TraitInsteadOf> {
+        let mut trait_name = None;
+        let mut method_name = None;
+        let mut instead_of = vec![];
+        for child in &self.children {
+            match &**child {
+                UseInsteadOfClauseChildren::ClassConstantAccessExpression(cc) => {
+                    trait_name = cc.get_class_names(state)?.into_iter().next();
+                    method_name = Some(cc.constant.get_name());
+                }
+                UseInsteadOfClauseChildren::Name(n) => {
+                    instead_of.push(ClassName::new_with_analysis_state(&n.get_name(), state))
+                }
+                UseInsteadOfClauseChildren::Extra(_) => (),
+            }
+        }
+        Some(TraitInsteadOf {
+            trait_name: trait_name?,
+            method_name: method_name?,
+            instead_of,
+            position: FileLocation::new(self.pos(state)),
+        })
+    }
 }
@@ -1,3 +1,4 @@
+use crate::locks::RecoverableLock;
 use std::sync::{Arc, RwLock};
 
 use crate::types::union::PHPType;
@@ -30,7 +31,7 @@ impl VariableNameNode {
         } else {
             return None;
         };
-        let data = lock.read().unwrap();
+        let data = lock.read_or_recover();
 
         let noe: Vec<_> = data
             .last_written_data
@@ -60,14 +61,14 @@ impl VariableNameNode {
     pub fn get_var_data(&self, state: &mut AnalysisState) -> Option<Arc<RwLock<VarData>>> {
         let var_name = self.get_variable_name();
         let scope = state.current_scope();
-        let read = scope.read().unwrap();
+        let read = scope.read_or_recover();
         read.get_var(&var_name)
     }
 
     fn get_or_create_var_data(&self, state: &mut AnalysisState) -> Arc<RwLock<VarData>> {
         let var_name = self.get_variable_name();
         let scope = state.current_scope();
-        let mut write = scope.write().unwrap();
+        let mut write = scope.write_or_recover();
         write.get_or_create_var(var_name).clone()
     }
 
@@ -77,7 +78,7 @@ impl VariableNameNode {
     pub fn check_can_be_read_from(&self, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
         let var_name = self.get_variable_name();
         let readable = if let Some(v) = self.get_var_data(state) {
-            let data = v.read().unwrap();
+            let data = v.read_or_recover();
             if data.is_partial {
                 emitter.emit(Issue::VariableNotInitializedInAllBranhces(
                     self.pos(state),
@@ -88,7 +89,7 @@ impl VariableNameNode {
         } else {
             false
         };
-        if !readable {
+        if !readable && !state.is_defined_by_includer(&var_name) {
             emitter.emit(Issue::UnknownVariable(self.pos(state), var_name));
         }
     }
@@ -99,7 +100,7 @@ impl VariableNameNode {
         } else {
             return;
         };
-        let mut data = lock.write().unwrap();
+        let mut data = lock.write_or_recover();
         data.read_from += 1;
     }
 
@@ -109,7 +110,7 @@ impl VariableNameNode {
         _emitter: &dyn IssueEmitter,
     ) -> Option<crate::value::PHPValue> {
         let val_data_handle = self.get_var_data(state)?;
-        let val_data = val_data_handle.read().unwrap();
+        let val_data = val_data_handle.read_or_recover();
         let data_iter = val_data.all_written_data.iter();
         let (_, data) = data_iter.as_ref().last()?;
         data.clone()
@@ -124,7 +125,7 @@ impl VariableNameNode {
     ) {
         let val_data = self.get_or_create_var_data(state);
         {
-            let mut wr_val_data = val_data.write().unwrap();
+            let mut wr_val_data = val_data.write_or_recover();
 
             let written_type = val_type.unwrap_or_else(|| DiscreteType::Unknown.into());
             let written_data = if state.in_conditional_branch {
@@ -146,9 +147,9 @@ impl ThirdPassAnalyzeableNode for VariableNameNode {
     ) -> bool {
         let vname = self.get_variable_name();
         let curr_scope = state.current_scope();
-        let mut scope = curr_scope.write().unwrap();
+        let mut scope = curr_scope.write_or_recover();
         let var_ref = scope.get_or_create_var(vname);
-        let mut var_data = var_ref.write().unwrap();
+        let mut var_data = var_ref.write_or_recover();
         var_data.referenced_ranges.push(self.range);
         true
     }
@@ -161,16 +162,18 @@ impl AnalysisOfType for VariableNameNode {
         emitter: &dyn IssueEmitter,
     ) -> Option<PHPType> {
         if state.pass == 1 {
-            todo!("Hvorfor kommer vi hit på pass 1");
+            return crate::missing_none!("Hvorfor kommer vi hit på pass 1");
         }
         let var_name = self.child.get_name();
         let scope = state.current_scope();
 
-        let read = scope.read().unwrap();
+        let read = scope.read_or_recover();
         if let Some(var_data) = read.vars.get(&var_name) {
-            var_data.read().unwrap().get_declared_type()
+            var_data.read_or_recover().get_declared_type()
         } else {
-            emitter.emit(Issue::UnknownVariable(self.pos(state), var_name.clone()));
+            if !state.is_defined_by_includer(&var_name) {
+                emitter.emit(Issue::UnknownVariable(self.pos(state), var_name.clone()));
+            }
             None
         }
     }
@@ -181,11 +184,11 @@ impl AnalysisOfType for VariableNameNode {
         _emitter: &dyn IssueEmitter,
     ) -> Option<PHPType> {
         let scope_handle = state.current_scope();
-        let scope = scope_handle.read().ok()?;
+        let scope = scope_handle.read_or_recover();
 
         let var_name = self.get_variable_name();
         let var_data_handle = scope.get_var(&var_name)?;
-        let var_data = var_data_handle.read().ok()?;
+        let var_data = var_data_handle.read_or_recover();
         let noe: Vec<_> = var_data
             .last_written_data
             .iter()
@@ -1,3 +1,4 @@
+use crate::locks::RecoverableLock;
 use crate::{
     analysis::state::{AnalysisState, FunctionDataPointer},
     autonodes::variadic_parameter::VariadicParameterNode,
@@ -52,8 +53,8 @@ impl VariadicParameterNode {
         let data = &func_state_ref.data.as_ref()?;
         let name = self.get_variable_name();
         let args = match data {
-            FunctionDataPointer::Method(m) => m.read().unwrap().arguments.clone(),
-            FunctionDataPointer::Function(f) => f.read().unwrap().arguments.clone(),
+            FunctionDataPointer::Method(m) => m.read_or_recover().arguments.clone(),
+            FunctionDataPointer::Function(f) => f.read_or_recover().arguments.clone(),
         };
 
         for arg in args {
@@ -25,12 +25,13 @@ impl VisibilityModifierNode {
     }
 
     pub fn get_visibility(&self) -> ClassMemberVisibility {
-        match &self.raw[..] {
+        match &self.raw.to_ascii_lowercase()[..] {
             b"public" => ClassMemberVisibility::Public,
             b"private" => ClassMemberVisibility::Private,
             b"protected" => ClassMemberVisibility::Protected,
             _ => {
-                panic!("Unknown visibility: {:?}", self.get_raw());
+                crate::missing!("Unknown visibility: {:?}", self.get_raw());
+                ClassMemberVisibility::Public
             }
         }
     }
@@ -1,8 +1,17 @@
 use crate::{
-    analysis::state::AnalysisState, autonodes::while_statement::WhileStatementNode,
-    issue::IssueEmitter, types::union::PHPType,
+    analysis::{
+        hardening::BranchTypeHardening,
+        scope::{BranchSide, BranchableScope},
+        state::AnalysisState,
+    },
+    autonodes::{any::AnyNodeRef, while_statement::WhileStatementNode},
+    autotree::NodeAccess,
+    issue::IssueEmitter,
+    types::union::PHPType,
 };
 
+use super::analysis::ThirdPassAnalyzeableNode;
+
 impl WhileStatementNode {
     pub fn read_from(&self, _state: &mut AnalysisState, _emitter: &dyn IssueEmitter) {
         crate::missing!("{}.read_from(..)", self.kind());
@@ -24,3 +33,47 @@ impl WhileStatementNode {
         crate::missing_none!("{}.get_utype(..)", self.kind())
     }
 }
+
+impl ThirdPassAnalyzeableNode for WhileStatementNode {
+    fn analyze_third_pass(
+        &self,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+        path: &[AnyNodeRef],
+    ) -> bool {
+        let scope = state.current_scope();
+        self.condition.read_from(state, emitter);
+        if !self
+            .condition
+            .as_any()
+            .analyze_third_pass(state, emitter, path)
+        {
+            return false;
+        }
+
+        // The body is only entered when the condition holds
+        let branch = self
+            .condition
+            .branch_with_hardened_types_base_on_conditional_node(
+                scope.clone(),
+                BranchSide::TrueBranch,
+                state,
+            );
+        state.push_scope(branch);
+        let carry_on = self.body.as_any().analyze_third_pass(state, emitter, path);
+        let branch = state.pop_scope();
+        if !carry_on {
+            return false;
+        }
+
+        let exit_scope = self
+            .condition
+            .branch_with_hardened_types_base_on_conditional_node(
+                scope.clone(),
+                BranchSide::FalseBranch,
+                state,
+            );
+        scope.join(vec![branch, exit_scope], emitter);
+        true
+    }
+}
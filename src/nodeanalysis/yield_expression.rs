@@ -1,11 +1,28 @@
 use crate::{
-    analysis::state::AnalysisState, autonodes::yield_expression::YieldExpressionNode,
-    issue::IssueEmitter, types::union::PHPType,
+    analysis::state::AnalysisState,
+    autonodes::{
+        any::AnyNodeRef,
+        array_element_initializer::ArrayElementInitializerValue,
+        yield_expression::{YieldExpressionChildren, YieldExpressionNode},
+    },
+    issue::{Issue, IssueEmitter},
+    types::{
+        traversable::{
+            can_hold_generator, get_declared_yield_types, get_generator_return_type,
+            get_generator_send_type, get_key_type, get_value_type,
+        },
+        union::{DiscreteType, PHPType},
+    },
 };
 
+use super::analysis::ThirdPassAnalyzeableNode;
+use crate::autotree::NodeAccess;
+
 impl YieldExpressionNode {
-    pub fn read_from(&self, _state: &mut AnalysisState, _emitter: &dyn IssueEmitter) {
-        crate::missing!("{}.read_from(..)", self.kind());
+    pub fn read_from(&self, state: &mut AnalysisState, emitter: &dyn IssueEmitter) {
+        if let Some(child) = &self.child {
+            child.read_from(state, emitter);
+        }
     }
 
     pub fn get_php_value(
@@ -13,14 +30,169 @@ impl YieldExpressionNode {
         _state: &mut AnalysisState,
         _emitter: &dyn IssueEmitter,
     ) -> Option<crate::value::PHPValue> {
-        crate::missing_none!("{}.get_php_value(..)", self.kind())
+        // The value is whatever is sent into the generator, which is not known statically
+        None
     }
 
+    /// The type of the yield-expression itself. For `yield` it's the value sent into the
+    /// generator, for `yield from` it's the return-value of the inner generator
     pub fn get_utype(
         &self,
-        _state: &mut AnalysisState,
-        _emitter: &dyn IssueEmitter,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
     ) -> Option<PHPType> {
-        crate::missing_none!("{}.get_utype(..)", self.kind())
+        match self.child.as_deref() {
+            Some(YieldExpressionChildren::_Expression(inner)) => {
+                let inner_type = inner.get_utype(state, emitter)?;
+                get_generator_return_type(&inner_type)
+            }
+            _ => {
+                let declared_type = state.in_function_stack.last()?.get_declared_return_type();
+
+                declared_type
+                    .and_then(|t| get_generator_send_type(&t))
+                    .or_else(|| Some(DiscreteType::Mixed.into()))
+            }
+        }
+    }
+
+    /// Returns the key- and value-type yielded by this expression
+    fn get_yielded_types(
+        &self,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) -> (Option<PHPType>, Option<PHPType>) {
+        match self.child.as_deref() {
+            // `yield;`
+            None => (
+                Some(DiscreteType::Int.into()),
+                Some(DiscreteType::NULL.into()),
+            ),
+            Some(YieldExpressionChildren::ArrayElementInitializer(init)) => {
+                if init.spread.is_some() {
+                    return (None, None);
+                }
+                let key_type = if let Some(key) = &init.key {
+                    key.get_utype(state, emitter)
+                } else {
+                    Some(DiscreteType::Int.into())
+                };
+                let value_type = match init.value.as_deref() {
+                    Some(ArrayElementInitializerValue::_Expression(v)) => {
+                        v.get_utype(state, emitter)
+                    }
+                    _ => None,
+                };
+                (key_type, value_type)
+            }
+            // `yield from <expr>`
+            Some(YieldExpressionChildren::_Expression(inner)) => {
+                let Some(inner_type) = inner.get_utype(state, emitter) else {
+                    return (None, None);
+                };
+                let symbol_data = state.symbol_data.clone();
+                (
+                    get_key_type(&inner_type, symbol_data.clone()),
+                    get_value_type(&inner_type, symbol_data),
+                )
+            }
+            Some(YieldExpressionChildren::Extra(_)) => (None, None),
+        }
+    }
+
+    /// Checks the yielded types against the declared return-type of the function
+    fn check_yielded_types(
+        &self,
+        declared_type: &PHPType,
+        key_type: &Option<PHPType>,
+        value_type: &Option<PHPType>,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) {
+        if !can_hold_generator(declared_type) {
+            emitter.emit(Issue::IncompatibleGeneratorReturnType(
+                self.pos(state),
+                declared_type.clone(),
+            ));
+            return;
+        }
+        let Some((declared_key, declared_value)) = get_declared_yield_types(declared_type) else {
+            return;
+        };
+        for (provided, expected) in [(key_type, declared_key), (value_type, declared_value)] {
+            let Some(provided) = provided else {
+                continue;
+            };
+            if let Some(false) = provided.can_be_passed_strictly_as(&expected, &state.symbol_data) {
+                emitter.emit(Issue::IncompatibleYieldType(
+                    self.pos(state),
+                    expected,
+                    provided.clone(),
+                ));
+            }
+        }
+    }
+}
+
+///
+/// Whether `node` contains a `yield`, which makes it a generator if it's a function.
+/// Nested functions and classes are not searched
+///
+pub(crate) fn contains_yield(node: &AnyNodeRef) -> bool {
+    node.children_any().iter().any(|child| match child {
+        AnyNodeRef::YieldExpression(_) => true,
+        AnyNodeRef::FunctionDefinition(_)
+        | AnyNodeRef::MethodDeclaration(_)
+        | AnyNodeRef::AnonymousFunctionCreationExpression(_)
+        | AnyNodeRef::ArrowFunction(_)
+        | AnyNodeRef::ClassDeclaration(_) => false,
+        _ => contains_yield(child),
+    })
+}
+
+/// The function-like node closest to the end of `path`
+pub(crate) fn get_enclosing_function<'a, 'b>(
+    path: &'a [AnyNodeRef<'b>],
+) -> Option<&'a AnyNodeRef<'b>> {
+    path.iter().rev().find(|node| {
+        matches!(
+            node,
+            AnyNodeRef::FunctionDefinition(_)
+                | AnyNodeRef::MethodDeclaration(_)
+                | AnyNodeRef::AnonymousFunctionCreationExpression(_)
+                | AnyNodeRef::ArrowFunction(_)
+        )
+    })
+}
+
+impl ThirdPassAnalyzeableNode for YieldExpressionNode {
+    fn analyze_third_pass(
+        &self,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+        path: &[AnyNodeRef],
+    ) -> bool {
+        self.read_from(state, emitter);
+        let (key_type, value_type) = self.get_yielded_types(state, emitter);
+
+        // Arrow functions don't have a function state of their own
+        let declared_type =
+            if let Some(AnyNodeRef::ArrowFunction(arrow)) = get_enclosing_function(path) {
+                arrow.get_declared_return_type(state, emitter)
+            } else if let Some(func_state) = state.in_function_stack.last() {
+                func_state.add_yield(key_type.clone(), value_type.clone());
+                func_state.get_declared_return_type()
+            } else {
+                emitter.emit(Issue::ParseAnomaly(
+                    self.pos(state),
+                    "yield expression not in function".into(),
+                ));
+                None
+            };
+        if let Some(declared_type) = declared_type {
+            self.check_yielded_types(&declared_type, &key_type, &value_type, state, emitter);
+        }
+
+        self.analyze_third_pass_children(&self.as_any(), state, emitter, path)
     }
 }
@@ -1,7 +1,33 @@
-use crate::{types::union::DiscreteType, value::PHPValue, Range};
+use std::os::unix::ffi::OsStrExt;
+
+use crate::{
+    analysis::{
+        hardening::{
+            check_null, get_type_name_check, get_variable_argument, get_variable_node,
+            narrow_type_by_check, new_scope_with_type_check, new_scope_with_variable_type,
+            TypeCheck, TypeMatch, TypeNameFunction,
+        },
+        scope::{BranchSide, BranchableScope, Scope},
+        state::AnalysisState,
+    },
+    autonodes::{
+        _expression::_ExpressionNode,
+        _primary_expression::_PrimaryExpressionNode,
+        binary_expression::BinaryExpressionRight,
+        class_constant_access_expression::{
+            ClassConstantAccessExpressionClass, ClassConstantAccessExpressionNode,
+        },
+        variable_name::VariableNameNode,
+    },
+    issue::VoidEmitter,
+    symbols::FullyQualifiedName,
+    types::union::{DiscreteType, PHPType},
+    value::PHPValue,
+    Range,
+};
 
 use super::{
-    binary::{BinaryOperator, BinaryOperatorOperandAccess},
+    binary::{BinaryOperator, BinaryOperatorBranchTypeHardening, BinaryOperatorOperandAccess},
     operator::Operator,
 };
 #[derive(Clone, Debug)]
@@ -44,3 +70,191 @@ impl BinaryOperator for IdenticalOperator {
         Some(PHPValue::Boolean(bool_value))
     }
 }
+
+/// The operands of `===` which tell us something about the type of a variable
+enum IdenticalOperand<'a> {
+    Variable(&'a VariableNameNode),
+    /// `gettype($x)` or `get_debug_type($x)`
+    TypeName(&'a VariableNameNode, TypeNameFunction),
+    /// `$x::class` or `get_class($x)`
+    ClassName(&'a VariableNameNode),
+    /// `Foo::class`
+    ClassLiteral(FullyQualifiedName),
+    Value(PHPValue),
+}
+
+impl<'a> IdenticalOperand<'a> {
+    fn from_expression(expression: &'a _ExpressionNode, state: &mut AnalysisState) -> Option<Self> {
+        if let Some(variable) = get_variable_node(expression) {
+            return Some(Self::Variable(variable));
+        }
+        if let _ExpressionNode::_PrimaryExpression(p) = expression {
+            match &**p {
+                _PrimaryExpressionNode::FunctionCallExpression(call) => {
+                    let fq_name = call.get_fq_function_name(state, &VoidEmitter::new())?;
+                    let variable = get_variable_argument(call, 0);
+                    match fq_name.to_ascii_lowercase().to_os_string().as_bytes() {
+                        b"\\gettype" => {
+                            return variable.map(|v| Self::TypeName(v, TypeNameFunction::GetType))
+                        }
+                        b"\\get_debug_type" => {
+                            return variable
+                                .map(|v| Self::TypeName(v, TypeNameFunction::GetDebugType))
+                        }
+                        b"\\get_class" => return variable.map(Self::ClassName),
+                        _ => (),
+                    }
+                }
+                _PrimaryExpressionNode::ClassConstantAccessExpression(c) => {
+                    return Self::from_class_constant(c, state);
+                }
+                _ => (),
+            }
+        }
+        expression
+            .get_php_value(state, &VoidEmitter::new())
+            .map(Self::Value)
+    }
+
+    fn from_class_constant(
+        access: &'a ClassConstantAccessExpressionNode,
+        state: &mut AnalysisState,
+    ) -> Option<Self> {
+        if access.constant.get_name() != b"class" as &[u8] {
+            return access
+                .get_php_value(state, &VoidEmitter::new())
+                .map(Self::Value);
+        }
+        if let ClassConstantAccessExpressionClass::VariableName(v) = &*access.class {
+            return Some(Self::ClassName(v));
+        }
+        match &access.get_class_names(state)?[..] {
+            [class_name] => Some(Self::ClassLiteral(class_name.get_fq_name().clone())),
+            _ => None,
+        }
+    }
+
+    fn from_right(right: &'a BinaryExpressionRight, state: &mut AnalysisState) -> Option<Self> {
+        match right {
+            BinaryExpressionRight::_Expression(e) => Self::from_expression(e, state),
+            BinaryExpressionRight::VariableName(v) => Some(Self::Variable(v)),
+            _ => None,
+        }
+    }
+}
+
+fn check_true(dtype: &DiscreteType) -> TypeMatch {
+    match dtype {
+        DiscreteType::True => TypeMatch::Always,
+        DiscreteType::Bool | DiscreteType::Mixed | DiscreteType::Unknown | DiscreteType::Scalar => {
+            TypeMatch::NarrowsTo(DiscreteType::True.into())
+        }
+        DiscreteType::Template(_) | DiscreteType::Operator(_) => TypeMatch::Maybe,
+        _ => TypeMatch::Never,
+    }
+}
+
+fn check_false(dtype: &DiscreteType) -> TypeMatch {
+    match dtype {
+        DiscreteType::False => TypeMatch::Always,
+        DiscreteType::Bool | DiscreteType::Mixed | DiscreteType::Unknown | DiscreteType::Scalar => {
+            TypeMatch::NarrowsTo(DiscreteType::False.into())
+        }
+        DiscreteType::Template(_) | DiscreteType::Operator(_) => TypeMatch::Maybe,
+        _ => TypeMatch::Never,
+    }
+}
+
+/// Narrows the variable compared to a known value
+fn branch_on_value(
+    variable: &VariableNameNode,
+    value: &PHPValue,
+    scope: std::sync::Arc<std::sync::RwLock<Scope>>,
+    branch_side: BranchSide,
+    state: &mut AnalysisState,
+) -> Option<std::sync::Arc<std::sync::RwLock<Scope>>> {
+    let check: TypeCheck = match value {
+        PHPValue::NULL => check_null,
+        PHPValue::Boolean(true) => check_true,
+        PHPValue::Boolean(false) => check_false,
+        _ => {
+            // Only the true branch tells us something about other values
+            let BranchSide::TrueBranch = branch_side else {
+                return None;
+            };
+            let utype = variable.get_utype(state, &VoidEmitter::new())?;
+            let literal_type = value.get_literal_utype()?;
+            let possible = !utype.as_discrete_variants().is_empty()
+                && !narrow_type_by_check(&utype, value_kind_check(value)?, branch_side)
+                    .as_discrete_variants()
+                    .is_empty();
+            return possible.then(|| new_scope_with_variable_type(scope, variable, literal_type));
+        }
+    };
+    Some(new_scope_with_type_check(
+        scope,
+        variable,
+        state,
+        check,
+        branch_side,
+    ))
+}
+
+/// The check for the kind of the value, like `is_int()` for integers
+fn value_kind_check(value: &PHPValue) -> Option<TypeCheck> {
+    let type_name: &[u8] = match value {
+        PHPValue::NULL => b"null",
+        PHPValue::Boolean(_) => b"bool",
+        PHPValue::Int(_) => b"int",
+        PHPValue::Float(_) => b"float",
+        PHPValue::String(_) => b"string",
+        PHPValue::Array(_) => b"array",
+        _ => return None,
+    };
+    get_type_name_check(TypeNameFunction::GetDebugType, type_name)
+}
+
+impl BinaryOperatorBranchTypeHardening for IdenticalOperator {
+    fn branch_with_hardened_types_base_on_conditional_node(
+        &self,
+        left: &_ExpressionNode,
+        right: &BinaryExpressionRight,
+        scope: std::sync::Arc<std::sync::RwLock<crate::analysis::scope::Scope>>,
+        branch_side: crate::analysis::scope::BranchSide,
+        state: &mut crate::analysis::state::AnalysisState,
+    ) -> Option<std::sync::Arc<std::sync::RwLock<crate::analysis::scope::Scope>>> {
+        let left = IdenticalOperand::from_expression(left, state)?;
+        let right = IdenticalOperand::from_right(right, state)?;
+        use IdenticalOperand::*;
+        match (left, right) {
+            (Variable(variable), Value(value)) | (Value(value), Variable(variable)) => {
+                branch_on_value(variable, &value, scope, branch_side, state)
+            }
+            (TypeName(variable, function), Value(PHPValue::String(type_name)))
+            | (Value(PHPValue::String(type_name)), TypeName(variable, function)) => {
+                let check = get_type_name_check(function, type_name.as_bytes())?;
+                Some(new_scope_with_type_check(
+                    scope,
+                    variable,
+                    state,
+                    check,
+                    branch_side,
+                ))
+            }
+            // Subclasses fail the comparison, so only the true branch can be narrowed
+            (ClassName(variable), ClassLiteral(fq_name))
+            | (ClassLiteral(fq_name), ClassName(variable))
+                if matches!(branch_side, BranchSide::TrueBranch) =>
+            {
+                let class_type: DiscreteType =
+                    crate::symboldata::class::ClassName::from(fq_name).into();
+                Some(new_scope_with_variable_type(
+                    scope,
+                    variable,
+                    PHPType::from(class_type),
+                ))
+            }
+            _ => Some(scope.branch()),
+        }
+    }
+}
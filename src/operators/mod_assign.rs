@@ -10,7 +10,7 @@ pub struct ModAssignOperator(pub Range);
 
 impl Operator for ModAssignOperator {
     fn brief_desc(&self) -> String {
-        todo!()
+        "ModAssignOperator".into()
     }
 
     fn range(&self) -> Range {
@@ -14,7 +14,7 @@ pub struct NotEqualOperator(pub Range);
 
 impl Operator for NotEqualOperator {
     fn brief_desc(&self) -> String {
-        todo!()
+        "NotEqualOperator".into()
     }
 
     fn range(&self) -> Range {
@@ -1,11 +1,13 @@
 use crate::{
+    autonodes::{_expression::_ExpressionNode, binary_expression::BinaryExpressionRight},
     types::union::{DiscreteType, PHPType},
     value::PHPValue,
     Range,
 };
 
 use super::{
-    binary::{BinaryOperator, BinaryOperatorOperandAccess},
+    binary::{BinaryOperator, BinaryOperatorBranchTypeHardening, BinaryOperatorOperandAccess},
+    identical::IdenticalOperator,
     operator::Operator,
 };
 #[derive(Clone, Debug)]
@@ -48,3 +50,23 @@ impl BinaryOperator for NotIdenticalOperator {
         Some(PHPValue::Boolean(!bool_value))
     }
 }
+
+impl BinaryOperatorBranchTypeHardening for NotIdenticalOperator {
+    fn branch_with_hardened_types_base_on_conditional_node(
+        &self,
+        left: &_ExpressionNode,
+        right: &BinaryExpressionRight,
+        scope: std::sync::Arc<std::sync::RwLock<crate::analysis::scope::Scope>>,
+        branch_side: crate::analysis::scope::BranchSide,
+        state: &mut crate::analysis::state::AnalysisState,
+    ) -> Option<std::sync::Arc<std::sync::RwLock<crate::analysis::scope::Scope>>> {
+        // `$a !== $b` passes where `$a === $b` fails
+        IdenticalOperator(self.0).branch_with_hardened_types_base_on_conditional_node(
+            left,
+            right,
+            scope,
+            branch_side.inverse(),
+            state,
+        )
+    }
+}
@@ -7,7 +7,7 @@ pub struct RightShiftAssignOperator(pub Range);
 
 impl Operator for RightShiftAssignOperator {
     fn brief_desc(&self) -> String {
-        todo!()
+        "RightShiftAssignOperator".into()
     }
 
     fn range(&self) -> Range {
@@ -1,6 +1,6 @@
 use crate::autotree::{AutoTree, ParseError};
 // use crate::wrapped_tree::WrappedTree;
-use tree_sitter::{Language, Parser};
+use tree_sitter::{Language, LanguageError, Parser};
 use tree_sitter::{Point, Tree};
 
 #[link(name = "c++")]
@@ -20,11 +20,15 @@ impl Default for PHPParser {
 
 impl PHPParser {
     pub fn new() -> Self {
+        Self::try_new().expect("The PHP grammar should be compatible with tree-sitter")
+    }
+
+    pub fn try_new() -> Result<Self, LanguageError> {
         let mut parser = Parser::new();
 
         let language = unsafe { tree_sitter_php() };
-        parser.set_language(&language).unwrap();
-        PHPParser { parser }
+        parser.set_language(&language)?;
+        Ok(PHPParser { parser })
     }
 
     pub fn parse(&mut self, text: impl AsRef<[u8]>, old_tree: Option<&Tree>) -> Option<Tree> {
@@ -42,7 +46,7 @@ impl PHPParser {
 
 use tree_sitter::Range as TSRange;
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub struct Range {
     pub start_byte: usize,
     pub end_byte: usize,
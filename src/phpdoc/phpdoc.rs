@@ -5,19 +5,25 @@ use std::{
 
 use nom::{
     branch::alt,
-    bytes::complete::{tag, tag_no_case, take_until},
-    character::complete::{alpha1, alphanumeric0, space0, space1},
-    combinator::opt,
+    bytes::complete::{tag, tag_no_case, take_till, take_until, take_while, take_while1},
+    character::complete::{space0, space1},
+    combinator::{map, opt},
     error::Error,
     multi::{many0, separated_list0},
-    sequence::preceded,
+    sequence::{delimited, preceded, terminated, tuple},
     Err, IResult, Slice,
 };
 //use tree_sitter::Range;
 use crate::types::parse_types::CompoundType;
 use crate::{parser::Range, types::parser::compound_type};
 
-use super::{position::PHPDocInput, types::PHPDocEntry};
+use super::{
+    position::PHPDocInput,
+    types::{
+        PHPDocAssert, PHPDocAssertKind, PHPDocEntry, PHPDocMethod, PHPDocMethodParam,
+        PHPDocPropertyAccess, PHPDocTemplate, TemplateVariance,
+    },
+};
 
 fn our_tag<'a>(
     buf: &'a [u8],
@@ -31,6 +37,30 @@ fn our_tag_no_case<'a>(
     tag_no_case(buf)
 }
 
+/// `@name`, optionally prefixed like `@psalm-name` or `@phpstan-name`
+fn tool_tag<'a>(name: &'a [u8]) -> impl Fn(PHPDocInput<'a>) -> IResult<PHPDocInput<'a>, bool> {
+    move |input| {
+        let (input, _) = our_tag(b"@")(input)?;
+        let (input, prefix) = opt(tool_prefix)(input)?;
+        let (input, _) = our_tag_no_case(name)(input)?;
+        Ok((input, prefix.is_some()))
+    }
+}
+
+fn tool_prefix(input: PHPDocInput) -> IResult<PHPDocInput, PHPDocInput> {
+    alt((our_tag_no_case(b"psalm-"), our_tag_no_case(b"phpstan-")))(input)
+}
+
+/// Strips `psalm-` or `phpstan-` from a tag name, and tells whether it was there
+pub(crate) fn without_tool_prefix(tag_name: &[u8]) -> (&[u8], bool) {
+    for prefix in [&b"psalm-"[..], &b"phpstan-"[..]] {
+        if tag_name.len() > prefix.len() && tag_name[..prefix.len()].eq_ignore_ascii_case(prefix) {
+            return (&tag_name[prefix.len()..], true);
+        }
+    }
+    (tag_name, false)
+}
+
 pub fn parse_phpdoc(input: PHPDocInput) -> IResult<PHPDocInput, Vec<PHPDocEntry>> {
     let input_range = input.1;
 
@@ -49,6 +79,7 @@ pub fn parse_phpdoc(input: PHPDocInput) -> IResult<PHPDocInput, Vec<PHPDocEntry>
     let subset = input.slice(0..end);
 
     let (_, entries) = separated_list0(our_tag(b"\n"), phpdoc_entry)(subset)?;
+    let entries = prefer_prefixed_entries(entries);
     let end_range = Range {
         start_byte: input_range.end_byte,
         end_byte: input_range.end_byte,
@@ -58,11 +89,53 @@ pub fn parse_phpdoc(input: PHPDocInput) -> IResult<PHPDocInput, Vec<PHPDocEntry>
     Ok((PHPDocInput(&[], end_range), entries))
 }
 
-fn phpdoc_entry(input: PHPDocInput) -> IResult<PHPDocInput, PHPDocEntry> {
+fn phpdoc_entry(input: PHPDocInput) -> IResult<PHPDocInput, (PHPDocEntry, bool)> {
     let (input, _) = space0(input)?;
     let (input, _) = many0(our_tag(b"*"))(input)?;
     let (input, _) = space0(input)?;
-    phpdoc_entry_content(input)
+    let is_prefixed = preceded(our_tag(b"@"), tool_prefix)(input.clone()).is_ok();
+    let (input, entry) = phpdoc_entry_content(input)?;
+    Ok((input, (entry, is_prefixed)))
+}
+
+///
+/// Tags prefixed with `@psalm-` or `@phpstan-` are often more precise than the plain ones,
+/// so when both are present for the same thing, the plain one is dropped
+///
+fn prefer_prefixed_entries(entries: Vec<(PHPDocEntry, bool)>) -> Vec<PHPDocEntry> {
+    let overridden: Vec<_> = entries
+        .iter()
+        .filter(|(_, is_prefixed)| *is_prefixed)
+        .filter_map(|(entry, _)| override_key(entry))
+        .collect();
+    entries
+        .into_iter()
+        .filter(|(entry, is_prefixed)| {
+            *is_prefixed
+                || override_key(entry)
+                    .map(|key| !overridden.contains(&key))
+                    .unwrap_or(true)
+        })
+        .map(|(entry, _)| entry)
+        .collect()
+}
+
+/// What the entry describes, for entries which can be overridden by a prefixed tag
+fn override_key(entry: &PHPDocEntry) -> Option<(&'static str, Option<OsString>)> {
+    Some(match entry {
+        PHPDocEntry::Var(_, _, name, _) => ("var", name.clone()),
+        PHPDocEntry::Param(_, _, name, _) => ("param", name.clone()),
+        PHPDocEntry::Return(_, _, _) => ("return", None),
+        PHPDocEntry::Template(_, template) => ("template", Some(template.name.clone())),
+        PHPDocEntry::Property(_, _, _, name, _) => ("property", Some(name.clone())),
+        PHPDocEntry::Method(_, method) => ("method", Some(method.name.clone())),
+        PHPDocEntry::Assert(_, assert) => match assert.kind {
+            PHPDocAssertKind::Always => ("assert", Some(assert.name.clone())),
+            PHPDocAssertKind::IfTrue => ("assert-if-true", Some(assert.name.clone())),
+            PHPDocAssertKind::IfFalse => ("assert-if-false", Some(assert.name.clone())),
+        },
+        _ => return None,
+    })
 }
 
 fn phpdoc_entry_content(input: PHPDocInput) -> IResult<PHPDocInput, PHPDocEntry> {
@@ -79,6 +152,11 @@ fn phpdoc_entry_content(input: PHPDocInput) -> IResult<PHPDocInput, PHPDocEntry>
         todo,
         parse_abstract,
         copyright,
+        property,
+        method,
+        type_alias,
+        import_type,
+        assert,
         // these two must be at the end
         general,
         anything,
@@ -111,16 +189,45 @@ fn _simple_tagged_no_case_with_opt_data<'a>(
 }
 
 fn template(input: PHPDocInput) -> IResult<PHPDocInput, PHPDocEntry> {
-    // @template [ident] <of [boundary]>
+    // @template[-covariant|-contravariant] [ident] <of [boundary]> <= [default]>
     let start_range = input.1;
-    let (input, _) = our_tag_no_case(b"@template")(input)?;
+    let (input, _) = tool_tag(b"template")(input)?;
+    let (input, variance) = opt(alt((
+        map(our_tag_no_case(b"-covariant"), |_| {
+            TemplateVariance::Covariant
+        }),
+        map(our_tag_no_case(b"-contravariant"), |_| {
+            TemplateVariance::Contravariant
+        }),
+    )))(input)?;
     let (input, _) = space1(input)?;
     let (input, name) = name(input)?;
-    let (input, desc) = opt(preceded(space1, text_until_eol))(input)?;
+    let (input, bound) = opt(preceded(
+        tuple((
+            space1,
+            alt((our_tag_no_case(b"of"), our_tag_no_case(b"as"))),
+            space1,
+        )),
+        our_compound_type,
+    ))(input)?;
+    let (input, default) = opt(preceded(
+        tuple((space0, our_tag(b"="), space0)),
+        our_compound_type,
+    ))(input)?;
+    let (input, description) = opt(preceded(space1, text_until_eol))(input)?;
     let end_range = input.1;
     let range = from_until_ranges(start_range, end_range);
 
-    let entry = PHPDocEntry::Template(range, name, desc);
+    let entry = PHPDocEntry::Template(
+        range,
+        PHPDocTemplate {
+            name,
+            variance: variance.unwrap_or(TemplateVariance::Invariant),
+            bound,
+            default,
+            description,
+        },
+    );
     Ok((input, entry))
 }
 
@@ -175,7 +282,7 @@ fn see(input: PHPDocInput) -> IResult<PHPDocInput, PHPDocEntry> {
 fn var(input: PHPDocInput) -> IResult<PHPDocInput, PHPDocEntry> {
     // https://docs.phpdoc.org/guide/references/phpdoc/tags/var.html
     let start_range = input.1;
-    let (input, _) = our_tag_no_case(b"@var")(input)?;
+    let (input, _) = tool_tag(b"var")(input)?;
     let (input, _) = space1(input)?;
     let (input, tdef) = our_compound_type(input)?;
     let (input, name) = opt(preceded(space1, var_name))(input)?;
@@ -223,14 +330,26 @@ fn text_until_eol(input: PHPDocInput) -> IResult<PHPDocInput, OsString> {
 }
 
 fn name(input: PHPDocInput) -> IResult<PHPDocInput, OsString> {
-    let (input, part1) = alpha1(input)?;
-    let (input, part2) = alphanumeric0(input)?;
+    let (input, part1) = take_while1(|c: u8| c.is_ascii_alphabetic() || c == b'_')(input)?;
+    let (input, part2) = take_while(|c: u8| c.is_ascii_alphanumeric() || c == b'_')(input)?;
     let mut name = OsString::new();
     name.push(OsStr::from_bytes(part1.0));
     name.push(OsStr::from_bytes(part2.0));
     Ok((input, name))
 }
 
+/// Tag names might contain dashes, like `psalm-suppress`
+fn tag_name(input: PHPDocInput) -> IResult<PHPDocInput, OsString> {
+    let (input, first) = name(input)?;
+    let (input, rest) = many0(preceded(our_tag(b"-"), name))(input)?;
+    let mut tag_name = first;
+    for part in rest {
+        tag_name.push("-");
+        tag_name.push(part);
+    }
+    Ok((input, tag_name))
+}
+
 fn name_or_var_name(input: PHPDocInput) -> IResult<PHPDocInput, OsString> {
     alt((var_name, name))(input)
 }
@@ -261,7 +380,7 @@ fn todo(input: PHPDocInput) -> IResult<PHPDocInput, PHPDocEntry> {
 
 fn param(input: PHPDocInput) -> IResult<PHPDocInput, PHPDocEntry> {
     let start_range = input.1;
-    let (input, _) = our_tag_no_case(b"@param")(input)?;
+    let (input, _) = tool_tag(b"param")(input)?;
     let (input, _) = space1(input)?;
     let (input, ctype) = our_compound_type(input)?;
     let (input, _) = space0(input)?;
@@ -271,6 +390,132 @@ fn param(input: PHPDocInput) -> IResult<PHPDocInput, PHPDocEntry> {
     Ok((input, PHPDocEntry::Param(range, ctype, name, desc)))
 }
 
+fn property(input: PHPDocInput) -> IResult<PHPDocInput, PHPDocEntry> {
+    // https://docs.phpdoc.org/guide/references/phpdoc/tags/property.html
+    // @property[<-read|-write>] [Type] [name] [<description>]
+    let start_range = input.1;
+    let (input, _) = tool_tag(b"property")(input)?;
+    let (input, access) = opt(alt((
+        map(our_tag_no_case(b"-read"), |_| PHPDocPropertyAccess::Read),
+        map(our_tag_no_case(b"-write"), |_| PHPDocPropertyAccess::Write),
+    )))(input)?;
+    let (input, _) = space1(input)?;
+    let (input, ptype) = our_compound_type(input)?;
+    let (input, _) = space1(input)?;
+    let (input, name) = var_name(input)?;
+    let (input, desc) = opt(preceded(space1, text_until_eol))(input)?;
+    let range = from_until_ranges(start_range, input.1);
+    let access = access.unwrap_or(PHPDocPropertyAccess::ReadWrite);
+    Ok((
+        input,
+        PHPDocEntry::Property(range, access, ptype, name, desc),
+    ))
+}
+
+fn method(input: PHPDocInput) -> IResult<PHPDocInput, PHPDocEntry> {
+    // https://docs.phpdoc.org/guide/references/phpdoc/tags/method.html
+    // @method [[static] return type] [name]([[type] [parameter]<, ...>]) [<description>]
+    let start_range = input.1;
+    let (input, _) = tool_tag(b"method")(input)?;
+    let (input, _) = space1(input)?;
+    let (input, (is_static, return_type, name)) = alt((
+        map(
+            tuple((
+                terminated(our_tag_no_case(b"static"), space1),
+                terminated(our_compound_type, space1),
+                name,
+            )),
+            |(_, rtype, name)| (true, Some(rtype), name),
+        ),
+        map(
+            tuple((terminated(our_compound_type, space1), name)),
+            |(rtype, name)| (false, Some(rtype), name),
+        ),
+        map(name, |name| (false, None, name)),
+    ))(input)?;
+    let (input, params) = delimited(
+        preceded(space0, our_tag(b"(")),
+        separated_list0(our_tag(b","), method_param),
+        preceded(space0, our_tag(b")")),
+    )(input)?;
+    let (input, description) = opt(preceded(space1, text_until_eol))(input)?;
+    let range = from_until_ranges(start_range, input.1);
+    let method = PHPDocMethod {
+        is_static,
+        return_type,
+        name,
+        params,
+        description,
+    };
+    Ok((input, PHPDocEntry::Method(range, method)))
+}
+
+fn type_alias(input: PHPDocInput) -> IResult<PHPDocInput, PHPDocEntry> {
+    // @phpstan-type [name] [type] or @psalm-type [name] = [type]
+    let start_range = input.1;
+    let (input, _) = our_tag(b"@")(input)?;
+    let (input, _) = tool_prefix(input)?;
+    let (input, _) = our_tag_no_case(b"type")(input)?;
+    let (input, _) = space1(input)?;
+    let (input, name) = name(input)?;
+    let (input, _) = alt((
+        map(tuple((space0, our_tag(b"="), space0)), |_| ()),
+        map(space1, |_| ()),
+    ))(input)?;
+    let (input, ptype) = our_compound_type(input)?;
+    let range = from_until_ranges(start_range, input.1);
+    Ok((input, PHPDocEntry::TypeAlias(range, name, ptype)))
+}
+
+fn import_type(input: PHPDocInput) -> IResult<PHPDocInput, PHPDocEntry> {
+    // @phpstan-import-type [name] from [class] [as [alias]]
+    let start_range = input.1;
+    let (input, _) = our_tag(b"@")(input)?;
+    let (input, _) = tool_prefix(input)?;
+    let (input, _) = our_tag_no_case(b"import-type")(input)?;
+    let (input, _) = space1(input)?;
+    let (input, alias_name) = name(input)?;
+    let (input, _) = tuple((space1, our_tag_no_case(b"from"), space1))(input)?;
+    let (input, class_name) =
+        take_while1(|c: u8| c.is_ascii_alphanumeric() || c == b'_' || c == b'\\')(input)?;
+    let (input, alias) = opt(preceded(
+        tuple((space1, our_tag_no_case(b"as"), space1)),
+        name,
+    ))(input)?;
+    let range = from_until_ranges(start_range, input.1);
+    let class_name = OsStr::from_bytes(class_name.0).into();
+    Ok((
+        input,
+        PHPDocEntry::ImportType(range, alias_name, class_name, alias),
+    ))
+}
+
+fn method_param(input: PHPDocInput) -> IResult<PHPDocInput, PHPDocMethodParam> {
+    // [type] [...]$name [= default]
+    let (input, _) = space0(input)?;
+    let (input, ptype) = opt(terminated(our_compound_type, space1))(input)?;
+    let (input, variadic) = opt(our_tag(b"..."))(input)?;
+    let (input, name) = var_name(input)?;
+    let (input, _) = space0(input)?;
+    let (input, default) = opt(preceded(
+        terminated(our_tag(b"="), space0),
+        take_till(|c| c == b',' || c == b')' || c == b'\n'),
+    ))(input)?;
+    let param = PHPDocMethodParam {
+        ptype,
+        name,
+        variadic: variadic.is_some(),
+        default: default.map(|d| {
+            let end =
+                d.0.iter()
+                    .rposition(|c| !c.is_ascii_whitespace())
+                    .map_or(0, |i| i + 1);
+            OsStr::from_bytes(&d.0[..end]).into()
+        }),
+    };
+    Ok((input, param))
+}
+
 pub fn our_compound_type(input: PHPDocInput) -> IResult<PHPDocInput, CompoundType> {
     let pre_length = input.0.len();
     let pre_range = input.1;
@@ -297,7 +542,7 @@ fn parse_return(input: PHPDocInput) -> IResult<PHPDocInput, PHPDocEntry> {
     // https://docs.phpdoc.org/guide/references/phpdoc/tags/return.html
     // @return [type] <description>
     let start_range = input.1;
-    let (input, _) = our_tag_no_case(b"@return")(input)?;
+    let (input, _) = tool_tag(b"return")(input)?;
     let (input, _) = space1(input)?;
     let (input, tdef) = our_compound_type(input)?;
     let (input, desc) = opt(preceded(space1, text_until_eol))(input)?;
@@ -308,10 +553,36 @@ fn parse_return(input: PHPDocInput) -> IResult<PHPDocInput, PHPDocEntry> {
     Ok((input, entry))
 }
 
+fn assert(input: PHPDocInput) -> IResult<PHPDocInput, PHPDocEntry> {
+    // https://psalm.dev/docs/annotating_code/adding_assertions/
+    // @assert[-if-true|-if-false] [!][type] [parameter] [<description>]
+    let start_range = input.1;
+    let (input, _) = tool_tag(b"assert")(input)?;
+    let (input, kind) = opt(alt((
+        map(our_tag_no_case(b"-if-true"), |_| PHPDocAssertKind::IfTrue),
+        map(our_tag_no_case(b"-if-false"), |_| PHPDocAssertKind::IfFalse),
+    )))(input)?;
+    let (input, _) = space1(input)?;
+    let (input, negated) = opt(our_tag(b"!"))(input)?;
+    let (input, atype) = our_compound_type(input)?;
+    let (input, _) = space1(input)?;
+    let (input, name) = var_name(input)?;
+    let (input, description) = opt(preceded(space1, text_until_eol))(input)?;
+    let range = from_until_ranges(start_range, input.1);
+    let assert = PHPDocAssert {
+        kind: kind.unwrap_or(PHPDocAssertKind::Always),
+        negated: negated.is_some(),
+        atype,
+        name,
+        description,
+    };
+    Ok((input, PHPDocEntry::Assert(range, assert)))
+}
+
 fn general(input: PHPDocInput) -> IResult<PHPDocInput, PHPDocEntry> {
     let start_range = input.1;
     let (input, _) = our_tag(b"@")(input)?;
-    let (input, name) = name(input)?;
+    let (input, name) = tag_name(input)?;
     let (input, param) = opt(preceded(space1, text_until_eol))(input)?;
     let end_range = input.1;
     let range = from_until_ranges(start_range, end_range);
@@ -43,7 +43,7 @@ pub enum PHPDocEntry {
     See(Range, OsString, Option<OsString>),
 
     /// https://docs.phpdoc.org/guide/references/phpdoc/tags/template.html
-    Template(Range, OsString, Option<OsString>),
+    Template(Range, PHPDocTemplate),
 
     /// https://docs.phpdoc.org/guide/references/phpdoc/tags/author.html
     Author(Range, OsString),
@@ -60,6 +60,37 @@ pub enum PHPDocEntry {
     /// https://docs.phpdoc.org/guide/references/phpdoc/tags/copyright.html
     Copyright(Range, OsString),
 
+    /// https://docs.phpdoc.org/guide/references/phpdoc/tags/property.html
+    /// *  .0 @property, @property-read or @property-write
+    /// *  .1 type
+    /// *  .2 Name, including the $
+    /// *  .3 Description
+    Property(
+        Range,
+        PHPDocPropertyAccess,
+        CompoundType,
+        OsString,
+        Option<OsString>,
+    ),
+
+    /// https://docs.phpdoc.org/guide/references/phpdoc/tags/method.html
+    Method(Range, PHPDocMethod),
+
+    /// `@phpstan-type` or `@psalm-type`
+    /// *  .0 Name of the alias
+    /// *  .1 type
+    TypeAlias(Range, OsString, CompoundType),
+
+    /// `@phpstan-import-type` or `@psalm-import-type`
+    /// *  .0 Name of the alias in the class it's imported from
+    /// *  .1 Class it's imported from
+    /// *  .2 Local name, if it's imported with `as`
+    ImportType(Range, OsString, OsString, Option<OsString>),
+
+    /// `@assert`, `@assert-if-true` or `@assert-if-false`, optionally prefixed with `psalm-` or
+    /// `phpstan-`
+    Assert(Range, PHPDocAssert),
+
     General(Range, OsString),
     GeneralWithParam(Range, OsString, OsString),
 
@@ -67,6 +98,75 @@ pub enum PHPDocEntry {
     EmptyLine(Range),
 }
 
+#[derive(Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub enum PHPDocPropertyAccess {
+    ReadWrite,
+    Read,
+    Write,
+}
+
+/// `@method [[static] return type] [name]([[type] [parameter]<, ...>]) [<description>]`
+#[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub struct PHPDocMethod {
+    pub is_static: bool,
+    pub return_type: Option<CompoundType>,
+    pub name: OsString,
+    pub params: Vec<PHPDocMethodParam>,
+    pub description: Option<OsString>,
+}
+
+#[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub struct PHPDocMethodParam {
+    pub ptype: Option<CompoundType>,
+    /// Name, including the $
+    pub name: OsString,
+    pub variadic: bool,
+    /// The default value as written, if any
+    pub default: Option<OsString>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TemplateVariance {
+    Invariant,
+    /// `@template-covariant`, only allowed where values are read, like return types
+    Covariant,
+    /// `@template-contravariant`, only allowed where values are written, like parameters
+    Contravariant,
+}
+
+/// `@template[-covariant|-contravariant] [name] [of|as [bound]] [= [default]] [<description>]`
+#[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub struct PHPDocTemplate {
+    pub name: OsString,
+    pub variance: TemplateVariance,
+    pub bound: Option<CompoundType>,
+    pub default: Option<CompoundType>,
+    pub description: Option<OsString>,
+}
+
+/// When an `@assert`-entry applies
+#[derive(Clone, Copy, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub enum PHPDocAssertKind {
+    /// `@assert`, the function doesn't return unless the assertion holds
+    Always,
+    /// `@assert-if-true`, the assertion holds when the function returns true
+    IfTrue,
+    /// `@assert-if-false`, the assertion holds when the function returns false
+    IfFalse,
+}
+
+/// `@assert[-if-true|-if-false] [!][type] [parameter] [<description>]`
+#[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub struct PHPDocAssert {
+    pub kind: PHPDocAssertKind,
+    /// `!type`, the parameter is asserted to not be of the type
+    pub negated: bool,
+    pub atype: CompoundType,
+    /// Name, including the $
+    pub name: OsString,
+    pub description: Option<OsString>,
+}
+
 #[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq)]
 pub struct PHPDocComment {
     pub raw: OsString,
@@ -164,12 +264,17 @@ impl PHPDocEntry {
             | PHPDocEntry::Description(range, _)
             | PHPDocEntry::Deprecated(range, _)
             | PHPDocEntry::See(range, _, _)
-            | PHPDocEntry::Template(range, _, _)
+            | PHPDocEntry::Template(range, _)
             | PHPDocEntry::Author(range, _)
             | PHPDocEntry::Version(range, _)
             | PHPDocEntry::Todo(range, _)
             | PHPDocEntry::Abstract(range)
             | PHPDocEntry::Copyright(range, _)
+            | PHPDocEntry::Property(range, _, _, _, _)
+            | PHPDocEntry::Method(range, _)
+            | PHPDocEntry::TypeAlias(range, _, _)
+            | PHPDocEntry::ImportType(range, _, _, _)
+            | PHPDocEntry::Assert(range, _)
             | PHPDocEntry::General(range, _)
             | PHPDocEntry::GeneralWithParam(range, _, _)
             | PHPDocEntry::Anything(range, _)
@@ -0,0 +1,183 @@
+use std::{os::unix::prelude::OsStrExt, sync::Arc};
+
+use crate::{
+    analysis::{scope::BranchSide, state::AnalysisState},
+    issue::{Issue, IssueEmitter},
+    phpdoc::types::{PHPDocAssertKind, PHPDocComment, PHPDocEntry},
+    symbols::Name,
+    types::{
+        parse_types::{CompoundType, ParsedType, TypeName},
+        type_operators::get_call_site_concretes,
+        type_parser::TypeParser,
+        union::{DiscreteType, DiscretlyAccessedType, PHPType, SpecialType, UnionType},
+    },
+};
+
+use super::{class::FunctionArgumentData, templates::TemplateData, SymbolData};
+
+/// A type asserted for a parameter with `@assert`, `@assert-if-true` or `@assert-if-false`
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct AssertionData {
+    pub kind: PHPDocAssertKind,
+    /// Name of the parameter, without the $
+    pub parameter: Name,
+    /// Position of the parameter
+    pub argument: usize,
+    /// Asserted to not be of the type, like with `!null`
+    pub negated: bool,
+    pub atype: PHPType,
+}
+
+impl AssertionData {
+    ///
+    /// Whether the assertion holds when the call evaluated to `branch_side`. With `None`, the
+    /// call returned, without us knowing what it evaluated to.
+    ///
+    pub fn holds_when(&self, branch_side: Option<BranchSide>) -> bool {
+        match self.kind {
+            PHPDocAssertKind::Always => true,
+            PHPDocAssertKind::IfTrue => branch_side == Some(BranchSide::TrueBranch),
+            PHPDocAssertKind::IfFalse => branch_side == Some(BranchSide::FalseBranch),
+        }
+    }
+
+    ///
+    /// The type of a variable of type `utype` after the assertion holds, or `None` if it tells
+    /// us nothing new
+    ///
+    pub fn narrow_type(
+        &self,
+        utype: Option<PHPType>,
+        symbol_data: &Arc<SymbolData>,
+    ) -> Option<PHPType> {
+        let Some(utype) = utype else {
+            return (!self.negated).then(|| self.atype.clone());
+        };
+        let mut narrowed = UnionType::new();
+        for variant in utype.as_discrete_variants() {
+            let DiscretlyAccessedType::Discrete(dtype) = &variant else {
+                crate::missing!("Intersection in assertion narrowing");
+                narrowed.append(&variant);
+                continue;
+            };
+            let is_asserted_type = PHPType::from(dtype.clone())
+                .can_be_passed_as(&self.atype, symbol_data)
+                == Some(true);
+            if is_asserted_type != self.negated {
+                narrowed.append(dtype.clone());
+            }
+        }
+        match (narrowed.is_empty(), self.negated) {
+            (false, _) => Some(narrowed.into()),
+            // Nothing is known to be of the asserted type, like `mixed`, so it becomes it
+            (true, false) => Some(self.atype.clone()),
+            (true, true) => None,
+        }
+    }
+}
+
+/// Assertions in the doc-comment, for the parameters among `arguments`
+pub(crate) fn get_phpdoc_assertion_data(
+    doc_comment: &PHPDocComment,
+    arguments: &[FunctionArgumentData],
+    templates: &Vec<Name>,
+    state: &mut AnalysisState,
+    emitter: &dyn IssueEmitter,
+) -> Vec<AssertionData> {
+    let mut assertions = vec![];
+    for entry in &doc_comment.entries {
+        let PHPDocEntry::Assert(range, assert) = entry else {
+            continue;
+        };
+        let parameter = Name::from(&assert.name.as_bytes()[1..]);
+        let Some(argument) = arguments.iter().position(|a| a.name == parameter) else {
+            emitter.emit(Issue::InvalidPHPDocEntry(
+                state.pos_from_range(*range),
+                format!(
+                    "@assert refers to unknown parameter {}",
+                    assert.name.to_string_lossy()
+                )
+                .into(),
+            ));
+            continue;
+        };
+        let Some(atype) =
+            TypeParser::from_parsed_type(assert.atype.clone(), state, emitter, Some(templates))
+        else {
+            continue;
+        };
+        assertions.push(AssertionData {
+            kind: assert.kind,
+            parameter,
+            argument,
+            negated: assert.negated,
+            atype,
+        });
+    }
+    assertions
+}
+
+///
+/// The assertions of a call, with templates given the types inferred from the arguments.
+/// Assertions on templates which can't be inferred are left out.
+///
+pub(crate) fn get_call_site_assertions(
+    assertions: &[AssertionData],
+    parameters: &[FunctionArgumentData],
+    templates: &[TemplateData],
+    argument_types: &[Option<PHPType>],
+) -> Vec<AssertionData> {
+    if !assertions.iter().any(|a| a.atype.contains_template()) {
+        return assertions.to_vec();
+    }
+    let mut concrete = get_call_site_concretes(parameters, argument_types, templates);
+    // Like `Assert::isInstanceOf($value, Foo::class)`, with `@param class-string<T> $class`
+    for (parameter, argument_type) in parameters.iter().zip(argument_types) {
+        let Some(PHPDocEntry::Param(_, ctype, _, _)) = &parameter.phpdoc_entry else {
+            continue;
+        };
+        let Some(template) = get_class_string_template(ctype, templates) else {
+            continue;
+        };
+        if let Some(DiscreteType::Special(SpecialType::ClassString(Some(fq_name)))) =
+            argument_type.as_ref().and_then(|t| t.single_type())
+        {
+            let class_type: DiscreteType = super::class::ClassName::from(fq_name).into();
+            concrete.entry(template).or_insert(class_type.into());
+        }
+    }
+    assertions
+        .iter()
+        .map(|assertion| AssertionData {
+            atype: assertion.atype.concretize_templates(&concrete),
+            ..assertion.clone()
+        })
+        .filter(|assertion| !assertion.atype.contains_template())
+        .collect()
+}
+
+/// The template `T`, when the parameter is declared as `class-string<T>`
+fn get_class_string_template(ctype: &CompoundType, templates: &[TemplateData]) -> Option<Name> {
+    let ParsedType::Type(type_struct) = &ctype.if_single_type()?.ptype else {
+        return None;
+    };
+    let TypeName::Name(type_name) = &type_struct.type_name else {
+        return None;
+    };
+    if !type_name.eq_ignore_ascii_case("class-string") {
+        return None;
+    }
+    let [generic] = &type_struct.generics.as_ref()?[..] else {
+        return None;
+    };
+    let ParsedType::Type(generic) = &generic.if_single_type()?.ptype else {
+        return None;
+    };
+    let TypeName::Name(name) = &generic.type_name else {
+        return None;
+    };
+    templates
+        .iter()
+        .find(|template| template.name == *name)
+        .map(|template| template.name.clone())
+}
@@ -0,0 +1,156 @@
+use std::{cmp::Ordering, fmt::Display};
+
+use crate::{
+    symbols::{FullyQualifiedName, Name},
+    value::PHPValue,
+};
+
+use super::{class::ClassName, FileLocation};
+
+// These mirror the constants in PHP's `Attribute`-class
+pub const ATTRIBUTE_TARGET_CLASS: i64 = 1;
+pub const ATTRIBUTE_TARGET_FUNCTION: i64 = 2;
+pub const ATTRIBUTE_TARGET_METHOD: i64 = 4;
+pub const ATTRIBUTE_TARGET_PROPERTY: i64 = 8;
+pub const ATTRIBUTE_TARGET_CLASS_CONSTANT: i64 = 16;
+pub const ATTRIBUTE_TARGET_PARAMETER: i64 = 32;
+pub const ATTRIBUTE_TARGET_ALL: i64 = 63;
+pub const ATTRIBUTE_IS_REPEATABLE: i64 = 64;
+
+/// What kind of declaration an attribute is attached to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AttributeTarget {
+    Class,
+    Function,
+    Method,
+    Property,
+    ClassConstant,
+    Parameter,
+    /// A constructor parameter which is also a property
+    PromotedProperty,
+}
+
+impl AttributeTarget {
+    pub fn flag(&self) -> i64 {
+        match self {
+            AttributeTarget::Class => ATTRIBUTE_TARGET_CLASS,
+            AttributeTarget::Function => ATTRIBUTE_TARGET_FUNCTION,
+            AttributeTarget::Method => ATTRIBUTE_TARGET_METHOD,
+            AttributeTarget::Property => ATTRIBUTE_TARGET_PROPERTY,
+            AttributeTarget::ClassConstant => ATTRIBUTE_TARGET_CLASS_CONSTANT,
+            AttributeTarget::Parameter => ATTRIBUTE_TARGET_PARAMETER,
+            AttributeTarget::PromotedProperty => {
+                ATTRIBUTE_TARGET_PROPERTY | ATTRIBUTE_TARGET_PARAMETER
+            }
+        }
+    }
+}
+
+impl Display for AttributeTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                AttributeTarget::Class => "class",
+                AttributeTarget::Function => "function",
+                AttributeTarget::Method => "method",
+                AttributeTarget::Property => "property",
+                AttributeTarget::ClassConstant => "class constant",
+                AttributeTarget::Parameter => "parameter",
+                AttributeTarget::PromotedProperty => "promoted property",
+            }
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct AttributeArgument {
+    /// Set for named arguments, `#[Foo(name: 42)]`
+    pub name: Option<Name>,
+    pub value: Option<PHPValue>,
+}
+
+/// An attribute attached to a declaration, `#[Foo(42)]`
+#[derive(Clone, Debug)]
+pub struct AttributeData {
+    pub class_name: ClassName,
+    pub position: FileLocation,
+    pub arguments: Vec<AttributeArgument>,
+}
+
+impl AttributeData {
+    pub fn is_attribute_class(&self, fq_name: &FullyQualifiedName) -> bool {
+        self.class_name.fq_name.to_ascii_lowercase() == fq_name.to_ascii_lowercase()
+    }
+
+    /// Get the value of an argument, either by its name or its position
+    pub fn get_argument(&self, position: usize, name: &Name) -> Option<&PHPValue> {
+        let lc_name = name.to_ascii_lowercase();
+        if let Some(arg) = self.arguments.iter().find(|a| {
+            a.name
+                .as_ref()
+                .map(|n| n.to_ascii_lowercase() == lc_name)
+                .unwrap_or(false)
+        }) {
+            return arg.value.as_ref();
+        }
+        self.arguments
+            .get(position)
+            .filter(|a| a.name.is_none())
+            .and_then(|a| a.value.as_ref())
+    }
+}
+
+// MethodData is ordered, so we need this to be as well. Two attributes at the same
+// location are the same attribute.
+impl PartialEq for AttributeData {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for AttributeData {}
+
+impl PartialOrd for AttributeData {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for AttributeData {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.position
+            .cmp(&other.position)
+            .then_with(|| self.class_name.cmp(&other.class_name))
+    }
+}
+
+/// Lookup of attributes attached to a symbol
+pub trait HasAttributes {
+    fn get_attributes(&self) -> &[AttributeData];
+
+    fn get_attribute(&self, fq_name: &FullyQualifiedName) -> Option<&AttributeData> {
+        self.get_attributes()
+            .iter()
+            .find(|a| a.is_attribute_class(fq_name))
+    }
+
+    fn has_attribute(&self, fq_name: &FullyQualifiedName) -> bool {
+        self.get_attribute(fq_name).is_some()
+    }
+}
+
+/// The flags of the attribute-classes that comes with PHP. The native symbols don't
+/// carry attributes, so they're listed here.
+pub fn get_native_attribute_flags(fq_name: &FullyQualifiedName) -> Option<i64> {
+    let lc_name = fq_name.to_string().to_ascii_lowercase();
+    Some(match lc_name.as_str() {
+        r"\attribute" => ATTRIBUTE_TARGET_CLASS,
+        r"\returntypewillchange" => ATTRIBUTE_TARGET_METHOD,
+        r"\allowdynamicproperties" => ATTRIBUTE_TARGET_CLASS,
+        r"\sensitiveparameter" => ATTRIBUTE_TARGET_PARAMETER,
+        r"\override" => ATTRIBUTE_TARGET_METHOD,
+        _ => return None,
+    })
+}
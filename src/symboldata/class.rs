@@ -1,10 +1,11 @@
 //use tree_sitter::Range;
+use crate::locks::RecoverableLock;
 use crate::parser::Range;
 use crate::{
     analysis::state::AnalysisState,
     phpdoc::types::{PHPDocComment, PHPDocEntry},
     symbols::{FullyQualifiedName, Name},
-    types::union::DiscreteType,
+    types::union::{DiscreteType, DiscretlyAccessedType},
     value::PHPValue,
 };
 
@@ -15,7 +16,16 @@ use std::{
     sync::{Arc, RwLock},
 };
 
-use super::{FileLocation, SymbolData};
+use super::{
+    assertions::AssertionData,
+    attributes::{
+        get_native_attribute_flags, AttributeData, HasAttributes, ATTRIBUTE_IS_REPEATABLE,
+        ATTRIBUTE_TARGET_ALL,
+    },
+    templates::{get_generic_concretes, TemplateData},
+    traits::TraitUses,
+    FileLocation, SymbolData,
+};
 use crate::types::union::{from_vec_parsed_type, PHPType};
 
 type MethodName = Name;
@@ -154,12 +164,12 @@ impl ClassType {
         &mut self,
         method_name: &Name,
         position: FileLocation,
-    ) -> Arc<RwLock<MethodData>> {
+    ) -> Option<Arc<RwLock<MethodData>>> {
         match self {
-            ClassType::None => panic!(),
-            ClassType::Class(c) => c.get_or_create_method(method_name, position),
-            ClassType::Interface(i) => i.get_or_create_method(method_name, position),
-            ClassType::Trait(t) => t.get_or_create_method(method_name, position),
+            ClassType::None => None,
+            ClassType::Class(c) => Some(c.get_or_create_method(method_name, position)),
+            ClassType::Interface(i) => Some(i.get_or_create_method(method_name, position)),
+            ClassType::Trait(t) => Some(t.get_or_create_method(method_name, position)),
         }
     }
 
@@ -169,7 +179,7 @@ impl ClassType {
         position: FileLocation,
     ) -> Option<Arc<RwLock<PropertyData>>> {
         match self {
-            ClassType::None => panic!(),
+            ClassType::None => None,
             ClassType::Class(c) => c.get_or_create_property(property_name, position),
             ClassType::Interface(_) => None,
             ClassType::Trait(t) => t.get_or_create_property(property_name, position),
@@ -182,10 +192,10 @@ impl ClassType {
         state: &AnalysisState,
     ) -> Option<PropertyData> {
         match self {
-            ClassType::None => panic!(),
+            ClassType::None => None,
             ClassType::Class(c) => c.get_property(property_name, state),
             ClassType::Interface(_) => None,
-            ClassType::Trait(_) => None,
+            ClassType::Trait(t) => t.get_property(property_name, state),
         }
     }
     pub fn with_generic_args(&self, generic_args: &Vec<PHPType>) -> Self {
@@ -228,6 +238,15 @@ impl ClassType {
         }
     }
 
+    pub fn get_template_data(&self) -> Vec<TemplateData> {
+        match self {
+            ClassType::None => vec![],
+            ClassType::Class(cdata) => cdata.template_data.clone(),
+            ClassType::Interface(idata) => idata.template_data.clone(),
+            ClassType::Trait(tdata) => tdata.template_data.clone(),
+        }
+    }
+
     pub fn get_constructor(&self, symbol_data: Arc<SymbolData>) -> Option<MethodData> {
         let name: Name = "__construct".into();
         self.get_method(&name, symbol_data)
@@ -237,8 +256,8 @@ impl ClassType {
         match self {
             ClassType::None => (),
             ClassType::Class(c) => c.generic_concretes = Some(noe),
-            ClassType::Interface(_) => todo!(),
-            ClassType::Trait(_) => todo!(),
+            ClassType::Interface(i) => i.generic_concretes = Some(noe),
+            ClassType::Trait(_) => crate::missing!("Generic concretes for traits"),
         }
     }
 
@@ -270,9 +289,11 @@ pub enum ClassMemberVisibility {
     Protected,
 }
 
+/// Where, and with which visibility, a class constant is declared
 #[derive(Clone, Debug)]
-pub struct TraitImport {
-    pub trait_name: ClassName,
+pub struct ClassConstantData {
+    pub position: FileLocation,
+    pub visibility: ClassMemberVisibility,
 }
 
 #[derive(Clone, Debug)]
@@ -286,14 +307,30 @@ pub struct ClassData {
     pub modifier: ClassModifier,
     pub read_only: bool,
     pub constants: HashMap<Name, Option<PHPValue>>,
+    pub constant_declarations: HashMap<Name, ClassConstantData>,
     pub methods: HashMap<Name, Arc<RwLock<MethodData>>>,
     pub properties: HashMap<Name, Arc<RwLock<PropertyData>>>,
     pub is_native: bool,
-    pub traits: Vec<TraitImport>,
+    pub traits: TraitUses,
+    pub attributes: Vec<AttributeData>,
     pub phpdoc: Option<PHPDocComment>,
     pub deprecated: Option<OsString>,
     pub generic_templates: Option<Vec<Name>>,
+    /// Bounds, variance and defaults of the `generic_templates`
+    pub template_data: Vec<TemplateData>,
     pub generic_concretes: Option<BTreeMap<Name, PHPType>>,
+    /// Magic properties declared with `@property`, `@property-read` and `@property-write`
+    pub phpdoc_properties: HashMap<Name, PropertyData>,
+    /// Magic methods declared with `@method`, keyed by lowercase name
+    pub phpdoc_methods: HashMap<Name, MethodData>,
+    /// Classes declared with `@mixin`, whose members are available through this class
+    pub mixins: Vec<ClassName>,
+    /// Declared with `@phpstan-type` or `@psalm-type`
+    pub type_aliases: HashMap<Name, PHPType>,
+    /// Declared with `@phpstan-import-type` or `@psalm-import-type`
+    /// *  key = Local name
+    /// *  value = Class it's imported from, and the name there
+    pub imported_type_aliases: HashMap<Name, (ClassName, Name)>,
 }
 
 impl ClassData {
@@ -308,14 +345,39 @@ impl ClassData {
             modifier: ClassModifier::None,
             read_only: false,
             constants: HashMap::new(),
+            constant_declarations: HashMap::new(),
             methods: HashMap::new(),
             properties: HashMap::new(),
-            traits: vec![],
+            traits: TraitUses::default(),
+            attributes: vec![],
             is_native: false,
             phpdoc: None,
             deprecated: None,
             generic_templates: None,
+            template_data: vec![],
             generic_concretes: None,
+            phpdoc_properties: HashMap::new(),
+            phpdoc_methods: HashMap::new(),
+            mixins: vec![],
+            type_aliases: HashMap::new(),
+            imported_type_aliases: HashMap::new(),
+        }
+    }
+
+    /// Resolves a type alias declared on, or imported into, this class
+    pub fn get_type_alias(&self, name: &Name, symbol_data: &Arc<SymbolData>) -> Option<PHPType> {
+        if let Some(utype) = self.type_aliases.get(name) {
+            return Some(utype.clone());
+        }
+        let (from_class, from_name) = self.imported_type_aliases.get(name)?;
+        if from_class.get_fq_name() == self.class_name.get_fq_name() {
+            return self.type_aliases.get(from_name).cloned();
+        }
+        let class_handle = symbol_data.get_class(from_class)?;
+        let unlocked = class_handle.read_or_recover();
+        match &*unlocked {
+            ClassType::Class(cdata) => cdata.type_aliases.get(from_name).cloned(),
+            _ => None,
         }
     }
 
@@ -323,6 +385,25 @@ impl ClassData {
         self.methods.get(&method_name.to_ascii_lowercase()).cloned()
     }
 
+    /// If this class is usable as an attribute, returns the `Attribute::TARGET_*` and
+    /// `Attribute::IS_REPEATABLE` flags it's declared with
+    pub fn get_attribute_flags(&self) -> Option<i64> {
+        if let Some(flags) = get_native_attribute_flags(self.class_name.get_fq_name()) {
+            return Some(flags);
+        }
+        let attribute = self.get_attribute(&r"\Attribute".into())?;
+        if attribute.arguments.is_empty() {
+            return Some(ATTRIBUTE_TARGET_ALL);
+        }
+        // If we can't determine the flags, we assume everything is allowed
+        Some(
+            attribute
+                .get_argument(0, &"flags".into())
+                .and_then(|v| v.as_i64())
+                .unwrap_or(ATTRIBUTE_TARGET_ALL | ATTRIBUTE_IS_REPEATABLE),
+        )
+    }
+
     pub fn get_base_class_data(
         &self,
         symbol_data: &Arc<SymbolData>,
@@ -331,31 +412,160 @@ impl ClassData {
         symbol_data.get_class(base)
     }
 
+    ///
+    /// Methods declared in code are looked up first, in the class, its traits, parents and
+    /// interfaces. Those declared with `@method` or available through `@mixin` are only used
+    /// when there is no such method
+    ///
     pub fn get_method(
         &self,
         method_name: &Name,
         symbol_data: Arc<SymbolData>,
     ) -> Option<MethodData> {
-        if let Some(m) = self.methods.get(&method_name.to_ascii_lowercase()) {
-            let mut mdata = m.read().unwrap().clone();
+        self.get_declared_method(method_name, &symbol_data)
+            .or_else(|| self.get_magic_method(method_name, &symbol_data, &mut vec![]))
+    }
+
+    fn get_declared_method(
+        &self,
+        method_name: &Name,
+        symbol_data: &Arc<SymbolData>,
+    ) -> Option<MethodData> {
+        let lc_method_name = method_name.to_ascii_lowercase();
+        if let Some(m) = self.methods.get(&lc_method_name) {
+            let mut mdata = m.read_or_recover().clone();
             mdata.generic_concretes.clone_from(&self.generic_concretes);
             return Some(mdata);
         }
 
-        if let Some(cdata_handle) = self.get_base_class_data(&symbol_data) {
-            let cdata = cdata_handle.read().unwrap();
-            if let Some(m) = (*cdata).get_method(method_name, symbol_data) {
+        // Methods from traits takes precedence over inherited methods
+        if !self.traits.is_empty() {
+            let resolved = self.traits.resolve_methods(symbol_data);
+            if let Some(imported) = resolved.methods.get(&lc_method_name) {
+                let mut mdata = imported.get_method_data().with_self_as(&self.class_name);
+                mdata.generic_concretes.clone_from(&self.generic_concretes);
+                return Some(mdata);
+            }
+        }
+
+        if let Some(cdata_handle) = self.get_base_class_data(symbol_data) {
+            let cdata = cdata_handle.read_or_recover();
+            let inherited = match &*cdata {
+                ClassType::Class(c) => c.get_declared_method(method_name, symbol_data),
+                other => other.get_method(method_name, symbol_data.clone()),
+            };
+            if let Some(mut m) = inherited {
+                if let Some(concrete) = self.get_parent_generic_concretes(&cdata) {
+                    m.inherit_generic_concretes(&concrete);
+                }
+                return Some(m);
+            }
+        }
+
+        // Abstract classes doesn't need to declare the methods of their interfaces
+        for iface in &self.interfaces {
+            let Some(iface_handle) = symbol_data.get_class(iface) else {
+                continue;
+            };
+            let idata = iface_handle.read_or_recover();
+            if let Some(mut m) = idata.get_method(method_name, symbol_data.clone()) {
+                if let Some(concrete) = self.get_parent_generic_concretes(&idata) {
+                    m.inherit_generic_concretes(&concrete);
+                }
                 return Some(m);
             }
         }
-        for _ptrait in &self.traits {
-            // FIXME trait-imports are more complex than this, we're just skipping it for now
-            crate::missing!("look for unknown method in imported trait");
+
+        None
+    }
+
+    ///
+    /// Methods declared with `@method` in the class or its parents, or available through
+    /// `@mixin`. `visited` holds the classes already searched, so mixins of each other don't
+    /// recurse endlessly
+    ///
+    fn get_magic_method(
+        &self,
+        method_name: &Name,
+        symbol_data: &Arc<SymbolData>,
+        visited: &mut Vec<FullyQualifiedName>,
+    ) -> Option<MethodData> {
+        let fq_name = self.get_fq_name();
+        if visited.contains(&fq_name) {
+            return None;
+        }
+        visited.push(fq_name);
+
+        if let Some(m) = self.phpdoc_methods.get(&method_name.to_ascii_lowercase()) {
+            let mut mdata = m.clone();
+            mdata.generic_concretes.clone_from(&self.generic_concretes);
+            return Some(mdata);
+        }
+
+        if let Some(cdata_handle) = self.get_base_class_data(symbol_data) {
+            let cdata = cdata_handle.read_or_recover();
+            if let ClassType::Class(c) = &*cdata {
+                if let Some(mut m) = c.get_magic_method(method_name, symbol_data, visited) {
+                    if let Some(concrete) = self.get_parent_generic_concretes(&cdata) {
+                        m.inherit_generic_concretes(&concrete);
+                    }
+                    return Some(m);
+                }
+            }
+        }
+
+        for mixin in &self.mixins {
+            if visited.contains(mixin.get_fq_name()) {
+                continue;
+            }
+            let Some(mixin_handle) = symbol_data.get_class(mixin) else {
+                continue;
+            };
+            let found = match &*mixin_handle.read_or_recover() {
+                ClassType::Class(c) => c
+                    .get_declared_method(method_name, symbol_data)
+                    .or_else(|| c.get_magic_method(method_name, symbol_data, visited)),
+                other => other.get_method(method_name, symbol_data.clone()),
+            };
+            if found.is_some() {
+                return found;
+            }
         }
 
         None
     }
 
+    ///
+    /// The concrete types of the templates of a base class or interface, as given with
+    /// `@extends Base<Foo>` or `@implements Iface<Bar>`
+    ///
+    pub(crate) fn get_parent_generic_concretes(
+        &self,
+        parent: &ClassType,
+    ) -> Option<BTreeMap<Name, PHPType>> {
+        let parent_fq_name = parent.get_fq_name();
+        let generic_args = self
+            .phpdoc_base_class_name
+            .iter()
+            .chain(self.phpdoc_interfaces.iter())
+            .find_map(|dtype| match dtype {
+                DiscreteType::Generic(base, generic_args) => match &**base {
+                    DiscreteType::Named(_, fq_name) if *fq_name == parent_fq_name => {
+                        Some(generic_args)
+                    }
+                    _ => None,
+                },
+                _ => None,
+            })?;
+        let mut concrete = get_generic_concretes(&parent.get_template_data(), generic_args);
+        if let Some(own_concrete) = &self.generic_concretes {
+            for ptype in concrete.values_mut() {
+                *ptype = ptype.concretize_templates(own_concrete);
+            }
+        }
+        Some(concrete)
+    }
+
     fn get_methods_from_interfaces(
         &self,
         method_name: &Name,
@@ -421,29 +631,89 @@ impl ClassData {
         self.properties.get(property_name).cloned()
     }
 
+    ///
+    /// Properties declared in code are looked up first, in the class, its traits and parents.
+    /// Those declared with `@property` or available through `@mixin` are only used when there
+    /// is no such property
+    ///
     pub fn get_property(
         &self,
         property_name: &Name,
         state: &AnalysisState,
+    ) -> Option<PropertyData> {
+        self.get_declared_property(property_name, state)
+            .or_else(|| self.get_magic_property(property_name, state, &mut vec![]))
+    }
+
+    fn get_declared_property(
+        &self,
+        property_name: &Name,
+        state: &AnalysisState,
     ) -> Option<PropertyData> {
         if let Some(m) = self.properties.get(property_name) {
-            return Some(m.read().unwrap().clone());
+            return Some(m.read_or_recover().clone());
         }
 
-        if let Some(base) = &self.base_class_name {
-            if let Some(cdata_handle) = state.symbol_data.get_class(base) {
-                let cdata = cdata_handle.read().unwrap();
-                if let ClassType::Class(c) = &*cdata {
-                    return c.get_property(property_name, state);
+        if let Some((trait_name, pdata)) =
+            self.traits
+                .get_property(property_name, &state.symbol_data, 0)
+        {
+            return Some(pdata.with_self_as(&trait_name, &self.class_name));
+        }
+
+        let cdata_handle = self.get_base_class_data(&state.symbol_data)?;
+        let cdata = cdata_handle.read_or_recover();
+        match &*cdata {
+            ClassType::Class(c) => c.get_declared_property(property_name, state),
+            _ => None,
+        }
+    }
+
+    ///
+    /// Properties declared with `@property` in the class or its parents, or available through
+    /// `@mixin`. `visited` holds the classes already searched
+    ///
+    fn get_magic_property(
+        &self,
+        property_name: &Name,
+        state: &AnalysisState,
+        visited: &mut Vec<FullyQualifiedName>,
+    ) -> Option<PropertyData> {
+        let fq_name = self.get_fq_name();
+        if visited.contains(&fq_name) {
+            return None;
+        }
+        visited.push(fq_name);
+
+        if let Some(pdata) = self.phpdoc_properties.get(property_name) {
+            return Some(pdata.clone());
+        }
+
+        if let Some(cdata_handle) = self.get_base_class_data(&state.symbol_data) {
+            if let ClassType::Class(c) = &*cdata_handle.read_or_recover() {
+                if let Some(pdata) = c.get_magic_property(property_name, state, visited) {
+                    return Some(pdata);
                 }
             }
         }
-        /* Traits don't have properties?
-        for _ptrait in &self.traits {
-            // FIXME trait-imports are more complex than this, we're just skipping it for now
-            crate::missing!("look for unknown method in imported trait");
-        }*/
 
+        for mixin in &self.mixins {
+            if visited.contains(mixin.get_fq_name()) {
+                continue;
+            }
+            let Some(mixin_handle) = state.symbol_data.get_class(mixin) else {
+                continue;
+            };
+            let found = match &*mixin_handle.read_or_recover() {
+                ClassType::Class(c) => c
+                    .get_declared_property(property_name, state)
+                    .or_else(|| c.get_magic_property(property_name, state, visited)),
+                other => other.get_property(property_name, state),
+            };
+            if found.is_some() {
+                return found;
+            }
+        }
         None
     }
 
@@ -457,7 +727,7 @@ impl ClassData {
         }
         if let Some(base) = &self.base_class_name {
             if let Some(cdata_handle) = symbol_data.get_class(base) {
-                let cdata = cdata_handle.read().unwrap();
+                let cdata = cdata_handle.read_or_recover();
                 return cdata.implements(iname, symbol_data);
             }
         }
@@ -477,7 +747,7 @@ impl ClassData {
         }
         if let Some(base) = &self.base_class_name {
             if let Some(cdata_handle) = symbol_data.get_class(base) {
-                let cdata = cdata_handle.read().unwrap();
+                let cdata = cdata_handle.read_or_recover();
                 return cdata.instanceof(tname, symbol_data);
             }
         }
@@ -487,7 +757,7 @@ impl ClassData {
     fn get_own_methods(&self, _symbol_data: Arc<SymbolData>) -> Vec<MethodData> {
         self.methods
             .iter()
-            .map(|x| x.1.read().unwrap().clone())
+            .map(|x| x.1.read_or_recover().clone())
             .collect()
     }
 
@@ -501,7 +771,7 @@ impl ClassData {
         }
 
         if let Some(base) = self.get_base_class_data(symbol_data) {
-            let unlocked = base.read().ok()?;
+            let unlocked = base.read_or_recover();
             if let Some(x) = unlocked.get_constant_value(symbol_data, constant_name) {
                 return Some(x);
             }
@@ -528,6 +798,9 @@ pub struct InterfaceData {
     pub is_native: bool,
     pub phpdoc: Option<PHPDocComment>,
     pub generic_templates: Option<Vec<Name>>,
+    /// Bounds, variance and defaults of the `generic_templates`
+    pub template_data: Vec<TemplateData>,
+    pub generic_concretes: Option<BTreeMap<Name, PHPType>>,
 }
 
 impl InterfaceData {
@@ -541,6 +814,8 @@ impl InterfaceData {
             is_native: false,
             phpdoc: None,
             generic_templates: None,
+            template_data: vec![],
+            generic_concretes: None,
         }
     }
 
@@ -555,11 +830,13 @@ impl InterfaceData {
     ) -> Option<MethodData> {
         let lc_mname = method_name.to_ascii_lowercase();
         if let Some(mdata) = self.methods.get(&lc_mname) {
-            Some(mdata.read().unwrap().clone())
+            let mut mdata = mdata.read_or_recover().clone();
+            mdata.generic_concretes.clone_from(&self.generic_concretes);
+            Some(mdata)
         } else if let Some(bases) = &self.base_interface_names {
             for base in bases {
                 if let Some(locked_idata) = symbol_data.get_class(base) {
-                    let unlocked = locked_idata.read().unwrap();
+                    let unlocked = locked_idata.read_or_recover();
                     match &*unlocked {
                         ClassType::Interface(idata) => {
                             if let Some(mdata) = idata.get_method(&lc_mname, symbol_data.clone()) {
@@ -626,7 +903,7 @@ impl InterfaceData {
     fn get_own_methods(&self, _symbol_data: Arc<SymbolData>) -> Vec<MethodData> {
         self.methods
             .iter()
-            .map(|x| x.1.read().unwrap().clone())
+            .map(|x| x.1.read_or_recover().clone())
             .collect()
     }
 
@@ -681,9 +958,12 @@ pub struct TraitData {
     pub base_name: Option<ClassName>,
     pub methods: HashMap<Name, Arc<RwLock<MethodData>>>,
     pub properties: HashMap<Name, Arc<RwLock<PropertyData>>>,
+    pub traits: TraitUses,
     pub is_native: bool,
     pub phpdoc: Option<PHPDocComment>,
     pub generic_templates: Option<Vec<Name>>,
+    /// Bounds, variance and defaults of the `generic_templates`
+    pub template_data: Vec<TemplateData>,
 }
 
 impl TraitData {
@@ -694,9 +974,11 @@ impl TraitData {
             base_name: None,
             methods: HashMap::new(),
             properties: HashMap::new(),
+            traits: TraitUses::default(),
             is_native: false,
             phpdoc: None,
             generic_templates: None,
+            template_data: vec![],
         }
     }
     pub fn get_own_method(&self, method_name: &Name) -> Option<Arc<RwLock<MethodData>>> {
@@ -704,10 +986,31 @@ impl TraitData {
     }
     pub fn get_method(
         &self,
-        _method_name: &Name,
-        _symbol_data: Arc<SymbolData>,
+        method_name: &Name,
+        symbol_data: Arc<SymbolData>,
     ) -> Option<MethodData> {
-        crate::missing_none!("Trait.get_method(..)")
+        let lc_method_name = method_name.to_ascii_lowercase();
+        if let Some(m) = self.methods.get(&lc_method_name) {
+            return Some(m.read_or_recover().clone());
+        }
+        let resolved = self.traits.resolve_methods(&symbol_data);
+        resolved
+            .methods
+            .get(&lc_method_name)
+            .map(|imported| imported.get_method_data())
+    }
+
+    pub fn get_property(
+        &self,
+        property_name: &Name,
+        state: &AnalysisState,
+    ) -> Option<PropertyData> {
+        if let Some(p) = self.properties.get(property_name) {
+            return Some(p.read_or_recover().clone());
+        }
+        self.traits
+            .get_property(property_name, &state.symbol_data, 0)
+            .map(|(_, pdata)| pdata)
     }
 
     pub fn get_or_create_method(
@@ -749,7 +1052,7 @@ impl TraitData {
     fn get_own_methods(&self, _symbol_data: Arc<SymbolData>) -> Vec<MethodData> {
         self.methods
             .iter()
-            .map(|x| x.1.read().unwrap().clone())
+            .map(|x| x.1.read_or_recover().clone())
             .collect()
     }
 }
@@ -765,6 +1068,8 @@ pub struct FunctionArgumentData {
     pub phpdoc_entry: Option<PHPDocEntry>,
     pub phpdoc_type: Option<PHPType>,
     pub variadic: bool,
+    /// Passed by reference, like `&$matches`
+    pub by_ref: bool,
 }
 
 impl Ord for FunctionArgumentData {
@@ -796,6 +1101,42 @@ impl FunctionArgumentData {
         }
         self.arg_type.clone()
     }
+
+    ///
+    /// Whether `null` can be passed to the parameter. Parameters we can't tell for, like
+    /// untyped ones, templates, or types naming unknown classes, are assumed to accept it
+    ///
+    pub fn accepts_null(&self, state: &mut AnalysisState) -> bool {
+        if self.nullable || matches!(self.default_value, Some(PHPValue::NULL)) {
+            return true;
+        }
+        if self.arg_type.as_ref().is_some_and(|t| t.can_be_null()) {
+            return true;
+        }
+        let Some(ptype) = self.get_type(state) else {
+            return true;
+        };
+        if ptype.contains_template() {
+            return true;
+        }
+        let symbol_data = state.symbol_data.clone();
+        let names_unknown_class = ptype.as_discrete_variants().iter().any(|v| {
+            matches!(
+                v,
+                DiscretlyAccessedType::Discrete(DiscreteType::Named(_, fq_name))
+                    if symbol_data.get_class(&ClassName::from(fq_name)).is_none()
+            )
+        });
+        names_unknown_class
+            || PHPType::from(DiscreteType::NULL).can_be_passed_as(&ptype, &symbol_data)
+                != Some(false)
+    }
+}
+
+impl HasAttributes for ClassData {
+    fn get_attributes(&self) -> &[AttributeData] {
+        &self.attributes
+    }
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
@@ -814,7 +1155,12 @@ pub struct MethodData {
     pub is_static: bool,
     pub visibility: ClassMemberVisibility,
     pub phpdoc: Option<PHPDocComment>,
+    pub attributes: Vec<AttributeData>,
     pub generic_templates: Option<Vec<Name>>,
+    /// Bounds, variance and defaults of the `generic_templates`
+    pub template_data: Vec<TemplateData>,
+    /// Types asserted for the parameters with `@assert` and similar
+    pub assertions: Vec<AssertionData>,
     pub generic_concretes: Option<BTreeMap<Name, PHPType>>,
 }
 
@@ -839,11 +1185,29 @@ impl MethodData {
             modifier: ClassModifier::None,
             visibility: ClassMemberVisibility::Public,
             phpdoc: None,
+            attributes: vec![],
             generic_templates: None,
+            template_data: vec![],
+            assertions: vec![],
             generic_concretes: None,
         }
     }
 
+    ///
+    /// Adds the concrete types a subclass gives to the templates of the class this method is
+    /// declared in. Concrete types already known might refer to the templates of the
+    /// subclass, and are concretized too.
+    ///
+    pub(crate) fn inherit_generic_concretes(&mut self, concrete: &BTreeMap<Name, PHPType>) {
+        let mut inherited = concrete.clone();
+        if let Some(own_concrete) = &self.generic_concretes {
+            for (name, ptype) in own_concrete {
+                inherited.insert(name.clone(), ptype.concretize_templates(concrete));
+            }
+        }
+        self.generic_concretes = Some(inherited);
+    }
+
     pub(crate) fn get_return_type(&self) -> Option<PHPType> {
         let call_return_type = self
             .comment_return_type
@@ -860,6 +1224,12 @@ impl MethodData {
     }
 }
 
+impl HasAttributes for MethodData {
+    fn get_attributes(&self) -> &[AttributeData] {
+        &self.attributes
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PropertyData {
     pub name: Name,
@@ -868,6 +1238,8 @@ pub struct PropertyData {
     pub visibility: ClassMemberVisibility,
     pub is_static: bool,
     pub readonly: bool,
+    /// Declared with `@property-write`, so reading it gives no known type
+    pub writeonly: bool,
     pub default_value: Option<PHPValue>,
     pub declared_type: Option<PHPType>,
     pub comment_type: Option<(PHPType, Range)>,
@@ -877,6 +1249,7 @@ pub struct PropertyData {
     pub written_to: usize,
     pub written_data: Vec<(PHPType, Option<PHPValue>)>,
     pub phpdoc: Option<PHPDocComment>,
+    pub attributes: Vec<AttributeData>,
     // void
 }
 
@@ -889,6 +1262,7 @@ impl PropertyData {
             visibility: ClassMemberVisibility::Public,
             is_static: false,
             readonly: false,
+            writeonly: false,
             default_value: None,
             declared_type: None,
             comment_type: None,
@@ -898,8 +1272,15 @@ impl PropertyData {
             written_to: 0,
             written_data: vec![],
             phpdoc: None,
+            attributes: vec![],
         }
     }
 
     // void
 }
+
+impl HasAttributes for PropertyData {
+    fn get_attributes(&self) -> &[AttributeData] {
+        &self.attributes
+    }
+}
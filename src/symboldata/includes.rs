@@ -0,0 +1,191 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::symbols::Name;
+
+use super::FileLocation;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IncludeKind {
+    Include,
+    IncludeOnce,
+    Require,
+    RequireOnce,
+}
+
+impl IncludeKind {
+    /// `require` and `require_once` are fatal if the file is missing, `include` only warns
+    pub fn is_required(&self) -> bool {
+        matches!(self, IncludeKind::Require | IncludeKind::RequireOnce)
+    }
+}
+
+impl Display for IncludeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                IncludeKind::Include => "include",
+                IncludeKind::IncludeOnce => "include_once",
+                IncludeKind::Require => "require",
+                IncludeKind::RequireOnce => "require_once",
+            }
+        )
+    }
+}
+
+/// A resolved `include`, `include_once`, `require` or `require_once`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IncludeData {
+    pub kind: IncludeKind,
+    pub target: PathBuf,
+    pub position: FileLocation,
+    /// Included from within a function, where the included file sees its variables
+    pub in_function: bool,
+    /// Whether `target` is known to exist. A relative path which isn't found next to the
+    /// including file might still be found in the `include_path` or the working directory
+    pub resolved: bool,
+}
+
+/// Which files includes which. Used to find the files which needs to be reanalyzed
+/// when a file changes, and to know which files are included from somewhere else
+#[derive(Debug, Default)]
+pub struct IncludeGraph {
+    includes: HashMap<PathBuf, Vec<IncludeData>>,
+    included_by: HashMap<PathBuf, HashSet<PathBuf>>,
+    /// The variables used in the top-level scope of each file, which the files it
+    /// includes can see
+    variables: HashMap<PathBuf, HashSet<Name>>,
+}
+
+impl IncludeGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_include(&mut self, from: &Path, include: IncludeData) {
+        let from = normalize_path(from);
+        let target = normalize_path(&include.target);
+        let entries = self.includes.entry(from.clone()).or_default();
+        if entries.contains(&include) {
+            return;
+        }
+        entries.push(include);
+        self.included_by.entry(target).or_default().insert(from);
+    }
+
+    /// Forget everything `file` includes, typically before it's analyzed again
+    pub fn clear_file(&mut self, file: &Path) {
+        let file = normalize_path(file);
+        self.variables.remove(&file);
+        let Some(entries) = self.includes.remove(&file) else {
+            return;
+        };
+        for entry in entries {
+            let target = normalize_path(&entry.target);
+            if let Some(included_by) = self.included_by.get_mut(&target) {
+                included_by.remove(&file);
+                if included_by.is_empty() {
+                    self.included_by.remove(&target);
+                }
+            }
+        }
+    }
+
+    pub fn set_variables(&mut self, file: &Path, variables: HashSet<Name>) {
+        self.variables.insert(normalize_path(file), variables);
+    }
+
+    ///
+    /// Whether a file which directly or indirectly includes `file` might have defined the
+    /// variable. A file included from within a function might see any variable
+    ///
+    pub fn is_variable_from_includer(&self, file: &Path, name: &Name) -> bool {
+        let mut seen = HashSet::new();
+        let mut queue = vec![normalize_path(file)];
+        while let Some(current) = queue.pop() {
+            let Some(included_by) = self.included_by.get(&current) else {
+                continue;
+            };
+            for includer in included_by {
+                if !seen.insert(includer.clone()) {
+                    continue;
+                }
+                let in_function = self.includes.get(includer).is_some_and(|entries| {
+                    entries
+                        .iter()
+                        .any(|entry| entry.in_function && normalize_path(&entry.target) == current)
+                });
+                let defines = self
+                    .variables
+                    .get(includer)
+                    .is_some_and(|variables| variables.contains(name));
+                if in_function || defines {
+                    return true;
+                }
+                queue.push(includer.clone());
+            }
+        }
+        false
+    }
+
+    pub fn get_includes(&self, file: &Path) -> Vec<IncludeData> {
+        self.includes
+            .get(&normalize_path(file))
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// The files which directly includes `file`
+    pub fn get_included_by(&self, file: &Path) -> Vec<PathBuf> {
+        let mut files: Vec<_> = self
+            .included_by
+            .get(&normalize_path(file))
+            .map(|f| f.iter().cloned().collect())
+            .unwrap_or_default();
+        files.sort();
+        files
+    }
+
+    pub fn is_included(&self, file: &Path) -> bool {
+        self.included_by.contains_key(&normalize_path(file))
+    }
+
+    /// Every file which directly or indirectly includes `file`, and therefore might be
+    /// affected by changes to it
+    pub fn get_dependents(&self, file: &Path) -> Vec<PathBuf> {
+        let mut seen = HashSet::new();
+        let mut queue = vec![normalize_path(file)];
+        while let Some(current) = queue.pop() {
+            if let Some(included_by) = self.included_by.get(&current) {
+                for includer in included_by {
+                    if seen.insert(includer.clone()) {
+                        queue.push(includer.clone());
+                    }
+                }
+            }
+        }
+        let mut files: Vec<_> = seen.into_iter().collect();
+        files.sort();
+        files
+    }
+}
+
+/// Resolves `.` and `..` without touching the file system
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            c => normalized.push(c.as_os_str()),
+        }
+    }
+    normalized
+}
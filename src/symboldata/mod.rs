@@ -1,7 +1,11 @@
+use crate::locks::RecoverableLock;
 use tree_sitter::Point;
 //use tree_sitter::Range;
+use crate::analysis::taint::TaintData;
 use crate::issue::IssuePosition;
+use crate::native::refinements::FunctionRefinement;
 use crate::parser::Range;
+use crate::phpdoc::types::PHPDocComment;
 use crate::symbols::FullyQualifiedName;
 use crate::symbols::Name;
 use crate::symbols::Symbol;
@@ -9,13 +13,20 @@ use crate::symbols::SymbolClass;
 use crate::types::union::PHPType;
 use crate::value::PHPValue;
 
+use self::assertions::AssertionData;
+use self::attributes::AttributeData;
+use self::attributes::HasAttributes;
 use self::class::ClassName;
 use self::class::ClassType;
 use self::class::FunctionArgumentData;
 use self::class::InterfaceData;
 use self::class::PropertyData;
 use self::class::TraitData;
+use self::includes::IncludeGraph;
+use self::templates::TemplateData;
+use self::usage::SymbolUsage;
 use std::collections::HashMap;
+use std::collections::HashSet;
 
 use std::ffi::OsString;
 use std::sync::Arc;
@@ -23,7 +34,13 @@ use std::sync::RwLock;
 
 use self::class::MethodData;
 
+pub mod assertions;
+pub mod attributes;
 pub mod class;
+pub mod includes;
+pub mod templates;
+pub mod traits;
+pub mod usage;
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
 pub struct FilePosition {
@@ -82,6 +99,20 @@ impl From<IssuePosition> for FileLocation {
     }
 }
 
+impl From<FileLocation> for IssuePosition {
+    fn from(location: FileLocation) -> Self {
+        Self {
+            uri: location.uri,
+            range: Range {
+                start_byte: location.start.byte,
+                end_byte: location.end.byte,
+                start_point: Point::new(location.start.line, location.start.column),
+                end_point: Point::new(location.end.line, location.end.column),
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FunctionData {
     pub name: FullyQualifiedName,
@@ -96,12 +127,42 @@ pub struct FunctionData {
     pub return_value: Option<PHPValue>,
     pub overload_map: HashMap<Vec<PHPValue>, Option<PHPType>>,
     pub generic_templates: Option<Vec<Name>>,
+    /// Bounds, variance and defaults of the `generic_templates`
+    pub template_data: Vec<TemplateData>,
+    /// Types asserted for the parameters with `@assert` and similar
+    pub assertions: Vec<AssertionData>,
+    pub phpdoc: Option<PHPDocComment>,
+    pub attributes: Vec<AttributeData>,
+    /// Provided by PHP, an extension or a stub-file
+    pub is_native: bool,
+}
+
+impl HasAttributes for FunctionData {
+    fn get_attributes(&self) -> &[AttributeData] {
+        &self.attributes
+    }
+}
+
+/// Loads the declarations of classes which aren't known yet, like an autoloader
+pub trait ClassResolver: Send + Sync + std::fmt::Debug {
+    /// Indexes the file declaring the class into `symbol_data`. Returns whether it was found
+    fn resolve_class(&self, symbol_data: Arc<SymbolData>, fq_name: &FullyQualifiedName) -> bool;
 }
 
 #[derive(Debug)]
 pub struct SymbolData {
     pub classes: Arc<RwLock<HashMap<FullyQualifiedName, Arc<RwLock<ClassType>>>>>,
     pub functions: Arc<RwLock<HashMap<FullyQualifiedName, Arc<RwLock<FunctionData>>>>>,
+    pub usage: Arc<RwLock<SymbolUsage>>,
+    pub includes: Arc<RwLock<IncludeGraph>>,
+    /// Rules for functions whose types depend on the arguments, by lowercased name
+    pub function_refinements: Arc<RwLock<HashMap<FullyQualifiedName, FunctionRefinement>>>,
+    /// Hook for indexing the files of unknown classes on demand
+    pub class_resolver: Arc<RwLock<Option<Arc<dyn ClassResolver>>>>,
+    /// Lowercased names of the classes the resolver has been asked for
+    pub resolved_class_names: Arc<RwLock<HashSet<FullyQualifiedName>>>,
+    /// User input returned from functions and stored in properties
+    pub taint: Arc<RwLock<TaintData>>,
 }
 
 impl Default for SymbolData {
@@ -116,21 +177,87 @@ impl SymbolData {
             classes: Arc::new(RwLock::new(HashMap::new())),
             // methods: Arc::new(RwLock::new(HashMap::new())),
             functions: Arc::new(RwLock::new(HashMap::new())),
+            usage: Arc::new(RwLock::new(SymbolUsage::new())),
+            includes: Arc::new(RwLock::new(IncludeGraph::new())),
+            function_refinements: Arc::new(RwLock::new(HashMap::new())),
+            class_resolver: Arc::new(RwLock::new(None)),
+            resolved_class_names: Arc::new(RwLock::new(HashSet::new())),
+            taint: Arc::new(RwLock::new(TaintData::new())),
         }
     }
 
+    /// A handle to the same data, as all of it is shared
+    fn share(&self) -> Arc<SymbolData> {
+        Arc::new(SymbolData {
+            classes: self.classes.clone(),
+            functions: self.functions.clone(),
+            usage: self.usage.clone(),
+            includes: self.includes.clone(),
+            function_refinements: self.function_refinements.clone(),
+            class_resolver: self.class_resolver.clone(),
+            resolved_class_names: self.resolved_class_names.clone(),
+            taint: self.taint.clone(),
+        })
+    }
+
+    ///
+    /// Sets the hook which is asked to load a class, when it isn't known. The lookup is
+    /// retried afterwards, so a subset of a project can be analyzed without reporting the
+    /// classes of the other files as unknown.
+    ///
+    pub fn set_class_resolver(&self, resolver: Arc<dyn ClassResolver>) {
+        *self.class_resolver.write_or_recover() = Some(resolver);
+    }
+
+    /// Registers a rule for computing types of calls to the function, replacing any earlier
+    pub fn register_function_refinement(
+        &self,
+        fname: FullyQualifiedName,
+        refinement: FunctionRefinement,
+    ) {
+        self.function_refinements
+            .write_or_recover()
+            .insert(fname.to_ascii_lowercase(), refinement);
+    }
+
+    pub fn get_function_refinement(
+        &self,
+        fname: &FullyQualifiedName,
+    ) -> Option<FunctionRefinement> {
+        self.function_refinements
+            .read_or_recover()
+            .get(&fname.to_ascii_lowercase())
+            .copied()
+    }
+
     pub fn get_class(&self, name: &ClassName) -> Option<Arc<RwLock<ClassType>>> {
-        self.classes
-            .read()
-            .unwrap()
-            .get(&name.get_fq_name().to_ascii_lowercase())
-            .cloned()
+        let lc_name = name.get_fq_name().to_ascii_lowercase();
+        let class = self.classes.read_or_recover().get(&lc_name).cloned();
+        if class.is_some() || !self.resolve_class(name.get_fq_name()) {
+            return class;
+        }
+        self.classes.read_or_recover().get(&lc_name).cloned()
+    }
+
+    /// Asks the class resolver to load the class. Each class is only tried once
+    fn resolve_class(&self, fq_name: &FullyQualifiedName) -> bool {
+        let Some(resolver) = self.class_resolver.read_or_recover().clone() else {
+            return false;
+        };
+        if !self
+            .resolved_class_names
+            .write_or_recover()
+            .insert(fq_name.to_ascii_lowercase())
+        {
+            return false;
+        }
+        resolver.resolve_class(self.share(), fq_name)
     }
 
     pub fn get_trait(&self, name: &ClassName) -> Option<TraitData> {
         let locked_data = self.get_class(name)?;
 
-        let unlocked_data = locked_data.read().ok()?;
+        let unlocked_data = locked_data.read_or_recover();
         match &*unlocked_data {
             ClassType::Trait(tdata) => Some(tdata.clone()),
             _ => None,
@@ -140,7 +267,7 @@ impl SymbolData {
     pub fn get_interface(&self, name: &ClassName) -> Option<InterfaceData> {
         let locked_data = self.get_class(name)?;
 
-        let unlocked_data = locked_data.read().ok()?;
+        let unlocked_data = locked_data.read_or_recover();
         match &*unlocked_data {
             ClassType::Interface(idata) => Some(idata.clone()),
             _ => None,
@@ -149,14 +276,14 @@ impl SymbolData {
 
     pub fn get_or_create_class(&self, name: &ClassName) -> Arc<RwLock<ClassType>> {
         {
-            let reader = self.classes.read().unwrap();
+            let reader = self.classes.read_or_recover();
 
             if let Some(x) = reader.get(&name.get_fq_name().to_ascii_lowercase()) {
                 return x.clone();
             }
         }
         {
-            let mut writer = self.classes.write().unwrap();
+            let mut writer = self.classes.write_or_recover();
             // Noen kan han rukket å endre i mellomtiden
             if let Some(x) = writer.get(&name.get_fq_name().to_ascii_lowercase()) {
                 return x.clone();
@@ -168,17 +295,17 @@ impl SymbolData {
     }
 
     pub fn get_method(&self, class: &ClassName, method: &Name) -> Option<Arc<RwLock<MethodData>>> {
-        let handle = self.classes.read().unwrap();
+        let handle = self.classes.read_or_recover();
         let cdata = handle.get(&class.fq_name.to_ascii_lowercase())?.clone();
 
-        let reader = cdata.read().unwrap();
+        let reader = cdata.read_or_recover();
         reader.get_own_method(method)
     }
 
     pub fn get_function(&self, fname: &FullyQualifiedName) -> Option<FunctionData> {
-        let handle = self.functions.read().unwrap();
+        let handle = self.functions.read_or_recover();
         let fdata_handle = handle.get(fname)?;
-        let fdata = fdata_handle.read().unwrap();
+        let fdata = fdata_handle.read_or_recover();
         Some(fdata.clone())
     }
 
@@ -187,20 +314,18 @@ impl SymbolData {
         class: &ClassName,
         method: &Name,
         location: FileLocation,
-    ) -> Arc<RwLock<MethodData>> {
-        let handle = self.classes.read().unwrap();
-
-        let cdata = handle
-            .get(&class.get_fq_name().to_ascii_lowercase())
-            .unwrap_or_else(|| {
-                panic!(
-                    "The class {:?} must exist when attempting to register method {:?}",
-                    class.fq_name.clone(),
-                    method
-                )
-            });
-
-        let mut writable_cdata = cdata.write().unwrap();
+    ) -> Option<Arc<RwLock<MethodData>>> {
+        let handle = self.classes.read_or_recover();
+
+        let Some(cdata) = handle.get(&class.get_fq_name().to_ascii_lowercase()) else {
+            return crate::missing_none!(
+                "The class {:?} must exist when attempting to register method {:?}",
+                class.fq_name.clone(),
+                method
+            );
+        };
+
+        let mut writable_cdata = cdata.write_or_recover();
         writable_cdata.get_or_create_method(method, location)
     }
 
@@ -210,19 +335,17 @@ impl SymbolData {
         property_name: &Name,
         location: FileLocation,
     ) -> Option<Arc<RwLock<PropertyData>>> {
-        let handle = self.classes.read().unwrap();
-
-        let cdata = handle
-            .get(&class.get_fq_name().to_ascii_lowercase())
-            .unwrap_or_else(|| {
-                panic!(
-                    "The class {:?} must exist when attempting to register property {:?}",
-                    class.fq_name.clone(),
-                    property_name
-                )
-            });
-
-        let mut writable_cdata = cdata.write().unwrap();
+        let handle = self.classes.read_or_recover();
+
+        let Some(cdata) = handle.get(&class.get_fq_name().to_ascii_lowercase()) else {
+            return crate::missing_none!(
+                "The class {:?} must exist when attempting to register property {:?}",
+                class.fq_name.clone(),
+                property_name
+            );
+        };
+
+        let mut writable_cdata = cdata.write_or_recover();
         writable_cdata.get_or_create_property(property_name, location)
     }
 }
@@ -245,7 +368,7 @@ impl ArcedSymbolAccess for Arc<SymbolData> {
         match symbol {
             Symbol::Class(class_info) => {
                 let class_handle = self.get_class_for_symbol(&class_info)?;
-                let class = class_handle.read().ok()?;
+                let class = class_handle.read_or_recover();
                 match &*class {
                     ClassType::None => None,
                     ClassType::Class(c) => Some(vec![c.position.clone()]),
@@ -255,7 +378,7 @@ impl ArcedSymbolAccess for Arc<SymbolData> {
             }
             Symbol::Method(m) => {
                 let class_handle = self.get_class_for_symbol(&m.class)?;
-                let class = class_handle.read().ok()?;
+                let class = class_handle.read_or_recover();
                 let method_data = class.get_method(&m.name, self.clone())?;
                 Some(vec![method_data.position.clone()])
             }
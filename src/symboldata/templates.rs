@@ -0,0 +1,329 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use crate::{
+    analysis::state::AnalysisState,
+    issue::IssueEmitter,
+    parser::Range,
+    phpdoc::types::{PHPDocComment, PHPDocEntry, PHPDocTemplate, TemplateVariance},
+    symbols::Name,
+    types::{
+        type_operators::TypeOperator,
+        type_parser::TypeParser,
+        union::{DiscreteType, DiscretlyAccessedType, PHPType, UnionType},
+    },
+};
+
+use super::{FileLocation, SymbolData};
+
+/// A template declared with `@template`
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct TemplateData {
+    pub name: Name,
+    pub variance: TemplateVariance,
+    /// Declared with `of` or `as`. Concrete types must be subtypes of it
+    pub bound: Option<PHPType>,
+    /// Used when the template can't be inferred
+    pub default: Option<PHPType>,
+    pub position: FileLocation,
+}
+
+impl TemplateData {
+    ///
+    /// `templates` are the templates which the bound and the default are allowed to
+    /// refer to
+    ///
+    pub(crate) fn from_phpdoc(
+        template: &PHPDocTemplate,
+        range: Range,
+        templates: &Vec<Name>,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+    ) -> Self {
+        let mut parse = |ctype: &Option<_>| {
+            ctype.clone().and_then(|ctype| {
+                TypeParser::from_parsed_type(ctype, state, emitter, Some(templates))
+            })
+        };
+        let bound = parse(&template.bound);
+        let default = parse(&template.default);
+        Self {
+            name: (&template.name).into(),
+            variance: template.variance,
+            bound,
+            default,
+            position: FileLocation::new(state.pos_from_range(range)),
+        }
+    }
+
+    /// Whether `concrete` is within the bound. `None` if we can't tell
+    pub fn accepts(&self, concrete: &PHPType, symbol_data: &Arc<SymbolData>) -> Option<bool> {
+        let Some(bound) = &self.bound else {
+            return Some(true);
+        };
+        if concrete.contains_template() || bound.contains_template() {
+            return None;
+        }
+        let mut result = Some(true);
+        for variant in concrete.as_discrete_variants() {
+            let DiscretlyAccessedType::Discrete(variant) = variant else {
+                result = None;
+                continue;
+            };
+            // A generic class is within the bound when the class is
+            let variant = match variant {
+                DiscreteType::Generic(base, _) => *base,
+                variant => variant,
+            };
+            match PHPType::from(variant).can_be_passed_as(bound, symbol_data) {
+                Some(true) => (),
+                Some(false) => return Some(false),
+                None => result = None,
+            }
+        }
+        result
+    }
+}
+
+/// Data of the templates declared in the doc-comment, for those among `templates`
+pub(crate) fn get_phpdoc_template_data(
+    doc_comment: &PHPDocComment,
+    templates: &Vec<Name>,
+    state: &mut AnalysisState,
+    emitter: &dyn IssueEmitter,
+) -> Vec<TemplateData> {
+    let mut template_data: Vec<TemplateData> = vec![];
+    for entry in &doc_comment.entries {
+        let PHPDocEntry::Template(range, template) = entry else {
+            continue;
+        };
+        let name: Name = (&template.name).into();
+        if !templates.contains(&name) || template_data.iter().any(|t| t.name == name) {
+            continue;
+        }
+        template_data.push(TemplateData::from_phpdoc(
+            template, *range, templates, state, emitter,
+        ));
+    }
+    template_data
+}
+
+///
+/// Templates which are given a concrete type outside of their bound
+/// *  .0 = Name of the template
+/// *  .1 = Bound
+/// *  .2 = Concrete type
+///
+pub(crate) fn get_bound_violations(
+    templates: &[TemplateData],
+    concrete: &BTreeMap<Name, PHPType>,
+    symbol_data: &Arc<SymbolData>,
+) -> Vec<(Name, PHPType, PHPType)> {
+    templates
+        .iter()
+        .filter_map(|template| {
+            let bound = template.bound.as_ref()?;
+            let concrete_type = concrete.get(&template.name)?;
+            match template.accepts(concrete_type, symbol_data) {
+                Some(false) => Some((template.name.clone(), bound.clone(), concrete_type.clone())),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Maps the templates to the generic arguments given, like in `@extends Base<Foo>`
+pub(crate) fn get_generic_concretes(
+    templates: &[TemplateData],
+    generic_args: &[PHPType],
+) -> BTreeMap<Name, PHPType> {
+    let mut concrete = BTreeMap::new();
+    for (template, generic_arg) in templates.iter().zip(generic_args) {
+        concrete.insert(template.name.clone(), generic_arg.clone());
+    }
+    apply_template_defaults(&mut concrete, templates);
+    concrete
+}
+
+/// Templates which couldn't be inferred get their default type, if they have any
+pub(crate) fn apply_template_defaults(
+    concrete: &mut BTreeMap<Name, PHPType>,
+    templates: &[TemplateData],
+) {
+    for template in templates {
+        if concrete.contains_key(&template.name) {
+            continue;
+        }
+        if let Some(default) = &template.default {
+            let default = default.concretize_templates(concrete);
+            concrete.insert(template.name.clone(), default);
+        }
+    }
+}
+
+///
+/// Infers the concrete type of the templates in `templated_type`, like the parameter type
+/// `list<T>`, from the type passed, like `list<int>`. When a template is inferred from
+/// several places, the concrete type becomes the union of them.
+///
+pub(crate) fn infer_template_types(
+    concrete: &mut BTreeMap<Name, PHPType>,
+    templated_type: &PHPType,
+    in_type: &PHPType,
+) {
+    if !templated_type.contains_template() {
+        return;
+    }
+    let templated_variants = templated_type.as_discrete_variants();
+    if templated_variants.len() != 1 {
+        // With `?T` or `T|false`, T is what's left when the other types are removed
+        let mut with_template = vec![];
+        let mut without_template = vec![];
+        for variant in templated_variants {
+            match variant {
+                DiscretlyAccessedType::Discrete(d) if d.contains_template() => {
+                    with_template.push(d)
+                }
+                DiscretlyAccessedType::Discrete(d) => without_template.push(d),
+                DiscretlyAccessedType::Intersection(_) => (),
+            }
+        }
+        let [templated] = &with_template[..] else {
+            crate::missing!("Infer templates from unions with several templates");
+            return;
+        };
+        let remaining: UnionType = in_type
+            .as_discrete_variants()
+            .into_iter()
+            .filter_map(|variant| match variant {
+                DiscretlyAccessedType::Discrete(d) => Some(d),
+                DiscretlyAccessedType::Intersection(_) => None,
+            })
+            .filter(|d| !without_template.iter().any(|w| w.is_same_type(d)))
+            .collect();
+        if !remaining.is_empty() {
+            infer_template_types(concrete, &templated.clone().into(), &remaining.into());
+        }
+        return;
+    }
+    let Some(DiscretlyAccessedType::Discrete(templated)) = templated_variants.first() else {
+        return;
+    };
+
+    let value_of = || TypeOperator::ValueOf(in_type.clone()).evaluate();
+    let key_of = || TypeOperator::KeyOf(in_type.clone()).evaluate();
+
+    match templated {
+        DiscreteType::Template(name) => {
+            let in_type = generalize_literals(in_type);
+            let inferred = match concrete.remove(name) {
+                Some(earlier) if earlier != in_type => {
+                    UnionType::from_pair(earlier, in_type).into()
+                }
+                _ => in_type,
+            };
+            concrete.insert(name.clone(), inferred);
+        }
+        DiscreteType::Vector(vtype)
+        | DiscreteType::List(vtype)
+        | DiscreteType::NonEmptyList(vtype) => {
+            if let Some(in_value) = value_of() {
+                infer_template_types(concrete, vtype, &in_value);
+            }
+        }
+        DiscreteType::HashMap(ktype, vtype) | DiscreteType::NonEmptyArray(ktype, vtype) => {
+            if let Some(in_key) = key_of() {
+                infer_template_types(concrete, ktype, &in_key);
+            }
+            if let Some(in_value) = value_of() {
+                infer_template_types(concrete, vtype, &in_value);
+            }
+        }
+        DiscreteType::Generic(base, generic_args) => {
+            let Some(DiscreteType::Generic(in_base, in_args)) = in_type.single_type() else {
+                return;
+            };
+            if !base.is_same_type(&in_base) {
+                crate::missing!("Infer templates from generic subclasses");
+                return;
+            }
+            for (generic_arg, in_arg) in generic_args.iter().zip(in_args.iter()) {
+                infer_template_types(concrete, generic_arg, in_arg);
+            }
+        }
+        _ => (),
+    }
+}
+
+///
+/// Whether `ptype` uses the template named `template` where its `variance` doesn't allow
+/// it. `position` is the variance of where `ptype` is used: covariant for return types,
+/// contravariant for parameters and invariant for writable properties. The parameters of
+/// a typed callable flip the position
+///
+pub(crate) fn violates_variance(
+    ptype: &PHPType,
+    template: &Name,
+    variance: TemplateVariance,
+    position: TemplateVariance,
+) -> bool {
+    if variance == TemplateVariance::Invariant {
+        return false;
+    }
+    ptype
+        .as_discrete_variants()
+        .iter()
+        .any(|variant| match variant {
+            DiscretlyAccessedType::Discrete(d) => {
+                discrete_violates_variance(d, template, variance, position)
+            }
+            DiscretlyAccessedType::Intersection(_) => false,
+        })
+}
+
+fn discrete_violates_variance(
+    dtype: &DiscreteType,
+    template: &Name,
+    variance: TemplateVariance,
+    position: TemplateVariance,
+) -> bool {
+    let violates = |t: &PHPType| violates_variance(t, template, variance, position);
+    match dtype {
+        DiscreteType::Template(name) => name == template && position != variance,
+        DiscreteType::Vector(t) | DiscreteType::List(t) | DiscreteType::NonEmptyList(t) => {
+            violates(t)
+        }
+        DiscreteType::HashMap(k, v) | DiscreteType::NonEmptyArray(k, v) => {
+            violates(k) || violates(v)
+        }
+        DiscreteType::Generic(_, generic_args) => generic_args.iter().any(violates),
+        DiscreteType::TypedCallable(params, return_type) => {
+            let flipped = match position {
+                TemplateVariance::Invariant => TemplateVariance::Invariant,
+                TemplateVariance::Covariant => TemplateVariance::Contravariant,
+                TemplateVariance::Contravariant => TemplateVariance::Covariant,
+            };
+            params
+                .iter()
+                .any(|t| violates_variance(t, template, variance, flipped))
+                || violates(return_type)
+        }
+        _ => false,
+    }
+}
+
+///
+/// Templates are inferred as `int` rather than `42` when passed a literal, as the literal
+/// is seldom what is intended
+///
+fn generalize_literals(ptype: &PHPType) -> PHPType {
+    let generalized = ptype.map(&|dtype| match dtype {
+        DiscreteType::IntLiteral(_) | DiscreteType::IntRange(_, _) => DiscreteType::Int,
+        DiscreteType::StringLiteral(_) => DiscreteType::String,
+        DiscreteType::True | DiscreteType::False => DiscreteType::Bool,
+        dtype => dtype,
+    });
+    match generalized.single_type() {
+        Some(single) => single.into(),
+        None => generalized,
+    }
+}
@@ -0,0 +1,357 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, OnceLock, RwLock},
+};
+
+use crate::{
+    locks::RecoverableLock,
+    symbols::{FullyQualifiedName, Name},
+    types::union::{DiscreteType, PHPType, SpecialType},
+};
+
+use super::{
+    class::{ClassMemberVisibility, ClassModifier, ClassName, MethodData, PropertyData},
+    FileLocation, SymbolData,
+};
+
+/// Traits using traits using traits... We stop following imports at this depth, to
+/// avoid looping forever on cyclic imports
+const MAX_TRAIT_DEPTH: usize = 16;
+
+#[derive(Clone, Debug)]
+pub struct TraitImport {
+    pub trait_name: ClassName,
+    pub position: FileLocation,
+}
+
+/// `TraitA::method insteadof TraitB;`
+#[derive(Clone, Debug)]
+pub struct TraitInsteadOf {
+    pub trait_name: ClassName,
+    pub method_name: Name,
+    pub instead_of: Vec<ClassName>,
+    pub position: FileLocation,
+}
+
+/// `[TraitA::]method as [visibility] [alias];`
+#[derive(Clone, Debug)]
+pub struct TraitAlias {
+    pub trait_name: Option<ClassName>,
+    pub method_name: Name,
+    pub alias: Option<Name>,
+    pub visibility: Option<ClassMemberVisibility>,
+    pub position: FileLocation,
+}
+
+/// All `use SomeTrait ...;` declarations inside a class or trait body
+#[derive(Clone, Debug, Default)]
+pub struct TraitUses {
+    pub imports: Vec<TraitImport>,
+    pub insteadofs: Vec<TraitInsteadOf>,
+    pub aliases: Vec<TraitAlias>,
+    /// The result of [`TraitUses::resolve_methods`], once all imported traits are known
+    resolved: OnceLock<Arc<ResolvedTraitMethods>>,
+}
+
+/// A method as it appears in the using class, after `insteadof` and `as` has been applied
+#[derive(Clone, Debug)]
+pub struct ImportedTraitMethod {
+    pub trait_name: ClassName,
+    pub method: Arc<RwLock<MethodData>>,
+    /// New name given with `as`
+    pub alias: Option<Name>,
+    /// New visibility given with `as`
+    pub visibility: Option<ClassMemberVisibility>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ResolvedTraitMethods {
+    /// Keyed on the lowercased method name (or alias)
+    pub methods: BTreeMap<Name, ImportedTraitMethod>,
+    /// Methods provided by more than one trait, which isn't resolved with `insteadof`
+    pub conflicts: BTreeMap<Name, Vec<ClassName>>,
+    /// Every imported trait was found, so the result won't change as more files are indexed
+    complete: bool,
+}
+
+fn same_class(a: &ClassName, b: &ClassName) -> bool {
+    a.fq_name.to_ascii_lowercase() == b.fq_name.to_ascii_lowercase()
+}
+
+impl TraitUses {
+    pub fn is_empty(&self) -> bool {
+        self.imports.is_empty()
+    }
+
+    /// Append the imports, `insteadof` and `as` rules of another `use` declaration
+    pub fn extend(&mut self, mut uses: TraitUses) {
+        self.imports.append(&mut uses.imports);
+        self.insteadofs.append(&mut uses.insteadofs);
+        self.aliases.append(&mut uses.aliases);
+        self.resolved = OnceLock::new();
+    }
+
+    pub fn resolve_methods(&self, symbol_data: &Arc<SymbolData>) -> Arc<ResolvedTraitMethods> {
+        if let Some(resolved) = self.resolved.get() {
+            return resolved.clone();
+        }
+        let resolved = Arc::new(self.resolve_methods_with_depth(symbol_data, 0));
+        if resolved.complete {
+            // Another thread might have got here first, with the same result
+            let _ = self.resolved.set(resolved.clone());
+        }
+        resolved
+    }
+
+    pub(crate) fn resolve_methods_with_depth(
+        &self,
+        symbol_data: &Arc<SymbolData>,
+        depth: usize,
+    ) -> ResolvedTraitMethods {
+        let mut resolved = ResolvedTraitMethods {
+            complete: true,
+            ..Default::default()
+        };
+        if depth > MAX_TRAIT_DEPTH {
+            return resolved;
+        }
+
+        let mut candidates: BTreeMap<Name, Vec<ImportedTraitMethod>> = BTreeMap::new();
+        for import in &self.imports {
+            let Some((methods, complete)) =
+                Self::get_trait_methods(&import.trait_name, symbol_data, depth)
+            else {
+                resolved.complete = false;
+                continue;
+            };
+            resolved.complete &= complete;
+            for (lc_name, method) in methods {
+                if self.is_excluded(&import.trait_name, &lc_name) {
+                    continue;
+                }
+                candidates.entry(lc_name).or_default().push(method);
+            }
+        }
+
+        for alias in &self.aliases {
+            let Some(mut source) = self.find_alias_source(alias, symbol_data, depth) else {
+                continue;
+            };
+            if alias.visibility.is_some() {
+                source.visibility.clone_from(&alias.visibility);
+            }
+            if let Some(alias_name) = &alias.alias {
+                source.alias = Some(alias_name.clone());
+                candidates
+                    .entry(alias_name.to_ascii_lowercase())
+                    .or_default()
+                    .push(source);
+            } else if let Some(entries) =
+                candidates.get_mut(&alias.method_name.to_ascii_lowercase())
+            {
+                // Only a change of visibility on the original method
+                for entry in entries.iter_mut() {
+                    if same_class(&entry.trait_name, &source.trait_name) {
+                        entry.visibility.clone_from(&source.visibility);
+                    }
+                }
+            }
+        }
+
+        for (lc_name, mut entries) in candidates {
+            if entries.len() > 1 {
+                // Abstract methods are satisfied by a concrete method from another trait
+                let concrete: Vec<_> = entries
+                    .iter()
+                    .filter(|e| e.method.read_or_recover().modifier != ClassModifier::Abstract)
+                    .cloned()
+                    .collect();
+                if !concrete.is_empty() {
+                    entries = concrete;
+                } else {
+                    entries.truncate(1);
+                }
+            }
+            if entries.len() > 1 {
+                resolved.conflicts.insert(
+                    lc_name.clone(),
+                    entries.iter().map(|e| e.trait_name.clone()).collect(),
+                );
+            }
+            resolved.methods.insert(lc_name, entries.swap_remove(0));
+        }
+        resolved
+    }
+
+    /// Look up a property in the imported traits, together with the trait declaring it
+    pub fn get_property(
+        &self,
+        property_name: &Name,
+        symbol_data: &Arc<SymbolData>,
+        depth: usize,
+    ) -> Option<(ClassName, PropertyData)> {
+        if depth > MAX_TRAIT_DEPTH {
+            return None;
+        }
+        for import in &self.imports {
+            let Some(tdata) = symbol_data.get_trait(&import.trait_name) else {
+                continue;
+            };
+            if let Some(pdata) = tdata.properties.get(property_name) {
+                return Some((tdata.trait_name.clone(), pdata.read_or_recover().clone()));
+            }
+            if let Some(found) = tdata
+                .traits
+                .get_property(property_name, symbol_data, depth + 1)
+            {
+                return Some(found);
+            }
+        }
+        None
+    }
+
+    /// Is `TraitB::method` excluded by a `TraitA::method insteadof TraitB`
+    fn is_excluded(&self, trait_name: &ClassName, lc_method_name: &Name) -> bool {
+        self.insteadofs.iter().any(|rule| {
+            rule.method_name.to_ascii_lowercase() == *lc_method_name
+                && rule.instead_of.iter().any(|t| same_class(t, trait_name))
+        })
+    }
+
+    fn find_alias_source(
+        &self,
+        alias: &TraitAlias,
+        symbol_data: &Arc<SymbolData>,
+        depth: usize,
+    ) -> Option<ImportedTraitMethod> {
+        let lc_method_name = alias.method_name.to_ascii_lowercase();
+        for import in &self.imports {
+            if let Some(tname) = &alias.trait_name {
+                if !same_class(tname, &import.trait_name) {
+                    continue;
+                }
+            }
+            let Some((methods, _)) =
+                Self::get_trait_methods(&import.trait_name, symbol_data, depth)
+            else {
+                continue;
+            };
+            if let Some((_, method)) = methods.into_iter().find(|(n, _)| *n == lc_method_name) {
+                return Some(method);
+            }
+        }
+        None
+    }
+
+    /// All methods of a trait, including those it imports from other traits, as imported
+    /// from `trait_name`. The flag tells if all traits imported by the trait were found
+    fn get_trait_methods(
+        trait_name: &ClassName,
+        symbol_data: &Arc<SymbolData>,
+        depth: usize,
+    ) -> Option<(Vec<(Name, ImportedTraitMethod)>, bool)> {
+        let tdata = symbol_data.get_trait(trait_name)?;
+        let nested = tdata
+            .traits
+            .resolve_methods_with_depth(symbol_data, depth + 1);
+        let mut methods = nested.methods;
+        for (lc_name, method) in &tdata.methods {
+            methods.insert(
+                lc_name.clone(),
+                ImportedTraitMethod {
+                    trait_name: trait_name.clone(),
+                    method: method.clone(),
+                    alias: None,
+                    visibility: None,
+                },
+            );
+        }
+        let methods = methods
+            .into_iter()
+            .map(|(lc_name, mut method)| {
+                method.trait_name = trait_name.clone();
+                (lc_name, method)
+            })
+            .collect();
+        Some((methods, nested.complete))
+    }
+}
+
+impl ImportedTraitMethod {
+    /// The current data of the method, with the name and visibility given with `as`
+    pub fn get_method_data(&self) -> MethodData {
+        let mut mdata = self.method.read_or_recover().clone();
+        if let Some(alias) = &self.alias {
+            mdata.name = alias.clone();
+        }
+        if let Some(visibility) = &self.visibility {
+            mdata.visibility = visibility.clone();
+        }
+        mdata
+    }
+}
+
+/// Inside a trait, `self` and `static` refers to the class using the trait. This rewrites
+/// a type declared in `trait_name`, as seen from `class_name`
+fn map_self_type(utype: &PHPType, trait_name: &ClassName, class_name: &ClassName) -> PHPType {
+    let trait_fq_name = trait_name.fq_name.to_ascii_lowercase();
+    utype.map(&|dtype: DiscreteType| -> DiscreteType {
+        match dtype {
+            DiscreteType::Special(SpecialType::Self_)
+            | DiscreteType::Special(SpecialType::Static) => class_name.clone().into(),
+            DiscreteType::Named(name, fq_name)
+                if fq_name.to_ascii_lowercase() == trait_fq_name
+                    || name.eq_ignore_ascii_case("self")
+                    || name.eq_ignore_ascii_case("static") =>
+            {
+                class_name.clone().into()
+            }
+            dtype => dtype,
+        }
+    })
+}
+
+impl MethodData {
+    /// Rewrites the types of a method declared in a trait, as seen from `class_name`
+    pub(crate) fn with_self_as(&self, class_name: &ClassName) -> MethodData {
+        let map_type = |utype: &PHPType| map_self_type(utype, &self.declared_in, class_name);
+
+        let mut mdata = self.clone();
+        mdata.php_return_type = self.php_return_type.as_ref().map(map_type);
+        mdata.comment_return_type = self
+            .comment_return_type
+            .as_ref()
+            .map(|(t, r)| (map_type(t), *r));
+        mdata.inferred_return_type = self.inferred_return_type.as_ref().map(map_type);
+        for arg in &mut mdata.arguments {
+            arg.arg_type = arg.arg_type.as_ref().map(map_type);
+            arg.phpdoc_type = arg.phpdoc_type.as_ref().map(map_type);
+        }
+        mdata
+    }
+}
+
+impl PropertyData {
+    /// Rewrites the types of a property declared in `trait_name`, as seen from `class_name`
+    pub(crate) fn with_self_as(&self, trait_name: &ClassName, class_name: &ClassName) -> Self {
+        let map_type = |utype: &PHPType| map_self_type(utype, trait_name, class_name);
+
+        let mut pdata = self.clone();
+        pdata.declared_type = self.declared_type.as_ref().map(map_type);
+        pdata.comment_type = self.comment_type.as_ref().map(|(t, r)| (map_type(t), *r));
+        pdata.constructor_type = self.constructor_type.as_ref().map(map_type);
+        for (utype, _) in &mut pdata.written_data {
+            *utype = map_type(utype);
+        }
+        pdata
+    }
+}
+
+impl ResolvedTraitMethods {
+    pub fn get_conflicting_traits(&self, lc_method_name: &Name) -> Vec<FullyQualifiedName> {
+        self.conflicts
+            .get(lc_method_name)
+            .map(|traits| traits.iter().map(|t| t.fq_name.clone()).collect())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::HashSet;
+
+use crate::symbols::{FullyQualifiedName, Name};
+
+use super::class::ClassName;
+
+/// References to symbols seen during analysis. This is what the dead-code-analysis
+/// uses to decide if a symbol is in use or not.
+///
+/// All names are stored lowercased, as PHP treats them case insensitive.
+#[derive(Debug, Default)]
+pub struct SymbolUsage {
+    classes: HashSet<FullyQualifiedName>,
+    functions: HashSet<FullyQualifiedName>,
+    /// Methods called on an object or class of known type
+    methods: HashSet<(FullyQualifiedName, Name)>,
+    /// Methods called on something we couldn't determine the type of
+    untyped_methods: HashSet<Name>,
+    /// Properties read from something we couldn't determine the type of
+    untyped_properties: HashSet<Name>,
+    constants: HashSet<(FullyQualifiedName, Name)>,
+}
+
+impl SymbolUsage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn mark_class(&mut self, class_name: &ClassName) {
+        self.classes
+            .insert(class_name.get_fq_name().to_ascii_lowercase());
+    }
+
+    pub fn mark_function(&mut self, fq_name: &FullyQualifiedName) {
+        self.functions.insert(fq_name.to_ascii_lowercase());
+    }
+
+    /// Register a method call. `None` as class name means that the type of the
+    /// object isn't known, so any method with this name might be the one called.
+    pub fn mark_method(&mut self, class_name: Option<&ClassName>, method_name: &Name) {
+        let lc_method_name = method_name.to_ascii_lowercase();
+        if let Some(class_name) = class_name {
+            self.methods.insert((
+                class_name.get_fq_name().to_ascii_lowercase(),
+                lc_method_name,
+            ));
+        } else {
+            self.untyped_methods.insert(lc_method_name);
+        }
+    }
+
+    /// Register a read of a property on an object of unknown type. Reads of known
+    /// properties are counted in `PropertyData::read_from`
+    pub fn mark_untyped_property(&mut self, property_name: &Name) {
+        self.untyped_properties
+            .insert(property_name.to_ascii_lowercase());
+    }
+
+    pub fn mark_constant(&mut self, class_name: &ClassName, constant_name: &Name) {
+        self.constants.insert((
+            class_name.get_fq_name().to_ascii_lowercase(),
+            constant_name.to_ascii_lowercase(),
+        ));
+    }
+
+    pub fn is_class_used(&self, fq_name: &FullyQualifiedName) -> bool {
+        self.classes.contains(&fq_name.to_ascii_lowercase())
+    }
+
+    pub fn is_function_used(&self, fq_name: &FullyQualifiedName) -> bool {
+        self.functions.contains(&fq_name.to_ascii_lowercase())
+    }
+
+    /// Whether the method has been called on the class itself, or on something we
+    /// couldn't determine the type of
+    pub fn is_method_used(&self, fq_name: &FullyQualifiedName, method_name: &Name) -> bool {
+        let lc_method_name = method_name.to_ascii_lowercase();
+        self.untyped_methods.contains(&lc_method_name)
+            || self
+                .methods
+                .contains(&(fq_name.to_ascii_lowercase(), lc_method_name))
+    }
+
+    pub fn is_untyped_property_used(&self, property_name: &Name) -> bool {
+        self.untyped_properties
+            .contains(&property_name.to_ascii_lowercase())
+    }
+
+    pub fn is_constant_used(&self, fq_name: &FullyQualifiedName, constant_name: &Name) -> bool {
+        self.constants.contains(&(
+            fq_name.to_ascii_lowercase(),
+            constant_name.to_ascii_lowercase(),
+        ))
+    }
+}
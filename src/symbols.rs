@@ -374,7 +374,12 @@ impl From<DiscreteType> for Symbol {
             DiscreteType::HashMap(_, _) => Symbol::Native("array"),
             DiscreteType::Special(SpecialType::Static) => Symbol::Native("static"),
             DiscreteType::Special(SpecialType::Self_) => Symbol::Native("self"),
-            DiscreteType::Special(_c @ SpecialType::ClassString(_)) => {
+            DiscreteType::Special(
+                _c @ (SpecialType::ClassString(_)
+                | SpecialType::NonEmptyString
+                | SpecialType::NumericString
+                | SpecialType::CallableString),
+            ) => {
                 // FIXME this should be something more precise?
                 Symbol::Native("string")
             }
@@ -383,10 +388,18 @@ impl From<DiscreteType> for Symbol {
                 let cname = ClassName::new_with_names(name, fqname);
                 Symbol::Class(SymbolClass::new(cname.name.clone(), cname.get_namespace()))
             }
-            DiscreteType::Generic(_, _) => todo!(),
-            DiscreteType::Shape(_) => todo!(),
-            DiscreteType::ClassType(_, _) => todo!(),
-            DiscreteType::Template(_t) => todo!(),
+            DiscreteType::Generic(base, _) => Symbol::from(*base),
+            DiscreteType::Shape(_) => Symbol::Native("array"),
+            // Neither a class constant type nor a template refers to a single symbol
+            DiscreteType::ClassType(_, _) | DiscreteType::Template(_) => Symbol::None,
+            DiscreteType::Never => Symbol::None,
+            DiscreteType::IntRange(_, _) | DiscreteType::IntLiteral(_) => Symbol::Native("int"),
+            DiscreteType::StringLiteral(_) => Symbol::Native("string"),
+            DiscreteType::ArrayKey | DiscreteType::Scalar | DiscreteType::Numeric => Symbol::None,
+            DiscreteType::List(_)
+            | DiscreteType::NonEmptyList(_)
+            | DiscreteType::NonEmptyArray(_, _) => Symbol::Native("array"),
+            DiscreteType::Operator(_) => Symbol::None,
         }
     }
 }
@@ -1,7 +1,10 @@
 use std::ffi::OsString;
 
 use crate::{
-    tests::{evaluate_php_buffers, get_inferred_return_value},
+    issue::Issue,
+    symbols::FullyQualifiedName,
+    tests::{evaluate_php_buffers, get_inferred_return_value, EvaluationResult},
+    types::union::{DiscreteType, PHPType, ShapeTypeKey},
     value::PHPValue,
 };
 
@@ -52,3 +55,237 @@ fn test_array() {
     );*/
     assert_eq!(result.issues.len(), 0);
 }
+
+fn get_return_type(result: &EvaluationResult, function: &str) -> Option<PHPType> {
+    let symbol_data = result.symbol_data.as_ref()?;
+    let func_data = symbol_data
+        .functions
+        .read()
+        .unwrap()
+        .get(&FullyQualifiedName::from(function))?
+        .clone();
+    let data = func_data.read().unwrap();
+    data.inferred_return_type.clone()
+}
+
+#[test]
+fn test_array_shape_tracking() {
+    let buffers: &[(OsString, OsString)] = &[(
+        "shapes.php".into(),
+        r#"<?php
+            function built(int $id, string $name) {
+                $row = [];
+                $row['id'] = $id;
+                $row['name'] = $name;
+                return $row['name'];
+            }
+
+            function literal(string $name) {
+                $row = ['id' => 1, 'name' => $name];
+                return $row['name'];
+            }
+
+            /** @param array{int, string} $pair */
+            function destructured(array $pair) {
+                [$id, $name] = $pair;
+                return $name;
+            }
+
+            /** @param array{id: int, name: string} $row */
+            function keyed(array $row) {
+                ['id' => $id] = $row;
+                return $id;
+            }
+            "#
+        .into(),
+    )];
+    let result = evaluate_php_buffers(Default::default(), buffers.to_vec(), false);
+    let string_type: PHPType = DiscreteType::String.into();
+    let int_type: PHPType = DiscreteType::Int.into();
+    assert_eq!(
+        Some(&string_type),
+        get_return_type(&result, "\\built").as_ref()
+    );
+    assert_eq!(
+        Some(&string_type),
+        get_return_type(&result, "\\literal").as_ref()
+    );
+    assert_eq!(
+        Some(&string_type),
+        get_return_type(&result, "\\destructured").as_ref()
+    );
+    assert_eq!(
+        Some(&int_type),
+        get_return_type(&result, "\\keyed").as_ref()
+    );
+}
+
+#[test]
+fn test_unknown_array_shape_key() {
+    let buffers: &[(OsString, OsString)] = &[(
+        "shapes.php".into(),
+        r#"<?php
+            function typo(string $name) {
+                $row = ['id' => 1, 'name' => $name];
+                if (isset($row['other'])) {
+                    echo "other";
+                }
+                $row['extra'] = 2;
+                echo $row['extra'];
+                return $row['naem'];
+            }
+
+            /** @param array{id: int, ...} $row */
+            function unsealed(array $row) {
+                return $row['other'];
+            }
+            "#
+        .into(),
+    )];
+    let result = evaluate_php_buffers(Default::default(), buffers.to_vec(), false);
+    let unknown_keys: Vec<_> = result
+        .issues
+        .iter()
+        .filter_map(|issue| match issue {
+            Issue::UnknownArrayShapeKey(_, key, _) => Some(key.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        vec![ShapeTypeKey::String("naem".into())],
+        unknown_keys,
+        "{:?}",
+        result.issues
+    );
+}
+
+#[test]
+fn test_offset_types() {
+    let buffers: &[(OsString, OsString)] = &[(
+        "offsets.php".into(),
+        r#"<?php
+            interface ArrayAccess {}
+            class Plain {}
+            class Collection implements ArrayAccess {}
+
+            /** @param list<int> $ids */
+            function offsets(array $ids, string $s, int $i, Plain $plain, Collection $collection) {
+                echo $ids['first'];
+                echo $ids[0];
+                echo $s[0];
+                echo $s['first'];
+                echo $i[0];
+                echo $plain['key'];
+                echo $collection['key'];
+                return isset($i[0]);
+            }
+            "#
+        .into(),
+    )];
+    let result = evaluate_php_buffers(Default::default(), buffers.to_vec(), false);
+    let names: Vec<_> = result
+        .issues
+        .iter()
+        .filter(|issue| {
+            matches!(
+                issue,
+                Issue::StringKeyOnList(_, _)
+                    | Issue::NonIntegerStringOffset(_, _)
+                    | Issue::ArrayAccessOnNonArrayType(_, _)
+            )
+        })
+        .map(|issue| issue.get_name())
+        .collect();
+    assert_eq!(
+        vec![
+            "StringKeyOnList",
+            "NonIntegerStringOffset",
+            "ArrayAccessOnNonArrayType",
+            "ArrayAccessOnNonArrayType",
+        ],
+        names,
+        "{:?}",
+        result.issues
+    );
+}
+
+#[test]
+fn test_possibly_undefined_array_key() {
+    let buffers: &[(OsString, OsString)] = &[(
+        "keys.php".into(),
+        r#"<?php
+            /**
+             * @param array<string, int> $map
+             * @param array{id: int, name?: string} $row
+             */
+            function keys(array $map, array $row) {
+                echo $map['a'];
+                echo $map['b'] ?? 0;
+                if (isset($map['c'])) {
+                    echo "c";
+                }
+                echo $row['id'];
+                echo $row['name'];
+                echo $row['name'] ?? "";
+            }
+            "#
+        .into(),
+    )];
+    let result = evaluate_php_buffers(Default::default(), buffers.to_vec(), false);
+    let keys: Vec<_> = result
+        .issues
+        .iter()
+        .filter_map(|issue| match issue {
+            Issue::PossiblyUndefinedArrayKey(_, key, _) => Some(key.to_string()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(vec!["'a'", "'name'"], keys, "{:?}", result.issues);
+}
+
+#[test]
+fn test_checked_array_keys() {
+    // The declaration of the native, as it isn't necessarily loaded
+    let stubs = r#"<?php
+        function preg_match(string $pattern, string $subject, &$matches = null, int $flags = 0, int $offset = 0): int|false {}
+    "#;
+    let buffers: &[(OsString, OsString)] = &[
+        ("stubs.php".into(), stubs.into()),
+        (
+            "keys.php".into(),
+            r#"<?php
+            /**
+             * @param array<string, int> $map
+             * @param array{id: int, name?: string} $row
+             */
+            function keys(array $map, array $row, string $subject) {
+                if (isset($map['c'], $row['name'])) {
+                    echo $map['c'];
+                    echo $row['name'];
+                }
+                foreach ($map as $key => $value) {
+                    echo $map[$key];
+                }
+                if (preg_match('/(\w+)@(\w+)/', $subject, $matches)) {
+                    echo $matches[1];
+                }
+                if (isset($map['d'])) {
+                    $map = [];
+                }
+                echo $map['c'];
+            }
+            "#
+            .into(),
+        ),
+    ];
+    let result = evaluate_php_buffers(Default::default(), buffers.to_vec(), true);
+    let keys: Vec<_> = result
+        .issues
+        .iter()
+        .filter_map(|issue| match issue {
+            Issue::PossiblyUndefinedArrayKey(_, key, _) => Some(key.to_string()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(vec!["'c'"], keys, "{:?}", result.issues);
+}
@@ -0,0 +1,156 @@
+use std::ffi::OsString;
+
+use crate::{
+    issue::Issue,
+    symboldata::{attributes::HasAttributes, class::ClassType},
+    symbols::FullyQualifiedName,
+    tests::evaluate_php_buffers,
+    value::PHPValue,
+};
+
+/// The issues, except for the unused constructor arguments of the attribute classes
+fn get_issues(code: &str) -> Vec<Issue> {
+    let buffers: &[(OsString, OsString)] = &[("test.php".into(), code.into())];
+    let result = evaluate_php_buffers(Default::default(), buffers.to_vec(), false);
+    result
+        .issues
+        .iter()
+        .filter(|x| !matches!(x, Issue::UnusedArgument(_, _)))
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn test_attribute_data() -> Result<(), &'static str> {
+    let buffers: &[(OsString, OsString)] = &[(
+        "test.php".into(),
+        r#"<?php
+            #[Attribute]
+            class Route {
+                function __construct(string $path, string $method = "GET") {}
+            }
+
+            #[Route("/home")]
+            class Home {
+                #[Route("/about", method: "POST")]
+                function about() {}
+            }
+        "#
+        .into(),
+    )];
+    let result = evaluate_php_buffers(Default::default(), buffers.to_vec(), false);
+    assert!(result
+        .issues
+        .iter()
+        .all(|x| matches!(x, Issue::UnusedArgument(_, _))));
+
+    let symbols = result.symbol_data.ok_or("Missing symbols")?;
+    let class = symbols
+        .get_class(&FullyQualifiedName::from(r"\Home").into())
+        .ok_or("Missing class Home")?;
+    let ClassType::Class(cdata) = &*class.read().unwrap() else {
+        return Err("Home is not a class");
+    };
+    let route = cdata
+        .get_attribute(&r"\Route".into())
+        .ok_or("Missing attribute on class")?;
+    assert_eq!(
+        route.get_argument(0, &"path".into()),
+        Some(&PHPValue::String("/home".into()))
+    );
+
+    let method = cdata
+        .get_method(&"about".into(), symbols.clone())
+        .ok_or("Missing method about")?;
+    let route = method
+        .get_attribute(&r"\Route".into())
+        .ok_or("Missing attribute on method")?;
+    assert_eq!(
+        route.get_argument(1, &"method".into()),
+        Some(&PHPValue::String("POST".into()))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_attribute_not_an_attribute() {
+    let issues = get_issues(
+        r#"<?php
+            class NotAnAttribute {}
+
+            #[NotAnAttribute]
+            class Foo {}
+        "#,
+    );
+    assert_eq!(issues.len(), 1);
+    assert!(matches!(issues[0], Issue::NotAnAttribute(_, _)));
+}
+
+#[test]
+fn test_attribute_target() {
+    let issues = get_issues(
+        r#"<?php
+            #[Attribute(Attribute::TARGET_METHOD)]
+            class OnlyMethods {}
+
+            #[OnlyMethods]
+            class Foo {
+                #[OnlyMethods]
+                function bar(#[OnlyMethods] $baz) {}
+            }
+        "#,
+    );
+    let target_issues: Vec<_> = issues
+        .iter()
+        .filter(|x| matches!(x, Issue::InvalidAttributeTarget(_, _, _)))
+        .collect();
+    assert_eq!(target_issues.len(), 2);
+}
+
+#[test]
+fn test_attribute_repeatable() {
+    let issues = get_issues(
+        r#"<?php
+            #[Attribute]
+            class Once {}
+
+            #[Attribute(Attribute::TARGET_ALL | Attribute::IS_REPEATABLE)]
+            class Many {}
+
+            #[Once, Once]
+            #[Many]
+            #[Many]
+            class Foo {}
+        "#,
+    );
+    let repeat_issues: Vec<_> = issues
+        .iter()
+        .filter(|x| matches!(x, Issue::NonRepeatableAttribute(_, _)))
+        .collect();
+    assert_eq!(repeat_issues.len(), 1);
+}
+
+#[test]
+fn test_attribute_argument_types() {
+    let issues = get_issues(
+        r#"<?php
+            #[Attribute]
+            class Route {
+                function __construct(string $path) {}
+            }
+
+            #[Route(null)]
+            class Foo {}
+
+            #[Route(path: "/bar")]
+            class Bar {}
+
+            #[Route(42)]
+            class Baz {}
+        "#,
+    );
+    assert_eq!(issues.len(), 2);
+    assert!(issues
+        .iter()
+        .all(|issue| matches!(issue, Issue::IncompatibleArgumentType(_, _, _, _, _))));
+}
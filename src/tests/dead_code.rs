@@ -0,0 +1,189 @@
+use std::ffi::OsString;
+
+use crate::{
+    analysis::dead_code::analyze_dead_code,
+    issue::Issue,
+    tests::{evaluate_php_buffers, TestEmitter},
+};
+
+fn get_dead_code_issues(code: &str) -> Vec<Issue> {
+    let buffers: &[(OsString, OsString)] = &[("test.php".into(), code.into())];
+    let result = evaluate_php_buffers(Default::default(), buffers.to_vec(), false);
+    let symbols = result.symbol_data.expect("Missing symbols");
+    let emitter = TestEmitter::new();
+    analyze_dead_code(&symbols, &Default::default(), &emitter);
+    let issues = emitter.issues.read().unwrap().clone();
+    issues
+}
+
+#[test]
+fn test_unused_private_method() {
+    let issues = get_dead_code_issues(
+        r#"<?php
+            class Service {
+                public function run(): int {
+                    return $this->helper();
+                }
+
+                private function helper(): int {
+                    return 1;
+                }
+
+                private function forgotten(): int {
+                    return 2;
+                }
+            }
+
+            function main() {
+                $s = new Service();
+                return $s->run();
+            }
+            main();
+        "#,
+    );
+    assert_eq!(issues.len(), 1);
+    assert!(matches!(&issues[0], Issue::UnusedMethod(_, _, m) if *m == "forgotten"));
+}
+
+#[test]
+fn test_unused_property() {
+    let issues = get_dead_code_issues(
+        r#"<?php
+            class Counter {
+                private int $count = 0;
+                private int $never_read = 0;
+
+                public function get(): int {
+                    $this->never_read = 1;
+                    return $this->count;
+                }
+            }
+
+            $c = new Counter();
+            $c->get();
+        "#,
+    );
+    assert_eq!(issues.len(), 1);
+    assert!(matches!(&issues[0], Issue::UnusedProperty(_, _, _)));
+}
+
+#[test]
+fn test_unused_function_and_class() {
+    let issues = get_dead_code_issues(
+        r#"<?php
+            function used() {}
+            function unused() {}
+            function callback() {}
+
+            class Unused {}
+            class UsedAsType {}
+
+            function takes(UsedAsType $x) {}
+
+            used();
+            takes(null);
+            array_map('callback', []);
+        "#,
+    );
+    let unused_functions: Vec<_> = issues
+        .iter()
+        .filter(|x| matches!(x, Issue::UnusedFunction(_, _)))
+        .collect();
+    assert_eq!(unused_functions.len(), 1);
+    let unused_classes: Vec<_> = issues
+        .iter()
+        .filter(|x| matches!(x, Issue::UnusedClass(_, _)))
+        .collect();
+    assert_eq!(unused_classes.len(), 1);
+}
+
+#[test]
+fn test_unused_interface_and_trait() {
+    let issues = get_dead_code_issues(
+        r#"<?php
+            interface UnusedInterface {}
+            interface Shape {}
+            trait UnusedTrait {}
+            trait Named {}
+
+            class Square implements Shape {
+                use Named;
+            }
+
+            new Square();
+        "#,
+    );
+    let unused: Vec<_> = issues
+        .iter()
+        .filter_map(|x| match x {
+            Issue::UnusedClass(_, fq_name) => Some(fq_name.to_string()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(unused.len(), 2);
+    assert!(unused.contains(&r"\UnusedInterface".to_string()));
+    assert!(unused.contains(&r"\UnusedTrait".to_string()));
+}
+
+#[test]
+fn test_unused_static_property() {
+    let issues = get_dead_code_issues(
+        r#"<?php
+            class Registry {
+                private static $items = [];
+                private static $never_read = 0;
+
+                public static function all(): array {
+                    self::$never_read = 1;
+                    return self::$items;
+                }
+            }
+
+            Registry::all();
+        "#,
+    );
+    assert_eq!(issues.len(), 1);
+    assert!(matches!(&issues[0], Issue::UnusedProperty(_, _, p) if *p == "never_read"));
+}
+
+#[test]
+fn test_dead_code_entry_points() {
+    let issues = get_dead_code_issues(
+        r#"<?php
+            class HomeController {}
+
+            /**
+             * @api
+             */
+            class PublicApi {}
+
+            /**
+             * @api
+             */
+            function exported() {}
+        "#,
+    );
+    assert_eq!(issues.len(), 0);
+}
+
+#[test]
+fn test_unused_private_constant() {
+    let issues = get_dead_code_issues(
+        r#"<?php
+            class Limits {
+                private const MAX = 10;
+                private const MIN = 0;
+                const PUBLIC_ONE = 1;
+
+                public function max(): int {
+                    return self::MAX;
+                }
+            }
+
+            $l = new Limits();
+            $l->max();
+        "#,
+    );
+    assert_eq!(issues.len(), 1);
+    assert!(matches!(&issues[0], Issue::UnusedClassConstant(_, _, c) if *c == "MIN"));
+}
@@ -0,0 +1,250 @@
+use std::ffi::OsString;
+
+use crate::{
+    issue::Issue,
+    symbols::FullyQualifiedName,
+    tests::{evaluate_php_buffers, get_inferred_return_type},
+    types::{
+        traversable::{generator_type, plain_generator_type},
+        union::DiscreteType,
+    },
+};
+
+#[test]
+fn test_yield_infers_generator() {
+    let return_type = get_inferred_return_type(
+        r#"
+        yield 1 => "foo";
+        yield 2 => "bar";
+        return 4.2;
+    "#,
+    );
+    assert_eq!(
+        return_type,
+        Some(generator_type(
+            DiscreteType::Int.into(),
+            DiscreteType::String.into(),
+            DiscreteType::Mixed.into(),
+            DiscreteType::Float.into(),
+        ))
+    );
+}
+
+#[test]
+fn test_yield_without_key_or_return() {
+    let return_type = get_inferred_return_type(
+        r#"
+        yield "foo";
+    "#,
+    );
+    assert_eq!(
+        return_type,
+        Some(generator_type(
+            DiscreteType::Int.into(),
+            DiscreteType::String.into(),
+            DiscreteType::Mixed.into(),
+            DiscreteType::Void.into(),
+        ))
+    );
+}
+
+#[test]
+fn test_yield_in_non_generator_function() -> Result<(), &'static str> {
+    let buffers: &[(OsString, OsString)] = &[(
+        "test.php".into(),
+        r#"<?php
+            function not_a_generator(): int {
+                yield 1;
+            }
+
+            /**
+             * @return Generator<int, string>
+             */
+            function a_generator() {
+                yield "foo";
+            }
+
+            function an_iterable(): iterable {
+                yield "foo";
+            }
+        "#
+        .into(),
+    )];
+    let result = evaluate_php_buffers(Default::default(), buffers.to_vec(), false);
+    let generator_issues: Vec<_> = result
+        .issues
+        .iter()
+        .filter(|x| matches!(x, Issue::IncompatibleGeneratorReturnType(_, _)))
+        .collect();
+    assert_eq!(generator_issues.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_foreach_over_generator() -> Result<(), &'static str> {
+    let buffers: &[(OsString, OsString)] = &[(
+        "test.php".into(),
+        r#"<?php
+            function gen() {
+                yield "foo" => 42;
+            }
+
+            function test_value() {
+                foreach (gen() as $key => $value) {
+                    return $value;
+                }
+                return 0;
+            }
+
+            function test_key() {
+                foreach (gen() as $key => $value) {
+                    return $key;
+                }
+                return "";
+            }
+
+            function test_yield_from() {
+                yield from gen();
+            }
+        "#
+        .into(),
+    )];
+    let result = evaluate_php_buffers(Default::default(), buffers.to_vec(), false);
+    let symbols = result.symbol_data.ok_or("Missing symbols")?;
+    let func_data = symbols.functions.read().unwrap();
+
+    let func_name: FullyQualifiedName = r"\test_value".into();
+    let func = func_data
+        .get(&func_name)
+        .ok_or("data of function test_value not found")?;
+    assert_eq!(
+        func.read().unwrap().inferred_return_type,
+        Some(DiscreteType::Int.into())
+    );
+
+    let func_name: FullyQualifiedName = r"\test_key".into();
+    let func = func_data
+        .get(&func_name)
+        .ok_or("data of function test_key not found")?;
+    assert_eq!(
+        func.read().unwrap().inferred_return_type,
+        Some(DiscreteType::String.into())
+    );
+
+    let func_name: FullyQualifiedName = r"\test_yield_from".into();
+    let func = func_data
+        .get(&func_name)
+        .ok_or("data of function test_yield_from not found")?;
+    assert_eq!(
+        func.read().unwrap().inferred_return_type,
+        Some(generator_type(
+            DiscreteType::String.into(),
+            DiscreteType::Int.into(),
+            DiscreteType::Mixed.into(),
+            DiscreteType::Void.into(),
+        ))
+    );
+    Ok(())
+}
+
+#[test]
+fn test_yield_type_mismatch() -> Result<(), &'static str> {
+    let buffers: &[(OsString, OsString)] = &[(
+        "test.php".into(),
+        r#"<?php
+            /**
+             * @return Generator<int, string, mixed, int>
+             */
+            function wrong_types() {
+                yield 1 => "foo";
+                yield 2 => 42;
+                yield "key" => "bar";
+                return "done";
+            }
+
+            /**
+             * @return iterable<string>
+             */
+            function wrong_value() {
+                yield 42;
+            }
+
+            /**
+             * @return Generator<int, string, mixed, int>
+             */
+            function delegating() {
+                return wrong_types();
+            }
+        "#
+        .into(),
+    )];
+    let result = evaluate_php_buffers(Default::default(), buffers.to_vec(), false);
+    let mismatches: Vec<_> = result
+        .issues
+        .iter()
+        .filter_map(|x| match x {
+            Issue::IncompatibleYieldType(_, declared, provided) => {
+                Some((declared.to_string(), provided.to_string()))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        mismatches,
+        vec![
+            ("string".to_string(), "int".to_string()),
+            ("int".to_string(), "string".to_string()),
+            ("int".to_string(), "string".to_string()),
+            ("string".to_string(), "int".to_string()),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_yield_in_closure() -> Result<(), &'static str> {
+    let buffers: &[(OsString, OsString)] = &[(
+        "test.php".into(),
+        r#"<?php
+            function returns_closure() {
+                $gen = function () {
+                    yield 1;
+                };
+                return $gen;
+            }
+
+            function returns_bad_closures() {
+                $closure = function (): int {
+                    yield 1;
+                };
+                $arrow = fn(): int => yield 1;
+                return 1;
+            }
+        "#
+        .into(),
+    )];
+    let result = evaluate_php_buffers(Default::default(), buffers.to_vec(), false);
+    let generator_issues = result
+        .issues
+        .iter()
+        .filter(|x| matches!(x, Issue::IncompatibleGeneratorReturnType(_, _)))
+        .count();
+    assert_eq!(generator_issues, 2);
+
+    let symbol_data = result.symbol_data.ok_or("Missing symbol data")?;
+    let functions = symbol_data.functions.read().unwrap();
+    let get_return_type = |name: &str| {
+        let func = functions.get(&FullyQualifiedName::from(name))?;
+        let data = func.read().unwrap();
+        data.inferred_return_type.clone()
+    };
+    assert_eq!(
+        get_return_type("\\returns_closure"),
+        Some(DiscreteType::TypedCallable(vec![], plain_generator_type()).into())
+    );
+    assert_eq!(
+        get_return_type("\\returns_bad_closures"),
+        Some(DiscreteType::Int.into())
+    );
+    Ok(())
+}
@@ -1,10 +1,19 @@
 use crate::{
+    issue::Issue,
     symbols::FullyQualifiedName,
-    tests::evaluate_php_buffers,
+    tests::{evaluate_php_buffers, evaluate_php_file, get_function_return_type, EvaluationResult},
     types::{type_parser::TypeParser, union::DiscreteType},
 };
 use std::ffi::OsString;
 
+fn count_issues(result: &EvaluationResult, name: &str) -> usize {
+    result
+        .issues
+        .iter()
+        .filter(|issue| issue.get_name() == name)
+        .count()
+}
+
 #[test]
 fn test_class_template() -> Result<(), &'static str> {
     let buffers: &[(OsString, OsString)] = &[(
@@ -260,6 +269,271 @@ fn test_gen_detect_duplicate_template() -> Result<(), &'static str> {
     Ok(())
 }
 
+#[test]
+fn test_template_bound_violation() {
+    let result = evaluate_php_file(
+        r#"<?php
+            class Animal {}
+            class Dog extends Animal {}
+
+            /**
+             * @template T of Animal
+             */
+            class Cage {
+                /**
+                 * @param T $animal
+                 */
+                public function __construct($animal) {
+                    echo get_class($animal);
+                }
+            }
+
+            function cage_dog() {
+                return new Cage(new Dog());
+            }
+
+            function cage_number() {
+                return new Cage(42);
+            }
+        "#,
+    );
+    assert_eq!(
+        1,
+        count_issues(&result, "TemplateBoundViolation"),
+        "{:?}",
+        result.issues
+    );
+    assert_eq!(
+        TypeParser::parse_simple("Cage<Dog>".into()),
+        get_function_return_type(&result, r"\cage_dog")
+    );
+}
+
+#[test]
+fn test_method_template_bound_violation() {
+    let result = evaluate_php_file(
+        r#"<?php
+            class Animal {}
+
+            class Vet {
+                /**
+                 * @template T of Animal
+                 * @param T $patient
+                 * @return T
+                 */
+                public function treat($patient) {
+                    return $patient;
+                }
+            }
+
+            function treat_animal() {
+                $vet = new Vet();
+                return $vet->treat(new Animal());
+            }
+
+            function treat_string() {
+                $vet = new Vet();
+                return $vet->treat("rock");
+            }
+        "#,
+    );
+    assert_eq!(
+        1,
+        count_issues(&result, "TemplateBoundViolation"),
+        "{:?}",
+        result.issues
+    );
+    assert_eq!(
+        TypeParser::parse_simple("Animal".into()),
+        get_function_return_type(&result, r"\treat_animal")
+    );
+}
+
+#[test]
+fn test_template_default() {
+    let result = evaluate_php_file(
+        r#"<?php
+            /**
+             * @template T = int
+             */
+            class Counter {
+                /**
+                 * @return T
+                 */
+                public function get() {
+                    return 1;
+                }
+            }
+
+            function get_count() {
+                $counter = new Counter();
+                return $counter->get();
+            }
+        "#,
+    );
+    assert_eq!(
+        Some(DiscreteType::Int.into()),
+        get_function_return_type(&result, r"\get_count")
+    );
+}
+
+#[test]
+fn test_extends_and_implements_propagation() {
+    let result = evaluate_php_file(
+        r#"<?php
+            class User {}
+
+            /**
+             * @template T
+             */
+            abstract class Repository {
+                /**
+                 * @return T
+                 */
+                abstract public function find();
+            }
+
+            /**
+             * @extends Repository<User>
+             */
+            abstract class UserRepository extends Repository {}
+
+            /**
+             * @template T
+             */
+            interface Factory {
+                /**
+                 * @return list<T>
+                 */
+                public function createMany();
+            }
+
+            /**
+             * @implements Factory<User>
+             */
+            abstract class UserFactory implements Factory {}
+
+            function find_user(UserRepository $repository) {
+                return $repository->find();
+            }
+
+            function create_users(UserFactory $factory) {
+                return $factory->createMany();
+            }
+        "#,
+    );
+    assert_eq!(
+        TypeParser::parse_simple("User".into()),
+        get_function_return_type(&result, r"\find_user")
+    );
+    assert_eq!(
+        TypeParser::parse_simple("list<User>".into()),
+        get_function_return_type(&result, r"\create_users")
+    );
+}
+
+#[test]
+fn test_template_variance_violation() {
+    let result = evaluate_php_file(
+        r#"<?php
+            /**
+             * @template-covariant T
+             */
+            interface Source {
+                /**
+                 * @return T
+                 */
+                public function read();
+
+                /**
+                 * @param T $item
+                 */
+                public function write($item);
+            }
+
+            /**
+             * @template-contravariant T
+             */
+            interface Sink {
+                /**
+                 * @param T $item
+                 */
+                public function write($item);
+
+                /**
+                 * @return T
+                 */
+                public function read();
+            }
+        "#,
+    );
+    let violations: Vec<_> = result
+        .issues
+        .iter()
+        .filter_map(|issue| match issue {
+            Issue::TemplateVarianceViolation(_, template, method) => {
+                Some((template.to_string(), method.to_string()))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            ("T".to_string(), "write".to_string()),
+            ("T".to_string(), "read".to_string())
+        ],
+        violations
+    );
+}
+
+#[test]
+fn test_template_variance_callables_and_properties() {
+    let result = evaluate_php_file(
+        r#"<?php
+            /**
+             * @template-covariant T
+             */
+            class Box {
+                /** @var T */
+                public $value;
+
+                /** @var T */
+                public readonly mixed $frozen;
+
+                /** @var T */
+                private $item;
+
+                /**
+                 * @param callable(T): void $callback
+                 */
+                public function each($callback) {}
+
+                /**
+                 * @return callable(T): void
+                 */
+                public function consumer() {}
+            }
+        "#,
+    );
+    let violations: Vec<_> = result
+        .issues
+        .iter()
+        .filter_map(|issue| match issue {
+            Issue::TemplateVarianceViolation(_, template, member) => {
+                Some((template.to_string(), member.to_string()))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            ("T".to_string(), "value".to_string()),
+            ("T".to_string(), "consumer".to_string())
+        ],
+        violations
+    );
+}
+
 #[test]
 fn balle() {
     /*
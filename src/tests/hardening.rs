@@ -1,6 +1,6 @@
 use std::ffi::OsString;
 
-use crate::tests::evaluate_php_buffers;
+use crate::{issue::Issue, tests::evaluate_php_buffers};
 
 #[test]
 fn test_hardening_true() {
@@ -266,3 +266,353 @@ fn test_hardening_instanceoof_multipath_2() {
     assert_eq!(result.issues.len(), 0);
     //assert!(false)
 }
+
+/// Issues from analyzing the functions, along with a class `X` with a method `foo()`
+fn get_hardening_issues(functions: &str) -> Vec<Issue> {
+    let code = format!(
+        "<?php
+            class X {{
+                function foo() {{}}
+            }}
+            {}
+        ",
+        functions
+    );
+    let buffers: Vec<(OsString, OsString)> = vec![("hardening.php".into(), code.into())];
+    evaluate_php_buffers(Default::default(), buffers, true).issues
+}
+
+#[test]
+fn test_hardening_identical_null() {
+    let issues = get_hardening_issues(
+        r#"
+            function a(?X $x) {
+                if ($x !== null) {
+                    $x->foo();
+                }
+            }
+            function b(?X $x) {
+                if (null === $x) {
+                    echo "none";
+                } else {
+                    $x->foo();
+                }
+            }
+        "#,
+    );
+    assert_eq!(issues.len(), 0, "{:?}", issues);
+}
+
+#[test]
+fn test_hardening_identical_null_keeps_null() {
+    let issues = get_hardening_issues(
+        r#"
+            function a(?X $x) {
+                if ($x === null) {
+                    $x->foo();
+                }
+            }
+        "#,
+    );
+    assert!(issues
+        .iter()
+        .any(|issue| matches!(issue, Issue::MethodCallOnNullableType(_, _))));
+}
+
+#[test]
+fn test_hardening_type_check_functions() {
+    let issues = get_hardening_issues(
+        r#"
+            function a(?X $x) {
+                if (isset($x)) {
+                    $x->foo();
+                }
+            }
+            function b(?X $x) {
+                if (!is_null($x)) {
+                    $x->foo();
+                }
+            }
+            function c(?X $x) {
+                if (is_object($x)) {
+                    $x->foo();
+                }
+            }
+            function d(?X $x) {
+                if (empty($x)) {
+                    return;
+                } else {
+                    $x->foo();
+                }
+            }
+        "#,
+    );
+    assert_eq!(issues.len(), 0, "{:?}", issues);
+}
+
+#[test]
+fn test_hardening_type_name_comparison() {
+    let issues = get_hardening_issues(
+        r#"
+            class Y {}
+            function a(?X $x) {
+                if (gettype($x) === 'object') {
+                    $x->foo();
+                }
+            }
+            function b(X|Y|null $x) {
+                if (get_class($x) === X::class) {
+                    $x->foo();
+                }
+            }
+            function c(?X $x) {
+                if (get_debug_type($x) !== 'null') {
+                    $x->foo();
+                }
+            }
+        "#,
+    );
+    assert_eq!(issues.len(), 0, "{:?}", issues);
+}
+
+#[test]
+fn test_hardening_type_name_of_other_function() {
+    let issues = get_hardening_issues(
+        r#"
+            function a(?X $x) {
+                if (get_debug_type($x) === 'object') {
+                    $x->foo();
+                }
+            }
+            function b(?X $x) {
+                if (gettype($x) !== 'null') {
+                    $x->foo();
+                }
+            }
+        "#,
+    );
+    assert_eq!(issues.len(), 2, "{:?}", issues);
+}
+
+#[test]
+fn test_hardening_guard_clause() {
+    let issues = get_hardening_issues(
+        r#"
+            function fail(): never {
+                throw new \Exception("failed");
+            }
+            function a(?X $x) {
+                if ($x === null) {
+                    return;
+                }
+                $x->foo();
+            }
+            function b(?X $x) {
+                if (!($x instanceof X)) {
+                    throw new \Exception("missing");
+                }
+                $x->foo();
+            }
+            function c(?X $x) {
+                if (is_null($x)) {
+                    exit(1);
+                }
+                $x->foo();
+            }
+            function d(?X $x) {
+                if ($x === null) {
+                    fail();
+                }
+                $x->foo();
+            }
+        "#,
+    );
+    assert_eq!(issues.len(), 0, "{:?}", issues);
+}
+
+#[test]
+fn test_hardening_guard_clause_without_termination() {
+    let issues = get_hardening_issues(
+        r#"
+            function a(?X $x) {
+                if ($x === null) {
+                    echo "none";
+                }
+                $x->foo();
+            }
+        "#,
+    );
+    assert!(issues
+        .iter()
+        .any(|issue| matches!(issue, Issue::MethodCallOnNullableType(_, _))));
+}
+
+#[test]
+fn test_hardening_boolean_operators() {
+    let issues = get_hardening_issues(
+        r#"
+            function a(?X $x) {
+                $x !== null && $x->foo();
+            }
+            function b(?X $x) {
+                $x === null || $x->foo();
+            }
+            function c(?X $x, ?X $y) {
+                if ($x !== null && $y !== null) {
+                    $x->foo();
+                    $y->foo();
+                }
+            }
+            function d(?X $x, ?X $y) {
+                if ($x === null || $y === null) {
+                    return;
+                }
+                $x->foo();
+                $y->foo();
+            }
+            function e(?X $x) {
+                $x ?? throw new \Exception("missing");
+                $x->foo();
+            }
+        "#,
+    );
+    assert_eq!(issues.len(), 0, "{:?}", issues);
+}
+
+#[test]
+fn test_hardening_boolean_or_keeps_null() {
+    let issues = get_hardening_issues(
+        r#"
+            function a(?X $x, ?X $y) {
+                if ($x !== null || $y !== null) {
+                    $x->foo();
+                }
+            }
+        "#,
+    );
+    assert!(issues
+        .iter()
+        .any(|issue| matches!(issue, Issue::MethodCallOnNullableType(_, _))));
+}
+
+#[test]
+fn test_hardening_ternary_and_loops() {
+    let issues = get_hardening_issues(
+        r#"
+            function a(?X $x) {
+                $x ? $x->foo() : null;
+            }
+            function b(?X $x) {
+                $x === null ? null : $x->foo();
+            }
+            function c(?X $x) {
+                while ($x !== null) {
+                    $x->foo();
+                    $x = null;
+                }
+            }
+            function d(?X $x) {
+                for (; $x instanceof X;) {
+                    $x->foo();
+                    $x = null;
+                }
+            }
+        "#,
+    );
+    assert_eq!(issues.len(), 0, "{:?}", issues);
+}
+
+#[test]
+fn test_hardening_assert() {
+    let issues = get_hardening_issues(
+        r#"
+            function a(?X $x) {
+                assert($x instanceof X);
+                $x->foo();
+            }
+            function b(?X $x) {
+                assert($x !== null);
+                $x->foo();
+            }
+        "#,
+    );
+    assert_eq!(issues.len(), 0, "{:?}", issues);
+}
+
+#[test]
+fn test_hardening_custom_assertions() {
+    let issues = get_hardening_issues(
+        r#"
+            /** @psalm-assert !null $value */
+            function assertNotNull($value): void {
+                if ($value === null) {
+                    throw new \Exception("null");
+                }
+            }
+            /** @phpstan-assert-if-true X $value */
+            function isX($value): bool {
+                return $value instanceof X;
+            }
+            /** @assert-if-false null $value */
+            function isMissing($value): bool {
+                return $value === null;
+            }
+            class Assert {
+                /**
+                 * @template T of object
+                 * @param class-string<T> $class
+                 * @psalm-assert T $value
+                 */
+                public static function isInstanceOf($value, $class): void {}
+            }
+            function a(?X $x) {
+                assertNotNull($x);
+                $x->foo();
+            }
+            function b($x) {
+                if (isX($x)) {
+                    $x->foo();
+                }
+            }
+            function c(?X $x) {
+                if (!isMissing($x)) {
+                    $x->foo();
+                }
+            }
+            function d($x) {
+                Assert::isInstanceOf($x, X::class);
+                $x->foo();
+            }
+        "#,
+    );
+    let method_call_issues: Vec<_> = issues
+        .iter()
+        .filter(|issue| {
+            matches!(
+                issue,
+                Issue::MethodCallOnNullableType(_, _) | Issue::MethodCallOnUnknownType(_, _, _)
+            )
+        })
+        .collect();
+    assert_eq!(method_call_issues.len(), 0, "{:?}", method_call_issues);
+}
+
+#[test]
+fn test_hardening_custom_assertion_only_on_its_branch() {
+    let issues = get_hardening_issues(
+        r#"
+            /** @psalm-assert-if-true X $value */
+            function isX(?X $value): bool {
+                return $value !== null;
+            }
+            function a(?X $x) {
+                if (!isX($x)) {
+                    $x->foo();
+                }
+            }
+        "#,
+    );
+    assert!(issues
+        .iter()
+        .any(|issue| matches!(issue, Issue::MethodCallOnNullableType(_, _))));
+}
@@ -0,0 +1,172 @@
+use std::{ffi::OsString, path::PathBuf};
+
+use crate::{
+    issue::Issue,
+    symboldata::{
+        includes::{IncludeData, IncludeGraph, IncludeKind},
+        FileLocation,
+    },
+    tests::{evaluate_php_buffers, write_test_files, EvaluationResult},
+};
+
+/// Writes the files to a temporary directory, as includes are checked against the file
+/// system, and analyzes them
+fn evaluate_php_files(test_name: &str, files: &[(&str, &str)]) -> (PathBuf, EvaluationResult) {
+    let dir = write_test_files(test_name, files);
+    let buffers: Vec<(OsString, OsString)> = files
+        .iter()
+        .map(|(name, code)| (dir.join(name).into_os_string(), (*code).into()))
+        .collect();
+    let result = evaluate_php_buffers(Default::default(), buffers, false);
+    (dir, result)
+}
+
+#[test]
+fn test_include_graph() {
+    let (dir, result) = evaluate_php_files(
+        "include-graph",
+        &[
+            (
+                "main.php",
+                r#"<?php
+                    require_once __DIR__ . "/lib/functions.php";
+                    include dirname(__FILE__) . "/view.php";
+                "#,
+            ),
+            ("view.php", "<?php\n"),
+            ("lib/functions.php", "<?php\nrequire \"../view.php\";\n"),
+        ],
+    );
+    let symbols = result.symbol_data.expect("Missing symbols");
+    let graph = symbols.includes.read().unwrap();
+
+    let includes = graph.get_includes(&dir.join("main.php"));
+    assert_eq!(includes.len(), 2);
+    assert_eq!(includes[0].kind, IncludeKind::RequireOnce);
+    assert_eq!(includes[0].target, dir.join("lib/functions.php"));
+    assert_eq!(includes[1].kind, IncludeKind::Include);
+    assert_eq!(includes[1].target, dir.join("view.php"));
+
+    assert_eq!(
+        graph.get_included_by(&dir.join("view.php")),
+        vec![dir.join("lib/functions.php"), dir.join("main.php")]
+    );
+    assert_eq!(
+        graph.get_dependents(&dir.join("lib/functions.php")),
+        vec![dir.join("main.php")]
+    );
+    assert!(!result.issues.iter().any(|i| matches!(
+        i,
+        Issue::IncludedFileNotFound(..) | Issue::RequiredFileNotFound(..)
+    )));
+}
+
+#[test]
+fn test_missing_include() {
+    let (dir, result) = evaluate_php_files(
+        "missing-include",
+        &[(
+            "main.php",
+            r#"<?php
+                require __DIR__ . "/does-not-exist.php";
+                include dirname(__FILE__) . "/also-missing.php";
+                include "maybe-in-include-path.php";
+                include $dynamic;
+            "#,
+        )],
+    );
+    let required: Vec<_> = result
+        .issues
+        .iter()
+        .filter(|i| matches!(i, Issue::RequiredFileNotFound(..)))
+        .collect();
+    assert_eq!(required.len(), 1);
+    let included: Vec<_> = result
+        .issues
+        .iter()
+        .filter(|i| matches!(i, Issue::IncludedFileNotFound(..)))
+        .collect();
+    assert_eq!(included.len(), 1);
+
+    // Relative paths might be found in the include_path, so they're only recorded
+    let symbols = result.symbol_data.expect("Missing symbols");
+    let includes = symbols
+        .includes
+        .read()
+        .unwrap()
+        .get_includes(&dir.join("main.php"));
+    let resolved: Vec<_> = includes.iter().map(|i| i.resolved).collect();
+    assert_eq!(resolved, vec![false, false, false]);
+}
+
+#[test]
+fn test_included_template_variables() {
+    let (_, result) = evaluate_php_files(
+        "included-template",
+        &[
+            (
+                "page.php",
+                r#"<?php
+                    $render = function ($text) {
+                        echo $text;
+                    };
+                    include __DIR__ . "/template.php";
+                "#,
+            ),
+            ("template.php", "<?php\n$render(\"Hello\");\n"),
+        ],
+    );
+    assert!(!result
+        .issues
+        .iter()
+        .any(|i| matches!(i, Issue::UnknownVariable(..))));
+}
+
+#[test]
+fn test_included_file_unknown_variables() {
+    let (_, result) = evaluate_php_files(
+        "included-unknown",
+        &[
+            (
+                "page.php",
+                r#"<?php
+                    $title = "Hello";
+                    include __DIR__ . "/template.php";
+                "#,
+            ),
+            ("template.php", "<?php\necho $title;\necho $missing;\n"),
+        ],
+    );
+    let unknown: Vec<_> = result
+        .issues
+        .iter()
+        .filter_map(|i| match i {
+            Issue::UnknownVariable(_, name) => Some(name.to_string()),
+            _ => None,
+        })
+        .collect();
+    assert!(unknown.contains(&"missing".to_string()));
+    assert!(!unknown.contains(&"title".to_string()));
+}
+
+#[test]
+fn test_clear_included_file() {
+    let mut graph = IncludeGraph::new();
+    let main = PathBuf::from("/project/main.php");
+    let view = PathBuf::from("/project/view.php");
+    graph.add_include(
+        &main,
+        IncludeData {
+            kind: IncludeKind::Include,
+            target: view.clone(),
+            position: FileLocation::internal(),
+            in_function: false,
+            resolved: true,
+        },
+    );
+    assert!(graph.is_included(&view));
+
+    graph.clear_file(&main);
+    assert!(!graph.is_included(&view));
+    assert!(graph.get_includes(&main).is_empty());
+}
@@ -0,0 +1,234 @@
+use crate::{
+    issue::Issue,
+    tests::{evaluate_php_file, get_function_return_type, EvaluationResult},
+    types::union::DiscreteType,
+};
+
+fn has_unknown_member(result: &EvaluationResult) -> bool {
+    result.issues.iter().any(|issue| {
+        matches!(
+            issue,
+            Issue::UnknownProperty(_, _, _) | Issue::UnknownMethod(_, _, _)
+        )
+    })
+}
+
+#[test]
+fn test_magic_property() {
+    let result = evaluate_php_file(
+        r#"<?php
+            /**
+             * @property string $title
+             * @property-read int $created_at
+             */
+            class Post {
+                public function __get($name) {
+                    return null;
+                }
+            }
+
+            function get_title(Post $post) {
+                return $post->title;
+            }
+
+            function get_created(Post $post) {
+                return $post->created_at;
+            }
+        "#,
+    );
+    assert!(!has_unknown_member(&result), "{:?}", result.issues);
+    assert_eq!(
+        Some(DiscreteType::String.into()),
+        get_function_return_type(&result, r"\get_title")
+    );
+    assert_eq!(
+        Some(DiscreteType::Int.into()),
+        get_function_return_type(&result, r"\get_created")
+    );
+}
+
+#[test]
+fn test_magic_method() {
+    let result = evaluate_php_file(
+        r#"<?php
+            /**
+             * @method string render(array $options = [])
+             */
+            class View {
+                public function __call($name, $arguments) {
+                    return null;
+                }
+            }
+
+            function render_view(View $view) {
+                return $view->render();
+            }
+        "#,
+    );
+    assert!(!has_unknown_member(&result), "{:?}", result.issues);
+    assert_eq!(
+        Some(DiscreteType::String.into()),
+        get_function_return_type(&result, r"\render_view")
+    );
+}
+
+#[test]
+fn test_mixin_method() {
+    let result = evaluate_php_file(
+        r#"<?php
+            class Builder {
+                public function count(): int {
+                    return 0;
+                }
+            }
+
+            /**
+             * @mixin Builder
+             */
+            class Model {
+                public function __call($name, $arguments) {
+                    return null;
+                }
+            }
+
+            function count_models(Model $model) {
+                return $model->count();
+            }
+
+            function missing(Model $model) {
+                return $model->missing();
+            }
+        "#,
+    );
+    assert_eq!(
+        Some(DiscreteType::Int.into()),
+        get_function_return_type(&result, r"\count_models")
+    );
+    let unknown: Vec<_> = result
+        .issues
+        .iter()
+        .filter(|issue| matches!(issue, Issue::UnknownMethod(_, _, _)))
+        .collect();
+    assert_eq!(1, unknown.len(), "{:?}", result.issues);
+}
+
+#[test]
+fn test_mixin_property() {
+    let result = evaluate_php_file(
+        r#"<?php
+            class Builder {
+                /** @var int */
+                public $limit = 10;
+            }
+
+            /**
+             * @mixin Builder
+             */
+            class Model {
+                public function __get($name) {
+                    return null;
+                }
+            }
+
+            function get_limit(Model $model) {
+                return $model->limit;
+            }
+        "#,
+    );
+    assert!(!has_unknown_member(&result), "{:?}", result.issues);
+    assert_eq!(
+        Some(DiscreteType::Int.into()),
+        get_function_return_type(&result, r"\get_limit")
+    );
+}
+
+#[test]
+fn test_write_only_magic_property() {
+    let result = evaluate_php_file(
+        r#"<?php
+            /**
+             * @property-write string $password
+             */
+            class User {
+                public function __get($name) {
+                    return null;
+                }
+
+                public function __set($name, $value) {
+                }
+            }
+
+            function set_password(User $user) {
+                $user->password = "secret";
+            }
+
+            function get_password(User $user) {
+                return $user->password;
+            }
+        "#,
+    );
+    assert!(!has_unknown_member(&result), "{:?}", result.issues);
+    assert_eq!(None, get_function_return_type(&result, r"\get_password"));
+}
+
+#[test]
+fn test_declared_method_wins_over_magic_method() {
+    let result = evaluate_php_file(
+        r#"<?php
+            class Base {
+                public function find(): int {
+                    return 1;
+                }
+            }
+
+            /**
+             * @method string find()
+             */
+            class Repository extends Base {
+            }
+
+            function find_one(Repository $repository) {
+                return $repository->find();
+            }
+        "#,
+    );
+    assert_eq!(
+        Some(DiscreteType::Int.into()),
+        get_function_return_type(&result, r"\find_one")
+    );
+}
+
+#[test]
+fn test_mixin_cycle() {
+    let result = evaluate_php_file(
+        r#"<?php
+            /**
+             * @mixin Second
+             */
+            class First {
+                public function __call($name, $arguments) {
+                    return null;
+                }
+            }
+
+            /**
+             * @mixin First
+             */
+            class Second {
+                public function __call($name, $arguments) {
+                    return null;
+                }
+            }
+
+            function missing(First $first) {
+                return $first->missing();
+            }
+        "#,
+    );
+    let unknown: Vec<_> = result
+        .issues
+        .iter()
+        .filter(|issue| matches!(issue, Issue::UnknownMethod(_, _, _)))
+        .collect();
+    assert_eq!(1, unknown.len(), "{:?}", result.issues);
+}
@@ -1,13 +1,25 @@
 mod array;
+mod attributes;
 mod basic;
+mod dead_code;
+mod generators;
 pub mod generics;
 pub mod hardening;
+mod includes;
 pub mod inline;
 pub mod interface;
+mod magic;
 pub mod namespace;
 pub mod native;
+mod null_safety;
 mod objects;
+mod parse_errors;
 pub mod phpdocs;
+mod project;
+mod robustness;
+mod security;
+mod taint;
+pub mod traits;
 pub mod traversable;
 pub mod try_catch;
 pub mod types;
@@ -31,7 +43,7 @@ use crate::{
 
 pub struct TestEmitter {
     pub file_name: RwLock<Option<PathBuf>>,
-    issues: Arc<RwLock<Vec<Issue>>>,
+    pub issues: Arc<RwLock<Vec<Issue>>>,
 }
 
 impl TestEmitter {
@@ -103,52 +115,112 @@ where
 {
     let emitter = TestEmitter::new();
 
+    let buffers: Vec<_> = buffers.into_iter().collect();
+    let symbols = index_php_buffers(&config, &buffers, load_native, &emitter);
+    third_pass_php_buffers(&config, &symbols, &buffers, &emitter);
+
+    let mut result = EvaluationResult::new();
+    result.symbol_data = Some(symbols);
+    result.issues.clone_from(&emitter.issues.read().unwrap());
+    crate::dump_missing_stats();
+
+    result
+}
+
+/// Runs the first and second pass over the buffers, and returns the symbols they declare
+fn index_php_buffers(
+    config: &PHPAnalyzeConfig,
+    buffers: &[(OsString, OsString)],
+    load_native: bool,
+    emitter: &dyn IssueEmitter,
+) -> Arc<SymbolData> {
     let symbols = Arc::new(SymbolData::new());
     if load_native {
         let mut state = AnalysisState::new_with_symbols(symbols.clone());
-        crate::native::register(&mut state);
+        crate::native::register(&mut state, emitter);
     }
 
-    let buffers: Vec<_> = buffers.into_iter().collect();
-    for (buffer_name, outer_buffer) in &buffers {
+    for (buffer_name, outer_buffer) in buffers {
         let mut state = AnalysisState::new_with_symbols(symbols.clone());
         state.pass = 1;
-        let mut analyzer =
-            Analyzer::new_from_buffer(config, outer_buffer.clone(), Some(buffer_name.clone()));
-        assert!(analyzer.parse(&emitter).is_ok());
+        state.filename = Some(PathBuf::from(buffer_name));
+        let mut analyzer = Analyzer::new_from_buffer(
+            config.clone(),
+            outer_buffer.clone(),
+            Some(buffer_name.clone()),
+        );
+        assert!(analyzer.parse(emitter).is_ok());
 
         // analyzer.dump();
-        analyzer.first_pass(&mut state, &emitter);
+        analyzer.first_pass(&mut state, emitter);
     }
 
-    for (buffer_name, outer_buffer) in &buffers {
+    for (buffer_name, outer_buffer) in buffers {
         let mut state = AnalysisState::new_with_symbols(symbols.clone());
         state.pass = 2;
-        let mut analyzer =
-            Analyzer::new_from_buffer(config, outer_buffer.clone(), Some(buffer_name.clone()));
-        assert!(analyzer.parse(&emitter).is_ok());
+        state.filename = Some(PathBuf::from(buffer_name));
+        let mut analyzer = Analyzer::new_from_buffer(
+            config.clone(),
+            outer_buffer.clone(),
+            Some(buffer_name.clone()),
+        );
+        assert!(analyzer.parse(emitter).is_ok());
 
         // analyzer.dump();
-        analyzer.second_pass(&mut state, &emitter);
+        analyzer.second_pass(&mut state, emitter);
     }
+    symbols
+}
+
+fn third_pass_php_buffers(
+    config: &PHPAnalyzeConfig,
+    symbols: &Arc<SymbolData>,
+    buffers: &[(OsString, OsString)],
+    emitter: &dyn IssueEmitter,
+) {
+    for (buffer_name, outer_buffer) in buffers {
+        let mut state = AnalysisState::new_with_symbols(symbols.clone());
+        state.config = config.clone();
+        state.pass = 3;
+        state.filename = Some(PathBuf::from(buffer_name));
+        let mut analyzer = Analyzer::new_from_buffer(
+            config.clone(),
+            outer_buffer.clone(),
+            Some(buffer_name.clone()),
+        );
+        assert!(analyzer.parse(emitter).is_ok());
+        analyzer.third_pass(&mut state, emitter);
+    }
+}
 
-    for idx in 0..1 {
-        for (buffer_name, outer_buffer) in &buffers {
-            let mut state = AnalysisState::new_with_symbols(symbols.clone());
-            state.pass = 3 + idx;
-            let mut analyzer =
-                Analyzer::new_from_buffer(config, outer_buffer.clone(), Some(buffer_name.clone()));
-            assert!(analyzer.parse(&emitter).is_ok());
-            analyzer.third_pass(&mut state, &emitter);
+/// Writes the files to a fresh temporary directory, for tests which need the file system
+fn write_test_files(test_name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("phpanalyzer-{}", test_name));
+    let _ = std::fs::remove_dir_all(&dir);
+    for (name, content) in files {
+        let path = dir.join(name);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("Failed creating test directory");
         }
+        std::fs::write(&path, content).expect("Failed writing test file");
     }
+    dir
+}
 
-    let mut result = EvaluationResult::new();
-    result.symbol_data = Some(symbols);
-    result.issues.clone_from(&emitter.issues.read().unwrap());
-    crate::dump_missing_stats();
+/// Analyzes `code` as the single file `test.php`
+fn evaluate_php_file(code: &str) -> EvaluationResult {
+    let buffers: &[(OsString, OsString)] = &[("test.php".into(), code.into())];
+    evaluate_php_buffers(Default::default(), buffers.to_vec(), false)
+}
 
-    result
+fn get_function_return_type(result: &EvaluationResult, name: &str) -> Option<PHPType> {
+    let symbols = result.symbol_data.as_ref().expect("Missing symbols");
+    let functions = symbols.functions.read().unwrap();
+    let func = functions
+        .get(&FullyQualifiedName::from(name))
+        .expect("Function not found");
+    let data = func.read().unwrap();
+    data.inferred_return_type.clone()
 }
 
 fn evaluate_php_code_in_function<T: Into<OsString>>(
@@ -166,7 +238,7 @@ fn evaluate_php_code_in_function<T: Into<OsString>>(
     assert!(analyzer.parse(&emitter).is_ok());
 
     let mut state = AnalysisState::new();
-    crate::native::register(&mut state);
+    crate::native::register(&mut state, &emitter);
     // analyzer.dump();
     analyzer.first_pass(&mut state, &emitter);
     analyzer.second_pass(&mut state, &emitter);
@@ -1,4 +1,14 @@
-use crate::{tests::evaluate_php_code_in_function, types::union::DiscreteType};
+use crate::{
+    analysis::state::AnalysisState,
+    config::NativeConfig,
+    issue::Issue,
+    native::is_extension_enabled,
+    symboldata::class::{ClassName, ClassType},
+    symbols::{FullyQualifiedName, Name},
+    tests::{evaluate_php_buffers, evaluate_php_code_in_function, TestEmitter},
+    types::union::{DiscreteType, PHPType, ShapeType, ShapeTypeKey},
+};
+use std::ffi::OsString;
 
 #[test]
 fn test_dom_api1() {
@@ -58,3 +68,196 @@ fn test_dom_api3() {
     );
     assert_eq!(result.issues.len(), 0);
 }
+
+#[test]
+fn test_stub_file() {
+    let emitter = TestEmitter::new();
+    let mut state = AnalysisState::new();
+    crate::native::stubs::register_stub_buffer(
+        &mut state,
+        br#"<?php
+        namespace Ext;
+        function ext_version(): string {}
+        class ExtClient {
+            public function connect(string $host): bool {}
+        }
+        "#
+        .to_vec(),
+        "ext.stub.php".into(),
+        &emitter,
+    )
+    .expect("Stub should be parsed");
+    assert!(emitter.issues.read().unwrap().is_empty());
+
+    let functions = state.symbol_data.functions.read().unwrap();
+    let function = functions
+        .get(&FullyQualifiedName::from("\\ext\\ext_version"))
+        .expect("Stub function should be registered")
+        .read()
+        .unwrap();
+    assert!(function.is_native);
+    assert_eq!(function.php_return_type, Some(DiscreteType::String.into()));
+
+    let class_name = ClassName::new_with_names("ExtClient".into(), "\\Ext\\ExtClient".into());
+    let class = state
+        .symbol_data
+        .get_class(&class_name)
+        .expect("Stub class should be registered");
+    let ClassType::Class(class_data) = &*class.read().unwrap() else {
+        panic!("Expected a class");
+    };
+    assert!(class_data.is_native);
+    assert!(class_data.methods.contains_key(&Name::from("connect")));
+}
+
+#[test]
+fn test_extension_selection() {
+    let config = NativeConfig {
+        extensions: &["intl"],
+        ..Default::default()
+    };
+    assert!(is_extension_enabled(&config, "Core"));
+    assert!(is_extension_enabled(&config, "intl"));
+    assert!(!is_extension_enabled(&config, "redis"));
+}
+
+#[test]
+#[cfg(any(
+    native_php_7_4,
+    native_php_8_0,
+    native_php_8_1,
+    native_php_8_2,
+    native_php_8_3,
+    native_php_8_4
+))]
+fn test_version_selection() {
+    use crate::{config::PHPVersion, native::get_native_version};
+
+    let oldest = get_native_version(PHPVersion::new(5, 6)).expect("No native symbol set loaded");
+    // Too old versions fall back to the oldest one compiled in
+    assert!(get_native_version(oldest).is_some_and(|v| v == oldest));
+    assert!(get_native_version(PHPVersion::new(99, 0)).is_some_and(|v| v >= oldest));
+}
+
+fn get_refined_return_type(code: &str, function: &str) -> Option<PHPType> {
+    // Declarations of the natives, as they aren't necessarily loaded
+    let stubs = r#"<?php
+        function json_decode(string $json, ?bool $associative = null, int $depth = 512, int $flags = 0): mixed {}
+        function explode(string $separator, string $string, int $limit = PHP_INT_MAX): array {}
+        function array_map(?callable $callback, array $array, array ...$arrays): array {}
+        function preg_match(string $pattern, string $subject, &$matches = null, int $flags = 0, int $offset = 0): int|false {}
+    "#;
+    let buffers: &[(OsString, OsString)] = &[
+        ("stubs.php".into(), stubs.into()),
+        ("test.php".into(), code.into()),
+    ];
+    let result = evaluate_php_buffers(Default::default(), buffers.to_vec(), true);
+    let symbols = result.symbol_data.expect("Missing symbols");
+    let functions = symbols.functions.read().unwrap();
+    let func = functions
+        .get(&FullyQualifiedName::from(function))
+        .expect("Function not found")
+        .read()
+        .unwrap();
+    func.inferred_return_type.clone()
+}
+
+#[test]
+fn test_refined_native_return_types() {
+    let code = r#"<?php
+        function decode_assoc(string $json) {
+            return json_decode($json, true);
+        }
+        function split_words(string $words) {
+            return explode(" ", $words);
+        }
+        /** @param list<int> $numbers */
+        function stringify(array $numbers) {
+            return array_map(fn (int $n): string => "$n", $numbers);
+        }
+    "#;
+    let assoc = get_refined_return_type(code, "\\decode_assoc").expect("Missing type");
+    assert!(!assoc.to_string().contains("stdClass"), "{}", assoc);
+    assert_eq!(
+        get_refined_return_type(code, "\\split_words"),
+        Some(DiscreteType::NonEmptyList(DiscreteType::String.into()).into())
+    );
+    assert_eq!(
+        get_refined_return_type(code, "\\stringify"),
+        Some(DiscreteType::List(DiscreteType::String.into()).into())
+    );
+}
+
+#[test]
+fn test_by_ref_out_parameter() {
+    let code = r#"<?php
+        function first_group(string $subject) {
+            preg_match("/(a+)(?:b)(?<tail>c)?/", $subject, $matches);
+            return $matches;
+        }
+        function any_group(string $pattern, string $subject) {
+            preg_match($pattern, $subject, $matches);
+            return $matches;
+        }
+    "#;
+    let groups = [
+        ShapeTypeKey::Int(0),
+        ShapeTypeKey::Int(1),
+        ShapeTypeKey::String("tail".into()),
+        ShapeTypeKey::Int(2),
+    ];
+    let shape = groups.into_iter().fold(ShapeType::new(), |shape, key| {
+        shape.with_key_type(key, DiscreteType::String.into())
+    });
+    assert_eq!(
+        get_refined_return_type(code, "\\first_group"),
+        Some(DiscreteType::Shape(shape).into())
+    );
+    assert_eq!(
+        get_refined_return_type(code, "\\any_group"),
+        Some(
+            DiscreteType::HashMap(DiscreteType::ArrayKey.into(), DiscreteType::String.into())
+                .into()
+        )
+    );
+}
+
+#[test]
+fn test_by_ref_out_parameter_groups() {
+    let code = r#"<?php
+        function numbered_group(string $subject) {
+            preg_match("/(a+)(?<tail>c)?/", $subject, $matches);
+            return $matches[1];
+        }
+        function named_group(string $subject) {
+            preg_match("/(a+)(?<tail>c)?/", $subject, $matches);
+            return $matches["tail"];
+        }
+    "#;
+    assert_eq!(
+        get_refined_return_type(code, "\\numbered_group"),
+        Some(DiscreteType::String.into())
+    );
+    assert_eq!(
+        get_refined_return_type(code, "\\named_group"),
+        Some(DiscreteType::String.into())
+    );
+}
+
+#[test]
+fn test_unreadable_stub_file_is_reported() {
+    let emitter = TestEmitter::new();
+    let mut state = AnalysisState::new();
+    state.config.native.stub_files = &["missing.stub.php"];
+    crate::native::register(&mut state, &emitter);
+
+    let issues = emitter.issues.read().unwrap();
+    assert!(
+        issues
+            .iter()
+            .any(|issue| matches!(issue, Issue::ParseError(_, _))
+                && issue.filename() == Some("missing.stub.php".into())),
+        "{:?}",
+        issues
+    );
+}
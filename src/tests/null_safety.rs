@@ -0,0 +1,128 @@
+use std::ffi::OsString;
+
+use crate::{issue::Issue, symbols::Name, tests::evaluate_php_buffers};
+
+fn get_null_safety_issues(functions: &str) -> Vec<Issue> {
+    let code = format!(
+        "<?php
+            class X {{
+                public int $id = 0;
+                public array $tags = [];
+                function foo() {{}}
+            }}
+            function takes_x(X $x) {{}}
+            function takes_nullable_x(?X $x) {{}}
+            function takes_defaulted_x(X $x = null) {{}}
+            {}
+        ",
+        functions
+    );
+    let buffers: Vec<(OsString, OsString)> = vec![("null_safety.php".into(), code.into())];
+    evaluate_php_buffers(Default::default(), buffers, true).issues
+}
+
+#[test]
+fn test_property_access_on_nullable() {
+    let issues = get_null_safety_issues(
+        r#"
+            function a(?X $x) {
+                return $x->id;
+            }
+            function b(?X $x) {
+                if ($x) {
+                    return $x->id;
+                }
+                return 0;
+            }
+            function c(?X $x) {
+                return isset($x->id) ? 1 : $x?->id ?? 0;
+            }
+        "#,
+    );
+    let reported: Vec<_> = issues
+        .iter()
+        .filter_map(|issue| match issue {
+            Issue::PropertyAccessOnNullableType(_, pname) => Some(pname.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(vec![Some(Name::from("id"))], reported, "{:?}", issues);
+}
+
+#[test]
+fn test_array_access_on_nullable() {
+    let issues = get_null_safety_issues(
+        r#"
+            function a(?array $row) {
+                return $row['id'];
+            }
+            function b(?array $row) {
+                return $row['id'] ?? null;
+            }
+            function c(array $row) {
+                return $row['id'];
+            }
+        "#,
+    );
+    let count = issues
+        .iter()
+        .filter(|issue| matches!(issue, Issue::ArrayAccessOnNullableType(_, _)))
+        .count();
+    assert_eq!(1, count, "{:?}", issues);
+}
+
+#[test]
+fn test_nullable_argument() {
+    let issues = get_null_safety_issues(
+        r#"
+            function a(?X $x) {
+                takes_x($x);
+                takes_x(x: $x);
+                takes_nullable_x($x);
+                takes_defaulted_x($x);
+                if ($x) {
+                    takes_x($x);
+                }
+            }
+        "#,
+    );
+    let reported: Vec<_> = issues
+        .iter()
+        .filter_map(|issue| match issue {
+            Issue::NullableArgumentType(_, callee, param, _) => {
+                Some((callee.clone(), param.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            (Name::from("\\takes_x"), Name::from("x")),
+            (Name::from("\\takes_x"), Name::from("x")),
+        ],
+        reported,
+        "{:?}",
+        issues
+    );
+}
+
+#[test]
+fn test_redundant_nullsafe_operator() {
+    let issues = get_null_safety_issues(
+        r#"
+            function a(X $x) {
+                $x?->foo();
+                return $x?->id;
+            }
+            function b(?X $x) {
+                $x?->foo();
+                return $x?->id;
+            }
+        "#,
+    );
+    let count = issues
+        .iter()
+        .filter(|issue| matches!(issue, Issue::RedundantNullsafeOperator(_, _)))
+        .count();
+    assert_eq!(2, count, "{:?}", issues);
+}
@@ -0,0 +1,128 @@
+use std::sync::Arc;
+
+use crate::{
+    analysis::{analyzer::Analyzer, state::AnalysisState},
+    issue::Issue,
+    symboldata::{class::ClassType, SymbolData},
+    symbols::FullyQualifiedName,
+    tests::TestEmitter,
+};
+
+/// Runs every pass over `code`, parsing it only once so each syntax error is reported once
+fn analyze(code: &str) -> (Vec<Issue>, Arc<SymbolData>) {
+    let emitter = TestEmitter::new();
+    let mut analyzer =
+        Analyzer::new_from_buffer(Default::default(), code.into(), Some("broken.php".into()));
+    assert!(analyzer.parse(&emitter).is_ok());
+
+    let mut state = AnalysisState::new();
+    state.filename = Some("broken.php".into());
+    analyzer.first_pass(&mut state, &emitter);
+    analyzer.second_pass(&mut state, &emitter);
+    analyzer.third_pass(&mut state, &emitter);
+
+    let issues = emitter.issues.read().unwrap().clone();
+    (issues, state.symbol_data)
+}
+
+fn parse_errors(issues: &[Issue]) -> Vec<&Issue> {
+    issues
+        .iter()
+        .filter(|issue| matches!(issue, Issue::ParseError(_, _)))
+        .collect()
+}
+
+#[test]
+fn test_missing_semicolon_keeps_the_file_analyzed() -> Result<(), &'static str> {
+    let (issues, symbols) = analyze(
+        r#"<?php
+            function broken(): int {
+                $value = 1
+                return $value;
+            }
+
+            class Later {
+                public function run(): int {
+                    return 2;
+                }
+            }
+
+            function later(): int {
+                return undefined_function();
+            }
+        "#,
+    );
+    assert!(!parse_errors(&issues).is_empty(), "{:?}", issues);
+    assert!(
+        issues
+            .iter()
+            .any(|issue| matches!(issue, Issue::UnknownFunction(_, _))),
+        "{:?}",
+        issues
+    );
+
+    symbols
+        .get_function(&FullyQualifiedName::from(r"\later"))
+        .ok_or("Missing function later")?;
+    symbols
+        .get_class(&FullyQualifiedName::from(r"\Later").into())
+        .ok_or("Missing class Later")?;
+    Ok(())
+}
+
+#[test]
+fn test_broken_method_keeps_the_rest_of_the_class() -> Result<(), &'static str> {
+    let (issues, symbols) = analyze(
+        r#"<?php
+            class Service {
+                public function broken() {
+                    return 1 + ;
+                }
+
+                public function working(): int {
+                    return 1;
+                }
+            }
+        "#,
+    );
+    assert!(!parse_errors(&issues).is_empty(), "{:?}", issues);
+
+    let class = symbols
+        .get_class(&FullyQualifiedName::from(r"\Service").into())
+        .ok_or("Missing class Service")?;
+    let ClassType::Class(cdata) = &*class.read().unwrap() else {
+        return Err("Service is not a class");
+    };
+    cdata
+        .get_method(&"working".into(), symbols.clone())
+        .ok_or("Missing method working")?;
+    Ok(())
+}
+
+#[test]
+fn test_every_syntax_error_is_reported() {
+    let (issues, _) = analyze(
+        r#"<?php
+            function one() { $a = 1 }
+            function two() { $b = ; }
+            function three() { $c = 3 }
+        "#,
+    );
+    let errors = parse_errors(&issues);
+    let mut rows: Vec<_> = errors
+        .iter()
+        .map(|issue| issue.range().start_point.row)
+        .collect();
+    rows.dedup();
+    assert_eq!(vec![1, 2, 3], rows, "{:?}", errors);
+
+    for error in errors {
+        let message = error.as_string();
+        assert!(
+            message.starts_with("Parse error: missing ")
+                || message.starts_with("Parse error: unexpected "),
+            "{}",
+            message
+        );
+    }
+}
@@ -6,7 +6,9 @@ use crate::{
     parser::Range,
     phpdoc::{
         position::fake_range,
-        types::{PHPDocComment, PHPDocEntry},
+        types::{
+            PHPDocAssertKind, PHPDocComment, PHPDocEntry, PHPDocPropertyAccess, TemplateVariance,
+        },
     },
     types::parser::compound_type,
 };
@@ -127,3 +129,222 @@ pub fn test_var3() {
         );
     }
 }
+
+#[test]
+pub fn test_property_and_method() {
+    let phpdoc = test_parse(
+        "/**
+          * @property-read int $created_at When it was created
+          * @method static self find(int $id, string ...$columns)
+          * @method where($column, $value = null)
+          */"
+        .into(),
+    )
+    .expect("Unable to parse doccomment");
+
+    let Some(PHPDocEntry::Property(_, access, _, name, desc)) = phpdoc.entries.get(1) else {
+        unreachable!("Expected @property-read, got {:?}", phpdoc.entries);
+    };
+    assert_eq!(PHPDocPropertyAccess::Read, *access);
+    assert_eq!(OsString::from("$created_at"), *name);
+    assert_eq!(Some(OsString::from("When it was created")), *desc);
+
+    let Some(PHPDocEntry::Method(_, method)) = phpdoc.entries.get(2) else {
+        unreachable!("Expected @method, got {:?}", phpdoc.entries);
+    };
+    assert!(method.is_static);
+    assert!(method.return_type.is_some());
+    assert_eq!(OsString::from("find"), method.name);
+    assert_eq!(2, method.params.len());
+    assert!(method.params[1].variadic);
+
+    let Some(PHPDocEntry::Method(_, method)) = phpdoc.entries.get(3) else {
+        unreachable!("Expected @method, got {:?}", phpdoc.entries);
+    };
+    assert!(!method.is_static);
+    assert!(method.return_type.is_none());
+    assert_eq!(OsString::from("where"), method.name);
+    assert!(method.params[0].ptype.is_none());
+    assert_eq!(Some(OsString::from("null")), method.params[1].default);
+}
+
+#[test]
+pub fn test_prefixed_tags_take_precedence() {
+    let phpdoc = test_parse(
+        "/**
+          * @param array $rows
+          * @psalm-param list<string> $rows
+          * @param int $limit
+          * @return array
+          * @phpstan-return list<string>
+          * @psalm-suppress MixedAssignment
+          */"
+        .into(),
+    )
+    .expect("Unable to parse doccomment");
+
+    let params: Vec<_> = phpdoc
+        .entries
+        .iter()
+        .filter_map(|entry| match entry {
+            PHPDocEntry::Param(_, ptype, name, _) => Some((ptype.to_string(), name.clone())),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            ("list<string>".to_string(), Some(OsString::from("$rows"))),
+            ("int".to_string(), Some(OsString::from("$limit"))),
+        ],
+        params
+    );
+
+    let returns: Vec<_> = phpdoc
+        .entries
+        .iter()
+        .filter_map(|entry| match entry {
+            PHPDocEntry::Return(_, ptype, _) => Some(ptype.to_string()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(vec!["list<string>".to_string()], returns);
+
+    assert!(phpdoc.entries.iter().any(|entry| matches!(
+        entry,
+        PHPDocEntry::GeneralWithParam(_, name, _) if name == "psalm-suppress"
+    )));
+}
+
+#[test]
+pub fn test_type_alias_entries() {
+    let phpdoc = test_parse(
+        "/**
+          * @phpstan-type UserRow array{id: int, name: string}
+          * @psalm-type UserId = int
+          * @phpstan-import-type Address from \\App\\Addresses as HomeAddress
+          */"
+        .into(),
+    )
+    .expect("Unable to parse doccomment");
+
+    let Some(PHPDocEntry::TypeAlias(_, name, _)) = phpdoc.entries.get(1) else {
+        unreachable!("Expected type alias, got {:?}", phpdoc.entries);
+    };
+    assert_eq!(OsString::from("UserRow"), *name);
+
+    let Some(PHPDocEntry::TypeAlias(_, name, ptype)) = phpdoc.entries.get(2) else {
+        unreachable!("Expected type alias, got {:?}", phpdoc.entries);
+    };
+    assert_eq!(OsString::from("UserId"), *name);
+    assert_eq!("int", ptype.to_string());
+
+    assert_eq!(
+        Some(&PHPDocEntry::ImportType(
+            *phpdoc.entries[3].range(),
+            "Address".into(),
+            "\\App\\Addresses".into(),
+            Some("HomeAddress".into())
+        )),
+        phpdoc.entries.get(3)
+    );
+}
+
+#[test]
+pub fn test_template_bounds_and_variance() {
+    let phpdoc = test_parse(
+        "/**
+          * @template TKey of array-key
+          * @template-covariant TValue as object = \\stdClass
+          * @psalm-template-contravariant TInput The input
+          */"
+        .into(),
+    )
+    .expect("Unable to parse doccomment");
+
+    let Some(PHPDocEntry::Template(_, key)) = phpdoc.entries.get(1) else {
+        unreachable!("Expected template, got {:?}", phpdoc.entries);
+    };
+    assert_eq!(OsString::from("TKey"), key.name);
+    assert_eq!(TemplateVariance::Invariant, key.variance);
+    assert_eq!(
+        Some("array-key".to_string()),
+        key.bound.as_ref().map(|b| b.to_string())
+    );
+    assert!(key.default.is_none());
+
+    let Some(PHPDocEntry::Template(_, value)) = phpdoc.entries.get(2) else {
+        unreachable!("Expected template, got {:?}", phpdoc.entries);
+    };
+    assert_eq!(TemplateVariance::Covariant, value.variance);
+    assert_eq!(
+        Some("object".to_string()),
+        value.bound.as_ref().map(|b| b.to_string())
+    );
+    assert_eq!(
+        Some("\\stdClass".to_string()),
+        value.default.as_ref().map(|d| d.to_string())
+    );
+
+    let Some(PHPDocEntry::Template(_, input)) = phpdoc.entries.get(3) else {
+        unreachable!("Expected template, got {:?}", phpdoc.entries);
+    };
+    assert_eq!(TemplateVariance::Contravariant, input.variance);
+    assert_eq!(Some(OsString::from("The input")), input.description);
+}
+
+#[test]
+pub fn test_assert_entries() {
+    let phpdoc = test_parse(
+        "/**
+          * @assert int $count
+          * @psalm-assert !null $user The user
+          * @phpstan-assert-if-true non-empty-string $name
+          * @psalm-assert-if-false !User $user
+          */"
+        .into(),
+    )
+    .expect("Unable to parse doccomment");
+
+    let asserts: Vec<_> = phpdoc
+        .entries
+        .iter()
+        .filter_map(|entry| match entry {
+            PHPDocEntry::Assert(_, assert) => Some((
+                assert.kind,
+                assert.negated,
+                assert.atype.to_string(),
+                assert.name.clone(),
+            )),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            (
+                PHPDocAssertKind::Always,
+                false,
+                "int".to_string(),
+                "$count".into()
+            ),
+            (
+                PHPDocAssertKind::Always,
+                true,
+                "null".to_string(),
+                "$user".into()
+            ),
+            (
+                PHPDocAssertKind::IfTrue,
+                false,
+                "non-empty-string".to_string(),
+                "$name".into()
+            ),
+            (
+                PHPDocAssertKind::IfFalse,
+                true,
+                "User".to_string(),
+                "$user".into()
+            ),
+        ],
+        asserts
+    );
+}
@@ -0,0 +1,275 @@
+use std::{path::PathBuf, sync::Arc};
+
+use crate::{
+    analysis::project::{analyze_project, analyze_project_files, AnalysisMode, ComposerProject},
+    config::PHPAnalyzeConfig,
+    issue::Issue,
+    symbols::{FullyQualifiedName, Name},
+    tests::{write_test_files, TestEmitter},
+};
+
+const COMPOSER_JSON: &str = r#"{
+    "name": "acme/app",
+    "autoload": {
+        "psr-4": { "App\\": "src/" },
+        "classmap": ["legacy/"]
+    },
+    "autoload-dev": {
+        "psr-4": { "App\\Tests\\": "tests/" }
+    }
+}"#;
+
+const INSTALLED_JSON: &str = r#"{
+    "packages": [
+        {
+            "name": "acme/lib",
+            "install-path": "../acme/lib",
+            "autoload": { "psr-0": { "Acme_": "lib/" } }
+        }
+    ]
+}"#;
+
+#[test]
+fn test_composer_files() {
+    let dir = write_test_files(
+        "composer-files",
+        &[
+            ("composer.json", COMPOSER_JSON),
+            ("vendor/composer/installed.json", INSTALLED_JSON),
+            (
+                "src/Models/User.php",
+                "<?php\nnamespace App\\Models;\nclass User {}\n",
+            ),
+            (
+                "tests/UserTest.php",
+                "<?php\nnamespace App\\Tests;\nclass UserTest {}\n",
+            ),
+            ("legacy/helpers.php", "<?php\nclass Legacy_Helper {}\n"),
+            (
+                "vendor/acme/lib/lib/Acme/Http/Client.php",
+                "<?php\nclass Acme_Http_Client {}\n",
+            ),
+        ],
+    );
+    let project = ComposerProject::load(&dir).expect("Failed loading project");
+    let mut files: Vec<_> = project
+        .get_files()
+        .into_iter()
+        .map(|file| {
+            (
+                file.path.strip_prefix(&dir).unwrap().to_path_buf(),
+                file.mode,
+            )
+        })
+        .collect();
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(
+        files,
+        vec![
+            (PathBuf::from("legacy/helpers.php"), AnalysisMode::Full),
+            (PathBuf::from("src/Models/User.php"), AnalysisMode::Full),
+            (PathBuf::from("tests/UserTest.php"), AnalysisMode::Full),
+            (
+                PathBuf::from("vendor/acme/lib/lib/Acme/Http/Client.php"),
+                AnalysisMode::IndexOnly
+            ),
+        ]
+    );
+
+    let locate = |name: &str| {
+        project
+            .locate_class(&FullyQualifiedName::from(name))
+            .map(|path| path.strip_prefix(&dir).unwrap().to_path_buf())
+    };
+    assert_eq!(
+        locate("\\App\\Models\\User"),
+        Some(PathBuf::from("src/Models/User.php"))
+    );
+    assert_eq!(
+        locate("\\Acme_Http_Client"),
+        Some(PathBuf::from("vendor/acme/lib/lib/Acme/Http/Client.php"))
+    );
+    assert_eq!(
+        locate("\\Legacy_Helper"),
+        Some(PathBuf::from("legacy/helpers.php"))
+    );
+    // Namespaces are case-insensitive
+    assert_eq!(
+        locate("\\app\\Models\\User"),
+        Some(PathBuf::from("src/Models/User.php"))
+    );
+    assert_eq!(locate("\\App\\Models\\Missing"), None);
+}
+
+#[test]
+fn test_psr4_violations() {
+    let dir = write_test_files(
+        "psr4-violations",
+        &[
+            ("composer.json", COMPOSER_JSON),
+            (
+                "src/Models/User.php",
+                "<?php\nnamespace App\\Models;\nclass User {}\n",
+            ),
+            (
+                "src/Models/Group.php",
+                "<?php\nnamespace App\\Models;\nclass Team {}\n",
+            ),
+            ("src/Other.php", "<?php\nnamespace Other;\nclass Other {}\n"),
+        ],
+    );
+    let project = ComposerProject::load(&dir).expect("Failed loading project");
+    let emitter = TestEmitter::new();
+    analyze_project(&project, Default::default(), &emitter);
+    let issues = emitter.issues.read().unwrap();
+
+    let mut location_mismatches = vec![];
+    let mut namespace_mismatches = vec![];
+    for issue in issues.iter() {
+        match issue {
+            Issue::ClassLocationMismatch(_, cname, path) => {
+                location_mismatches.push((cname.to_string(), PathBuf::from(path)))
+            }
+            Issue::ClassNamespaceMismatch(_, cname, prefix) => {
+                namespace_mismatches.push((cname.to_string(), prefix.clone()))
+            }
+            _ => (),
+        }
+    }
+    assert_eq!(
+        location_mismatches,
+        vec![(
+            "\\App\\Models\\Team".to_string(),
+            dir.join("src/Models/Team.php")
+        )]
+    );
+    assert_eq!(
+        namespace_mismatches,
+        vec![("\\Other\\Other".to_string(), "App\\".into())]
+    );
+}
+
+#[test]
+fn test_lazy_class_loading() {
+    let dir = write_test_files(
+        "lazy-class-loading",
+        &[
+            ("composer.json", COMPOSER_JSON),
+            (
+                "src/Models/User.php",
+                "<?php\nnamespace App\\Models;\nclass User { const ROLE = 'user'; }\n",
+            ),
+            (
+                "src/Service.php",
+                r#"<?php
+                    namespace App;
+                    use App\Models\User;
+                    use App\Models\Missing;
+                    function roles() { return [User::ROLE, Missing::ROLE]; }
+                "#,
+            ),
+        ],
+    );
+    let project = Arc::new(ComposerProject::load(&dir).expect("Failed loading project"));
+    let emitter = TestEmitter::new();
+    let symbol_data = analyze_project_files(
+        project,
+        &[dir.join("src/Service.php")],
+        Default::default(),
+        &emitter,
+    );
+    let unknown: Vec<_> = emitter
+        .issues
+        .read()
+        .unwrap()
+        .iter()
+        .filter_map(|issue| match issue {
+            Issue::UnknownClass(_, cname) => Some(cname.to_string()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(unknown, vec!["\\App\\Models\\Missing".to_string()]);
+    assert!(symbol_data
+        .classes
+        .read()
+        .unwrap()
+        .contains_key(&FullyQualifiedName::from("\\app\\models\\user")));
+}
+
+#[test]
+fn test_lazy_loading_skips_analyzed_files() {
+    let dir = write_test_files(
+        "lazy-loading-analyzed",
+        &[
+            ("composer.json", COMPOSER_JSON),
+            (
+                "src/Service.php",
+                r#"<?php
+                    namespace App;
+                    use App\Models\User;
+                    function role() { return User::ROLE; }
+                "#,
+            ),
+            (
+                "src/Models/User.php",
+                "<?php\nnamespace App\\Models;\nclass User { const ROLE = 'user'; }\n",
+            ),
+        ],
+    );
+    let project = Arc::new(ComposerProject::load(&dir).expect("Failed loading project"));
+    let emitter = TestEmitter::new();
+    analyze_project_files(
+        project,
+        &[dir.join("src/Service.php"), dir.join("src/Models/User.php")],
+        Default::default(),
+        &emitter,
+    );
+    let issues = emitter.issues.read().unwrap();
+    assert!(
+        !issues.iter().any(|issue| matches!(
+            issue,
+            Issue::DuplicateClass(_, _) | Issue::UnknownClass(_, _)
+        )),
+        "{:?}",
+        issues
+    );
+}
+
+#[test]
+fn test_project_dead_code() {
+    let files = [
+        ("composer.json", COMPOSER_JSON),
+        (
+            "src/Service.php",
+            r#"<?php
+                namespace App;
+                class Service {
+                    public function run() { return 1; }
+                    private function forgotten() { return 2; }
+                }
+            "#,
+        ),
+    ];
+    let unused_methods = |config: PHPAnalyzeConfig| {
+        let dir = write_test_files("project-dead-code", &files);
+        let project = ComposerProject::load(&dir).expect("Failed loading project");
+        let emitter = TestEmitter::new();
+        analyze_project(&project, config, &emitter);
+        let issues = emitter.issues.read().unwrap();
+        issues
+            .iter()
+            .filter_map(|issue| match issue {
+                Issue::UnusedMethod(_, _, name) => Some(name.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        unused_methods(Default::default()),
+        vec![Name::from("forgotten")]
+    );
+
+    let mut config = PHPAnalyzeConfig::default();
+    config.dead_code.enabled = false;
+    assert!(unused_methods(config).is_empty());
+}
@@ -0,0 +1,80 @@
+use std::{
+    ffi::OsString,
+    io::{Error, ErrorKind},
+    sync::{Arc, RwLock},
+    thread,
+};
+
+use crate::{
+    analysis::analyzer::{Analyzer, AnalyzerError},
+    issue::Issue,
+    locks::RecoverableLock,
+    tests::{evaluate_php_buffers, TestEmitter},
+};
+
+#[test]
+fn test_unreadable_content() {
+    let emitter = TestEmitter::new();
+    let mut analyzer = Analyzer::new(
+        Default::default(),
+        Box::new(|| Err(Error::new(ErrorKind::NotFound, "gone"))),
+        "missing.php".into(),
+    );
+    let result = analyzer.parse(&emitter);
+    assert!(
+        matches!(result, Err(AnalyzerError::Read(_))),
+        "{:?}",
+        result
+    );
+}
+
+#[test]
+fn test_poisoned_lock_is_recovered() {
+    let lock = Arc::new(RwLock::new(vec![1]));
+    let poisoning = lock.clone();
+    let _ = thread::spawn(move || {
+        let mut guard = poisoning.write_or_recover();
+        guard.push(2);
+        panic!("Panic while holding the lock");
+    })
+    .join();
+
+    assert!(lock.is_poisoned());
+    lock.write_or_recover().push(3);
+    assert_eq!(vec![1, 2, 3], *lock.read_or_recover());
+}
+
+#[test]
+fn test_uncommon_constructs_are_analyzed() {
+    let buffers: Vec<(OsString, OsString)> = vec![(
+        "uncommon.php".into(),
+        r#"<?php
+            namespace App;
+            use Some\Library\{First, Second};
+
+            const LIMIT = 10;
+            define('INCOMPLETE');
+
+            trait Logging {}
+            trait Logging {}
+
+            class Limits {
+                PUBLIC function get(): self {
+                    return $this;
+                }
+            }
+
+            function limit(): int {
+                return LIMIT;
+            }
+        "#
+        .into(),
+    )];
+    let issues = evaluate_php_buffers(Default::default(), buffers, true).issues;
+    let names: Vec<_> = issues
+        .iter()
+        .map(Issue::get_name)
+        .filter(|name| ["DuplicateClass", "UnknownConstant", "ParseAnomaly"].contains(name))
+        .collect();
+    assert_eq!(vec!["DuplicateClass"], names, "{:?}", issues);
+}
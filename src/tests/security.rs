@@ -0,0 +1,122 @@
+use std::ffi::OsString;
+
+use crate::{config::PHPAnalyzeConfig, issue::Issue, tests::evaluate_php_buffers};
+
+const SECURITY_ISSUES: &[&str] = &[
+    "UseOfEval",
+    "BacktickShellCommand",
+    "UseOfExtract",
+    "UnserializeOfVariableInput",
+    "AssertWithStringArgument",
+    "PregReplaceEvalModifier",
+    "WeakPasswordHash",
+    "InsecureRandomToken",
+    "LooseHashComparison",
+    "ErrorSuppression",
+];
+
+fn get_security_issue_names(config: PHPAnalyzeConfig, code: &str) -> Vec<&'static str> {
+    let buffers: Vec<(OsString, OsString)> = vec![("security.php".into(), code.into())];
+    evaluate_php_buffers(config, buffers, true)
+        .issues
+        .iter()
+        .map(Issue::get_name)
+        .filter(|name| SECURITY_ISSUES.contains(name))
+        .collect()
+}
+
+#[test]
+fn test_dangerous_constructs() {
+    let names = get_security_issue_names(
+        Default::default(),
+        r#"<?php
+            function run(string $code, string $data, array $vars) {
+                eval($code);
+                $files = `ls`;
+                extract($vars);
+                $object = unserialize($data);
+                $config = unserialize('a:0:{}');
+                assert('$code > 0');
+                assert($code !== '');
+                $html = preg_replace('/(\w+)/e', 'strtoupper("$1")', $data);
+                $text = preg_replace('/(\w+)/i', '$1', $data);
+                $contents = @file_get_contents($data);
+            }
+        "#,
+    );
+    assert_eq!(
+        vec![
+            "UseOfEval",
+            "BacktickShellCommand",
+            "UseOfExtract",
+            "UnserializeOfVariableInput",
+            "AssertWithStringArgument",
+            "PregReplaceEvalModifier",
+            "ErrorSuppression",
+        ],
+        names
+    );
+}
+
+#[test]
+fn test_weak_password_hash_and_insecure_random() {
+    let names = get_security_issue_names(
+        Default::default(),
+        r#"<?php
+            function store(string $password, string $name) {
+                $hashed = md5($password);
+                $stored = sha1($_POST['password']);
+                $checksum = md5($name);
+                $token = md5(uniqid());
+                $count = rand(1, 6);
+            }
+            function generate_csrf_token() {
+                return bin2hex(random_bytes(16)) . mt_rand();
+            }
+        "#,
+    );
+    assert_eq!(
+        vec![
+            "WeakPasswordHash",
+            "WeakPasswordHash",
+            "InsecureRandomToken",
+            "InsecureRandomToken",
+        ],
+        names
+    );
+}
+
+#[test]
+fn test_loose_hash_comparison() {
+    let names = get_security_issue_names(
+        Default::default(),
+        r#"<?php
+            function check(string $input, string $stored_hash, ?string $hash) {
+                if (md5($input) == $stored_hash) {}
+                if ($input != $stored_hash) {}
+                if (md5($input) === $stored_hash) {}
+                if ($hash == null) {}
+                if ($input == 'admin') {}
+            }
+        "#,
+    );
+    assert_eq!(vec!["LooseHashComparison", "LooseHashComparison"], names);
+}
+
+#[test]
+fn test_disabled_security_rules() {
+    let mut config = PHPAnalyzeConfig::default();
+    config.security.eval = false;
+    config.security.error_suppression = false;
+    let names = get_security_issue_names(
+        config,
+        r#"<?php
+            function run(string $code, string $data) {
+                eval($code);
+                $contents = @file_get_contents($data);
+                extract([]);
+            }
+        "#,
+    );
+    assert_eq!(vec!["UseOfExtract"], names);
+}
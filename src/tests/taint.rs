@@ -0,0 +1,217 @@
+use std::ffi::OsString;
+
+use crate::{
+    analysis::{project::MAX_THIRD_PASS_ROUNDS, taint::TaintRoundEmitter},
+    config::{PHPAnalyzeConfig, TaintSanitizer, TaintSink, TaintSinkKind},
+    issue::Issue,
+    tests::{index_php_buffers, third_pass_php_buffers, TestEmitter},
+};
+
+fn get_taint_issues(code: &str) -> Vec<Issue> {
+    get_configured_taint_issues(Default::default(), code)
+}
+
+fn get_configured_taint_issues(config: PHPAnalyzeConfig, code: &str) -> Vec<Issue> {
+    let buffers: Vec<(OsString, OsString)> = vec![("taint.php".into(), code.into())];
+    let emitter = TestEmitter::new();
+    let symbols = index_php_buffers(&config, &buffers, true, &emitter);
+
+    // Rerun while the input returned from functions changes, like the project analysis
+    let round_emitter = TaintRoundEmitter::new(&emitter);
+    for _ in 0..MAX_THIRD_PASS_ROUNDS {
+        third_pass_php_buffers(&config, &symbols, &buffers, &round_emitter);
+        if !symbols.taint.write().unwrap().take_changed_returns() {
+            break;
+        }
+        round_emitter.next_round();
+    }
+
+    let issues = emitter.issues.read().unwrap().clone();
+    issues
+        .into_iter()
+        .filter(|issue| matches!(issue, Issue::TaintedSink(_, _, _, _)))
+        .collect()
+}
+
+fn get_names(issues: &[Issue]) -> Vec<&'static str> {
+    issues.iter().map(|issue| issue.get_name()).collect()
+}
+
+#[test]
+fn test_tainted_sinks() {
+    let issues = get_taint_issues(
+        r#"<?php
+            $id = $_GET['id'];
+            $query = "SELECT * FROM users WHERE id = " . $id;
+            mysqli_query($db, $query);
+            exec("ls " . $_POST['dir']);
+            echo $_COOKIE['name'];
+            include $_GET['page'] . ".php";
+            eval($_REQUEST['code']);
+            $output = `cat {$_GET['file']}`;
+        "#,
+    );
+    assert_eq!(
+        vec![
+            "SqlInjection",
+            "ShellInjection",
+            "CrossSiteScripting",
+            "FileInclusion",
+            "CodeInjection",
+            "ShellInjection",
+        ],
+        get_names(&issues),
+        "{:?}",
+        issues
+    );
+    let Issue::TaintedSink(_, _, sink, path) = &issues[0] else {
+        unreachable!();
+    };
+    assert_eq!("mysqli_query", sink.to_string());
+    assert_eq!(vec!["$_GET", "$id", "$query"], *path);
+}
+
+#[test]
+fn test_sanitized_input() {
+    let issues = get_taint_issues(
+        r#"<?php
+            echo htmlspecialchars($_GET['name']);
+            exec("ls " . escapeshellarg($_GET['dir']));
+            $id = intval($_GET['id']);
+            mysqli_query($db, "SELECT * FROM users WHERE id = $id");
+            $page = (int) $_GET['page'];
+            include "page$page.php";
+            mysqli_query($db, "SELECT '" . htmlspecialchars($_GET['name']) . "'");
+        "#,
+    );
+    assert_eq!(vec!["SqlInjection"], get_names(&issues), "{:?}", issues);
+}
+
+#[test]
+fn test_input_source_functions() {
+    let issues = get_taint_issues(
+        r#"<?php
+            $body = file_get_contents('php://input');
+            echo $body;
+            echo file_get_contents('config.json');
+        "#,
+    );
+    assert_eq!(
+        vec!["CrossSiteScripting"],
+        get_names(&issues),
+        "{:?}",
+        issues
+    );
+}
+
+#[test]
+fn test_taint_through_returns_and_properties() {
+    let issues = get_taint_issues(
+        r#"<?php
+            function get_name(): string {
+                return trim($_GET['name']);
+            }
+            class Request {
+                public string $path = '';
+                public function __construct() {
+                    $this->path = $_SERVER['REQUEST_URI'];
+                }
+            }
+            echo get_name();
+            $request = new Request();
+            exec($request->path);
+        "#,
+    );
+    assert_eq!(
+        vec!["CrossSiteScripting", "ShellInjection"],
+        get_names(&issues),
+        "{:?}",
+        issues
+    );
+}
+
+#[test]
+fn test_taint_through_arrays_and_loops() {
+    let issues = get_taint_issues(
+        r#"<?php
+            $args = [];
+            $args[] = $_GET['arg'];
+            system(implode(' ', $args));
+            foreach ($_POST as $key => $value) {
+                echo $value;
+            }
+            $count = count($_POST);
+            echo $count;
+        "#,
+    );
+    assert_eq!(
+        vec!["ShellInjection", "CrossSiteScripting"],
+        get_names(&issues),
+        "{:?}",
+        issues
+    );
+}
+
+#[test]
+fn test_hashes_and_escapes() {
+    let issues = get_taint_issues(
+        r#"<?php
+            mysqli_query($db, "SELECT * FROM users WHERE name = '" . addslashes($_GET['name']) . "'");
+            mysqli_query($db, "SELECT * FROM users WHERE hash = '" . md5($_GET['name']) . "'");
+            include md5($_GET['page']) . ".php";
+        "#,
+    );
+    assert_eq!(
+        vec!["SqlInjection", "FileInclusion"],
+        get_names(&issues),
+        "{:?}",
+        issues
+    );
+}
+
+#[test]
+fn test_taint_through_later_declared_returns() {
+    let issues = get_taint_issues(
+        r#"<?php
+            echo get_input();
+            function get_input() {
+                return wrap_input();
+            }
+            function wrap_input() {
+                return $_GET['input'];
+            }
+        "#,
+    );
+    assert_eq!(
+        vec!["CrossSiteScripting"],
+        get_names(&issues),
+        "{:?}",
+        issues
+    );
+}
+
+#[test]
+fn test_configured_sinks_and_sanitizers() {
+    let mut config = PHPAnalyzeConfig::default();
+    config.taint.sinks.push(TaintSink {
+        function: "run_query".into(),
+        argument: Some(0),
+        kind: TaintSinkKind::Sql,
+    });
+    config.taint.sanitizers.push(TaintSanitizer {
+        function: "quote_value".into(),
+        kinds: vec![TaintSinkKind::Sql],
+    });
+    let issues = get_configured_taint_issues(
+        config,
+        r#"<?php
+            function run_query(string $query) {}
+            function quote_value(string $value): string {
+                return $value;
+            }
+            run_query("SELECT " . $_GET['column']);
+            run_query("SELECT " . quote_value($_GET['column']));
+        "#,
+    );
+    assert_eq!(vec!["SqlInjection"], get_names(&issues), "{:?}", issues);
+}
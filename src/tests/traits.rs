@@ -0,0 +1,208 @@
+use std::ffi::OsString;
+
+use crate::{
+    issue::Issue,
+    symboldata::class::ClassName,
+    symbols::{FullyQualifiedName, Name},
+    tests::{evaluate_php_buffers, evaluate_php_file, get_function_return_type},
+    types::union::{DiscreteType, PHPType},
+};
+
+fn get_method_return_type(
+    code: &str,
+    class_name: &str,
+    method_name: &str,
+) -> (Option<PHPType>, Vec<Issue>) {
+    let buffers: &[(OsString, OsString)] = &[("test.php".into(), code.into())];
+    let result = evaluate_php_buffers(Default::default(), buffers.to_vec(), false);
+    let symbols = result.symbol_data.expect("Missing symbols");
+    let class_name = ClassName::new_with_fq_name(FullyQualifiedName::from(class_name));
+    let class = symbols.get_class(&class_name).expect("Missing class");
+    let method_name: Name = method_name.into();
+    let mdata = class
+        .read()
+        .unwrap()
+        .get_method(&method_name, symbols.clone());
+    (
+        mdata.and_then(|m| m.get_return_type()),
+        result.issues.clone(),
+    )
+}
+
+#[test]
+fn test_trait_method_import() {
+    let (return_type, issues) = get_method_return_type(
+        r#"<?php
+            trait Greeting {
+                function hello(): string {
+                    return "hello";
+                }
+            }
+
+            class Greeter {
+                use Greeting;
+            }
+
+            function test_it() {
+                $g = new Greeter();
+                return $g->hello();
+            }
+        "#,
+        r"\Greeter",
+        "hello",
+    );
+    assert_eq!(return_type, Some(DiscreteType::String.into()));
+    assert_eq!(issues.len(), 0);
+}
+
+#[test]
+fn test_trait_self_resolves_to_using_class() {
+    let (return_type, _) = get_method_return_type(
+        r#"<?php
+            trait Fluent {
+                function me(): self {
+                    return $this;
+                }
+            }
+
+            class Builder {
+                use Fluent;
+            }
+        "#,
+        r"\Builder",
+        "me",
+    );
+    let builder = ClassName::new_with_fq_name(r"\Builder".into());
+    assert_eq!(return_type, Some(DiscreteType::from(builder).into()));
+}
+
+#[test]
+fn test_trait_property_self_resolves_to_using_class() {
+    let result = evaluate_php_file(
+        r#"<?php
+            trait Linked {
+                /** @var static */
+                public $next;
+            }
+
+            class Node {
+                use Linked;
+            }
+
+            function next_node(Node $node) {
+                return $node->next;
+            }
+        "#,
+    );
+    let node = ClassName::new_with_fq_name(r"\Node".into());
+    assert_eq!(
+        get_function_return_type(&result, r"\next_node"),
+        Some(DiscreteType::from(node).into())
+    );
+}
+
+#[test]
+fn test_trait_insteadof_and_alias() {
+    let code = r#"<?php
+        trait A {
+            function talk(): string {
+                return "a";
+            }
+        }
+
+        trait B {
+            function talk(): int {
+                return 1;
+            }
+        }
+
+        class Talker {
+            use A, B {
+                A::talk insteadof B;
+                B::talk as protected talkB;
+            }
+        }
+    "#;
+    let (return_type, issues) = get_method_return_type(code, r"\Talker", "talk");
+    assert_eq!(return_type, Some(DiscreteType::String.into()));
+    assert!(!issues
+        .iter()
+        .any(|i| matches!(i, Issue::TraitMethodConflict(..))));
+
+    let (return_type, _) = get_method_return_type(code, r"\Talker", "talkB");
+    assert_eq!(return_type, Some(DiscreteType::Int.into()));
+}
+
+#[test]
+fn test_trait_method_conflict() {
+    let buffers: &[(OsString, OsString)] = &[(
+        "test.php".into(),
+        r#"<?php
+            trait A {
+                function talk() {}
+            }
+
+            trait B {
+                function talk() {}
+            }
+
+            class Talker {
+                use A, B;
+            }
+
+            class ResolvedTalker {
+                use A, B;
+
+                function talk() {}
+            }
+        "#
+        .into(),
+    )];
+    let result = evaluate_php_buffers(Default::default(), buffers.to_vec(), false);
+    let conflicts: Vec<_> = result
+        .issues
+        .iter()
+        .filter(|x| matches!(x, Issue::TraitMethodConflict(..)))
+        .collect();
+    assert_eq!(conflicts.len(), 1);
+}
+
+#[test]
+fn test_trait_abstract_method_not_implemented() {
+    let buffers: &[(OsString, OsString)] = &[(
+        "test.php".into(),
+        r#"<?php
+            trait NeedsName {
+                abstract function getName(): string;
+
+                function greet(): string {
+                    return "Hello " . $this->getName();
+                }
+            }
+
+            class Anonymous {
+                use NeedsName;
+            }
+
+            class Named {
+                use NeedsName;
+
+                function getName(): string {
+                    return "Bob";
+                }
+            }
+
+            abstract class StillAbstract {
+                use NeedsName;
+            }
+        "#
+        .into(),
+    )];
+    let result = evaluate_php_buffers(Default::default(), buffers.to_vec(), false);
+    let unimplemented: Vec<_> = result
+        .issues
+        .iter()
+        .filter(|x| matches!(x, Issue::UnimplementedAbstractTraitMethod(..)))
+        .collect();
+    assert_eq!(unimplemented.len(), 1);
+}
@@ -6,6 +6,8 @@ use crate::{
     analysis::state::AnalysisState,
     issue::VoidEmitter,
     parser::Range,
+    symbols::FullyQualifiedName,
+    tests::evaluate_php_buffers,
     types::{
         type_parser::TypeParser,
         union::{DiscreteType, PHPType},
@@ -31,3 +33,98 @@ fn parse_array() {
         assert!(false);
     }
 }
+
+#[test]
+fn conditional_return_type_at_call_site() {
+    let buffers: &[(OsString, OsString)] = &[(
+        "test.php".into(),
+        r#"<?php
+            /**
+             * @return ($as_string is true ? string : int)
+             */
+            function get_count(bool $as_string) {
+                return $as_string ? "1" : 1;
+            }
+
+            function get_text() {
+                return get_count(true);
+            }
+
+            function get_number() {
+                return get_count(false);
+            }
+        "#
+        .into(),
+    )];
+    let result = evaluate_php_buffers(Default::default(), buffers.to_vec(), false);
+    let symbols = result.symbol_data.expect("Missing symbols");
+    let functions = symbols.functions.read().unwrap();
+    let return_type = |name: &str| {
+        let func = functions
+            .get(&FullyQualifiedName::from(name))
+            .expect("Function not found");
+        let data = func.read().unwrap();
+        data.inferred_return_type.clone()
+    };
+    assert_eq!(return_type(r"\get_text"), Some(DiscreteType::String.into()));
+    assert_eq!(return_type(r"\get_number"), Some(DiscreteType::Int.into()));
+}
+
+#[test]
+fn type_aliases_in_class() {
+    let buffers: &[(OsString, OsString)] = &[(
+        "test.php".into(),
+        r#"<?php
+            /**
+             * @phpstan-type UserId int
+             * @psalm-type UserIds = list<UserId>
+             */
+            class Users {
+                /**
+                 * @return UserIds
+                 */
+                function ids() {
+                    return [];
+                }
+            }
+
+            /**
+             * @phpstan-import-type UserId from Users as Id
+             */
+            class Session {
+                /**
+                 * @return Id
+                 */
+                function user_id() {
+                    return 1;
+                }
+            }
+
+            function get_ids() {
+                $users = new Users();
+                return $users->ids();
+            }
+
+            function get_user_id() {
+                $session = new Session();
+                return $session->user_id();
+            }
+        "#
+        .into(),
+    )];
+    let result = evaluate_php_buffers(Default::default(), buffers.to_vec(), false);
+    let symbols = result.symbol_data.expect("Missing symbols");
+    let functions = symbols.functions.read().unwrap();
+    let return_type = |name: &str| {
+        let func = functions
+            .get(&FullyQualifiedName::from(name))
+            .expect("Function not found");
+        let data = func.read().unwrap();
+        data.inferred_return_type.clone()
+    };
+    assert_eq!(
+        return_type(r"\get_ids"),
+        Some(DiscreteType::List(DiscreteType::Int.into()).into())
+    );
+    assert_eq!(return_type(r"\get_user_id"), Some(DiscreteType::Int.into()));
+}
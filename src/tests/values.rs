@@ -114,3 +114,60 @@ fn test_echo_inline_string_variable() {
 
     assert_eq!(0, noe.issues.len(), "expected to emit 0 issues");
 }
+
+#[test]
+fn return_pure_native_values() {
+    let cases: &[(&str, PHPValue)] = &[
+        ("return strtolower('ABC');", PHPValue::String("abc".into())),
+        (
+            "return sprintf('%s-%03d', 'v', 7);",
+            PHPValue::String("v-007".into()),
+        ),
+        (
+            "return implode(',', ['a', 'b']);",
+            PHPValue::String("a,b".into()),
+        ),
+        ("return count([1, 2]);", PHPValue::Int(2)),
+        ("return max(1, 5, 3);", PHPValue::Int(5)),
+        (
+            "return str_repeat('ab', 2);",
+            PHPValue::String("abab".into()),
+        ),
+    ];
+    for (code, expected) in cases {
+        assert_eq!(
+            get_inferred_return_value(OsString::from(*code)).as_ref(),
+            Some(expected),
+            "{}",
+            code
+        );
+    }
+}
+
+#[test]
+fn return_pure_native_unknown_argument() {
+    let returned_value = get_inferred_return_value(OsString::from(
+        "
+        return strtolower($_GET['x']);
+    ",
+    ));
+    assert_eq!(None, returned_value);
+}
+
+#[test]
+fn return_pure_native_oversized_string() {
+    let cases = [
+        "return str_repeat('ab', 100000000);",
+        "return str_pad('a', 100000000);",
+        "return sprintf('%100000000s', 'a');",
+        "return sprintf('%.100000000f', 1.5);",
+    ];
+    for code in cases {
+        assert_eq!(
+            None,
+            get_inferred_return_value(OsString::from(code)),
+            "{}",
+            code
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::locks::RecoverableLock;
 use std::{collections::BTreeMap, fmt::Display, sync::Arc};
 
 use crate::{
@@ -15,6 +16,11 @@ use super::{
     union::{Consequence, Consequences, DiscreteType, PHPType, SpecialType},
 };
 
+const SCALAR_INT: u8 = 1;
+const SCALAR_FLOAT: u8 = 2;
+const SCALAR_STRING: u8 = 4;
+const SCALAR_BOOL: u8 = 8;
+
 impl DiscreteType {
     pub fn to_markdown(&self) -> String {
         self.to_string()
@@ -78,7 +84,9 @@ impl DiscreteType {
                     ))
                 }
             }
-            DiscreteType::ClassType(_, _) => todo!(),
+            DiscreteType::ClassType(_, _) => {
+                crate::missing!("Validate that the class and constants exists")
+            }
             DiscreteType::Template(t) => {
                 // FIXME this should be done in a separate method
                 // As calling this from two different points would result in all other bad types to be emitted twice
@@ -95,6 +103,22 @@ impl DiscreteType {
                     generic_type.ensure_valid(state, emitter, range, allow_unforfilled_templates);
                 }
             }
+            DiscreteType::Never => (),
+            DiscreteType::IntRange(_, _) => (),
+            DiscreteType::IntLiteral(_) => (),
+            DiscreteType::StringLiteral(_) => (),
+            DiscreteType::ArrayKey => (),
+            DiscreteType::Scalar => (),
+            DiscreteType::Numeric => (),
+            DiscreteType::List(v) | DiscreteType::NonEmptyList(v) => {
+                v.ensure_valid(state, emitter, range, allow_unforfilled_templates)
+            }
+            DiscreteType::NonEmptyArray(k, v) => {
+                k.ensure_valid(state, emitter, range, allow_unforfilled_templates);
+                v.ensure_valid(state, emitter, range, allow_unforfilled_templates);
+            }
+            // Evaluated at the call site
+            DiscreteType::Operator(_) => (),
         }
     }
 
@@ -124,6 +148,17 @@ impl DiscreteType {
             DiscreteType::Generic(_, _) => true,
             DiscreteType::ClassType(_, _) => true,
             DiscreteType::Template(_) => true,
+            DiscreteType::Never => false,
+            DiscreteType::IntRange(min, max) => *min != Some(0) || *max != Some(0),
+            DiscreteType::IntLiteral(i) => *i != 0,
+            DiscreteType::StringLiteral(s) => !s.is_empty() && s != "0",
+            DiscreteType::ArrayKey => true,
+            DiscreteType::Scalar => true,
+            DiscreteType::Numeric => true,
+            DiscreteType::List(_) => true,
+            DiscreteType::NonEmptyList(_) => true,
+            DiscreteType::NonEmptyArray(_, _) => true,
+            DiscreteType::Operator(_) => true,
         }
     }
 
@@ -146,7 +181,10 @@ impl DiscreteType {
             DiscreteType::Object => false,
             DiscreteType::Callable => false,
             DiscreteType::TypedCallable(_, _) => false,
-            DiscreteType::Special(_) => false,
+            // "0" is both non-empty and numeric
+            DiscreteType::Special(s) => {
+                matches!(s, SpecialType::NonEmptyString | SpecialType::NumericString)
+            }
             DiscreteType::Vector(_) => true,
             DiscreteType::HashMap(_, _) => true,
             DiscreteType::Shape(_) => true,
@@ -158,6 +196,19 @@ impl DiscreteType {
             }
             DiscreteType::ClassType(_, _) => true,
             DiscreteType::Template(_) => true,
+            DiscreteType::Never => false,
+            DiscreteType::IntRange(min, max) => {
+                min.map_or(true, |min| min <= 0) && max.map_or(true, |max| max >= 0)
+            }
+            DiscreteType::IntLiteral(i) => *i == 0,
+            DiscreteType::StringLiteral(s) => s.is_empty() || s == "0",
+            DiscreteType::ArrayKey => true,
+            DiscreteType::Scalar => true,
+            DiscreteType::Numeric => true,
+            DiscreteType::List(_) => true,
+            DiscreteType::NonEmptyList(_) => false,
+            DiscreteType::NonEmptyArray(_, _) => false,
+            DiscreteType::Operator(_) => true,
         }
     }
 
@@ -199,8 +250,7 @@ impl DiscreteType {
                 let check_cname: ClassName = check_cname.into();
                 if let Some(class_data) = symbol_data.get_class(&cname) {
                     return class_data
-                        .read()
-                        .unwrap()
+                        .read_or_recover()
                         .instanceof(&check_cname, symbol_data.clone());
                 }
                 false
@@ -217,6 +267,17 @@ impl DiscreteType {
                 crate::missing!();
                 true
             }
+            DiscreteType::Never => false,
+            DiscreteType::IntRange(_, _) => false,
+            DiscreteType::IntLiteral(_) => false,
+            DiscreteType::StringLiteral(_) => false,
+            DiscreteType::ArrayKey => false,
+            DiscreteType::Scalar => false,
+            DiscreteType::Numeric => false,
+            DiscreteType::List(_) => false,
+            DiscreteType::NonEmptyList(_) => false,
+            DiscreteType::NonEmptyArray(_, _) => false,
+            DiscreteType::Operator(_) => true,
         }
     }
 
@@ -231,7 +292,7 @@ impl DiscreteType {
                 let potential_parent_type_name: ClassName =
                     potential_parent_type.try_into().ok()?;
                 let locked_child_type_data = state.symbol_data.get_class(&potential_child_name)?;
-                let child_type_data = locked_child_type_data.read().ok()?;
+                let child_type_data = locked_child_type_data.read_or_recover();
                 let is_instance_of = child_type_data
                     .instanceof(&potential_parent_type_name, state.symbol_data.clone());
                 Some(is_instance_of)
@@ -302,6 +363,21 @@ impl DiscreteType {
             DiscreteType::ClassType(_, _) => (),
 
             DiscreteType::Template(_) => (),
+            DiscreteType::Never => (),
+            DiscreteType::IntRange(_, _) => (),
+            DiscreteType::IntLiteral(_) => (),
+            DiscreteType::StringLiteral(_) => (),
+            DiscreteType::ArrayKey => (),
+            DiscreteType::Scalar => (),
+            DiscreteType::Numeric => (),
+            DiscreteType::List(v) | DiscreteType::NonEmptyList(v) => {
+                v.check_type_casing(range, state, emitter)
+            }
+            DiscreteType::NonEmptyArray(k, v) => {
+                k.check_type_casing(range, state, emitter);
+                v.check_type_casing(range, state, emitter);
+            }
+            DiscreteType::Operator(_) => (),
         }
     }
 
@@ -344,13 +420,227 @@ impl DiscreteType {
             (DiscreteType::Generic(a, b), DiscreteType::Generic(c, d)) => a == c && b == d,
             (DiscreteType::ClassType(a, b), DiscreteType::ClassType(c, d)) => a == c && b == d,
             (DiscreteType::Template(a), DiscreteType::Template(b)) => a == b,
+            (DiscreteType::Never, DiscreteType::Never) => true,
+            (DiscreteType::IntRange(a, b), DiscreteType::IntRange(c, d)) => a == c && b == d,
+            (DiscreteType::IntLiteral(a), DiscreteType::IntLiteral(b)) => a == b,
+            (DiscreteType::StringLiteral(a), DiscreteType::StringLiteral(b)) => a == b,
+            (DiscreteType::ArrayKey, DiscreteType::ArrayKey) => true,
+            (DiscreteType::Scalar, DiscreteType::Scalar) => true,
+            (DiscreteType::Numeric, DiscreteType::Numeric) => true,
+            (DiscreteType::List(a), DiscreteType::List(b)) => a == b,
+            (DiscreteType::NonEmptyList(a), DiscreteType::NonEmptyList(b)) => a == b,
+            (DiscreteType::NonEmptyArray(a, b), DiscreteType::NonEmptyArray(c, d)) => {
+                a == c && b == d
+            }
+            (DiscreteType::Operator(a), DiscreteType::Operator(b)) => a == b,
             _ => false,
         }
     }
 
+    /// Can a value of this type be passed to a parameter declared as `target`?
+    /// Returns `None` when it depends on things we can't know, like `strict_types` or
+    /// unknown classes
+    pub(crate) fn can_be_passed_as(
+        &self,
+        target: &DiscreteType,
+        symbol_data: &Arc<SymbolData>,
+    ) -> Option<bool> {
+        match (self, target) {
+            (DiscreteType::Named(_, fq_name), DiscreteType::Named(_, target_fq_name))
+                if !self.is_same_type(target) =>
+            {
+                let cname: ClassName = fq_name.into();
+                let class_data = symbol_data.get_class(&cname)?;
+                let is_instance = class_data
+                    .read_or_recover()
+                    .instanceof(&target_fq_name.into(), symbol_data.clone());
+                Some(is_instance)
+            }
+            _ => self.can_be_passed_as_builtin(target),
+        }
+    }
+
+    /// Same as [DiscreteType::can_be_passed_as], but without looking up classes, so
+    /// whether a class can be passed as another class is unknown
+    pub(crate) fn can_be_passed_as_builtin(&self, target: &DiscreteType) -> Option<bool> {
+        if self.is_same_type(target) {
+            return Some(true);
+        }
+        let is_int = |t: &DiscreteType| {
+            matches!(
+                t,
+                DiscreteType::Int | DiscreteType::IntRange(_, _) | DiscreteType::IntLiteral(_)
+            )
+        };
+        let is_string = |t: &DiscreteType| {
+            t.is_string() || matches!(t, DiscreteType::Special(SpecialType::ClassString(_)))
+        };
+        let is_scalar = |t: &DiscreteType| {
+            is_int(t)
+                || is_string(t)
+                || matches!(
+                    t,
+                    DiscreteType::Float
+                        | DiscreteType::Bool
+                        | DiscreteType::True
+                        | DiscreteType::False
+                        | DiscreteType::ArrayKey
+                        | DiscreteType::Scalar
+                        | DiscreteType::Numeric
+                )
+        };
+        let is_array = |t: &DiscreteType| {
+            matches!(
+                t,
+                DiscreteType::Array
+                    | DiscreteType::Vector(_)
+                    | DiscreteType::HashMap(_, _)
+                    | DiscreteType::Shape(_)
+                    | DiscreteType::List(_)
+                    | DiscreteType::NonEmptyList(_)
+                    | DiscreteType::NonEmptyArray(_, _)
+            )
+        };
+
+        match (self, target) {
+            (DiscreteType::Never, _) => Some(true),
+            (_, DiscreteType::Never) => Some(false),
+            (_, DiscreteType::Mixed) => Some(true),
+            // Refined integers
+            (a, b) if a.int_bounds().is_some() && b.int_bounds().is_some() => {
+                let (a_min, a_max) = a.int_bounds()?;
+                let (b_min, b_max) = b.int_bounds()?;
+                let lower = |x: Option<i64>| x.unwrap_or(i64::MIN);
+                let upper = |x: Option<i64>| x.unwrap_or(i64::MAX);
+                if lower(a_min) >= lower(b_min) && upper(a_max) <= upper(b_max) {
+                    Some(true)
+                } else if upper(a_max) < lower(b_min) || lower(a_min) > upper(b_max) {
+                    Some(false)
+                } else {
+                    // Depends on the actual value
+                    None
+                }
+            }
+            (a, DiscreteType::Int | DiscreteType::Float) if is_int(a) => Some(true),
+            // Refined strings
+            (DiscreteType::StringLiteral(_), DiscreteType::StringLiteral(_)) => Some(false),
+            (a, DiscreteType::String) if is_string(a) => Some(true),
+            (
+                DiscreteType::StringLiteral(s),
+                DiscreteType::Special(SpecialType::NonEmptyString),
+            ) => Some(!s.is_empty()),
+            (DiscreteType::StringLiteral(s), DiscreteType::Special(SpecialType::NumericString)) => {
+                Some(is_numeric_string(s))
+            }
+            (
+                DiscreteType::Special(
+                    SpecialType::NumericString
+                    | SpecialType::CallableString
+                    | SpecialType::ClassString(_),
+                ),
+                DiscreteType::Special(SpecialType::NonEmptyString),
+            ) => Some(true),
+            (a, b) if is_string(a) && is_string(b) => None,
+            // Unions of scalar types
+            (a, DiscreteType::ArrayKey) if is_int(a) || is_string(a) => Some(true),
+            (DiscreteType::StringLiteral(s), DiscreteType::Numeric) => Some(is_numeric_string(s)),
+            (a, DiscreteType::Numeric)
+                if is_int(a)
+                    || matches!(
+                        a,
+                        DiscreteType::Float | DiscreteType::Special(SpecialType::NumericString)
+                    ) =>
+            {
+                Some(true)
+            }
+            (a, DiscreteType::Scalar) if is_scalar(a) => Some(true),
+            (DiscreteType::True | DiscreteType::False, DiscreteType::Bool) => Some(true),
+            // Depends on strict_types
+            (a, b) if is_scalar(a) && is_scalar(b) => None,
+            (DiscreteType::NULL, _) => Some(false),
+            // Lists and non-empty arrays
+            (
+                DiscreteType::NonEmptyList(_),
+                DiscreteType::List(_) | DiscreteType::NonEmptyArray(_, _),
+            ) => Some(true),
+            // Might be empty, or have non-sequential keys
+            (
+                a,
+                DiscreteType::List(_)
+                | DiscreteType::NonEmptyList(_)
+                | DiscreteType::NonEmptyArray(_, _),
+            ) if is_array(a) => None,
+            (a, b) if is_array(a) && (is_array(b) || *b == DiscreteType::Iterable) => Some(true),
+            (a, b) if is_array(a) && (is_scalar(b) || matches!(b, DiscreteType::Object)) => {
+                Some(false)
+            }
+            (a, b) if is_scalar(a) && (is_array(b) || *b == DiscreteType::Iterable) => Some(false),
+            (DiscreteType::Named(_, _), DiscreteType::Object) => Some(true),
+            (a, DiscreteType::Named(_, _)) if is_scalar(a) || is_array(a) => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Same as [DiscreteType::can_be_passed_as], but as with `strict_types=1`, where a
+    /// scalar is never coerced into another scalar type, except int into float. Used for
+    /// values PHP checks without coercion, like yielded values and constant expressions
+    pub(crate) fn can_be_passed_strictly_as(
+        &self,
+        target: &DiscreteType,
+        symbol_data: &Arc<SymbolData>,
+    ) -> Option<bool> {
+        let result = self.can_be_passed_as(target, symbol_data);
+        if result.is_some() {
+            return result;
+        }
+        let (Some(source_kinds), Some(mut target_kinds)) =
+            (self.scalar_kinds(), target.scalar_kinds())
+        else {
+            return None;
+        };
+        if target_kinds & SCALAR_FLOAT != 0 {
+            target_kinds |= SCALAR_INT;
+        }
+        if source_kinds & target_kinds == 0 {
+            Some(false)
+        } else {
+            // Depends on the actual value
+            None
+        }
+    }
+
+    /// The scalar types a value of this type might have, as a set of `SCALAR_*` bits
+    fn scalar_kinds(&self) -> Option<u8> {
+        let kinds = match self {
+            DiscreteType::Int | DiscreteType::IntRange(_, _) | DiscreteType::IntLiteral(_) => {
+                SCALAR_INT
+            }
+            DiscreteType::Float => SCALAR_FLOAT,
+            DiscreteType::Bool | DiscreteType::True | DiscreteType::False => SCALAR_BOOL,
+            DiscreteType::Special(SpecialType::ClassString(_)) => SCALAR_STRING,
+            DiscreteType::ArrayKey => SCALAR_INT | SCALAR_STRING,
+            DiscreteType::Numeric => SCALAR_INT | SCALAR_FLOAT | SCALAR_STRING,
+            DiscreteType::Scalar => SCALAR_INT | SCALAR_FLOAT | SCALAR_STRING | SCALAR_BOOL,
+            t if t.is_string() => SCALAR_STRING,
+            _ => return None,
+        };
+        Some(kinds)
+    }
+
+    /// Inclusive bounds of integer types, where `None` is unbounded
+    fn int_bounds(&self) -> Option<(Option<i64>, Option<i64>)> {
+        match self {
+            DiscreteType::Int => Some((None, None)),
+            DiscreteType::IntRange(min, max) => Some((*min, *max)),
+            DiscreteType::IntLiteral(i) => Some((Some(*i), Some(*i))),
+            _ => None,
+        }
+    }
+
     pub(crate) fn contains_template(&self) -> bool {
         match self {
             DiscreteType::Template(_) => return true,
+            DiscreteType::Operator(operator) => return operator.contains_template(),
             DiscreteType::Generic(_gtype, utypes) => {
                 // gtype is not allowed to be generic
                 for u in utypes {
@@ -359,6 +649,12 @@ impl DiscreteType {
                     }
                 }
             }
+            DiscreteType::Vector(t) | DiscreteType::List(t) | DiscreteType::NonEmptyList(t) => {
+                return t.contains_template()
+            }
+            DiscreteType::HashMap(k, v) | DiscreteType::NonEmptyArray(k, v) => {
+                return k.contains_template() || v.contains_template()
+            }
             _ => (),
         }
         false
@@ -373,6 +669,32 @@ impl DiscreteType {
                     dtype.into()
                 }
             }
+            DiscreteType::Operator(operator) => operator.concretize_templates(concrete),
+            DiscreteType::Generic(gtype, utypes) => DiscreteType::Generic(
+                gtype.clone(),
+                utypes
+                    .iter()
+                    .map(|u| u.concretize_templates(concrete))
+                    .collect(),
+            )
+            .into(),
+            DiscreteType::Vector(t) => {
+                DiscreteType::Vector(t.concretize_templates(concrete)).into()
+            }
+            DiscreteType::List(t) => DiscreteType::List(t.concretize_templates(concrete)).into(),
+            DiscreteType::NonEmptyList(t) => {
+                DiscreteType::NonEmptyList(t.concretize_templates(concrete)).into()
+            }
+            DiscreteType::HashMap(k, v) => DiscreteType::HashMap(
+                k.concretize_templates(concrete),
+                v.concretize_templates(concrete),
+            )
+            .into(),
+            DiscreteType::NonEmptyArray(k, v) => DiscreteType::NonEmptyArray(
+                k.concretize_templates(concrete),
+                v.concretize_templates(concrete),
+            )
+            .into(),
             t => t.into(),
         }
     }
@@ -380,7 +702,10 @@ impl DiscreteType {
 
 impl TypeTraits for DiscreteType {
     fn is_int(&self) -> bool {
-        matches!(self, DiscreteType::Int)
+        matches!(
+            self,
+            DiscreteType::Int | DiscreteType::IntRange(_, _) | DiscreteType::IntLiteral(_)
+        )
     }
 
     fn is_float(&self) -> bool {
@@ -388,7 +713,16 @@ impl TypeTraits for DiscreteType {
     }
 
     fn is_string(&self) -> bool {
-        matches!(self, DiscreteType::String)
+        matches!(
+            self,
+            DiscreteType::String
+                | DiscreteType::StringLiteral(_)
+                | DiscreteType::Special(
+                    SpecialType::NonEmptyString
+                        | SpecialType::NumericString
+                        | SpecialType::CallableString
+                )
+        )
     }
 
     fn is_bool(&self) -> bool {
@@ -432,8 +766,8 @@ impl TypeTraits for DiscreteType {
         }
     }
 
-    fn is_same_type(&self, _other: &Self) -> bool {
-        todo!()
+    fn is_same_type(&self, other: &Self) -> bool {
+        DiscreteType::is_same_type(self, other)
     }
 
     fn can_be_cast_to_string(&self) -> Option<Consequences> {
@@ -507,6 +841,9 @@ impl Display for DiscreteType {
                             value.utype
                         ));
                     }
+                    if !shape.sealed {
+                        parts.push("...".to_string());
+                    }
                     buf.push_str(&parts.join(","));
                     buf.push('}');
                     buf
@@ -519,7 +856,36 @@ impl Display for DiscreteType {
                 DiscreteType::ClassType(fq_cname, tname) => {
                     format!("{}::{}", fq_cname, tname)
                 }
+                DiscreteType::Never => "never".to_string(),
+                DiscreteType::IntRange(Some(1), None) => "positive-int".to_string(),
+                DiscreteType::IntRange(Some(0), None) => "non-negative-int".to_string(),
+                DiscreteType::IntRange(None, Some(-1)) => "negative-int".to_string(),
+                DiscreteType::IntRange(None, Some(0)) => "non-positive-int".to_string(),
+                DiscreteType::IntRange(min, max) => format!(
+                    "int<{}, {}>",
+                    min.map_or("min".to_string(), |x| x.to_string()),
+                    max.map_or("max".to_string(), |x| x.to_string())
+                ),
+                DiscreteType::IntLiteral(i) => i.to_string(),
+                DiscreteType::StringLiteral(s) => format!("'{}'", s),
+                DiscreteType::ArrayKey => "array-key".to_string(),
+                DiscreteType::Scalar => "scalar".to_string(),
+                DiscreteType::Numeric => "numeric".to_string(),
+                DiscreteType::List(t) => format!("list<{}>", t),
+                DiscreteType::NonEmptyList(t) => format!("non-empty-list<{}>", t),
+                DiscreteType::NonEmptyArray(k, v) => format!("non-empty-array<{},{}>", k, v),
+                DiscreteType::Operator(operator) => operator.to_string(),
             }
         )
     }
 }
+
+/// Whether PHP would consider the string numeric, as in `is_numeric()`
+fn is_numeric_string(s: &str) -> bool {
+    let trimmed = s.trim();
+    // Rust also accepts `inf` and `NaN`, which PHP does not
+    trimmed
+        .chars()
+        .all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-'))
+        && trimmed.parse::<f64>().is_ok()
+}
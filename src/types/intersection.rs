@@ -20,11 +20,10 @@ impl IntersectionType {
     /// Returns Some(<type>) if the union safely can coalesce into one single type
     /// if it is empty or has multiple types it will return None
     pub fn single_type(&self) -> Option<DiscreteType> {
-        todo!("HELP AGAIN");
-        //if self.types.len() == 1 {
-        //    return self.types.iter().next().cloned();
-        //}
-        // None
+        if self.types.len() == 1 {
+            return self.types.first().and_then(|t| t.single_type());
+        }
+        None
     }
 
     pub(crate) fn ensure_valid(
@@ -177,8 +176,12 @@ impl TypeTraits for IntersectionType {
         !self.types.is_empty()
     }
 
-    fn is_same_type(&self, _other: &Self) -> bool {
-        todo!()
+    fn is_same_type(&self, other: &Self) -> bool {
+        self.types.len() == other.types.len()
+            && self
+                .types
+                .iter()
+                .all(|t| other.types.iter().any(|o| t.is_same_type(o)))
     }
 
     fn can_be_cast_to_string(&self) -> Option<Consequences> {
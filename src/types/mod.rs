@@ -8,6 +8,7 @@ pub mod intersection;
 pub mod phptype;
 pub mod shapes;
 pub mod specials;
+pub mod type_operators;
 
 #[cfg(test)]
 mod tests;
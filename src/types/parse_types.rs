@@ -153,18 +153,30 @@ pub type ReturnType = CompoundType;
 #[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq)]
 pub enum ParsedType {
     Type(TypeStruct),
-    Shape(Vec<ShapeEntry>),
+    /// *  .0 entries
+    /// *  .1 whether the shape is sealed, which it isn't when it ends with `...`
+    Shape(Vec<ShapeEntry>, bool),
     Callable(ArgumentVector, Option<Box<ReturnType>>),
     ClassType(TypeName, Name),
     CallableUntyped,
     Parenthesized(Box<CompoundType>),
+    /// Literal string type, like `'asc'`, without the quotes
+    StringLiteral(String),
+    /// Literal integer type, like `1` or `-1`
+    IntLiteral(i64),
+    /// `T[K]`
+    /// *  .0 = Type being accessed
+    /// *  .1 = Offset type
+    OffsetAccess(Box<ConcreteType>, Box<CompoundType>),
+    /// `($param is T ? A : B)`
+    Conditional(Box<ConditionalTypeStruct>),
 }
 
 impl std::fmt::Display for ParsedType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ParsedType::Type(t) => write!(f, "{}", t),
-            ParsedType::Shape(s) => {
+            ParsedType::Shape(s, sealed) => {
                 write!(f, "array{{")?;
                 for (i, x) in s.iter().enumerate() {
                     if i > 0 {
@@ -172,16 +184,63 @@ impl std::fmt::Display for ParsedType {
                     }
                     write!(f, "{}", x)?;
                 }
+                if !sealed {
+                    write!(f, ",...")?;
+                }
                 write!(f, "}}")
             }
             ParsedType::Callable(argv, rettype) => write!(f, "callable({:?}):{:?}", argv, rettype),
             ParsedType::ClassType(typename, name) => write!(f, "{}::{}", typename, name),
             ParsedType::CallableUntyped => write!(f, "callable"),
             ParsedType::Parenthesized(pt) => write!(f, "({})", pt),
+            ParsedType::StringLiteral(s) => write!(f, "'{}'", s),
+            ParsedType::IntLiteral(i) => write!(f, "{}", i),
+            ParsedType::OffsetAccess(base, offset) => write!(f, "{}[{}]", base, offset),
+            ParsedType::Conditional(c) => write!(f, "{}", c),
         }
     }
 }
 
+#[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub enum ConditionalSubject {
+    /// Parameter name, including the `$`
+    Parameter(Name),
+    Type(ConcreteType),
+}
+
+impl std::fmt::Display for ConditionalSubject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConditionalSubject::Parameter(p) => write!(f, "{}", p),
+            ConditionalSubject::Type(t) => write!(f, "{}", t),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq)]
+pub struct ConditionalTypeStruct {
+    pub subject: ConditionalSubject,
+    /// `is not` instead of `is`
+    pub negated: bool,
+    pub is_type: CompoundType,
+    pub then_type: CompoundType,
+    pub else_type: CompoundType,
+}
+
+impl std::fmt::Display for ConditionalTypeStruct {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "({} is {}{} ? {} : {})",
+            self.subject,
+            if self.negated { "not " } else { "" },
+            self.is_type,
+            self.then_type,
+            self.else_type
+        )
+    }
+}
+
 #[derive(Clone, Debug, PartialOrd, Ord, PartialEq, Eq)]
 pub enum TypeName {
     Name(Name),
@@ -1,12 +1,16 @@
 mod basetokens;
 mod callable;
 mod class_type;
+mod conditional;
+mod literal;
 mod nomhelpers;
 mod normal_types;
 mod shape;
 mod type_names;
 
 use callable::callable_type;
+use conditional::conditional_type;
+use literal::literal_type;
 use nom::{
     branch::alt,
     bytes::complete::tag,
@@ -16,7 +20,7 @@ use nom::{
     IResult,
 };
 use nomhelpers::{ourspace0, separated_list2, space0_and_separator};
-use normal_types::{generic_args, normal_type_with_optional_generics};
+use normal_types::{generic_args, normal_type_with_optional_generics, this_type};
 use shape::shape_type;
 
 use crate::symbols::Name;
@@ -102,6 +106,18 @@ fn concrete_type(multiline: bool) -> impl Fn(&[u8]) -> IResult<&[u8], ConcreteTy
             let (input, _) = ourspace0(multiline)(iter_input)?;
 
             let (input, post_decl_array) = opt(many1(tag(b"[]")))(input)?;
+            if post_decl_array.is_none() {
+                // Handle `T[K]` offset access
+                if let Ok((input, offset)) = offset_access(multiline)(input) {
+                    let base = ConcreteType {
+                        nullable: false,
+                        ptype: parsed_type,
+                    };
+                    parsed_type = ParsedType::OffsetAccess(Box::new(base), Box::new(offset));
+                    iter_input = input;
+                    continue;
+                }
+            }
             if let Some(levels) = post_decl_array {
                 for _ in levels {
                     // Wrap parsed_type in an array, converting from `thing[]` to `array<thing>`
@@ -138,7 +154,10 @@ fn one_type(multiline: bool) -> impl Fn(&[u8]) -> IResult<&[u8], ParsedType> {
             shape_type(multiline),
             callable_type(multiline),
             tuple_type(multiline),
+            literal_type(multiline),
+            this_type(multiline),
             normal_type_with_optional_generics(multiline),
+            conditional_type(multiline),
             parenthesized_type(multiline),
         ))(input)
     }
@@ -157,6 +176,16 @@ fn parenthesized_type(multiline: bool) -> impl Fn(&[u8]) -> IResult<&[u8], Parse
     }
 }
 
+fn offset_access(multiline: bool) -> impl Fn(&[u8]) -> IResult<&[u8], CompoundType> {
+    move |input| {
+        let (input, _) = tag(b"[")(input)?;
+        let (input, offset) = compound_type(multiline)(input)?;
+        let (input, _) = ourspace0(multiline)(input)?;
+        let (input, _) = tag(b"]")(input)?;
+        Ok((input, offset))
+    }
+}
+
 fn tuple_type(multiline: bool) -> impl Fn(&[u8]) -> IResult<&[u8], ParsedType> {
     move |input| {
         let (input, mut type_entries) =
@@ -167,7 +196,7 @@ fn tuple_type(multiline: bool) -> impl Fn(&[u8]) -> IResult<&[u8], ParsedType> {
             .map(|vtypes| ShapeEntry(None, vtypes))
             .collect();
 
-        Ok((input, ParsedType::Shape(shape_entries)))
+        Ok((input, ParsedType::Shape(shape_entries, true)))
     }
 }
 
@@ -50,12 +50,12 @@ pub(super) fn php_var_name(input: &[u8]) -> IResult<&[u8], OsString> {
             true
         }
         State::First => false,
-        State::Dollar if x.is_ascii_alphabetic() => {
+        State::Dollar if x.is_ascii_alphabetic() || x == b'_' => {
             state.set(State::Alpha);
             true
         }
         State::Dollar => false,
-        State::Alpha => x.is_ascii_alphanumeric(),
+        State::Alpha => x.is_ascii_alphanumeric() || x == b'_',
     })(input)?;
     Ok((input, OsStr::from_bytes(result).into()))
 }
@@ -4,9 +4,10 @@ use super::{basetokens::php_var_name, concrete_type, nomhelpers::ourspace0};
 use nom::{
     bytes::complete::tag,
     combinator::opt,
+    error::{Error, ErrorKind},
     multi::separated_list1,
     sequence::{delimited, preceded, terminated},
-    IResult,
+    Err, IResult,
 };
 
 use super::super::parse_types::{ArgumentVector, CompoundType, ParsedType, ReturnType};
@@ -15,6 +16,15 @@ pub(super) fn callable_type(multiline: bool) -> impl Fn(&[u8]) -> IResult<&[u8],
     move |input| {
         let (input, _) = ourspace0(multiline)(input)?;
         let (input, _) = tag(b"callable")(input)?;
+        // Don't swallow the start of `callable-string` and similar
+        if let Some(next) = input.first() {
+            if next.is_ascii_alphanumeric() || *next == b'-' || *next == b'_' {
+                return Err(Err::Error(Error {
+                    input,
+                    code: ErrorKind::Tag,
+                }));
+            }
+        }
         let (input, details) = opt(callable_details(multiline))(input)?;
         let ptype = if let Some((types, return_type)) = details {
             ParsedType::Callable(types, return_type.map(Box::new))
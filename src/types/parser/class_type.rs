@@ -1,8 +1,8 @@
-use nom::{bytes::complete::tag, IResult};
+use nom::{bytes::complete::tag, bytes::complete::take_while1, IResult};
 
-use crate::types::parse_types::ParsedType;
+use crate::{symbols::Name, types::parse_types::ParsedType};
 
-use super::{basetokens::simple_type_name, nomhelpers::ourspace0, type_names::type_name};
+use super::{nomhelpers::ourspace0, type_names::type_name};
 
 pub(super) fn class_type(multiline: bool) -> impl Fn(&[u8]) -> IResult<&[u8], ParsedType> {
     move |input| {
@@ -12,7 +12,16 @@ pub(super) fn class_type(multiline: bool) -> impl Fn(&[u8]) -> IResult<&[u8], Pa
         let (input, _) = ourspace0(multiline)(input)?;
         let (input, _) = tag(b"::")(input)?;
         let (input, _) = ourspace0(multiline)(input)?;
-        let (input, tname) = simple_type_name(input)?;
+        let (input, tname) = constant_pattern(input)?;
         Ok((input, ParsedType::ClassType(cname, tname)))
     }
 }
+
+///
+/// Constant name, possibly with `*` wildcards, like `STATUS_*`
+///
+fn constant_pattern(input: &[u8]) -> IResult<&[u8], Name> {
+    let (input, result) =
+        take_while1(|x: u8| x.is_ascii_alphanumeric() || x == b'_' || x == b'*')(input)?;
+    Ok((input, Name::from(result)))
+}
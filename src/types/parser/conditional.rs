@@ -0,0 +1,57 @@
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    combinator::{map, opt},
+    sequence::terminated,
+    IResult,
+};
+
+use crate::{
+    symbols::Name,
+    types::parse_types::{ConditionalSubject, ConditionalTypeStruct, ParsedType},
+};
+
+use super::{
+    basetokens::php_var_name,
+    compound_type, concrete_type,
+    nomhelpers::{ourspace0, ourspace1},
+};
+
+///
+/// Conditional types, like `($flag is true ? string : int)` or `(T is not null ? T : never)`
+///
+pub(super) fn conditional_type(multiline: bool) -> impl Fn(&[u8]) -> IResult<&[u8], ParsedType> {
+    move |input| {
+        let (input, _) = ourspace0(multiline)(input)?;
+        let (input, _) = tag(b"(")(input)?;
+        let (input, _) = ourspace0(multiline)(input)?;
+        let (input, subject) = alt((
+            map(php_var_name, |name| {
+                ConditionalSubject::Parameter(Name::from(name))
+            }),
+            map(concrete_type(multiline), ConditionalSubject::Type),
+        ))(input)?;
+        let (input, _) = ourspace1(multiline)(input)?;
+        let (input, _) = tag(b"is")(input)?;
+        let (input, _) = ourspace1(multiline)(input)?;
+        let (input, negated) = opt(terminated(tag(b"not"), ourspace1(multiline)))(input)?;
+        let (input, is_type) = compound_type(multiline)(input)?;
+        let (input, _) = ourspace0(multiline)(input)?;
+        let (input, _) = tag(b"?")(input)?;
+        let (input, then_type) = compound_type(multiline)(input)?;
+        let (input, _) = ourspace0(multiline)(input)?;
+        let (input, _) = tag(b":")(input)?;
+        let (input, else_type) = compound_type(multiline)(input)?;
+        let (input, _) = ourspace0(multiline)(input)?;
+        let (input, _) = tag(b")")(input)?;
+
+        let conditional = ConditionalTypeStruct {
+            subject,
+            negated: negated.is_some(),
+            is_type,
+            then_type,
+            else_type,
+        };
+        Ok((input, ParsedType::Conditional(Box::new(conditional))))
+    }
+}
@@ -0,0 +1,48 @@
+use nom::{
+    branch::alt,
+    bytes::complete::{tag, take_while},
+    character::complete::digit1,
+    combinator::{opt, recognize},
+    error::{Error, ErrorKind},
+    sequence::{delimited, pair},
+    Err, IResult,
+};
+
+use crate::types::parse_types::ParsedType;
+
+use super::nomhelpers::ourspace0;
+
+///
+/// Literal types, like `'asc'`, `"desc"`, `1` or `-1`
+///
+pub(super) fn literal_type(multiline: bool) -> impl Fn(&[u8]) -> IResult<&[u8], ParsedType> {
+    move |input| {
+        let (input, _) = ourspace0(multiline)(input)?;
+        alt((string_literal, int_literal))(input)
+    }
+}
+
+fn string_literal(input: &[u8]) -> IResult<&[u8], ParsedType> {
+    // Escapes are not supported, as they are hardly ever used in literal types
+    let (input, content) = alt((
+        delimited(tag(b"'"), take_while(|x| x != b'\''), tag(b"'")),
+        delimited(tag(b"\""), take_while(|x| x != b'"'), tag(b"\"")),
+    ))(input)?;
+    let content = String::from_utf8_lossy(content).to_string();
+    Ok((input, ParsedType::StringLiteral(content)))
+}
+
+fn int_literal(input: &[u8]) -> IResult<&[u8], ParsedType> {
+    // The sign is parsed with the digits, so i64::MIN doesn't overflow
+    let (input, literal) = recognize(pair(opt(tag(b"-")), digit1))(input)?;
+
+    // The literal is an optional sign and ascii digits, so this can only fail on overflow
+    let Ok(ival) = String::from_utf8_lossy(literal).parse::<i64>() else {
+        return Err(Err::Error(Error {
+            input,
+            code: ErrorKind::Digit,
+        }));
+    };
+
+    Ok((input, ParsedType::IntLiteral(ival)))
+}
@@ -1,6 +1,6 @@
 use nom::{
     bytes::complete::tag,
-    character::complete::{multispace0, space0},
+    character::complete::{multispace0, multispace1, space0, space1},
     error::{ErrorKind, ParseError},
     AsChar, Err, IResult, InputLength, InputTake, InputTakeAtPosition, Parser,
 };
@@ -74,6 +74,24 @@ where
     }
 }
 
+///
+/// Like [ourspace0], but requires at least one whitespace
+///
+pub(super) fn ourspace1<I, E>(multiline: bool) -> impl FnMut(I) -> IResult<I, I, E>
+where
+    I: InputTakeAtPosition,
+    <I as InputTakeAtPosition>::Item: AsChar + Clone,
+    E: ParseError<I>,
+{
+    move |input| {
+        if multiline {
+            multispace1(input)
+        } else {
+            space1(input)
+        }
+    }
+}
+
 pub(super) fn space0_and_separator<I, S>(
     separator: S,
     multiline: bool,
@@ -25,6 +25,21 @@ pub(super) fn normal_type_with_optional_generics(
     }
 }
 
+///
+/// `$this`, which is the same as `static`
+///
+pub(super) fn this_type(multiline: bool) -> impl Fn(&[u8]) -> IResult<&[u8], ParsedType> {
+    move |input| {
+        let (input, _) = ourspace0(multiline)(input)?;
+        let (input, _) = tag(b"$this")(input)?;
+        let type_struct = TypeStruct {
+            type_name: TypeName::Name(Name::from("$this")),
+            generics: None,
+        };
+        Ok((input, ParsedType::Type(type_struct)))
+    }
+}
+
 pub(super) fn generic_args(multiline: bool) -> impl Fn(&[u8]) -> IResult<&[u8], Vec<CompoundType>> {
     move |input| {
         let (input, _) = ourspace0(multiline)(input)?;
@@ -8,6 +8,7 @@ use nom::{
     combinator::opt,
     error::{Error, ErrorKind},
     multi::separated_list1,
+    sequence::preceded,
     Err, IResult,
 };
 
@@ -23,9 +24,14 @@ pub(super) fn shape_type(multiline: bool) -> impl Fn(&[u8]) -> IResult<&[u8], Pa
             space0_and_separator(b",", multiline),
             shape_entry(multiline, false),
         )(input)?;
+        // `array{id: int, ...}` may have other keys as well
+        let (input, unsealed) = opt(preceded(
+            space0_and_separator(b",", multiline),
+            preceded(ourspace0(multiline), tag(b"...")),
+        ))(input)?;
         let (input, _) = ourspace0(multiline)(input)?;
         let (input, _) = tag(b"}")(input)?;
-        Ok((input, ParsedType::Shape(entries)))
+        Ok((input, ParsedType::Shape(entries, unsealed.is_none())))
     }
 }
 
@@ -1,6 +1,9 @@
+use std::sync::Arc;
+
 use crate::{
     analysis::state::AnalysisState, issue::IssueEmitter, missing,
-    operators::binary::InstanceOfSymbol, parser::Range, types::union::DiscretlyAccessedType,
+    operators::binary::InstanceOfSymbol, parser::Range, symboldata::SymbolData,
+    types::union::DiscretlyAccessedType,
 };
 
 use super::union::{Consequences, DiscreteType, PHPType};
@@ -9,7 +12,7 @@ impl PHPType {
     pub(crate) fn is_same_type(&self, other: &PHPType) -> bool {
         match (self, other) {
             (PHPType::Union(a), PHPType::Union(b)) => a.is_same_type(b),
-            (PHPType::Intersection(a), PHPType::Intersection(_b)) => todo!("a.is_same_type(b)"),
+            (PHPType::Intersection(a), PHPType::Intersection(b)) => a.is_same_type(b),
             (PHPType::Discrete(a), PHPType::Discrete(b)) => a.is_same_type(b),
             _ => {
                 missing!("Check if types are the same");
@@ -103,6 +106,68 @@ impl PHPType {
         }
     }
 
+    /// Can a value of this type be passed to a parameter declared as `target`? Only
+    /// returns `Some(false)` when none of the variants can be passed
+    pub(crate) fn can_be_passed_as(
+        &self,
+        target: &PHPType,
+        symbol_data: &Arc<SymbolData>,
+    ) -> Option<bool> {
+        self.can_be_passed_as_with(target, |source, target| {
+            source.can_be_passed_as(target, symbol_data)
+        })
+    }
+
+    /// Same as [PHPType::can_be_passed_as], but without coercion between scalar types,
+    /// see [DiscreteType::can_be_passed_strictly_as]
+    pub(crate) fn can_be_passed_strictly_as(
+        &self,
+        target: &PHPType,
+        symbol_data: &Arc<SymbolData>,
+    ) -> Option<bool> {
+        self.can_be_passed_as_with(target, |source, target| {
+            source.can_be_passed_strictly_as(target, symbol_data)
+        })
+    }
+
+    fn can_be_passed_as_with(
+        &self,
+        target: &PHPType,
+        can_be_passed: impl Fn(&DiscreteType, &DiscreteType) -> Option<bool>,
+    ) -> Option<bool> {
+        let mut all_false = true;
+        for source in self.as_discrete_variants() {
+            let DiscretlyAccessedType::Discrete(source) = source else {
+                return None;
+            };
+            let mut source_result = Some(false);
+            for target in target.as_discrete_variants() {
+                let DiscretlyAccessedType::Discrete(target) = target else {
+                    source_result = None;
+                    continue;
+                };
+                match can_be_passed(&source, &target) {
+                    Some(true) => {
+                        source_result = Some(true);
+                        break;
+                    }
+                    Some(false) => (),
+                    None => source_result = None,
+                }
+            }
+            match source_result {
+                Some(true) => return Some(true),
+                Some(false) => (),
+                None => all_false = false,
+            }
+        }
+        if all_false {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
     pub(crate) fn map(&self, discrete: &impl Fn(DiscreteType) -> DiscreteType) -> Self {
         match self {
             PHPType::Union(u) => PHPType::Union(u.map(discrete)),
@@ -200,8 +265,8 @@ impl TypeTraits for PHPType {
         }
     }
 
-    fn is_same_type(&self, _other: &Self) -> bool {
-        todo!("Check if types are the same");
+    fn is_same_type(&self, other: &Self) -> bool {
+        PHPType::is_same_type(self, other)
     }
 
     fn can_be_cast_to_string(&self) -> Option<Consequences> {
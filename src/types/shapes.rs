@@ -1,16 +1,53 @@
 use std::{collections::BTreeMap, fmt::Display};
 
-use crate::{analysis::state::AnalysisState, issue::IssueEmitter, parser::Range};
+use crate::{
+    analysis::state::AnalysisState, issue::IssueEmitter, parser::Range, symbols::Name,
+    value::PHPValue,
+};
 
 use super::{
     parse_types::ShapeKey,
-    union::{ShapeType, ShapeTypeKey, ShapeTypeValue},
+    union::{DiscreteType, PHPType, ShapeType, ShapeTypeKey, ShapeTypeValue},
 };
 
 impl ShapeType {
     pub fn new() -> Self {
         let map = BTreeMap::new();
-        Self { map }
+        Self { map, sealed: true }
+    }
+
+    /// A shape of an array which may have other keys than those we know of
+    pub fn new_unsealed() -> Self {
+        Self {
+            sealed: false,
+            ..Self::new()
+        }
+    }
+
+    /// The shape after a value of type `utype` is written to `key`
+    pub fn with_key_type(&self, key: ShapeTypeKey, utype: PHPType) -> Self {
+        let mut shape = self.clone();
+        shape.map.insert(
+            key,
+            ShapeTypeValue {
+                optional: false,
+                utype,
+            },
+        );
+        shape
+    }
+
+    ///
+    /// Whether the shape has `key`. `None` when it only might have it, like when the key
+    /// is optional, or the shape is unsealed without it
+    ///
+    pub fn has_key(&self, key: &ShapeTypeKey) -> Option<bool> {
+        match self.map.get(key) {
+            Some(value) if value.optional => None,
+            Some(_) => Some(true),
+            None if self.sealed => Some(false),
+            None => None,
+        }
     }
 
     pub(crate) fn ensure_valid(
@@ -49,6 +86,32 @@ impl Display for ShapeTypeKey {
     }
 }
 
+impl ShapeTypeKey {
+    /// The key `value` is stored with in an array, if it's usable as a key
+    pub fn from_php_value(value: &PHPValue) -> Option<Self> {
+        match value.as_php_array_key()? {
+            PHPValue::Int(i) => Some(Self::Int(i)),
+            PHPValue::String(s) => {
+                let str = s.to_string_lossy();
+                // PHP stores keys like "12" as integers
+                match str.parse::<i64>() {
+                    Ok(i) if i.to_string() == str => Some(Self::Int(i)),
+                    _ => Some(Self::String(Name::from(&s))),
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// The literal type of the key, like `'id'` or `0`
+    pub fn get_literal_type(&self) -> DiscreteType {
+        match self {
+            ShapeTypeKey::String(s) => DiscreteType::StringLiteral(s.to_string()),
+            ShapeTypeKey::Int(i) => DiscreteType::IntLiteral(*i),
+        }
+    }
+}
+
 impl From<ShapeKey> for ShapeTypeKey {
     fn from(key: ShapeKey) -> Self {
         match key {
@@ -1,4 +1,6 @@
 pub mod callable;
 mod object;
+mod pseudo_types;
 pub mod shapes;
+mod type_operators;
 mod various;
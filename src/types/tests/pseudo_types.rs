@@ -0,0 +1,192 @@
+use std::ffi::OsString;
+
+use crate::{
+    analysis::state::AnalysisState,
+    issue::VoidEmitter,
+    phpdoc::position::fake_range,
+    types::{
+        type_parser::TypeParser,
+        union::{DiscreteType, PHPType, SpecialType},
+    },
+};
+
+fn parse_type(type_str: &str) -> PHPType {
+    let mut state = AnalysisState::new();
+    let emitter = VoidEmitter::new();
+    let buffer = OsString::from(type_str);
+    let (Some(ptype), None) =
+        TypeParser::parse_with_remainder(buffer.clone(), fake_range(&buffer), &mut state, &emitter)
+    else {
+        panic!("Failed parsing {}", type_str);
+    };
+    ptype
+}
+
+fn parse_discrete(type_str: &str) -> DiscreteType {
+    let PHPType::Discrete(dtype) = parse_type(type_str) else {
+        panic!("Expected {} to be a discrete type", type_str);
+    };
+    *dtype
+}
+
+#[test]
+fn test_pseudo_type_parsing() {
+    let cases = [
+        ("never", DiscreteType::Never),
+        ("no-return", DiscreteType::Never),
+        ("positive-int", DiscreteType::IntRange(Some(1), None)),
+        ("negative-int", DiscreteType::IntRange(None, Some(-1))),
+        ("int<0, max>", DiscreteType::IntRange(Some(0), None)),
+        ("int<-5, 5>", DiscreteType::IntRange(Some(-5), Some(5))),
+        ("int<min, 10>", DiscreteType::IntRange(None, Some(10))),
+        ("array-key", DiscreteType::ArrayKey),
+        ("scalar", DiscreteType::Scalar),
+        ("numeric", DiscreteType::Numeric),
+        (
+            "non-empty-string",
+            DiscreteType::Special(SpecialType::NonEmptyString),
+        ),
+        (
+            "numeric-string",
+            DiscreteType::Special(SpecialType::NumericString),
+        ),
+        (
+            "callable-string",
+            DiscreteType::Special(SpecialType::CallableString),
+        ),
+        ("list", DiscreteType::List(DiscreteType::Mixed.into())),
+        (
+            "list<string>",
+            DiscreteType::List(DiscreteType::String.into()),
+        ),
+        (
+            "non-empty-list<int>",
+            DiscreteType::NonEmptyList(DiscreteType::Int.into()),
+        ),
+        (
+            "non-empty-array<string>",
+            DiscreteType::NonEmptyArray(DiscreteType::ArrayKey.into(), DiscreteType::String.into()),
+        ),
+        (
+            "non-empty-array<string, int>",
+            DiscreteType::NonEmptyArray(DiscreteType::String.into(), DiscreteType::Int.into()),
+        ),
+        ("'asc'", DiscreteType::StringLiteral("asc".to_string())),
+        ("\"desc\"", DiscreteType::StringLiteral("desc".to_string())),
+        ("42", DiscreteType::IntLiteral(42)),
+        ("-1", DiscreteType::IntLiteral(-1)),
+        ("-9223372036854775808", DiscreteType::IntLiteral(i64::MIN)),
+    ];
+    for (type_str, expected) in cases {
+        assert_eq!(expected, parse_discrete(type_str), "Parsing {}", type_str);
+    }
+}
+
+#[test]
+fn test_literal_unions() {
+    let PHPType::Union(utype) = parse_type("'asc'|'desc'") else {
+        panic!("Expected union type");
+    };
+    assert_eq!(2, utype.len());
+    assert!(utype
+        .types
+        .contains(&DiscreteType::StringLiteral("asc".to_string()).into()));
+    assert!(utype
+        .types
+        .contains(&DiscreteType::StringLiteral("desc".to_string()).into()));
+
+    let PHPType::Union(utype) = parse_type("1|2|null") else {
+        panic!("Expected union type");
+    };
+    assert_eq!(3, utype.len());
+    assert!(utype.types.contains(&DiscreteType::IntLiteral(2).into()));
+}
+
+#[test]
+fn test_pseudo_type_display() {
+    let cases = [
+        ("never", "never"),
+        ("positive-int", "positive-int"),
+        ("int<1, max>", "positive-int"),
+        ("int<0, 100>", "int<0, 100>"),
+        ("int<min, 100>", "int<min, 100>"),
+        ("'asc'", "'asc'"),
+        ("-3", "-3"),
+        ("array-key", "array-key"),
+        ("numeric-string", "numeric-string"),
+        ("list<int>", "list<int>"),
+        ("non-empty-list<string>", "non-empty-list<string>"),
+        (
+            "non-empty-array<string, int>",
+            "non-empty-array<string,int>",
+        ),
+    ];
+    for (type_str, expected) in cases {
+        assert_eq!(expected, parse_type(type_str).to_string());
+    }
+}
+
+#[test]
+fn test_pseudo_type_subtypes() {
+    let state = AnalysisState::new();
+    let passes = |source: &str, target: &str| {
+        parse_discrete(source).can_be_passed_as(&parse_discrete(target), &state.symbol_data)
+    };
+
+    assert_eq!(Some(true), passes("positive-int", "int"));
+    assert_eq!(Some(true), passes("positive-int", "non-negative-int"));
+    assert_eq!(Some(true), passes("5", "int<0, 10>"));
+    assert_eq!(Some(false), passes("-1", "positive-int"));
+    assert_eq!(Some(false), passes("negative-int", "positive-int"));
+    assert_eq!(None, passes("int", "positive-int"));
+    assert_eq!(None, passes("non-negative-int", "positive-int"));
+
+    assert_eq!(Some(true), passes("'asc'", "string"));
+    assert_eq!(Some(true), passes("'asc'", "non-empty-string"));
+    assert_eq!(Some(false), passes("''", "non-empty-string"));
+    assert_eq!(Some(true), passes("'1.5'", "numeric-string"));
+    assert_eq!(Some(false), passes("'asc'", "'desc'"));
+    assert_eq!(Some(true), passes("numeric-string", "non-empty-string"));
+    assert_eq!(None, passes("string", "non-empty-string"));
+
+    assert_eq!(Some(true), passes("int", "array-key"));
+    assert_eq!(Some(true), passes("non-empty-string", "array-key"));
+    assert_eq!(Some(true), passes("numeric-string", "numeric"));
+    assert_eq!(Some(false), passes("'abc'", "numeric"));
+    assert_eq!(Some(true), passes("float", "scalar"));
+    assert_eq!(Some(false), passes("list<int>", "scalar"));
+
+    assert_eq!(Some(true), passes("non-empty-list<int>", "list<int>"));
+    assert_eq!(Some(true), passes("list<int>", "array"));
+    assert_eq!(Some(true), passes("non-empty-array<string>", "iterable"));
+    assert_eq!(None, passes("array", "non-empty-array"));
+    assert_eq!(None, passes("list<int>", "non-empty-list<int>"));
+
+    assert_eq!(Some(true), passes("never", "string"));
+    assert_eq!(Some(false), passes("string", "never"));
+}
+
+#[test]
+fn test_strict_scalar_subtypes() {
+    let state = AnalysisState::new();
+    let passes = |source: &str, target: &str| {
+        parse_discrete(source)
+            .can_be_passed_strictly_as(&parse_discrete(target), &state.symbol_data)
+    };
+
+    assert_eq!(Some(false), passes("int", "string"));
+    assert_eq!(Some(false), passes("string", "int"));
+    assert_eq!(Some(false), passes("float", "int"));
+    assert_eq!(Some(false), passes("bool", "string"));
+    assert_eq!(Some(false), passes("float", "array-key"));
+    assert_eq!(Some(true), passes("int", "float"));
+    assert_eq!(None, passes("array-key", "float"));
+    assert_eq!(None, passes("array-key", "int"));
+    assert_eq!(None, passes("string", "non-empty-string"));
+    assert_eq!(None, passes("scalar", "bool"));
+
+    // Only coerced without strict_types
+    let loose =
+        parse_discrete("int").can_be_passed_as(&parse_discrete("string"), &state.symbol_data);
+    assert_eq!(None, loose);
+}
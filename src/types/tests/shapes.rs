@@ -1,2 +1,36 @@
+use std::ffi::OsString;
+
+use crate::{
+    analysis::state::AnalysisState,
+    issue::VoidEmitter,
+    phpdoc::position::fake_range,
+    types::{type_parser::TypeParser, union::DiscreteType},
+};
+
+fn parse_shape(type_str: &str) -> DiscreteType {
+    let mut state = AnalysisState::new();
+    let emitter = VoidEmitter::new();
+    let buffer = OsString::from(type_str);
+    let (Some(ptype), None) =
+        TypeParser::parse_with_remainder(buffer.clone(), fake_range(&buffer), &mut state, &emitter)
+    else {
+        panic!("Failed parsing {}", type_str);
+    };
+    ptype.single_type().expect("Expected a single type")
+}
+
 #[test]
-pub fn test_shapes() {}
+pub fn test_shapes() {
+    let DiscreteType::Shape(shape) = parse_shape("array{id: int, name?: string}") else {
+        panic!("Expected a shape");
+    };
+    assert!(shape.sealed);
+    assert_eq!(2, shape.map.len());
+
+    let DiscreteType::Shape(shape) = parse_shape("array{id: int, ...}") else {
+        panic!("Expected a shape");
+    };
+    assert!(!shape.sealed);
+    assert_eq!(1, shape.map.len());
+    assert_eq!("array{id:int,...}", DiscreteType::Shape(shape).to_string());
+}
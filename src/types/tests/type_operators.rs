@@ -0,0 +1,146 @@
+use std::{collections::BTreeMap, ffi::OsString};
+
+use crate::{
+    analysis::state::AnalysisState,
+    issue::VoidEmitter,
+    phpdoc::position::fake_range,
+    symbols::Name,
+    types::{
+        parse_types::{ConditionalSubject, ParsedType, TypeName},
+        parser::compound_type,
+        type_operators::TypeOperator,
+        type_parser::TypeParser,
+        union::{DiscreteType, PHPType, UnionType},
+    },
+};
+
+fn parse_type(type_str: &str) -> PHPType {
+    let mut state = AnalysisState::new();
+    let emitter = VoidEmitter::new();
+    let buffer = OsString::from(type_str);
+    let (Some(ptype), None) =
+        TypeParser::parse_with_remainder(buffer.clone(), fake_range(&buffer), &mut state, &emitter)
+    else {
+        panic!("Failed parsing {}", type_str);
+    };
+    ptype
+}
+
+fn parse_single(type_str: &str) -> ParsedType {
+    let (rest, ctype) = compound_type(false)(type_str.as_bytes()).expect("Failed parsing");
+    assert!(rest.is_empty(), "Remainder after parsing {}", type_str);
+    ctype
+        .if_single_type()
+        .expect("Expected single type")
+        .ptype
+        .clone()
+}
+
+#[test]
+fn test_parse_conditional() {
+    let ParsedType::Conditional(conditional) = parse_single("($flag is true ? string : int)")
+    else {
+        panic!("Expected conditional type");
+    };
+    assert_eq!(
+        ConditionalSubject::Parameter(Name::from("$flag")),
+        conditional.subject
+    );
+    assert!(!conditional.negated);
+
+    let ParsedType::Conditional(conditional) = parse_single("(T is not null ? T : never)") else {
+        panic!("Expected conditional type");
+    };
+    assert!(matches!(conditional.subject, ConditionalSubject::Type(_)));
+    assert!(conditional.negated);
+}
+
+#[test]
+fn test_parse_offset_access_and_wildcards() {
+    let ParsedType::OffsetAccess(base, _) = parse_single("T['key']") else {
+        panic!("Expected offset access");
+    };
+    assert!(matches!(base.ptype, ParsedType::Type(_)));
+
+    let ParsedType::ClassType(TypeName::Name(class), constant) = parse_single("static::STATUS_*")
+    else {
+        panic!("Expected class constant type");
+    };
+    assert_eq!(Name::from("static"), class);
+    assert_eq!(Name::from("STATUS_*"), constant);
+}
+
+#[test]
+fn test_key_of_and_value_of() {
+    assert_eq!(
+        parse_type("'a'|'b'"),
+        parse_type("key-of<array{a: int, b: string}>")
+    );
+    assert_eq!(
+        parse_type("int|string"),
+        parse_type("value-of<array{a: int, b: string}>")
+    );
+    assert_eq!(parse_type("string"), parse_type("value-of<list<string>>"));
+    assert_eq!(
+        parse_type("int"),
+        parse_type("array{a: int, b: string}['a']")
+    );
+    assert_eq!(
+        DiscreteType::Special(crate::types::union::SpecialType::Static),
+        *match parse_type("$this") {
+            PHPType::Discrete(d) => d,
+            _ => panic!("Expected discrete type"),
+        }
+    );
+}
+
+#[test]
+fn test_operators_on_templates() {
+    let ptype = PHPType::from(DiscreteType::Operator(TypeOperator::KeyOf(
+        DiscreteType::Template(Name::from("T")).into(),
+    )));
+    assert_eq!("key-of<T>", ptype.to_string());
+
+    let mut concrete = BTreeMap::new();
+    concrete.insert(
+        Name::from("T"),
+        DiscreteType::HashMap(DiscreteType::String.into(), DiscreteType::Int.into()).into(),
+    );
+    assert_eq!(
+        PHPType::from(DiscreteType::String),
+        ptype.concretize_templates(&concrete)
+    );
+}
+
+#[test]
+fn test_conditional_evaluation() {
+    let ptype = parse_type("($flag is true ? string : int)");
+    assert!(matches!(&ptype, PHPType::Discrete(d) if matches!(**d, DiscreteType::Operator(_))));
+
+    let evaluate = |argument: DiscreteType| {
+        let mut concrete = BTreeMap::new();
+        concrete.insert(Name::from("$flag"), argument.into());
+        ptype.concretize_templates(&concrete)
+    };
+    assert_eq!(
+        PHPType::from(DiscreteType::String),
+        evaluate(DiscreteType::True)
+    );
+    assert_eq!(
+        PHPType::from(DiscreteType::Int),
+        evaluate(DiscreteType::False)
+    );
+    assert_eq!(
+        PHPType::from(UnionType::from_pair(
+            DiscreteType::String,
+            DiscreteType::Int
+        )),
+        evaluate(DiscreteType::Bool)
+    );
+
+    // Known subjects are evaluated right away
+    assert_eq!(
+        parse_type("int"),
+        parse_type("(string is not string ? float : int)")
+    );
+}
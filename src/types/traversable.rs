@@ -1,8 +1,14 @@
+use crate::locks::RecoverableLock;
 use std::sync::Arc;
 
+use crate::symbols::Name;
+
 use crate::{symboldata::SymbolData, symbols::FullyQualifiedName, types::union::DiscreteType};
 
-use super::union::{DiscretlyAccessedType, PHPType, UnionType};
+use super::{
+    type_operators::TypeOperator,
+    union::{DiscretlyAccessedType, PHPType, UnionType},
+};
 
 pub fn get_key_type(traversable_type: &PHPType, symbol_data: Arc<SymbolData>) -> Option<PHPType> {
     let mut types = UnionType::new();
@@ -41,10 +47,20 @@ fn get_key_type_from_discrete_type(
     match ttype {
         // Vectors are indiced by int
         DiscreteType::Vector(_) => Some(DiscreteType::Int.into()),
-        DiscreteType::HashMap(k, _) => Some(k.clone()),
+        DiscreteType::List(_) | DiscreteType::NonEmptyList(_) => {
+            Some(DiscreteType::IntRange(Some(0), None).into())
+        }
+        DiscreteType::HashMap(k, _) | DiscreteType::NonEmptyArray(k, _) => Some(k.clone()),
+        DiscreteType::Shape(_) => TypeOperator::KeyOf(ttype.clone().into()).evaluate(),
         DiscreteType::Generic(gen, args) => match &**gen {
             DiscreteType::Array if args.len() == 1 => Some(DiscreteType::Int.into()),
             DiscreteType::Array if args.len() == 2 => Some(args[0].clone()),
+            gen if is_iterator_like(gen) => match args.len() {
+                // Single argument means only the value-type is known
+                1 => Some(DiscreteType::Mixed.into()),
+                n if n >= 2 => Some(args[0].clone()),
+                _ => None,
+            },
             DiscreteType::Named(_, fqname) => {
                 get_key_type_from_class_type(fqname, args, symbol_data)
             }
@@ -89,13 +105,20 @@ fn get_value_type_from_discrete_type(
     match traversable_type {
         // Vectors are indiced by int
         DiscreteType::Vector(v) => Some(v.clone()),
-        DiscreteType::HashMap(_, v) => Some(v.clone()),
+        DiscreteType::List(v) | DiscreteType::NonEmptyList(v) => Some(v.clone()),
+        DiscreteType::HashMap(_, v) | DiscreteType::NonEmptyArray(_, v) => Some(v.clone()),
+        DiscreteType::Shape(_) => TypeOperator::ValueOf(traversable_type.clone().into()).evaluate(),
         DiscreteType::Named(_, _) => {
             crate::missing_none!("Need to extract a value_type from a class-type , Perhaps it's traversable or similar")
         }
         DiscreteType::Generic(gen, args) => match &**gen {
             DiscreteType::Array if args.len() == 1 => Some(args[0].clone()),
             DiscreteType::Array if args.len() == 2 => Some(args[1].clone()),
+            gen if is_iterator_like(gen) => match args.len() {
+                1 => Some(args[0].clone()),
+                n if n >= 2 => Some(args[1].clone()),
+                _ => None,
+            },
             DiscreteType::Named(_, fqname) => {
                 get_value_type_from_class_type(fqname, args, symbol_data)
             }
@@ -126,7 +149,7 @@ fn get_value_type_from_class_type(
     symbol_data: Arc<SymbolData>,
 ) -> Option<PHPType> {
     let locked_cdata = symbol_data.get_class(&fq_name.into())?;
-    let cdata = locked_cdata.read().ok()?;
+    let cdata = locked_cdata.read_or_recover();
     let cdata = cdata.with_generic_args(generic_args);
     if cdata.implements(
         &FullyQualifiedName::from("\\Iterator").into(),
@@ -164,3 +187,124 @@ fn get_key_type_from_class_type(
         "Need to extract a value_type from a generic-type , Perhaps it's traversable or similar"
     )
 }
+
+/// Base types whose generic arguments are `<TKey, TValue>` or just `<TValue>`,
+/// and for `Generator` additionally `<.., TSend, TReturn>`
+fn is_iterator_like(dtype: &DiscreteType) -> bool {
+    match dtype {
+        DiscreteType::Iterable => true,
+        DiscreteType::Named(_, fqname) => {
+            let lc_name = fqname.to_ascii_lowercase();
+            lc_name == FullyQualifiedName::from("\\generator")
+                || lc_name == FullyQualifiedName::from("\\iterator")
+                || lc_name == FullyQualifiedName::from("\\traversable")
+                || lc_name == FullyQualifiedName::from("\\iteratoraggregate")
+        }
+        _ => false,
+    }
+}
+
+fn generator_class() -> DiscreteType {
+    DiscreteType::Named(
+        Name::from("Generator"),
+        FullyQualifiedName::from("\\Generator"),
+    )
+}
+
+/// Builds `Generator<TKey, TValue, TSend, TReturn>`
+pub fn generator_type(key: PHPType, value: PHPType, send: PHPType, ret: PHPType) -> PHPType {
+    DiscreteType::Generic(Box::new(generator_class()), vec![key, value, send, ret]).into()
+}
+
+/// `Generator` without generic arguments, for when the yielded types aren't known
+pub fn plain_generator_type() -> PHPType {
+    generator_class().into()
+}
+
+/// Determines if a function with the declared return-type `declared` is allowed to
+/// contain `yield`. That's the case if at least one of the variants of the declared type
+/// is something a `Generator` is an instance of.
+pub fn can_hold_generator(declared: &PHPType) -> bool {
+    for datype in declared.as_discrete_variants() {
+        let DiscretlyAccessedType::Discrete(dtype) = datype else {
+            continue;
+        };
+        let is_compatible = match &dtype {
+            DiscreteType::Mixed | DiscreteType::Object | DiscreteType::Iterable => true,
+            DiscreteType::Generic(gen, _) => is_iterator_like(gen),
+            dtype => is_iterator_like(dtype),
+        };
+        if is_compatible {
+            return true;
+        }
+    }
+    false
+}
+
+///
+/// The key- and value-type a generator must yield to match the declared return-type, like
+/// `Generator<K, V>` or `iterable<V>`. None when any variant that can hold a `Generator`
+/// doesn't constrain them
+///
+pub fn get_declared_yield_types(declared: &PHPType) -> Option<(PHPType, PHPType)> {
+    let mut key_types = UnionType::new();
+    let mut value_types = UnionType::new();
+    for datype in declared.as_discrete_variants() {
+        let DiscretlyAccessedType::Discrete(dtype) = datype else {
+            continue;
+        };
+        match &dtype {
+            DiscreteType::Generic(gen, args) if is_iterator_like(gen) => match &args[..] {
+                [value] => {
+                    key_types.append(DiscreteType::Mixed);
+                    value_types.append(value.clone());
+                }
+                [key, value, ..] => {
+                    key_types.append(key.clone());
+                    value_types.append(value.clone());
+                }
+                [] => return None,
+            },
+            DiscreteType::Mixed | DiscreteType::Object | DiscreteType::Iterable => return None,
+            dtype if is_iterator_like(dtype) => return None,
+            _ => (),
+        }
+    }
+    if key_types.is_empty() {
+        None
+    } else {
+        Some((key_types.into(), value_types.into()))
+    }
+}
+
+/// Extracts the `TSend`-type from a declared `Generator<TKey, TValue, TSend, TReturn>`
+pub fn get_generator_send_type(declared: &PHPType) -> Option<PHPType> {
+    get_generator_arg(declared, 2)
+}
+
+/// Extracts the `TReturn`-type from a declared `Generator<TKey, TValue, TSend, TReturn>`
+pub fn get_generator_return_type(generator_type: &PHPType) -> Option<PHPType> {
+    get_generator_arg(generator_type, 3)
+}
+
+fn get_generator_arg(generator_type: &PHPType, idx: usize) -> Option<PHPType> {
+    let mut types = UnionType::new();
+    for datype in generator_type.as_discrete_variants() {
+        let DiscretlyAccessedType::Discrete(DiscreteType::Generic(gen, args)) = datype else {
+            continue;
+        };
+        let DiscreteType::Named(_, fqname) = &*gen else {
+            continue;
+        };
+        if fqname.to_ascii_lowercase() != FullyQualifiedName::from("\\generator") {
+            continue;
+        }
+        types.append(args.get(idx)?.clone());
+    }
+
+    if !types.is_empty() {
+        Some(types.into())
+    } else {
+        None
+    }
+}
@@ -0,0 +1,338 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use crate::{
+    symboldata::{
+        class::FunctionArgumentData,
+        templates::{apply_template_defaults, infer_template_types, TemplateData},
+    },
+    symbols::Name,
+};
+
+use super::union::{DiscreteType, DiscretlyAccessedType, PHPType, ShapeTypeKey, UnionType};
+
+///
+/// PHPDoc types which are computed from other types, like `key-of<T>`. They are kept
+/// as they are until the types they depend on are known, typically when templates or
+/// parameters are concretized at a call site.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TypeOperator {
+    KeyOf(PHPType),
+    ValueOf(PHPType),
+    /// `T[K]`
+    /// *  .0 = Type being accessed
+    /// *  .1 = Offset type
+    OffsetAccess(PHPType, PHPType),
+    Conditional(ConditionalType),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ConditionalSubject {
+    /// Parameter name, including the `$`
+    Parameter(Name),
+    Type(PHPType),
+}
+
+/// `($param is T ? A : B)` or `(T is not null ? A : B)`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ConditionalType {
+    pub subject: ConditionalSubject,
+    pub negated: bool,
+    pub is_type: PHPType,
+    pub then_type: PHPType,
+    pub else_type: PHPType,
+}
+
+impl TypeOperator {
+    ///
+    /// Computes the resulting type, or `None` if it depends on templates or parameters
+    /// which are not known yet
+    ///
+    pub fn evaluate(&self) -> Option<PHPType> {
+        match self {
+            TypeOperator::KeyOf(t) => map_discrete(t, key_type),
+            TypeOperator::ValueOf(t) => map_discrete(t, value_type),
+            TypeOperator::OffsetAccess(t, offset) => {
+                if offset.contains_template() {
+                    return None;
+                }
+                map_discrete(t, |dtype| offset_type(dtype, offset))
+            }
+            TypeOperator::Conditional(c) => c.evaluate(),
+        }
+    }
+
+    /// The evaluated type, or the operator itself when it can't be evaluated yet
+    pub(crate) fn evaluate_or_keep(self) -> PHPType {
+        self.evaluate()
+            .unwrap_or_else(|| DiscreteType::Operator(self).into())
+    }
+
+    pub(crate) fn contains_template(&self) -> bool {
+        match self {
+            TypeOperator::KeyOf(t) | TypeOperator::ValueOf(t) => t.contains_template(),
+            TypeOperator::OffsetAccess(t, offset) => {
+                t.contains_template() || offset.contains_template()
+            }
+            TypeOperator::Conditional(c) => {
+                matches!(&c.subject, ConditionalSubject::Type(t) if t.contains_template())
+                    || c.is_type.contains_template()
+                    || c.then_type.contains_template()
+                    || c.else_type.contains_template()
+            }
+        }
+    }
+
+    ///
+    /// Replaces templates with their concrete types and evaluates the operator. Parameters
+    /// in conditional types are looked up in `concrete` by their name, including the `$`.
+    ///
+    pub(crate) fn concretize_templates(&self, concrete: &BTreeMap<Name, PHPType>) -> PHPType {
+        let operator = match self {
+            TypeOperator::KeyOf(t) => TypeOperator::KeyOf(t.concretize_templates(concrete)),
+            TypeOperator::ValueOf(t) => TypeOperator::ValueOf(t.concretize_templates(concrete)),
+            TypeOperator::OffsetAccess(t, offset) => TypeOperator::OffsetAccess(
+                t.concretize_templates(concrete),
+                offset.concretize_templates(concrete),
+            ),
+            TypeOperator::Conditional(c) => {
+                let subject = match &c.subject {
+                    ConditionalSubject::Parameter(name) => match concrete.get(name) {
+                        Some(t) => ConditionalSubject::Type(t.clone()),
+                        None => ConditionalSubject::Parameter(name.clone()),
+                    },
+                    ConditionalSubject::Type(t) => {
+                        ConditionalSubject::Type(t.concretize_templates(concrete))
+                    }
+                };
+                TypeOperator::Conditional(ConditionalType {
+                    subject,
+                    negated: c.negated,
+                    is_type: c.is_type.concretize_templates(concrete),
+                    then_type: c.then_type.concretize_templates(concrete),
+                    else_type: c.else_type.concretize_templates(concrete),
+                })
+            }
+        };
+        operator.evaluate_or_keep()
+    }
+}
+
+impl ConditionalType {
+    fn evaluate(&self) -> Option<PHPType> {
+        let ConditionalSubject::Type(subject) = &self.subject else {
+            return None;
+        };
+        if subject.contains_template() || self.is_type.contains_template() {
+            return None;
+        }
+        let is_variants = discrete_variants(&self.is_type)?;
+        let mut matches = vec![];
+        for variant in discrete_variants(subject)? {
+            if matches!(variant, DiscreteType::Operator(_)) {
+                return None;
+            }
+            let mut result = Some(false);
+            for is_variant in &is_variants {
+                match is_type_of(&variant, is_variant) {
+                    Some(true) => {
+                        result = Some(true);
+                        break;
+                    }
+                    Some(false) => (),
+                    None => result = None,
+                }
+            }
+            matches.push(result);
+        }
+
+        let all_match = matches.iter().all(|m| *m == Some(true));
+        let none_match = matches.iter().all(|m| *m == Some(false));
+        let (when_true, when_false) = if self.negated {
+            (&self.else_type, &self.then_type)
+        } else {
+            (&self.then_type, &self.else_type)
+        };
+        if all_match {
+            Some(when_true.clone())
+        } else if none_match {
+            Some(when_false.clone())
+        } else {
+            Some(UnionType::from_pair(when_true.clone(), when_false.clone()).into())
+        }
+    }
+}
+
+/// Whether `subject` is of the type `is_type`. Unlike when passing arguments, scalars
+/// are never coerced
+fn is_type_of(subject: &DiscreteType, is_type: &DiscreteType) -> Option<bool> {
+    if let Some(result) = subject.can_be_passed_as_builtin(is_type) {
+        return Some(result);
+    }
+    let is_class = |t: &DiscreteType| {
+        matches!(
+            t,
+            DiscreteType::Named(_, _) | DiscreteType::Generic(_, _) | DiscreteType::Object
+        )
+    };
+    if is_class(subject) || is_class(is_type) {
+        return None;
+    }
+    // When `is_type` is a narrower type, like `true` for `bool`, it depends on the
+    // actual value
+    match is_type.can_be_passed_as_builtin(subject) {
+        Some(true) => None,
+        _ => Some(false),
+    }
+}
+
+impl Display for TypeOperator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TypeOperator::KeyOf(t) => write!(f, "key-of<{}>", t),
+            TypeOperator::ValueOf(t) => write!(f, "value-of<{}>", t),
+            TypeOperator::OffsetAccess(t, offset) => write!(f, "{}[{}]", t, offset),
+            TypeOperator::Conditional(c) => write!(
+                f,
+                "({} is {}{} ? {} : {})",
+                match &c.subject {
+                    ConditionalSubject::Parameter(name) => name.to_string(),
+                    ConditionalSubject::Type(t) => t.to_string(),
+                },
+                if c.negated { "not " } else { "" },
+                c.is_type,
+                c.then_type,
+                c.else_type
+            ),
+        }
+    }
+}
+
+/// Whether the type has operators which aren't evaluated yet
+pub(crate) fn contains_operator(ptype: &PHPType) -> bool {
+    ptype.as_discrete_variants().iter().any(|variant| {
+        matches!(
+            variant,
+            DiscretlyAccessedType::Discrete(DiscreteType::Operator(_))
+        )
+    })
+}
+
+///
+/// What we know from the arguments at a call site, for use with
+/// [PHPType::concretize_templates]. Each parameter name, including the `$`, is mapped to the
+/// type of the argument, as are templates inferred from the types of the parameters.
+/// Templates which can't be inferred get their default, if declared with one.
+///
+pub(crate) fn get_call_site_concretes(
+    parameters: &[FunctionArgumentData],
+    argument_types: &[Option<PHPType>],
+    templates: &[TemplateData],
+) -> BTreeMap<Name, PHPType> {
+    let mut concrete = BTreeMap::new();
+    let mut inferred = BTreeMap::new();
+    for (parameter, argument_type) in parameters.iter().zip(argument_types) {
+        let Some(argument_type) = argument_type else {
+            continue;
+        };
+        if parameter.variadic {
+            break;
+        }
+        let mut parameter_name = vec![b'$'];
+        parameter_name.extend(parameter.name.as_bytes());
+        concrete.insert(Name::from(parameter_name), argument_type.clone());
+
+        let declared_type = parameter
+            .phpdoc_type
+            .as_ref()
+            .or(parameter.arg_type.as_ref());
+        if let Some(declared_type) = declared_type {
+            infer_template_types(&mut inferred, declared_type, argument_type);
+        }
+    }
+    inferred.retain(|name, _| templates.iter().any(|t| t.name == *name));
+    apply_template_defaults(&mut inferred, templates);
+    concrete.extend(inferred);
+    concrete
+}
+
+fn discrete_variants(ptype: &PHPType) -> Option<Vec<DiscreteType>> {
+    ptype
+        .as_discrete_variants()
+        .into_iter()
+        .map(|variant| match variant {
+            DiscretlyAccessedType::Discrete(d) => Some(d),
+            DiscretlyAccessedType::Intersection(_) => None,
+        })
+        .collect()
+}
+
+/// Applies `operator` on each of the discrete types, giving up if any of them fails
+fn map_discrete(
+    ptype: &PHPType,
+    operator: impl Fn(&DiscreteType) -> Option<PHPType>,
+) -> Option<PHPType> {
+    let mut result = UnionType::new();
+    for dtype in discrete_variants(ptype)? {
+        result.append(operator(&dtype)?);
+    }
+    if result.is_empty() {
+        None
+    } else {
+        Some(result.into())
+    }
+}
+
+fn key_type(dtype: &DiscreteType) -> Option<PHPType> {
+    match dtype {
+        DiscreteType::Shape(shape) => {
+            let keys: UnionType = shape.map.keys().map(|key| key.get_literal_type()).collect();
+            Some(keys.into())
+        }
+        DiscreteType::HashMap(k, _) | DiscreteType::NonEmptyArray(k, _) => Some(k.clone()),
+        DiscreteType::Vector(_) => Some(DiscreteType::Int.into()),
+        DiscreteType::List(_) | DiscreteType::NonEmptyList(_) => {
+            Some(DiscreteType::IntRange(Some(0), None).into())
+        }
+        DiscreteType::Array => Some(DiscreteType::ArrayKey.into()),
+        _ => None,
+    }
+}
+
+fn value_type(dtype: &DiscreteType) -> Option<PHPType> {
+    match dtype {
+        DiscreteType::Shape(shape) => {
+            let mut values = UnionType::new();
+            for value in shape.map.values() {
+                values.append(value.utype.clone());
+            }
+            Some(values.into())
+        }
+        DiscreteType::HashMap(_, v)
+        | DiscreteType::NonEmptyArray(_, v)
+        | DiscreteType::Vector(v)
+        | DiscreteType::List(v)
+        | DiscreteType::NonEmptyList(v) => Some(v.clone()),
+        DiscreteType::Array => Some(DiscreteType::Mixed.into()),
+        _ => None,
+    }
+}
+
+fn offset_type(dtype: &DiscreteType, offset: &PHPType) -> Option<PHPType> {
+    match dtype {
+        DiscreteType::Shape(shape) => {
+            let mut values = UnionType::new();
+            for key in discrete_variants(offset)? {
+                let key = match key {
+                    DiscreteType::StringLiteral(s) => ShapeTypeKey::String(Name::from(s.as_str())),
+                    DiscreteType::IntLiteral(i) => ShapeTypeKey::Int(i),
+                    // Any of the values
+                    _ => return value_type(dtype),
+                };
+                values.append(shape.map.get(&key)?.utype.clone());
+            }
+            Some(values.into())
+        }
+        _ => value_type(dtype),
+    }
+}
@@ -34,7 +34,7 @@ impl TypeParser {
     ) -> Option<PHPType> {
         let parse_result = compound_type_with_colon(true)(type_str.as_bytes());
         let (utype, remainder) =
-            Self::handle_parse_result(type_str.clone(), parse_result, state, emitter);
+            Self::handle_parse_result(type_str.clone(), parse_result, state, emitter, None);
         Self::handle_remainder(utype, remainder, state, emitter, range)
     }
 
@@ -43,6 +43,7 @@ impl TypeParser {
         parse_result: Result<(&[u8], CompoundType), nom::Err<Error<&[u8]>>>,
         state: &mut AnalysisState,
         emitter: &dyn IssueEmitter,
+        temp_generics: Option<&Vec<Name>>,
     ) -> (Option<PHPType>, Option<OsString>) {
         let (rest, parsed_type) = if let Ok((rest, parsed_type)) = parse_result {
             (rest, parsed_type)
@@ -57,7 +58,7 @@ impl TypeParser {
             None
         };
         let found_types = if let Some(utype) =
-            from_vec_parsed_type(parsed_type.clone(), state, Some(emitter), None)
+            from_vec_parsed_type(parsed_type.clone(), state, Some(emitter), temp_generics)
         {
             Some(utype)
         } else {
@@ -88,7 +89,7 @@ impl TypeParser {
     ) -> (Option<PHPType>, Option<OsString>) {
         let parse_result = compound_type(true)(type_str.as_bytes());
 
-        Self::handle_parse_result(type_str.clone(), parse_result, state, emitter)
+        Self::handle_parse_result(type_str.clone(), parse_result, state, emitter, None)
     }
 
     fn handle_parse_vec_result(
@@ -166,6 +167,25 @@ impl TypeParser {
         Self::handle_remainder(utype, remainder, state, emitter, range)
     }
 
+    /// Same as [TypeParser::parse], where the names in `temp_generics` are templates
+    pub fn parse_with_templates(
+        type_str: OsString,
+        range: Range,
+        state: &mut AnalysisState,
+        emitter: &dyn IssueEmitter,
+        temp_generics: Option<&Vec<Name>>,
+    ) -> Option<PHPType> {
+        let parse_result = compound_type(true)(type_str.as_bytes());
+        let (utype, remainder) = Self::handle_parse_result(
+            type_str.clone(),
+            parse_result,
+            state,
+            emitter,
+            temp_generics,
+        );
+        Self::handle_remainder(utype, remainder, state, emitter, range)
+    }
+
     fn handle_remainder<T>(
         utype: Option<T>,
         remainder: Option<OsString>,
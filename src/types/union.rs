@@ -1,3 +1,4 @@
+use crate::locks::RecoverableLock;
 use std::{
     collections::{BTreeMap, BTreeSet},
     fmt::Display,
@@ -12,13 +13,17 @@ use crate::{
     issue::IssueEmitter,
     operators::binary::InstanceOfSymbol,
     parser::Range,
-    symboldata::class::ClassName,
+    symboldata::class::{ClassName, ClassType},
     symbols::{FullyQualifiedName, Name},
 };
 
 use super::parse_types::CompoundType;
+use super::type_operators::{ConditionalSubject, ConditionalType, TypeOperator};
 use super::{
-    parse_types::{ConcreteType, ParsedType, ShapeKey, TypeName, TypeStruct},
+    parse_types::{
+        ConcreteType, ConditionalSubject as ParsedConditionalSubject, ParsedType, ShapeKey,
+        TypeName, TypeStruct,
+    },
     phptype::TypeTraits,
 };
 
@@ -27,6 +32,9 @@ pub enum SpecialType {
     Static,
     Self_,
     ClassString(Option<FullyQualifiedName>),
+    NonEmptyString,
+    NumericString,
+    CallableString,
 }
 
 impl Display for SpecialType {
@@ -41,6 +49,9 @@ impl Display for SpecialType {
                     write!(f, "class-string")
                 }
             }
+            Self::NonEmptyString => write!(f, "non-empty-string"),
+            Self::NumericString => write!(f, "numeric-string"),
+            Self::CallableString => write!(f, "callable-string"),
         }
     }
 }
@@ -60,6 +71,8 @@ pub struct ShapeTypeValue {
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct ShapeType {
     pub map: BTreeMap<ShapeTypeKey, ShapeTypeValue>,
+    /// Whether the array has no other keys than those in `map`, unlike `array{id: int, ...}`
+    pub sealed: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
@@ -101,6 +114,30 @@ pub enum DiscreteType {
 
     ClassType(FullyQualifiedName, Name),
     Template(Name),
+
+    /// Return type of functions which never returns, like those always throwing
+    Never,
+    /// Integer within an inclusive range, like `positive-int` or `int<0, max>`
+    /// *  .0 = Lower bound, `None` means `min`
+    /// *  .1 = Upper bound, `None` means `max`
+    IntRange(Option<i64>, Option<i64>),
+    IntLiteral(i64),
+    StringLiteral(String),
+    /// `int|string`
+    ArrayKey,
+    /// `int|float|string|bool`
+    Scalar,
+    /// `int|float|numeric-string`
+    Numeric,
+    /// Array with consecutive integer keys starting at 0
+    List(PHPType),
+    NonEmptyList(PHPType),
+    /// *  .0 = Key type
+    /// *  .1 = Value type
+    NonEmptyArray(PHPType, PHPType),
+    /// `key-of<T>`, conditional types and similar, which can't be evaluated until
+    /// templates or parameters are known
+    Operator(TypeOperator),
 }
 
 /*
@@ -132,7 +169,7 @@ impl PHPType {
     pub(crate) fn to_markdown(&self) -> String {
         match self {
             PHPType::Union(u) => u.to_markdown(),
-            PHPType::Intersection(_) => todo!(),
+            PHPType::Intersection(i) => format!("`{}`", i),
             PHPType::Discrete(d) => d.to_markdown(),
         }
     }
@@ -165,6 +202,35 @@ impl PHPType {
         }
     }
 
+    /// Whether any of the types is `null`, unlike [super::phptype::TypeTraits::is_nullable]
+    /// which requires all of them to be
+    pub fn can_be_null(&self) -> bool {
+        self.as_discrete_variants()
+            .iter()
+            .any(|v| matches!(v, DiscretlyAccessedType::Discrete(DiscreteType::NULL)))
+    }
+
+    ///
+    /// Whether the type is known well enough to tell it's never `null`. Types like `mixed`,
+    /// templates and unevaluated operators could be `null`, so they aren't
+    ///
+    pub fn is_never_null(&self) -> bool {
+        let variants = self.as_discrete_variants();
+        !variants.is_empty()
+            && variants.iter().all(|v| match v {
+                DiscretlyAccessedType::Intersection(_) => true,
+                DiscretlyAccessedType::Discrete(dtype) => !matches!(
+                    dtype,
+                    DiscreteType::NULL
+                        | DiscreteType::Void
+                        | DiscreteType::Mixed
+                        | DiscreteType::Unknown
+                        | DiscreteType::Template(_)
+                        | DiscreteType::Operator(_)
+                ),
+            })
+    }
+
     /// See [DiscretlyAccessedType] for more details
     pub fn as_discrete_variants(&self) -> Vec<DiscretlyAccessedType> {
         let simple = self.simplify();
@@ -570,8 +636,8 @@ impl TypeTraits for UnionType {
         !self.types.is_empty()
     }
 
-    fn is_same_type(&self, _other: &Self) -> bool {
-        todo!()
+    fn is_same_type(&self, other: &Self) -> bool {
+        UnionType::is_same_type(self, other)
     }
 
     fn can_be_cast_to_string(&self) -> Option<Consequences> {
@@ -590,7 +656,7 @@ impl TypeTraits for UnionType {
     }
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Consequence {
     /// Ok
     Ok,
@@ -604,12 +670,24 @@ pub enum Consequence {
     Error(&'static str),
 }
 
+impl Consequence {
+    fn severity(&self) -> u8 {
+        match self {
+            Consequence::Ok => 0,
+            Consequence::Nonidiomatic(_) => 1,
+            Consequence::Notice(_) => 2,
+            Consequence::Warning(_) => 3,
+            Consequence::Error(_) => 4,
+        }
+    }
+}
+
 #[derive(Default)]
 pub struct Consequences(Vec<Consequence>);
 
 impl Consequences {
     pub fn most_severe(&self) -> Option<Consequence> {
-        todo!()
+        self.0.iter().max_by_key(|c| c.severity()).cloned()
     }
 
     pub fn push(&mut self, x: Consequence) {
@@ -706,8 +784,9 @@ fn from_parsed_type(
         ParsedType::Type(type_struct) => {
             from_type_struct(type_struct, state, maybe_emitter, temp_generics)
         }
-        ParsedType::Shape(entries) => {
+        ParsedType::Shape(entries, sealed) => {
             let mut shape = ShapeType::new();
+            shape.sealed = sealed;
             let mut key_idx = 0;
             for entry in entries {
                 let (key, optional) = if let Some((k, optional)) = entry.0 {
@@ -762,6 +841,15 @@ fn from_parsed_type(
         }
         ParsedType::ClassType(cname, tname) => {
             let fq_name = match cname {
+                TypeName::Name(symbol_name)
+                    if symbol_name.eq_ignore_ascii_case("self")
+                        || symbol_name.eq_ignore_ascii_case("static") =>
+                {
+                    let Some(class_state) = &state.in_class else {
+                        return crate::missing_none!("{}:: outside of class", symbol_name);
+                    };
+                    class_state.get_name().get_fq_name().clone()
+                }
                 TypeName::Name(symbol_name) => {
                     state.get_fq_symbol_name_from_local_name(&symbol_name)
                 }
@@ -772,11 +860,43 @@ fn from_parsed_type(
                     );
                 }
             };
-            Some(DiscreteType::ClassType(fq_name, tname).into())
+            if let Some(constant_types) = get_class_constant_types(&fq_name, &tname, state) {
+                Some(constant_types)
+            } else {
+                Some(DiscreteType::ClassType(fq_name, tname).into())
+            }
         }
         ParsedType::Parenthesized(ptype) => {
             from_vec_parsed_type(*ptype, state, maybe_emitter, temp_generics)
         }
+        ParsedType::StringLiteral(s) => Some(DiscreteType::StringLiteral(s).into()),
+        ParsedType::IntLiteral(i) => Some(DiscreteType::IntLiteral(i).into()),
+        ParsedType::OffsetAccess(base, offset) => {
+            let base = from_parsed_type(*base, state, maybe_emitter, temp_generics)?;
+            let offset = from_vec_parsed_type(*offset, state, maybe_emitter, temp_generics)?;
+            Some(TypeOperator::OffsetAccess(base, offset).evaluate_or_keep())
+        }
+        ParsedType::Conditional(conditional) => {
+            let subject = match conditional.subject {
+                ParsedConditionalSubject::Parameter(name) => ConditionalSubject::Parameter(name),
+                ParsedConditionalSubject::Type(t) => ConditionalSubject::Type(from_parsed_type(
+                    t,
+                    state,
+                    maybe_emitter,
+                    temp_generics,
+                )?),
+            };
+            let mut convert =
+                |ctype| from_vec_parsed_type(ctype, state, maybe_emitter, temp_generics);
+            let conditional = ConditionalType {
+                subject,
+                negated: conditional.negated,
+                is_type: convert(conditional.is_type)?,
+                then_type: convert(conditional.then_type)?,
+                else_type: convert(conditional.else_type)?,
+            };
+            Some(TypeOperator::Conditional(conditional).evaluate_or_keep())
+        }
     };
 
     if let Some(utype) = utype {
@@ -803,7 +923,27 @@ fn from_type_struct(
         // FIXME ensure that any non-used generics are being reported
         match lc_type_str.as_bytes() {
             b"string" => Some(DiscreteType::String),
-            b"int" => Some(DiscreteType::Int),
+            b"int" => {
+                if let Some(gen) = &type_struct.generics {
+                    // int<0, max>
+                    let (min, max) = int_range_bounds(gen)?;
+                    return Some(DiscreteType::IntRange(min, max).into());
+                }
+                Some(DiscreteType::Int)
+            }
+            b"positive-int" => Some(DiscreteType::IntRange(Some(1), None)),
+            b"non-negative-int" => Some(DiscreteType::IntRange(Some(0), None)),
+            b"negative-int" => Some(DiscreteType::IntRange(None, Some(-1))),
+            b"non-positive-int" => Some(DiscreteType::IntRange(None, Some(0))),
+            b"non-empty-string" => Some(DiscreteType::Special(SpecialType::NonEmptyString)),
+            b"numeric-string" => Some(DiscreteType::Special(SpecialType::NumericString)),
+            b"callable-string" => Some(DiscreteType::Special(SpecialType::CallableString)),
+            b"array-key" => Some(DiscreteType::ArrayKey),
+            b"scalar" => Some(DiscreteType::Scalar),
+            b"numeric" => Some(DiscreteType::Numeric),
+            b"never" | b"never-return" | b"never-returns" | b"no-return" | b"noreturn" => {
+                Some(DiscreteType::Never)
+            }
             b"integer" => Some(DiscreteType::Int),
             b"float" | b"double" => Some(DiscreteType::Float),
             b"boolean" | b"bool" => Some(DiscreteType::Bool),
@@ -811,7 +951,21 @@ fn from_type_struct(
             b"true" => Some(DiscreteType::True),
             b"resource" => Some(DiscreteType::Resource),
             b"self" => Some(DiscreteType::Special(SpecialType::Self_)),
-            b"static" => Some(DiscreteType::Special(SpecialType::Static)),
+            b"static" | b"$this" => Some(DiscreteType::Special(SpecialType::Static)),
+            b"key-of" | b"value-of" => {
+                let gen = type_struct.generics.as_ref()?;
+                if gen.len() != 1 {
+                    return None;
+                }
+                let operand =
+                    from_vec_parsed_type(gen[0].clone(), state, maybe_emitter, temp_generics)?;
+                let operator = if lc_type_str.as_bytes() == b"key-of" {
+                    TypeOperator::KeyOf(operand)
+                } else {
+                    TypeOperator::ValueOf(operand)
+                };
+                return Some(operator.evaluate_or_keep());
+            }
             b"mixed" => Some(DiscreteType::Mixed),
             b"void" => Some(DiscreteType::Void),
             b"iterable" => Some(DiscreteType::Iterable),
@@ -847,7 +1001,11 @@ fn from_type_struct(
                     let fqname = match noe {
                         TypeName::Name(name) => state.get_fq_symbol_name_from_local_name(&name),
                         TypeName::FQName(fq) => fq,
-                        TypeName::RelativeName(_) => todo!(),
+                        TypeName::RelativeName(path) => {
+                            let mut fq_name = state.namespace.clone().unwrap_or_default();
+                            fq_name.append(path);
+                            fq_name
+                        }
                     };
                     Some(DiscreteType::Special(SpecialType::ClassString(Some(
                         fqname,
@@ -895,6 +1053,41 @@ fn from_type_struct(
                     Some(DiscreteType::Array)
                 }
             }
+            b"list" | b"non-empty-list" => {
+                let value = match &type_struct.generics {
+                    None => DiscreteType::Mixed.into(),
+                    Some(gen) if gen.len() == 1 => {
+                        from_vec_parsed_type(gen[0].clone(), state, maybe_emitter, temp_generics)?
+                    }
+                    Some(_) => return None,
+                };
+                if lc_type_str.as_bytes() == b"list" {
+                    Some(DiscreteType::List(value))
+                } else {
+                    Some(DiscreteType::NonEmptyList(value))
+                }
+            }
+            b"non-empty-array" => {
+                let mut gen_types = vec![];
+                for gen in type_struct.generics.iter().flatten() {
+                    gen_types.push(from_vec_parsed_type(
+                        gen.clone(),
+                        state,
+                        maybe_emitter,
+                        temp_generics,
+                    )?);
+                }
+                let (key, value) = match gen_types.len() {
+                    0 => (DiscreteType::ArrayKey.into(), DiscreteType::Mixed.into()),
+                    1 => (DiscreteType::ArrayKey.into(), gen_types.remove(0)),
+                    2 => {
+                        let value = gen_types.remove(1);
+                        (gen_types.remove(0), value)
+                    }
+                    _ => return None,
+                };
+                Some(DiscreteType::NonEmptyArray(key, value))
+            }
             _ => None,
         }
     } else {
@@ -910,6 +1103,11 @@ fn from_type_struct(
                     return Some(DiscreteType::Template(x.clone()).into());
                 }
             }
+            if dtype.is_none() {
+                if let Some(alias) = state.get_type_alias(x) {
+                    return Some(alias);
+                }
+            }
         }
     }
     // ...
@@ -931,18 +1129,13 @@ fn from_type_struct(
                 match (&type_struct.generics, &generic_templates) {
                     (None, Some(templates)) if templates.contains(name) => {
                         // The type-name is in the list of available templates
-                        eprintln!(
-                            "Trying to create a union-type from a type-struct: {:?}",
-                            &type_struct
-                        );
-                        eprintln!("generic templates: {:?}", generic_templates);
-                        todo!();
+                        return Some(DiscreteType::Template(name.clone()).into());
                     }
                     (Some(_), Some(templates)) if templates.contains(name) => {
                         // The type-name is in the list of available templates
                         // but it also has generic arguments itself?
                         // this is probably wrong in some sense
-                        panic!();
+                        return crate::missing_none!("Template {:?} with generic arguments", name);
                     }
                     (None, Some(_templates)) => {
                         ClassName::new_with_analysis_state(name, state)
@@ -995,7 +1188,11 @@ fn from_type_struct(
         cname.into()
     };
     match &base_type {
-        DiscreteType::Vector(_) | DiscreteType::HashMap(_, _) => return Some(base_type.into()),
+        DiscreteType::Vector(_)
+        | DiscreteType::HashMap(_, _)
+        | DiscreteType::List(_)
+        | DiscreteType::NonEmptyList(_)
+        | DiscreteType::NonEmptyArray(_, _) => return Some(base_type.into()),
         _ => (),
     }
 
@@ -1019,6 +1216,71 @@ fn from_type_struct(
     Some(utype.into())
 }
 
+/// The literal types of the constants matching `pattern`, which might contain `*`
+/// wildcards, like in `Foo::STATUS_*`. Gives `None` unless all matching constants
+/// are known
+fn get_class_constant_types(
+    fq_name: &FullyQualifiedName,
+    pattern: &Name,
+    state: &AnalysisState,
+) -> Option<PHPType> {
+    let class_handle = state.symbol_data.get_class(&fq_name.into())?;
+    let class_type = class_handle.read_or_recover();
+    let ClassType::Class(class_data) = &*class_type else {
+        return None;
+    };
+    let pattern = pattern.to_string();
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let matches_pattern = |name: &str| {
+        let (first, last) = (parts[0], parts[parts.len() - 1]);
+        if parts.len() == 1 {
+            return name == first;
+        }
+        if !name.starts_with(first) || !name[first.len()..].ends_with(last) {
+            return false;
+        }
+        let mut rest = &name[first.len()..name.len() - last.len()];
+        for part in &parts[1..parts.len() - 1] {
+            let Some(idx) = rest.find(part) else {
+                return false;
+            };
+            rest = &rest[idx + part.len()..];
+        }
+        true
+    };
+
+    let mut utype = UnionType::new();
+    for (constant_name, value) in &class_data.constants {
+        if !matches_pattern(&constant_name.to_string()) {
+            continue;
+        }
+        utype.append(value.as_ref()?.get_literal_utype()?);
+    }
+    if utype.is_empty() {
+        None
+    } else {
+        Some(utype.into())
+    }
+}
+
+/// Bounds of `int<min, max>`, where `min` and `max` are unbounded
+fn int_range_bounds(generics: &[CompoundType]) -> Option<(Option<i64>, Option<i64>)> {
+    if generics.len() != 2 {
+        return None;
+    }
+    let bound = |ctype: &CompoundType, unbounded: &[u8]| -> Option<Option<i64>> {
+        match &ctype.if_single_type()?.ptype {
+            ParsedType::IntLiteral(i) => Some(Some(*i)),
+            ParsedType::Type(TypeStruct {
+                type_name: TypeName::Name(name),
+                generics: None,
+            }) if name.to_os_string().to_ascii_lowercase().as_bytes() == unbounded => Some(None),
+            _ => None,
+        }
+    };
+    Some((bound(&generics[0], b"min")?, bound(&generics[1], b"max")?))
+}
+
 impl From<ClassName> for DiscreteType {
     fn from(cname: ClassName) -> Self {
         DiscreteType::Named(cname.get_name().clone(), cname.get_fq_name().clone())
@@ -1036,7 +1298,7 @@ impl From<UnionType> for PHPType {
 }
 impl From<&UnionType> for PHPType {
     fn from(utype: &UnionType) -> Self {
-        utype.into()
+        utype.clone().into()
     }
 }
 
@@ -159,6 +159,20 @@ impl PHPValue {
         )
     }
 
+    /// Like [PHPValue::get_utype], but with literal types like `true` or `'asc'` where possible
+    pub fn get_literal_utype(&self) -> Option<PHPType> {
+        Some(
+            match self {
+                PHPValue::Boolean(true) => DiscreteType::True,
+                PHPValue::Boolean(false) => DiscreteType::False,
+                PHPValue::Int(i) => DiscreteType::IntLiteral(*i),
+                PHPValue::String(s) => DiscreteType::StringLiteral(s.to_string_lossy().to_string()),
+                _ => return self.get_utype(),
+            }
+            .into(),
+        )
+    }
+
     pub fn as_php_string(&self) -> Option<PHPValue> {
         Some(match self {
             PHPValue::NULL => PHPValue::String(OsString::new()),
@@ -384,9 +398,29 @@ impl PHPValue {
             PHPValue::Int(i) => format!("{}", i).into(),
             PHPValue::Float(f) => format!("{}", (*f).as_f64()).into(),
             PHPValue::String(s) => format!(r#""{:?}""#, s).into(),
-            PHPValue::Array(_) => todo!(),
-            PHPValue::ObjectInstance(_) => todo!(),
-            PHPValue::Enum(_, _) => todo!(),
+            PHPValue::Array(PHPArray::Empty) => OsStr::from_bytes(b"[]").into(),
+            PHPValue::Array(PHPArray::Vector(v)) => {
+                let entries: Vec<_> = v
+                    .iter()
+                    .map(|x| x.as_raw_php().to_string_lossy().to_string())
+                    .collect();
+                format!("[{}]", entries.join(", ")).into()
+            }
+            PHPValue::Array(PHPArray::HashMap(m)) => {
+                let entries: Vec<_> = m
+                    .iter()
+                    .map(|(k, v)| {
+                        format!(
+                            "{} => {}",
+                            k.as_raw_php().to_string_lossy(),
+                            v.as_raw_php().to_string_lossy()
+                        )
+                    })
+                    .collect();
+                format!("[{}]", entries.join(", ")).into()
+            }
+            PHPValue::ObjectInstance(o) => format!("new {}()", o.fq_name).into(),
+            PHPValue::Enum(fq_name, case) => format!("{}::{}", fq_name, case).into(),
         }
     }
 
@@ -456,8 +490,54 @@ impl PHPArray {
         }
     }
 
-    pub(crate) fn get_type_by_key(&self, php_idx: PHPValue) -> Option<PHPType> {
-        let v = self.get_value_by_key(php_idx)?;
-        v.get_utype()
+    /// The key-value pairs, in order
+    pub(crate) fn entries(&self) -> Vec<(PHPValue, PHPValue)> {
+        match self {
+            PHPArray::Empty => vec![],
+            PHPArray::Vector(v) => v
+                .iter()
+                .enumerate()
+                .map(|(idx, value)| (PHPValue::Int(idx as i64), value.clone()))
+                .collect(),
+            PHPArray::HashMap(h) => h.clone(),
+        }
+    }
+
+    pub(crate) fn values(&self) -> Vec<PHPValue> {
+        match self {
+            PHPArray::Empty => vec![],
+            PHPArray::Vector(v) => v.clone(),
+            PHPArray::HashMap(h) => h.iter().map(|(_, value)| value.clone()).collect(),
+        }
+    }
+
+    /// The array after `value` is written to `key_val`
+    pub(crate) fn with_value_by_key(&self, key_val: PHPValue, value: PHPValue) -> Option<Self> {
+        let array_key = key_val.as_php_array_key()?;
+        let mut entries = self.entries();
+        match entries
+            .iter_mut()
+            .find(|(key, _)| key.equal_to(&array_key).unwrap_or(false))
+        {
+            Some(entry) => entry.1 = value,
+            None => entries.push((array_key, value)),
+        }
+        Some(Self::from_entries(entries))
+    }
+
+    /// Builds a vector when the keys are consecutive integers starting at 0
+    pub(crate) fn from_entries(entries: Vec<(PHPValue, PHPValue)>) -> Self {
+        if entries.is_empty() {
+            return PHPArray::Empty;
+        }
+        let is_list = entries
+            .iter()
+            .enumerate()
+            .all(|(idx, (key, _))| matches!(key, PHPValue::Int(i) if *i == idx as i64));
+        if is_list {
+            PHPArray::Vector(entries.into_iter().map(|(_, value)| value).collect())
+        } else {
+            PHPArray::HashMap(entries)
+        }
     }
 }